@@ -24,7 +24,7 @@ fn main() {
 
     Runtime::new().unwrap().block_on(async {
         let exchange = "binance";
-        let pc_symbol = "BTC_USDT_221230_SWAP";
+        let pc_symbol = "BTC_USD_SWAP";
         let pu_symbol = "BTC_USDT_SWAP";
         let spot_symbol = "BTC_USDT";
         let _ = vec![pc_symbol, pu_symbol, spot_symbol];
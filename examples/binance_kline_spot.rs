@@ -0,0 +1,45 @@
+use tokio::runtime::Runtime;
+use tokio::time::{sleep, Duration};
+
+use snapshot::{BarAggregator, KlineManager, TickerManager};
+
+/// const KLINE_URL_PC: &str =    "wss://dstream.binance.com/stream?streams=btcusd_221230@kline_1m";
+/// const KLINE_URL_PU: &str =    "wss://fstream.binance.com/stream?streams=btcusdt@kline_1m";
+/// const KLINE_URL_SPOT: &str =  "wss://stream.binance.com:9443/ws/bnbbtc@kline_1m";
+
+fn main() {
+    println!("Hello");
+
+    tracing_subscriber::fmt::init();
+
+    Runtime::new().unwrap().block_on(async {
+        let exchange = "binance";
+        let symbol = "BTC_USDT";
+        println!("using symbol {}", symbol);
+
+        let manager1 = KlineManager::new(exchange, symbol, "1m");
+        println!("using manager1 config {:?}", manager1.config);
+        tokio::spawn(async move {
+            let mut receiver = manager1.subscribe();
+            while let Some(bar) = receiver.recv().await {
+                if bar.closed {
+                    println!("exchange bar {:?}", bar);
+                }
+            }
+        });
+
+        let manager2 = TickerManager::new(exchange, symbol);
+        println!("using manager2 config {:?}", manager2.config);
+        tokio::spawn(async move {
+            let aggregator = BarAggregator::with_interval("1m").unwrap();
+            let mut receiver = aggregator.subscribe(&manager2);
+            while let Some(bar) = receiver.recv().await {
+                println!("local bar    {:?}", bar);
+            }
+        });
+
+        loop {
+            sleep(Duration::from_secs(1)).await;
+        }
+    });
+}
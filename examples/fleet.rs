@@ -0,0 +1,52 @@
+use tokio::runtime::Runtime;
+
+use snapshot::{FleetConfig, FleetData};
+
+const CONFIG: &str = r#"
+[reconnect]
+initial_delay_ms = 500
+max_delay_ms = 10000
+
+[[subscriptions]]
+exchange = "binance"
+symbols = ["BTC_USDT", "ETH_USDT"]
+streams = ["depth", "ticker"]
+limit = 1000
+
+[[subscriptions]]
+exchange = "okx"
+symbols = ["BTC_USDT_SWAP"]
+streams = ["bookTicker"]
+sinks = [{ type = "log" }]
+"#;
+
+/// cargo run --example fleet [fleet.toml | fleet.yaml]
+fn main() {
+    tracing_subscriber::fmt::init();
+
+    let config = match std::env::args().nth(1) {
+        Some(path) => FleetConfig::load(path).unwrap(),
+        None => FleetConfig::from_toml(CONFIG).unwrap(),
+    };
+    config.validate().unwrap();
+
+    Runtime::new().unwrap().block_on(async {
+        let mut fleet = config.start().unwrap();
+        println!("streams {:?}", fleet.names());
+
+        while let Some(event) = fleet.recv().await {
+            match event.data {
+                FleetData::Depth(depth) | FleetData::BookTicker(depth) => println!(
+                    "{} id {}, ts {}, asks {} bids {}",
+                    event.name,
+                    depth.id,
+                    depth.ts,
+                    depth.asks.len(),
+                    depth.bids.len()
+                ),
+                FleetData::Ticker(tickers) => println!("{} {} trades", event.name, tickers.len()),
+                FleetData::State(state) => println!("{} {:?}", event.name, state),
+            }
+        }
+    });
+}
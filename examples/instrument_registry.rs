@@ -0,0 +1,32 @@
+use tokio::runtime::Runtime;
+
+use snapshot::{DepthManager, InstrumentRegistry};
+
+const CACHE: &str = "instruments.json";
+
+fn main() {
+    tracing_subscriber::fmt::init();
+
+    Runtime::new().unwrap().block_on(async {
+        let registry = InstrumentRegistry::global();
+
+        // Offline: reuse the instruments saved by an earlier run
+        match registry.load_file(CACHE) {
+            Ok(count) => println!("loaded {} instruments from {}", count, CACHE),
+            Err(_) => {
+                for exchange in ["binance", "crypto"] {
+                    let count = registry.load(exchange).await.unwrap();
+                    println!("fetched {} instruments of {}", count, exchange);
+                }
+                registry.save_file(CACHE).unwrap();
+            }
+        }
+
+        println!("{:?}", registry.get("binance", "BTCUSDT", true));
+        println!("{:?}", registry.get("crypto", "BTCUSD-PERP", false));
+
+        // Once loaded, unknown instruments fail before connecting
+        let typo = std::panic::catch_unwind(|| DepthManager::new("binance", "BTC_USTD"));
+        println!("BTC_USTD rejected: {}", typo.is_err());
+    });
+}
@@ -0,0 +1,355 @@
+use crate::binance::BinanceAdapter;
+use crate::bitfinex::BitfinexAdapter;
+use crate::bybit::BybitAdapter;
+use crate::coinbase::CoinbaseAdapter;
+use crate::config::{DepthType, Instrument, SymbolType};
+use crate::crypto::CryptoAdapter;
+use crate::deribit::DeribitAdapter;
+use crate::gateio::GateioAdapter;
+use crate::kraken::KrakenAdapter;
+use crate::kucoin::KucoinAdapter;
+use crate::okx::OkxAdapter;
+use crate::{
+    DepthConfig, DepthT, ExchangeType, InstrumentInfo, InstrumentRegistry, ReconnectPolicy,
+    TickerConfig, TickerT,
+};
+use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Everything `DepthManager` and `TickerManager` need from one venue.
+///
+/// Implement it outside the crate and register it with
+/// `AdapterRegistry::global().register(..)` to add a new exchange,
+/// the managers then find it by `name()`.
+pub trait ExchangeAdapter: Send + Sync {
+    /// Registry key, e.g. "binance"
+    fn name(&self) -> &'static str;
+
+    fn exchange_type(&self) -> ExchangeType {
+        ExchangeType::Other(self.name())
+    }
+
+    /// Inputs: canonical `Instrument` strings, e.g. BTC_USDT / BTC_USDT_SWAP,
+    /// output: symbol in exchange notation
+    fn validate_symbol(&self, symbol: &str, limit: Option<i32>) -> Result<SymbolType>;
+
+    /// Level Mode when limit is none, Depth Mode otherwise
+    fn depth_url(&self, symbol_type: &SymbolType, limit: Option<i32>) -> Result<DepthType>;
+
+    fn ticker_url(&self, symbol_type: &SymbolType) -> Result<String>;
+
+    fn depth_connection(&self, config: &DepthConfig) -> Result<Arc<dyn DepthT + Send + Sync>>;
+
+    fn ticker_connection(&self, config: &TickerConfig) -> Result<Arc<dyn TickerT + Send + Sync>>;
+
+    /// `instrument` in exchange notation, what `validate_symbol` gives for
+    /// its canonical string
+    fn native_symbol(&self, instrument: &Instrument) -> Result<SymbolType> {
+        Err(anyhow!(
+            "Unsupported Symbol {} for {}",
+            instrument,
+            self.name()
+        ))
+    }
+
+    /// Inverse of `native_symbol`
+    fn instrument_of(&self, symbol_type: &SymbolType) -> Result<Instrument> {
+        Err(anyhow!(
+            "Unsupported symbol {:?} for {}",
+            symbol_type,
+            self.name()
+        ))
+    }
+
+    /// Name of `symbol_type` in `instruments()`,
+    /// `None` skips the check against `InstrumentRegistry::global()`
+    fn instrument_name(&self, _symbol_type: &SymbolType) -> Option<String> {
+        None
+    }
+
+    /// Every instrument from the exchange info endpoints
+    fn instruments(&self) -> BoxFuture<'_, Result<Vec<InstrumentInfo>>> {
+        Box::pin(async move { Err(anyhow!("Instruments are unsupported for {}", self.name())) })
+    }
+
+    /// Err if instruments of the exchange are in `registry`
+    /// and `symbol_type` is not a trading one among them
+    fn check_instrument(
+        &self,
+        registry: &InstrumentRegistry,
+        symbol_type: &SymbolType,
+    ) -> Result<()> {
+        match self.instrument_name(symbol_type) {
+            Some(name) => {
+                let spot = matches!(symbol_type, SymbolType::Spot(_));
+                registry.validate(self.name(), &name, spot)
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn depth_config(&self, symbol: &str, limit: Option<i32>) -> Result<DepthConfig> {
+        let symbol_type = self.validate_symbol(symbol, limit)?;
+        self.check_instrument(InstrumentRegistry::global(), &symbol_type)?;
+        let depth_url = self.depth_url(&symbol_type, limit)?;
+
+        Ok(DepthConfig {
+            depth_url,
+            symbol_type,
+            exchange_type: self.exchange_type(),
+            limit,
+            checksum: None,
+            audit_interval: None,
+            reconnect: ReconnectPolicy::default(),
+        })
+    }
+
+    fn ticker_config(&self, symbol: &str) -> Result<TickerConfig> {
+        let symbol_type = self.validate_symbol(symbol, None)?;
+        self.check_instrument(InstrumentRegistry::global(), &symbol_type)?;
+        let ticker_url = self.ticker_url(&symbol_type)?;
+
+        Ok(TickerConfig {
+            ticker_url,
+            symbol_type,
+            exchange_type: self.exchange_type(),
+            reconnect: ReconnectPolicy::default(),
+        })
+    }
+}
+
+/// Adapters by name
+#[derive(Default)]
+pub struct AdapterRegistry {
+    adapters: RwLock<HashMap<&'static str, Arc<dyn ExchangeAdapter>>>,
+}
+
+impl AdapterRegistry {
+    /// Registry used by the managers, every exchange of `ExchangeType` is built in
+    pub fn global() -> &'static AdapterRegistry {
+        static REGISTRY: OnceLock<AdapterRegistry> = OnceLock::new();
+
+        REGISTRY.get_or_init(|| {
+            let registry = AdapterRegistry::default();
+            registry.register(BinanceAdapter);
+            registry.register(CryptoAdapter);
+            registry.register(OkxAdapter);
+            registry.register(BybitAdapter);
+            registry.register(CoinbaseAdapter);
+            registry.register(KrakenAdapter);
+            registry.register(DeribitAdapter);
+            registry.register(BitfinexAdapter::new());
+            registry.register(KucoinAdapter);
+            registry.register(GateioAdapter);
+            registry
+        })
+    }
+
+    /// Replaces the adapter registered under the same name
+    pub fn register(&self, adapter: impl ExchangeAdapter + 'static) {
+        let mut adapters = self.adapters.write().unwrap();
+        adapters.insert(adapter.name(), Arc::new(adapter));
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn ExchangeAdapter>> {
+        self.adapters.read().unwrap().get(name).cloned()
+    }
+
+    /// Sorted names of all adapters
+    pub fn names(&self) -> Vec<&'static str> {
+        let mut names: Vec<_> = self.adapters.read().unwrap().keys().copied().collect();
+        names.sort();
+        names
+    }
+}
+
+/// Panic on unregistered exchange, like the managers always did
+pub(crate) fn get_adapter(exchange: &str) -> Arc<dyn ExchangeAdapter> {
+    AdapterRegistry::global()
+        .get(exchange)
+        .unwrap_or_else(|| panic!("Unsupported Exchange {}", exchange))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::adapter::{AdapterRegistry, ExchangeAdapter};
+    use crate::config::{DepthType, SymbolType};
+    use crate::{
+        ConnectionState, Depth, DepthConfig, DepthManager, DepthT, InstrumentInfo, InstrumentKind,
+        InstrumentRegistry, InstrumentStatus, TickerConfig, TickerT,
+    };
+    use anyhow::{anyhow, Result};
+    use std::sync::Arc;
+    use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+    struct MockDepth;
+
+    impl DepthT for MockDepth {
+        fn new() -> Self {
+            MockDepth
+        }
+
+        fn depth_snapshot(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
+            self.depth(config)
+        }
+
+        fn depth(&self, _config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
+            let (sender, receiver) = mpsc::unbounded_channel();
+            sender.send(self.snapshot().unwrap())?;
+            Ok(receiver)
+        }
+
+        fn snapshot(&self) -> Option<Depth> {
+            Some(Depth {
+                ts: 1,
+                lts: 2,
+                id: 3,
+                asks: vec![],
+                bids: vec![],
+            })
+        }
+
+        fn subscribe_state(&self) -> UnboundedReceiver<ConnectionState> {
+            mpsc::unbounded_channel().1
+        }
+    }
+
+    struct MockAdapter;
+
+    impl ExchangeAdapter for MockAdapter {
+        fn name(&self) -> &'static str {
+            "mock"
+        }
+
+        fn validate_symbol(&self, symbol: &str, _limit: Option<i32>) -> Result<SymbolType> {
+            Ok(SymbolType::Spot(symbol.replace('_', "-")))
+        }
+
+        fn depth_url(&self, symbol_type: &SymbolType, _limit: Option<i32>) -> Result<DepthType> {
+            Ok(DepthType::Depth(format!(
+                "ws://localhost/{:?}",
+                symbol_type
+            )))
+        }
+
+        fn ticker_url(&self, _symbol_type: &SymbolType) -> Result<String> {
+            Err(anyhow!("No trades for mock"))
+        }
+
+        fn depth_connection(&self, _config: &DepthConfig) -> Result<Arc<dyn DepthT + Send + Sync>> {
+            Ok(Arc::new(MockDepth::new()))
+        }
+
+        fn ticker_connection(
+            &self,
+            _config: &TickerConfig,
+        ) -> Result<Arc<dyn TickerT + Send + Sync>> {
+            Err(anyhow!("No trades for mock"))
+        }
+
+        fn instrument_name(&self, symbol_type: &SymbolType) -> Option<String> {
+            match symbol_type {
+                SymbolType::Spot(name) => Some(name.clone()),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn builtin_adapters() {
+        let registry = AdapterRegistry::global();
+        assert!(registry.get("binance").is_some());
+        assert!(registry.get("crypto").is_some());
+        assert!(registry.get("binanc").is_none());
+
+        let config = registry
+            .get("binance")
+            .unwrap()
+            .depth_config("BTC_USDT_SWAP", Some(1000))
+            .unwrap();
+        assert!(config.is_binance());
+        assert!(config.is_depth_snapshot());
+        assert_eq!(config.get_symbol(), "btcusdt");
+    }
+
+    #[test]
+    fn builtin_instrument_names() {
+        let binance = AdapterRegistry::global().get("binance").unwrap();
+        let symbol_type = binance
+            .validate_symbol("BTC_USD_221230_SWAP", None)
+            .unwrap();
+        assert_eq!(
+            binance.instrument_name(&symbol_type).as_deref(),
+            Some("BTCUSD_221230")
+        );
+
+        let crypto = AdapterRegistry::global().get("crypto").unwrap();
+        let symbol_type = crypto.validate_symbol("BTC_USDT", Some(10)).unwrap();
+        assert_eq!(
+            crypto.instrument_name(&symbol_type).as_deref(),
+            Some("BTC_USDT")
+        );
+        let symbol_type = crypto.validate_symbol("BTC_USDT_SWAP", None).unwrap();
+        assert_eq!(
+            crypto.instrument_name(&symbol_type).as_deref(),
+            Some("BTCUSD-PERP")
+        );
+    }
+
+    #[test]
+    fn registered_adapter_drives_manager() {
+        AdapterRegistry::global().register(MockAdapter);
+        assert!(AdapterRegistry::global().names().contains(&"mock"));
+
+        let manager = DepthManager::new("mock", "BTC_USDT");
+        assert_eq!(manager.config.exchange_type.name(), "mock");
+        assert_eq!(manager.config.get_symbol(), "BTC-USDT");
+
+        let depth = manager.subscribe_depth().try_recv().unwrap();
+        assert_eq!(depth.id, 3);
+        assert_eq!(manager.latest_depth().unwrap().ts, 1);
+    }
+
+    #[test]
+    fn unknown_instrument_rejected() {
+        let instrument = InstrumentInfo {
+            name: String::from("BTC-USDT"),
+            kind: InstrumentKind::Spot,
+            tick_size: 0.1,
+            lot_size: 0.001,
+            min_notional: None,
+            contract_size: None,
+            expiry: None,
+            status: InstrumentStatus::Trading,
+        };
+        let registry = InstrumentRegistry::default();
+        let check = |symbol: &str| {
+            let symbol_type = MockAdapter.validate_symbol(symbol, None).unwrap();
+            MockAdapter.check_instrument(&registry, &symbol_type)
+        };
+        assert!(check("BTC_USTD").is_ok());
+
+        registry.insert("mock", vec![instrument]);
+        assert!(check("BTC_USDT").is_ok());
+        assert!(check("BTC_USTD").is_err());
+
+        // The global registry only holds the bundled exchanges
+        assert!(MockAdapter.depth_config("BTC_USTD", None).is_ok());
+    }
+
+    #[test]
+    fn bundled_cache_lets_missing_symbols_through() {
+        for (exchange, symbol) in [("binance", "AVAX_USDT"), ("crypto", "AVAX_USDT")] {
+            let adapter = AdapterRegistry::global().get(exchange).unwrap();
+            let config = adapter.depth_config(symbol, Some(10)).unwrap();
+            assert_eq!(config.exchange_type.name(), exchange);
+            assert!(adapter.ticker_config(symbol).is_ok());
+        }
+
+        // Delivered at the end of 2022
+        let binance = AdapterRegistry::global().get("binance").unwrap();
+        assert!(binance.depth_config("BTC_USD_221230_SWAP", None).is_err());
+    }
+}
@@ -0,0 +1,205 @@
+use crate::config::interval_millis;
+use crate::{Bar, Ticker, TickerManager};
+use anyhow::Result;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tracing::error;
+
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum BarKind {
+    /// Bars of fixed length in milliseconds, aligned the same way as exchange klines
+    Time(i64),
+    /// Bars of fixed number of trades
+    Tick(u64),
+    /// Bars closed once traded volume reaches the threshold
+    Volume(f64),
+}
+
+/// Build `Bar` from `Ticker`
+pub struct BarAggregator {
+    kind: BarKind,
+    current: Option<Bar>,
+}
+
+impl BarAggregator {
+    pub fn new(kind: BarKind) -> Self {
+        Self {
+            kind,
+            current: None,
+        }
+    }
+
+    /// Time bars comparable with `KlineManager::new(.., interval)`
+    pub fn with_interval(interval: &str) -> Result<Self> {
+        Ok(Self::new(BarKind::Time(interval_millis(interval)?)))
+    }
+
+    /// Add one trade, return the bar it completes if any.
+    ///
+    /// Time bars are only completed by the first trade of a later bar,
+    /// trades older than the open bar are added to the open bar
+    pub fn push(&mut self, ticker: &Ticker) -> Option<Bar> {
+        let mut finished = None;
+
+        if let (BarKind::Time(length), Some(current)) = (self.kind, self.current) {
+            if ticker.ts - ticker.ts.rem_euclid(length) > current.open_ts {
+                finished = self.flush();
+            }
+        }
+
+        let bar = match self.current.as_mut() {
+            Some(bar) => {
+                bar.high = bar.high.max(ticker.price);
+                bar.low = bar.low.min(ticker.price);
+                bar.close = ticker.price;
+                bar.volume += ticker.amount;
+                bar.trades = bar.trades.map(|trades| trades + 1);
+                bar.lts = ticker.lts;
+                if !matches!(self.kind, BarKind::Time(_)) {
+                    bar.close_ts = ticker.ts;
+                }
+                bar
+            }
+            None => self.current.insert(self.open(ticker)),
+        };
+
+        let complete = match self.kind {
+            BarKind::Time(_) => false,
+            BarKind::Tick(count) => bar.trades >= Some(count),
+            BarKind::Volume(volume) => bar.volume >= volume,
+        };
+
+        if complete {
+            finished = self.flush();
+        }
+
+        finished
+    }
+
+    /// The bar still being built
+    pub fn current(&self) -> Option<Bar> {
+        self.current
+    }
+
+    /// Close the bar still being built and return it
+    pub fn flush(&mut self) -> Option<Bar> {
+        self.current.take().map(|bar| Bar {
+            closed: true,
+            ..bar
+        })
+    }
+
+    /// Aggregate every ticker of `manager` into bars
+    pub fn subscribe(mut self, manager: &TickerManager) -> UnboundedReceiver<Bar> {
+        let mut ticker_receiver = manager.subscribe();
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            while let Some(mut tickers) = ticker_receiver.recv().await {
+                tickers.sort_by_key(|ticker| (ticker.ts, ticker.id));
+
+                for ticker in tickers {
+                    if let Some(bar) = self.push(&ticker) {
+                        if sender.send(bar).is_err() {
+                            error!("BarAggregator send Bar error");
+                        }
+                    }
+                }
+            }
+        });
+
+        receiver
+    }
+
+    fn open(&self, ticker: &Ticker) -> Bar {
+        let (open_ts, close_ts) = match self.kind {
+            BarKind::Time(length) => {
+                let open_ts = ticker.ts - ticker.ts.rem_euclid(length);
+                (open_ts, open_ts + length - 1)
+            }
+            _ => (ticker.ts, ticker.ts),
+        };
+
+        Bar {
+            open_ts,
+            close_ts,
+            lts: ticker.lts,
+            open: ticker.price,
+            high: ticker.price,
+            low: ticker.price,
+            close: ticker.price,
+            volume: ticker.amount,
+            trades: Some(1),
+            closed: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BarAggregator, BarKind};
+    use crate::{OrderDirection, Ticker};
+
+    fn ticker(ts: i64, price: f64, amount: f64) -> Ticker {
+        Ticker {
+            lts: ts + 5,
+            ts,
+            price,
+            amount,
+            direction: OrderDirection::Buy,
+            id: ts as u64,
+        }
+    }
+
+    #[test]
+    fn time_bars() {
+        let mut aggregator = BarAggregator::with_interval("1m").unwrap();
+
+        assert!(aggregator.push(&ticker(60_000, 10.0, 1.0)).is_none());
+        assert!(aggregator.push(&ticker(61_000, 12.0, 2.0)).is_none());
+        assert!(aggregator.push(&ticker(119_999, 9.0, 0.5)).is_none());
+
+        let bar = aggregator.push(&ticker(120_000, 11.0, 1.0)).unwrap();
+        assert_eq!(bar.open_ts, 60_000);
+        assert_eq!(bar.close_ts, 119_999);
+        assert_eq!(bar.open, 10.0);
+        assert_eq!(bar.high, 12.0);
+        assert_eq!(bar.low, 9.0);
+        assert_eq!(bar.close, 9.0);
+        assert_eq!(bar.volume, 3.5);
+        assert_eq!(bar.trades, Some(3));
+        assert!(bar.closed);
+
+        let current = aggregator.current().unwrap();
+        assert_eq!(current.open_ts, 120_000);
+        assert!(!current.closed);
+    }
+
+    #[test]
+    fn tick_bars() {
+        let mut aggregator = BarAggregator::new(BarKind::Tick(2));
+
+        assert!(aggregator.push(&ticker(1, 10.0, 1.0)).is_none());
+        let bar = aggregator.push(&ticker(2, 11.0, 1.0)).unwrap();
+        assert_eq!((bar.open_ts, bar.close_ts), (1, 2));
+        assert_eq!(bar.trades, Some(2));
+        assert!(aggregator.current().is_none());
+    }
+
+    #[test]
+    fn volume_bars() {
+        let mut aggregator = BarAggregator::new(BarKind::Volume(3.0));
+
+        assert!(aggregator.push(&ticker(1, 10.0, 1.0)).is_none());
+        assert!(aggregator.push(&ticker(2, 11.0, 1.5)).is_none());
+        let bar = aggregator.push(&ticker(3, 8.0, 1.0)).unwrap();
+        assert_eq!(bar.volume, 3.5);
+        assert_eq!(bar.low, 8.0);
+        assert_eq!(bar.close_ts, 3);
+
+        assert!(aggregator.push(&ticker(4, 8.0, 1.0)).is_none());
+        let bar = aggregator.flush().unwrap();
+        assert_eq!(bar.open_ts, 4);
+        assert!(bar.closed);
+    }
+}
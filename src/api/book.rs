@@ -0,0 +1,318 @@
+use crate::Quote;
+use ordered_float::OrderedFloat;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Side of an `OrderBook`
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BookSide {
+    Bid,
+    Ask,
+}
+
+/// One price level kept by an `OrderBook`,
+/// exchanges that need more than a `Quote` (e.g. the strings a checksum
+/// is built from) keep their own level type
+pub trait BookLevel: Clone {
+    fn price(&self) -> f64;
+
+    /// Amount `0` removes the level
+    fn amount(&self) -> f64;
+
+    fn quote(&self) -> Quote;
+}
+
+impl BookLevel for Quote {
+    fn price(&self) -> f64 {
+        self.price
+    }
+
+    fn amount(&self) -> f64 {
+        self.amount
+    }
+
+    fn quote(&self) -> Quote {
+        *self
+    }
+}
+
+/// Levels to apply to one book to get another, best first,
+/// removed levels have amount `0`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BookDiff {
+    pub bids: Vec<Quote>,
+    pub asks: Vec<Quote>,
+}
+
+impl BookDiff {
+    pub fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+}
+
+type Levels<Level> = BTreeMap<OrderedFloat<f64>, Level>;
+
+/// Both sides of an order book, sorted by price.
+///
+/// Only the levels are kept, ids and times are up to the exchange
+/// format feeding the book
+#[derive(Clone, Debug)]
+pub struct OrderBook<Level = Quote> {
+    bids: Levels<Level>,
+    asks: Levels<Level>,
+}
+
+impl<Level> Default for OrderBook<Level> {
+    fn default() -> Self {
+        OrderBook {
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+        }
+    }
+}
+
+impl<Level: BookLevel> OrderBook<Level> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn side(&self, side: BookSide) -> &Levels<Level> {
+        match side {
+            BookSide::Bid => &self.bids,
+            BookSide::Ask => &self.asks,
+        }
+    }
+
+    fn side_mut(&mut self, side: BookSide) -> &mut Levels<Level> {
+        match side {
+            BookSide::Bid => &mut self.bids,
+            BookSide::Ask => &mut self.asks,
+        }
+    }
+
+    /// Replace the level at its price, amount `0` removes it
+    pub fn apply_level(&mut self, side: BookSide, level: Level) {
+        let price = OrderedFloat(level.price());
+        let levels = self.side_mut(side);
+
+        if level.amount() == 0.0 {
+            levels.remove(&price);
+        } else {
+            levels.insert(price, level);
+        }
+    }
+
+    pub fn apply_levels(&mut self, side: BookSide, levels: impl IntoIterator<Item = Level>) {
+        for level in levels {
+            self.apply_level(side, level);
+        }
+    }
+
+    /// Replace the whole book
+    pub fn apply_snapshot(
+        &mut self,
+        bids: impl IntoIterator<Item = Level>,
+        asks: impl IntoIterator<Item = Level>,
+    ) {
+        self.clear();
+        self.apply_levels(BookSide::Bid, bids);
+        self.apply_levels(BookSide::Ask, asks);
+    }
+
+    pub fn clear(&mut self) {
+        self.bids.clear();
+        self.asks.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+
+    /// Number of levels of `side`
+    pub fn len(&self, side: BookSide) -> usize {
+        self.side(side).len()
+    }
+
+    pub fn get(&self, side: BookSide, price: f64) -> Option<&Level> {
+        self.side(side).get(&OrderedFloat(price))
+    }
+
+    /// Drop the worst levels beyond `levels` of each side
+    pub fn truncate(&mut self, levels: usize) {
+        while self.asks.len() > levels {
+            self.asks.pop_last();
+        }
+        while self.bids.len() > levels {
+            self.bids.pop_first();
+        }
+    }
+
+    /// Bids from the best (highest) price
+    pub fn bids(&self) -> impl Iterator<Item = &Level> {
+        self.bids.values().rev()
+    }
+
+    /// Asks from the best (lowest) price
+    pub fn asks(&self) -> impl Iterator<Item = &Level> {
+        self.asks.values()
+    }
+
+    pub fn best(&self, side: BookSide) -> Option<&Level> {
+        match side {
+            BookSide::Bid => self.bids().next(),
+            BookSide::Ask => self.asks().next(),
+        }
+    }
+
+    /// The best `levels` of `side`
+    pub fn top(&self, side: BookSide, levels: usize) -> Vec<Quote> {
+        match side {
+            BookSide::Bid => self.bids().take(levels).map(Level::quote).collect(),
+            BookSide::Ask => self.asks().take(levels).map(Level::quote).collect(),
+        }
+    }
+
+    /// Every level of `side`, best first
+    pub fn quotes(&self, side: BookSide) -> Vec<Quote> {
+        self.top(side, usize::MAX)
+    }
+
+    /// Levels to apply to this book to get `other`
+    pub fn diff(&self, other: &OrderBook<Level>) -> BookDiff {
+        let mut bids = diff_levels(&self.bids, &other.bids);
+        bids.reverse();
+
+        BookDiff {
+            bids,
+            asks: diff_levels(&self.asks, &other.asks),
+        }
+    }
+}
+
+impl OrderBook<Quote> {
+    pub fn apply_diff(&mut self, diff: &BookDiff) {
+        self.apply_levels(BookSide::Bid, diff.bids.iter().copied());
+        self.apply_levels(BookSide::Ask, diff.asks.iter().copied());
+    }
+}
+
+/// Changed levels from `from` to `to`, lowest price first
+fn diff_levels<Level: BookLevel>(from: &Levels<Level>, to: &Levels<Level>) -> Vec<Quote> {
+    let removed = from
+        .keys()
+        .filter(|price| !to.contains_key(price))
+        .map(|price| Quote {
+            price: price.into_inner(),
+            amount: 0.0,
+            orders: None,
+        });
+
+    let changed = to
+        .iter()
+        .filter(|(price, level)| from.get(price).map(Level::quote) != Some(level.quote()))
+        .map(|(_, level)| level.quote());
+
+    let mut changes: Vec<Quote> = removed.chain(changed).collect();
+    changes.sort_by(|a, b| a.price.total_cmp(&b.price));
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::book::{BookSide, OrderBook};
+    use crate::Quote;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    fn quote(price: f64, amount: f64) -> Quote {
+        Quote {
+            price,
+            amount,
+            orders: None,
+        }
+    }
+
+    #[test]
+    fn levels_and_snapshot() {
+        let mut book = OrderBook::new();
+        book.apply_snapshot(
+            [quote(99.0, 1.0), quote(98.0, 2.0), quote(97.0, 3.0)],
+            [quote(101.0, 1.0), quote(102.0, 2.0)],
+        );
+        assert_eq!(book.best(BookSide::Bid), Some(&quote(99.0, 1.0)));
+        assert_eq!(book.best(BookSide::Ask), Some(&quote(101.0, 1.0)));
+
+        book.apply_level(BookSide::Bid, quote(99.0, 0.0));
+        book.apply_level(BookSide::Ask, quote(100.5, 4.0));
+        book.apply_level(BookSide::Ask, quote(102.0, 5.0));
+
+        assert_eq!(book.top(BookSide::Bid, 1), vec![quote(98.0, 2.0)]);
+        assert_eq!(
+            book.quotes(BookSide::Ask),
+            vec![quote(100.5, 4.0), quote(101.0, 1.0), quote(102.0, 5.0)]
+        );
+        assert_eq!(book.get(BookSide::Bid, 97.0), Some(&quote(97.0, 3.0)));
+        assert!(book.get(BookSide::Bid, 99.0).is_none());
+
+        book.truncate(1);
+        assert_eq!(book.len(BookSide::Bid), 1);
+        assert_eq!(book.quotes(BookSide::Ask), vec![quote(100.5, 4.0)]);
+
+        book.apply_snapshot([], [quote(101.0, 1.0)]);
+        assert_eq!(book.len(BookSide::Bid), 0);
+        assert_eq!(book.len(BookSide::Ask), 1);
+        assert!(!book.is_empty());
+    }
+
+    #[test]
+    fn diff_of_books() {
+        let mut from = OrderBook::new();
+        from.apply_snapshot([quote(99.0, 1.0), quote(98.0, 2.0)], [quote(101.0, 1.0)]);
+
+        let mut to = from.clone();
+        assert!(from.diff(&to).is_empty());
+
+        to.apply_level(BookSide::Bid, quote(98.0, 0.0));
+        to.apply_level(BookSide::Bid, quote(99.5, 3.0));
+        to.apply_level(BookSide::Ask, quote(101.0, 2.0));
+
+        let diff = from.diff(&to);
+        assert_eq!(diff.bids, vec![quote(99.5, 3.0), quote(98.0, 0.0)]);
+        assert_eq!(diff.asks, vec![quote(101.0, 2.0)]);
+    }
+
+    fn levels() -> impl Strategy<Value = Vec<Quote>> {
+        vec((90u8..110, 0u8..4), 0..20).prop_map(|levels| {
+            levels
+                .into_iter()
+                .map(|(price, amount)| quote(price as f64, amount as f64))
+                .collect()
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn diff_applies_to_other(
+            from in (levels(), levels()),
+            to in (levels(), levels()),
+        ) {
+            let mut book = OrderBook::new();
+            book.apply_levels(BookSide::Bid, from.0);
+            book.apply_levels(BookSide::Ask, from.1);
+
+            let mut other = OrderBook::new();
+            other.apply_levels(BookSide::Bid, to.0);
+            other.apply_levels(BookSide::Ask, to.1);
+
+            let diff = book.diff(&other);
+            book.apply_diff(&diff);
+            prop_assert_eq!(book.quotes(BookSide::Bid), other.quotes(BookSide::Bid));
+            prop_assert_eq!(book.quotes(BookSide::Ask), other.quotes(BookSide::Ask));
+            prop_assert!(book.diff(&other).is_empty());
+
+            let bids = other.quotes(BookSide::Bid);
+            prop_assert!(bids.windows(2).all(|pair| pair[0].price > pair[1].price));
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{Depth, Quote};
+use anyhow::{anyhow, Result};
+use std::fmt::{Debug, Display};
+use std::sync::Arc;
+
+/// Check a maintained book against the checksum the exchange sent with it
+pub trait ChecksumVerifier: Debug + Send + Sync {
+    fn verify(&self, depth: &Depth, checksum: i64) -> bool;
+
+    /// `verify` of a book kept as the text the exchange sent,
+    /// `(price, amount)` of each level from the best one
+    fn verify_text(
+        &self,
+        depth: &Depth,
+        _bids: &[(&str, &str)],
+        _asks: &[(&str, &str)],
+        checksum: i64,
+    ) -> bool {
+        self.verify(depth, checksum)
+    }
+}
+
+/// OKX-style CRC32 over the top `levels` levels,
+/// "bid1_price:bid1_amount:ask1_price:ask1_amount:bid2_price:..."
+/// compared as signed 32 bits.
+///
+/// Prices and amounts are formatted with the shortest `f64` representation
+/// by `verify`, and kept as sent by `verify_text`
+#[derive(Clone, Debug)]
+pub struct Crc32Checksum {
+    pub levels: usize,
+}
+
+impl Crc32Checksum {
+    pub fn new(levels: usize) -> Self {
+        Self { levels }
+    }
+
+    pub fn checksum(&self, depth: &Depth) -> i32 {
+        let side = |quotes: &[Quote]| {
+            quotes
+                .iter()
+                .map(|quote| (quote.price, quote.amount))
+                .collect::<Vec<_>>()
+        };
+
+        self.crc32(&side(&depth.bids), &side(&depth.asks))
+    }
+
+    fn crc32<T: Display>(&self, bids: &[(T, T)], asks: &[(T, T)]) -> i32 {
+        let mut fields = Vec::new();
+        for level in 0..self.levels.min(bids.len().max(asks.len())) {
+            for (price, amount) in [bids.get(level), asks.get(level)].into_iter().flatten() {
+                fields.push(format!("{}:{}", price, amount));
+            }
+        }
+
+        crc32fast::hash(fields.join(":").as_bytes()) as i32
+    }
+}
+
+impl ChecksumVerifier for Crc32Checksum {
+    fn verify(&self, depth: &Depth, checksum: i64) -> bool {
+        self.checksum(depth) == checksum as i32
+    }
+
+    /// Signed or unsigned `checksum`, only its 32 bits count
+    fn verify_text(
+        &self,
+        _depth: &Depth,
+        bids: &[(&str, &str)],
+        asks: &[(&str, &str)],
+        checksum: i64,
+    ) -> bool {
+        self.crc32(bids, asks) == checksum as i32
+    }
+}
+
+/// `depth` if it passes the configured verifier against the `checksum`
+/// the exchange sent with it
+pub(crate) fn verify_checksum(
+    depth: Depth,
+    checksum: Option<i64>,
+    verifier: &Option<Arc<dyn ChecksumVerifier>>,
+) -> Result<Depth> {
+    match (verifier, checksum) {
+        (Some(verifier), Some(expected)) if !verifier.verify(&depth, expected) => Err(anyhow!(
+            "Checksum mismatch, order book {}, checksum {}",
+            depth.id,
+            expected
+        )),
+        _ => Ok(depth),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChecksumVerifier, Crc32Checksum};
+    use crate::{Depth, Quote};
+
+    fn quote(price: f64, amount: f64) -> Quote {
+        Quote {
+            price,
+            amount,
+            orders: None,
+        }
+    }
+
+    #[test]
+    fn crc32_checksum() {
+        let depth = Depth {
+            ts: 0,
+            lts: 0,
+            id: 0,
+            asks: vec![quote(3366.8, 9.0), quote(3368.0, 8.0), quote(3372.0, 2.0)],
+            bids: vec![quote(3366.1, 7.0), quote(3366.0, 6.0)],
+        };
+
+        // "3366.1:7:3366.8:9:3366:6:3368:8:3372:2"
+        let expected = crc32fast::hash(b"3366.1:7:3366.8:9:3366:6:3368:8:3372:2") as i32;
+        let verifier = Crc32Checksum::new(25);
+
+        assert_eq!(verifier.checksum(&depth), expected);
+        assert!(verifier.verify(&depth, expected as i64));
+        assert!(!verifier.verify(&depth, expected as i64 + 1));
+
+        // Only the top level
+        let expected = crc32fast::hash(b"3366.1:7:3366.8:9") as i32;
+        assert_eq!(Crc32Checksum::new(1).checksum(&depth), expected);
+
+        // Trailing zeros as sent, signed or unsigned
+        let bids = [("3366.10", "7.0")];
+        let asks = [("3366.80", "9.0"), ("3368.00", "8.0")];
+        let expected = crc32fast::hash(b"3366.10:7.0:3366.80:9.0:3368.00:8.0");
+        assert!(verifier.verify_text(&depth, &bids, &asks, expected as i64));
+        assert!(verifier.verify_text(&depth, &bids, &asks, expected as i32 as i64));
+        assert!(!verifier.verify(&depth, expected as i64));
+    }
+}
@@ -0,0 +1,861 @@
+use crate::api::sink;
+use crate::api::wire::Versioned;
+use crate::{Depth, OrderDirection, Quote, Ticker, SCHEMA_VERSION};
+use anyhow::{anyhow, Result};
+use arrow_array::builder::{
+    ArrayBuilder, Float64Builder, Int64Builder, StringBuilder, TimestampMillisecondBuilder,
+    UInt32Builder, UInt64Builder,
+};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use parquet::format::KeyValue;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Rows kept in builders before they are handed to the parquet writer
+const BATCH_ROWS: usize = 4096;
+
+/// Columns of a `Depth` table
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DepthLayout {
+    /// One row per book with `ask_price_<i>`, `ask_amount_<i>`, `bid_price_<i>`
+    /// and `bid_amount_<i>` of the best `n` levels, level 0 is the best,
+    /// missing levels are null
+    Wide(usize),
+    /// One row per level: ts, lts, id, side, level, price, amount
+    Long,
+}
+
+impl DepthLayout {
+    fn name(&self) -> String {
+        match self {
+            DepthLayout::Wide(levels) => format!("wide:{}", levels),
+            DepthLayout::Long => "long".to_string(),
+        }
+    }
+}
+
+/// Where and how Parquet files are written
+#[derive(Clone, Debug)]
+pub struct ParquetOptions {
+    pub dir: PathBuf,
+    pub layout: DepthLayout,
+    /// Start a new file once a row is received this long after the first row of the file
+    pub max_age: Duration,
+    /// Start a new file once about this many bytes are written
+    pub max_bytes: u64,
+}
+
+impl ParquetOptions {
+    /// Top 10 levels, files of at most an hour and 256 MiB
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        ParquetOptions {
+            dir: dir.into(),
+            layout: DepthLayout::Wide(10),
+            max_age: Duration::from_secs(3600),
+            max_bytes: 256 * 1024 * 1024,
+        }
+    }
+}
+
+/// Builders of the rows not written yet
+trait Columns {
+    fn fields(&self) -> Vec<Field>;
+
+    fn rows(&self) -> usize;
+
+    fn finish(&mut self) -> Vec<ArrayRef>;
+}
+
+fn time_field(name: &str) -> Field {
+    let utc = DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()));
+    Field::new(name, utc, false)
+}
+
+fn time_builder() -> TimestampMillisecondBuilder {
+    TimestampMillisecondBuilder::new().with_timezone("UTC")
+}
+
+/// Parquet files of one table started again once they are too big or too old,
+/// named "<exchange>_<symbol>_<table>.<first lts>.<sequence>.parquet"
+struct RollingFile<C: Columns> {
+    options: ParquetOptions,
+    prefix: String,
+    schema: SchemaRef,
+    columns: C,
+    /// Open file and the receive time of its first row
+    file: Option<(ArrowWriter<File>, i64)>,
+    sequence: u64,
+    written: Vec<PathBuf>,
+}
+
+impl<C: Columns> RollingFile<C> {
+    fn new(options: ParquetOptions, exchange: &str, symbol: &str, table: &str, columns: C) -> Self {
+        let metadata = HashMap::from([
+            ("exchange".to_string(), exchange.to_string()),
+            ("symbol".to_string(), symbol.to_string()),
+            ("table".to_string(), table.to_string()),
+            ("layout".to_string(), options.layout.name()),
+            ("schema_version".to_string(), SCHEMA_VERSION.to_string()),
+        ]);
+        let schema = Schema::new(columns.fields()).with_metadata(metadata);
+
+        RollingFile {
+            prefix: sink::file_prefix(&format!("{} {} {}", exchange, symbol, table)),
+            options,
+            schema: Arc::new(schema),
+            columns,
+            file: None,
+            sequence: 0,
+            written: vec![],
+        }
+    }
+
+    /// Make sure a file is open for the rows received at `lts`
+    fn start_rows(&mut self, lts: i64) -> Result<()> {
+        if let Some((writer, first)) = &self.file {
+            let bytes = (writer.bytes_written() + writer.in_progress_size()) as u64;
+            let age = lts.saturating_sub(*first);
+            if bytes >= self.options.max_bytes || age >= self.options.max_age.as_millis() as i64 {
+                self.finish_file()?;
+            }
+        }
+
+        if self.file.is_none() {
+            let path = self
+                .options
+                .dir
+                .join(format!("{}.{}.{}.parquet", self.prefix, lts, self.sequence));
+            let file = File::create(&path)
+                .map_err(|e| anyhow!("Fail to create {}: {}", path.display(), e))?;
+            self.file = Some((
+                ArrowWriter::try_new(file, self.schema.clone(), Some(self.properties()))?,
+                lts,
+            ));
+            self.sequence += 1;
+            self.written.push(path);
+        }
+
+        Ok(())
+    }
+
+    /// Parquet key value metadata, the same pairs as the arrow schema
+    fn properties(&self) -> WriterProperties {
+        let metadata = self
+            .schema
+            .metadata()
+            .iter()
+            .map(|(key, value)| KeyValue::new(key.clone(), value.clone()))
+            .collect();
+
+        WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .set_key_value_metadata(Some(metadata))
+            .build()
+    }
+
+    fn end_rows(&mut self) -> Result<()> {
+        if self.columns.rows() >= BATCH_ROWS {
+            self.write_batch()?;
+        }
+        Ok(())
+    }
+
+    fn write_batch(&mut self) -> Result<()> {
+        let Some((writer, _)) = self.file.as_mut() else {
+            return Ok(());
+        };
+        if self.columns.rows() == 0 {
+            return Ok(());
+        }
+
+        let batch = RecordBatch::try_new(self.schema.clone(), self.columns.finish())?;
+        writer.write(&batch)?;
+        Ok(())
+    }
+
+    fn finish_file(&mut self) -> Result<()> {
+        self.write_batch()?;
+        if let Some((writer, _)) = self.file.take() {
+            writer.close()?;
+        }
+        Ok(())
+    }
+
+    fn close(mut self) -> Result<Vec<PathBuf>> {
+        self.finish_file()?;
+        Ok(mem::take(&mut self.written))
+    }
+}
+
+impl<C: Columns> Drop for RollingFile<C> {
+    fn drop(&mut self) {
+        let _ = self.finish_file();
+    }
+}
+
+/// One per writer, the size of the variants does not matter
+#[allow(clippy::large_enum_variant)]
+enum DepthColumns {
+    Wide {
+        ts: TimestampMillisecondBuilder,
+        lts: TimestampMillisecondBuilder,
+        id: Int64Builder,
+        /// Ask price, ask amount, bid price, bid amount of every level
+        levels: Vec<Float64Builder>,
+    },
+    Long {
+        ts: TimestampMillisecondBuilder,
+        lts: TimestampMillisecondBuilder,
+        id: Int64Builder,
+        side: StringBuilder,
+        level: UInt32Builder,
+        price: Float64Builder,
+        amount: Float64Builder,
+    },
+}
+
+impl DepthColumns {
+    fn new(layout: DepthLayout) -> Self {
+        match layout {
+            DepthLayout::Wide(levels) => DepthColumns::Wide {
+                ts: time_builder(),
+                lts: time_builder(),
+                id: Int64Builder::new(),
+                levels: (0..levels * 4).map(|_| Float64Builder::new()).collect(),
+            },
+            DepthLayout::Long => DepthColumns::Long {
+                ts: time_builder(),
+                lts: time_builder(),
+                id: Int64Builder::new(),
+                side: StringBuilder::new(),
+                level: UInt32Builder::new(),
+                price: Float64Builder::new(),
+                amount: Float64Builder::new(),
+            },
+        }
+    }
+
+    fn push(&mut self, depth: &Depth) {
+        match self {
+            DepthColumns::Wide {
+                ts,
+                lts,
+                id,
+                levels,
+            } => {
+                ts.append_value(depth.ts);
+                lts.append_value(depth.lts);
+                id.append_value(depth.id);
+
+                for (i, level) in levels.chunks_mut(4).enumerate() {
+                    let (ask, bid) = (depth.asks.get(i), depth.bids.get(i));
+                    level[0].append_option(ask.map(|ask| ask.price));
+                    level[1].append_option(ask.map(|ask| ask.amount));
+                    level[2].append_option(bid.map(|bid| bid.price));
+                    level[3].append_option(bid.map(|bid| bid.amount));
+                }
+            }
+            DepthColumns::Long {
+                ts,
+                lts,
+                id,
+                side,
+                level,
+                price,
+                amount,
+            } => {
+                for (name, quotes) in [("ask", &depth.asks), ("bid", &depth.bids)] {
+                    for (i, quote) in quotes.iter().enumerate() {
+                        ts.append_value(depth.ts);
+                        lts.append_value(depth.lts);
+                        id.append_value(depth.id);
+                        side.append_value(name);
+                        level.append_value(i as u32);
+                        price.append_value(quote.price);
+                        amount.append_value(quote.amount);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Columns for DepthColumns {
+    fn fields(&self) -> Vec<Field> {
+        let mut fields = vec![
+            time_field("ts"),
+            time_field("lts"),
+            Field::new("id", DataType::Int64, false),
+        ];
+
+        match self {
+            DepthColumns::Wide { levels, .. } => {
+                for i in 0..levels.len() / 4 {
+                    for name in ["ask_price", "ask_amount", "bid_price", "bid_amount"] {
+                        fields.push(Field::new(
+                            format!("{}_{}", name, i),
+                            DataType::Float64,
+                            true,
+                        ));
+                    }
+                }
+            }
+            DepthColumns::Long { .. } => fields.extend([
+                Field::new("side", DataType::Utf8, false),
+                Field::new("level", DataType::UInt32, false),
+                Field::new("price", DataType::Float64, false),
+                Field::new("amount", DataType::Float64, false),
+            ]),
+        }
+
+        fields
+    }
+
+    fn rows(&self) -> usize {
+        match self {
+            DepthColumns::Wide { ts, .. } | DepthColumns::Long { ts, .. } => ts.len(),
+        }
+    }
+
+    fn finish(&mut self) -> Vec<ArrayRef> {
+        match self {
+            DepthColumns::Wide {
+                ts,
+                lts,
+                id,
+                levels,
+            } => {
+                let mut columns: Vec<ArrayRef> = vec![
+                    Arc::new(ts.finish()),
+                    Arc::new(lts.finish()),
+                    Arc::new(id.finish()),
+                ];
+                columns.extend(
+                    levels
+                        .iter_mut()
+                        .map(|level| Arc::new(level.finish()) as ArrayRef),
+                );
+                columns
+            }
+            DepthColumns::Long {
+                ts,
+                lts,
+                id,
+                side,
+                level,
+                price,
+                amount,
+            } => vec![
+                Arc::new(ts.finish()),
+                Arc::new(lts.finish()),
+                Arc::new(id.finish()),
+                Arc::new(side.finish()),
+                Arc::new(level.finish()),
+                Arc::new(price.finish()),
+                Arc::new(amount.finish()),
+            ],
+        }
+    }
+}
+
+/// Parquet files of the books of one exchange symbol,
+/// laid out as `ParquetOptions::layout`
+pub struct DepthParquetWriter {
+    file: RollingFile<DepthColumns>,
+}
+
+impl DepthParquetWriter {
+    pub fn new(options: ParquetOptions, exchange: &str, symbol: &str) -> Self {
+        Self::with_table(options, exchange, symbol, "depth")
+    }
+
+    fn with_table(options: ParquetOptions, exchange: &str, symbol: &str, table: &str) -> Self {
+        let columns = DepthColumns::new(options.layout);
+
+        DepthParquetWriter {
+            file: RollingFile::new(options, exchange, symbol, table, columns),
+        }
+    }
+
+    pub fn write(&mut self, depth: &Depth) -> Result<()> {
+        self.file.start_rows(depth.lts)?;
+        self.file.columns.push(depth);
+        self.file.end_rows()
+    }
+
+    /// Finish the open file, return every file written
+    pub fn close(self) -> Result<Vec<PathBuf>> {
+        self.file.close()
+    }
+}
+
+struct TickerColumns {
+    ts: TimestampMillisecondBuilder,
+    lts: TimestampMillisecondBuilder,
+    id: UInt64Builder,
+    price: Float64Builder,
+    amount: Float64Builder,
+    direction: StringBuilder,
+}
+
+impl Columns for TickerColumns {
+    fn fields(&self) -> Vec<Field> {
+        vec![
+            time_field("ts"),
+            time_field("lts"),
+            Field::new("id", DataType::UInt64, false),
+            Field::new("price", DataType::Float64, false),
+            Field::new("amount", DataType::Float64, false),
+            Field::new("direction", DataType::Utf8, false),
+        ]
+    }
+
+    fn rows(&self) -> usize {
+        self.ts.len()
+    }
+
+    fn finish(&mut self) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(self.ts.finish()),
+            Arc::new(self.lts.finish()),
+            Arc::new(self.id.finish()),
+            Arc::new(self.price.finish()),
+            Arc::new(self.amount.finish()),
+            Arc::new(self.direction.finish()),
+        ]
+    }
+}
+
+/// Parquet files of the trades of one exchange symbol,
+/// one row per `Ticker` with direction "buy" or "sell"
+pub struct TickerParquetWriter {
+    file: RollingFile<TickerColumns>,
+}
+
+impl TickerParquetWriter {
+    pub fn new(options: ParquetOptions, exchange: &str, symbol: &str) -> Self {
+        let columns = TickerColumns {
+            ts: time_builder(),
+            lts: time_builder(),
+            id: UInt64Builder::new(),
+            price: Float64Builder::new(),
+            amount: Float64Builder::new(),
+            direction: StringBuilder::new(),
+        };
+
+        TickerParquetWriter {
+            file: RollingFile::new(options, exchange, symbol, "trades", columns),
+        }
+    }
+
+    pub fn write(&mut self, tickers: &[Ticker]) -> Result<()> {
+        let Some(first) = tickers.first() else {
+            return Ok(());
+        };
+        self.file.start_rows(first.lts)?;
+
+        let columns = &mut self.file.columns;
+        for ticker in tickers {
+            columns.ts.append_value(ticker.ts);
+            columns.lts.append_value(ticker.lts);
+            columns.id.append_value(ticker.id);
+            columns.price.append_value(ticker.price);
+            columns.amount.append_value(ticker.amount);
+            columns.direction.append_value(match ticker.direction {
+                OrderDirection::Buy => "buy",
+                OrderDirection::Sell => "sell",
+            });
+        }
+
+        self.file.end_rows()
+    }
+
+    /// Finish the open file, return every file written
+    pub fn close(self) -> Result<Vec<PathBuf>> {
+        self.file.close()
+    }
+}
+
+/// Record of a `snapshot-record` line inside its schema tag, see `FleetEvent::to_json`
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum RecordLine {
+    #[serde(rename = "depth")]
+    Depth(BookLine),
+    #[serde(rename = "bookTicker")]
+    BookTicker(BookLine),
+    #[serde(rename = "trades")]
+    Trades {
+        exchange: String,
+        symbol: String,
+        trades: Vec<TradeLine>,
+    },
+    #[serde(rename = "state")]
+    State,
+}
+
+#[derive(Deserialize)]
+struct BookLine {
+    exchange: String,
+    symbol: String,
+    ts: i64,
+    lts: i64,
+    id: i64,
+    asks: Vec<Quote>,
+    bids: Vec<Quote>,
+}
+
+#[derive(Deserialize)]
+struct TradeLine {
+    ts: i64,
+    lts: i64,
+    id: u64,
+    price: f64,
+    amount: f64,
+    direction: OrderDirection,
+}
+
+/// What `convert_records` read and wrote
+#[derive(Debug, Default)]
+pub struct Conversion {
+    pub books: u64,
+    pub trades: u64,
+    /// State lines and lines of raw exchange frames
+    pub skipped: u64,
+    pub files: Vec<PathBuf>,
+}
+
+/// Convert JSON Lines files of `snapshot-record` to Parquet, in the given order.
+///
+/// Books, book tickers and trades of each exchange symbol go to their own files,
+/// raw exchange frames (`--raw`) are not decoded and are skipped like states
+pub fn convert_records(inputs: &[PathBuf], options: &ParquetOptions) -> Result<Conversion> {
+    let mut conversion = Conversion::default();
+    let mut books = BTreeMap::new();
+    let mut trades = BTreeMap::new();
+
+    for input in inputs {
+        read_records(input, |line| {
+            match line {
+                Some(RecordLine::Depth(book)) => {
+                    write_book(&mut books, options, "depth", book)?;
+                    conversion.books += 1;
+                }
+                Some(RecordLine::BookTicker(book)) => {
+                    write_book(&mut books, options, "bookTicker", book)?;
+                    conversion.books += 1;
+                }
+                Some(RecordLine::Trades {
+                    exchange,
+                    symbol,
+                    trades: lines,
+                }) => {
+                    let tickers = lines
+                        .into_iter()
+                        .map(|trade| Ticker {
+                            lts: trade.lts,
+                            ts: trade.ts,
+                            price: trade.price,
+                            amount: trade.amount,
+                            direction: trade.direction,
+                            id: trade.id,
+                        })
+                        .collect::<Vec<_>>();
+                    trades
+                        .entry((exchange.clone(), symbol.clone()))
+                        .or_insert_with(|| {
+                            TickerParquetWriter::new(options.clone(), &exchange, &symbol)
+                        })
+                        .write(&tickers)?;
+                    conversion.trades += tickers.len() as u64;
+                }
+                Some(RecordLine::State) | None => conversion.skipped += 1,
+            }
+            Ok(())
+        })?;
+    }
+
+    for writer in books.into_values() {
+        conversion.files.extend(DepthParquetWriter::close(writer)?);
+    }
+    for writer in trades.into_values() {
+        conversion.files.extend(TickerParquetWriter::close(writer)?);
+    }
+    conversion.files.sort();
+
+    Ok(conversion)
+}
+
+fn write_book(
+    writers: &mut BTreeMap<(String, String, &'static str), DepthParquetWriter>,
+    options: &ParquetOptions,
+    table: &'static str,
+    book: BookLine,
+) -> Result<()> {
+    let depth = Depth {
+        ts: book.ts,
+        lts: book.lts,
+        id: book.id,
+        asks: book.asks,
+        bids: book.bids,
+    };
+
+    writers
+        .entry((book.exchange.clone(), book.symbol.clone(), table))
+        .or_insert_with(|| {
+            DepthParquetWriter::with_table(options.clone(), &book.exchange, &book.symbol, table)
+        })
+        .write(&depth)
+}
+
+/// Every line of one file, `None` for raw exchange frames
+fn read_records(
+    path: &Path,
+    mut record: impl FnMut(Option<RecordLine>) -> Result<()>,
+) -> Result<()> {
+    let file = File::open(path).map_err(|e| anyhow!("Fail to open {}: {}", path.display(), e))?;
+
+    for (number, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let value: serde_json::Value = serde_json::from_str(&line)
+            .map_err(|e| anyhow!("{} line {}: {}", path.display(), number + 1, e))?;
+        // Raw exchange frames are not records
+        if value.get("text").is_some() {
+            record(None)?;
+            continue;
+        }
+
+        let Versioned::V1(line) = serde_json::from_value(value)
+            .map_err(|e| anyhow!("{} line {}: {}", path.display(), number + 1, e))?;
+        record(Some(line))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        convert_records, Depth, DepthLayout, DepthParquetWriter, OrderDirection, ParquetOptions,
+        Quote, Ticker, TickerParquetWriter,
+    };
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Float64Type, TimestampMillisecondType, UInt32Type};
+    use arrow_array::RecordBatch;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::collections::HashMap;
+    use std::fs::{self, File};
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("snapshot-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn quote(price: f64, amount: f64) -> Quote {
+        Quote {
+            price,
+            amount,
+            orders: None,
+        }
+    }
+
+    fn depth(lts: i64) -> Depth {
+        Depth {
+            ts: lts - 1,
+            lts,
+            id: lts * 10,
+            asks: vec![quote(101.0, 1.0), quote(102.0, 2.0)],
+            bids: vec![quote(100.0, 3.0)],
+        }
+    }
+
+    /// Rows of a file and the metadata of its schema
+    fn read(path: &Path) -> (RecordBatch, HashMap<String, String>) {
+        let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap()).unwrap();
+        let metadata = builder.schema().metadata().clone();
+
+        let mut batches = builder.build().unwrap().map(Result::unwrap);
+        let batch = batches.next().unwrap();
+        assert!(batches.next().is_none());
+        (batch, metadata)
+    }
+
+    fn floats(batch: &RecordBatch, name: &str) -> Vec<Option<f64>> {
+        let column = batch.column_by_name(name).unwrap();
+        column.as_primitive::<Float64Type>().iter().collect()
+    }
+
+    #[test]
+    fn depth_layouts() {
+        let dir = temp_dir("parquet-layouts");
+
+        let mut options = ParquetOptions::new(&dir);
+        options.layout = DepthLayout::Wide(2);
+        let mut writer = DepthParquetWriter::new(options.clone(), "binance", "BTC_USDT");
+        writer.write(&depth(1000)).unwrap();
+        writer.write(&depth(2000)).unwrap();
+        let files = writer.close().unwrap();
+        assert_eq!(files, [dir.join("binance_BTC_USDT_depth.1000.0.parquet")]);
+
+        let (batch, metadata) = read(&files[0]);
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.num_columns(), 3 + 2 * 4);
+        assert_eq!(floats(&batch, "ask_price_1"), [Some(102.0), Some(102.0)]);
+        assert_eq!(floats(&batch, "bid_amount_0"), [Some(3.0), Some(3.0)]);
+        assert_eq!(floats(&batch, "bid_price_1"), [None, None]);
+        let lts = batch.column_by_name("lts").unwrap();
+        assert_eq!(
+            lts.as_primitive::<TimestampMillisecondType>().value(1),
+            2000
+        );
+        assert_eq!(metadata["exchange"], "binance");
+        assert_eq!(metadata["symbol"], "BTC_USDT");
+        assert_eq!(metadata["layout"], "wide:2");
+
+        options.layout = DepthLayout::Long;
+        let mut writer = DepthParquetWriter::new(options, "okx", "BTC_USDT_SWAP");
+        writer.write(&depth(3000)).unwrap();
+        let files = writer.close().unwrap();
+
+        let (batch, metadata) = read(&files[0]);
+        assert_eq!(batch.num_rows(), 3);
+        let sides = batch.column_by_name("side").unwrap().as_string::<i32>();
+        assert_eq!(
+            sides.iter().collect::<Vec<_>>(),
+            [Some("ask"), Some("ask"), Some("bid")]
+        );
+        let levels = batch
+            .column_by_name("level")
+            .unwrap()
+            .as_primitive::<UInt32Type>();
+        assert_eq!(levels.values().to_vec(), [0, 1, 0]);
+        assert_eq!(
+            floats(&batch, "price"),
+            [Some(101.0), Some(102.0), Some(100.0)]
+        );
+        assert_eq!(metadata["table"], "depth");
+        assert_eq!(metadata["layout"], "long");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn roll_by_time_and_size() {
+        let dir = temp_dir("parquet-roll");
+
+        let mut options = ParquetOptions::new(&dir);
+        options.max_age = Duration::from_secs(1);
+        let mut writer = DepthParquetWriter::new(options.clone(), "binance", "BTC_USDT");
+        for lts in [1000, 1500, 1999, 2000, 2500] {
+            writer.write(&depth(lts)).unwrap();
+        }
+        let files = writer.close().unwrap();
+        let rows = files
+            .iter()
+            .map(|file| read(file).0.num_rows())
+            .collect::<Vec<_>>();
+        assert_eq!(rows, [3, 2]);
+        assert_eq!(files[1], dir.join("binance_BTC_USDT_depth.2000.1.parquet"));
+
+        options.max_bytes = 1;
+        let mut writer = TickerParquetWriter::new(options, "binance", "BTC_USDT");
+        let trade = Ticker {
+            lts: 1000,
+            ts: 999,
+            price: 100.0,
+            amount: 1.0,
+            direction: OrderDirection::Buy,
+            id: 1,
+        };
+        writer.write(&[trade, trade]).unwrap();
+        writer.write(&[trade]).unwrap();
+        writer.write(&[]).unwrap();
+        let files = writer.close().unwrap();
+        let rows = files
+            .iter()
+            .map(|file| read(file).0.num_rows())
+            .collect::<Vec<_>>();
+        assert_eq!(rows, [2, 1]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn convert_recorded_lines() {
+        let dir = temp_dir("parquet-convert");
+        let record = dir.join("binance_BTC_USDT_depth.1000.0.jsonl");
+        fs::write(
+            &record,
+            [
+                r#"{"v1":{"type":"state","exchange":"binance","symbol":"BTC_USDT","lts":999,"state":"connected"}}"#,
+                r#"{"v1":{"type":"depth","exchange":"binance","symbol":"BTC_USDT","ts":999,"lts":1000,"id":1,"asks":[[101.0,1.0,null]],"bids":[[100.0,2.0,3]]}}"#,
+                r#"{"v1":{"type":"bookTicker","exchange":"binance","symbol":"BTC_USDT","ts":999,"lts":1001,"id":2,"asks":[[101.0,1.0,null]],"bids":[[100.0,2.0,null]]}}"#,
+                r#"{"v1":{"type":"trades","exchange":"binance","symbol":"BTC_USDT","trades":[{"ts":999,"lts":1002,"id":7,"price":100.5,"amount":0.25,"direction":"sell"}]}}"#,
+                r#"{"lts":1003,"text":"{\"e\":\"depthUpdate\"}"}"#,
+                "",
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        let out = dir.join("parquet");
+        fs::create_dir_all(&out).unwrap();
+        let conversion =
+            convert_records(std::slice::from_ref(&record), &ParquetOptions::new(&out)).unwrap();
+        assert_eq!(
+            (conversion.books, conversion.trades, conversion.skipped),
+            (2, 1, 2)
+        );
+        let names = conversion
+            .files
+            .iter()
+            .map(|file| file.file_name().unwrap().to_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "binance_BTC_USDT_bookTicker.1001.0.parquet",
+                "binance_BTC_USDT_depth.1000.0.parquet",
+                "binance_BTC_USDT_trades.1002.0.parquet",
+            ]
+        );
+
+        let (batch, metadata) = read(&conversion.files[2]);
+        assert_eq!(floats(&batch, "amount"), [Some(0.25)]);
+        let directions = batch
+            .column_by_name("direction")
+            .unwrap()
+            .as_string::<i32>();
+        assert_eq!(directions.value(0), "sell");
+        assert_eq!(metadata["table"], "trades");
+
+        fs::write(&record, r#"{"v1":{"type":"depth","exchange":"binance"}}"#).unwrap();
+        let error =
+            convert_records(std::slice::from_ref(&record), &ParquetOptions::new(&out)).unwrap_err();
+        assert!(error.to_string().contains("line 1"));
+
+        fs::write(&record, r#"{"v2":{"type":"depth"}}"#).unwrap();
+        let error = convert_records(&[record], &ParquetOptions::new(&out)).unwrap_err();
+        assert!(error.to_string().contains("line 1"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,19 +1,17 @@
 
-use crate::{get_depth_config_from, DepthConfig};
-use serde::Deserialize;
+use crate::api::adapter::get_adapter;
+use crate::{AdapterRegistry, ChecksumVerifier, ConnectionState, DepthConfig};
 use std::fmt;
 use std::fmt::Debug;
-use anyhow::Result;
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::{anyhow, Result};
 use tokio::sync::mpsc::UnboundedReceiver;
-use crate::binance::connection::binance_perpetual_coin::BinanceSpotOrderBookPerpetualCoin;
-use crate::binance::connection::binance_perpetual_usdt::BinanceSpotOrderBookPerpetualUSDT;
-use crate::binance::connection::binance_spot::BinanceOrderBookSpot;
-use crate::crypto::CryptoDepth;
-use crate::config::SymbolType;
 
+#[derive(Clone)]
 pub struct DepthManager {
     pub config: DepthConfig,
-    connection: Box<dyn DepthT>,
+    connection: Arc<dyn DepthT + Send + Sync>,
 }
 
 impl DepthManager {
@@ -27,6 +25,20 @@ impl DepthManager {
         Self::new_from(exchange, symbol, Some(limit))
     }
 
+    /// Verify exchange checksums of the maintained book,
+    /// a mismatch rebuilds the book
+    pub fn with_checksum(mut self, verifier: impl ChecksumVerifier + 'static) -> Self {
+        self.config.checksum = Some(Arc::new(verifier));
+        self
+    }
+
+    /// Compare the maintained book with a REST snapshot every `interval`,
+    /// differences are reported as `ConnectionState::Diverged`
+    pub fn with_audit(mut self, interval: Duration) -> Self {
+        self.config.audit_interval = Some(interval);
+        self
+    }
+
     /// Get snapshot stream
     pub fn subscribe_depth(&self) -> UnboundedReceiver<Depth> {
         let config = self.config.clone();
@@ -48,36 +60,37 @@ impl DepthManager {
         self.connection.snapshot()
     }
 
+    /// Get connection state notices
+    pub fn subscribe_state(&self) -> UnboundedReceiver<ConnectionState> {
+        self.connection.subscribe_state()
+    }
+
+    /// Manager of a config made by `ExchangeAdapter::depth_config`,
+    /// e.g. with its addresses replaced
+    pub fn from_config(config: DepthConfig) -> Result<Self> {
+        if !config.is_correct() {
+            return Err(anyhow!("Unsupported config {:?}", config));
+        }
+
+        let exchange = config.exchange_type.name();
+        let adapter = AdapterRegistry::global()
+            .get(exchange)
+            .ok_or_else(|| anyhow!("Unsupported Exchange {}", exchange))?;
+        let connection = adapter.depth_connection(&config)?;
+
+        Ok(Self { config, connection })
+    }
+
     fn new_from(exchange: &str, symbol: &str, limit: Option<i32>) -> Self {
-        let config = get_depth_config_from(exchange, symbol, limit);
-
-        assert!(config.is_correct(), "Unsupported config {:?}", config);
-
-        let connection: Box<dyn DepthT> = match config.exchange_type {
-            ExchangeType::Binance => {
-                match config.symbol_type {
-                    SymbolType::Spot(_) => {
-                        Box::new(BinanceOrderBookSpot::new())
-                    }
-                    SymbolType::ContractUSDT(_) => {
-                        Box::new(BinanceSpotOrderBookPerpetualUSDT::new())
-                    }
-                    SymbolType::ContractCoin(_) => {
-                        Box::new(BinanceSpotOrderBookPerpetualCoin::new())
-                    }
-                }
-            }
-            ExchangeType::Crypto => {
-                Box::new(CryptoDepth::new())
-            }
-        };
-
-        Self { config, connection }
+        let config = get_adapter(exchange).depth_config(symbol, limit).unwrap();
+
+        Self::from_config(config).unwrap()
     }
 }
 
 #[allow(dead_code)]
-#[derive(Deserialize, Clone)]
+/// Serialized in the schema of `api::wire`
+#[derive(Clone)]
 pub struct Depth {
     /// Send time from Exchange,
     /// if not have, use receive time
@@ -106,15 +119,48 @@ impl Debug for Depth {
 pub struct Quote {
     pub price: f64,
     pub amount: f64,
+    /// Number of orders at this price level,
+    /// `None` if exchange does not tell
+    pub orders: Option<u64>,
 }
 
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
 pub enum ExchangeType {
     Binance,
     Crypto,
+    Okx,
+    Bybit,
+    Coinbase,
+    Kraken,
+    Deribit,
+    Bitfinex,
+    Kucoin,
+    Gateio,
+    /// Exchange added through `AdapterRegistry`, named by its adapter
+    Other(&'static str),
+}
+
+impl ExchangeType {
+    /// Registry name of the exchange
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExchangeType::Binance => "binance",
+            ExchangeType::Crypto => "crypto",
+            ExchangeType::Okx => "okx",
+            ExchangeType::Bybit => "bybit",
+            ExchangeType::Coinbase => "coinbase",
+            ExchangeType::Kraken => "kraken",
+            ExchangeType::Deribit => "deribit",
+            ExchangeType::Bitfinex => "bitfinex",
+            ExchangeType::Kucoin => "kucoin",
+            ExchangeType::Gateio => "gateio",
+            ExchangeType::Other(name) => name,
+        }
+    }
 }
 
-pub(crate) trait DepthT {
+/// Order book maintenance of one exchange
+pub trait DepthT {
     fn new() -> Self
         where
             Self: Sized;
@@ -124,4 +170,6 @@ pub(crate) trait DepthT {
     fn depth(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>>;
 
     fn snapshot(&self) -> Option<Depth>;
+
+    fn subscribe_state(&self) -> UnboundedReceiver<ConnectionState>;
 }
\ No newline at end of file
@@ -0,0 +1,397 @@
+use crate::api::sync::{DiffBook, DiffBookSynchronizer, DiffEvent, DiffSnapshot, SnapshotFetcher};
+use crate::metrics;
+use crate::{Backoff, ConnectionState, Depth, ReconnectPolicy, StateSender};
+use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
+use futures_util::{pin_mut, stream, Stream};
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::sync::{Arc, Mutex, RwLock};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tracing::{debug, error, info, warn};
+
+/// Websocket of a venue subscribed to one book channel
+pub(crate) trait BookFeed: Send {
+    /// Next push of the channel decoded as `T`, keeping the connection alive,
+    /// Err once the connection fails
+    fn next<T: DeserializeOwned + Send + 'static>(&mut self) -> BoxFuture<'_, Result<T>>;
+
+    /// Ask for a fresh snapshot on the same connection,
+    /// Err if the feed has to reconnect for it
+    fn resubscribe(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async { Err(anyhow!("Resubscribe is not supported")) })
+    }
+}
+
+/// Sequence numbers of two pushes in a row are not consecutive,
+/// `BookFeed::next` fails with it to get a fresh snapshot
+#[derive(Debug)]
+pub(crate) struct SequenceGap {
+    pub last: u64,
+    pub received: u64,
+}
+
+impl fmt::Display for SequenceGap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Sequence gap, last {}, received {}",
+            self.last, self.received
+        )
+    }
+}
+
+impl std::error::Error for SequenceGap {}
+
+/// `text` decoded as `T`, None is counted as a decode error of `exchange`
+pub(crate) fn decode<T: DeserializeOwned>(exchange: &'static str, text: &str) -> Option<T> {
+    match serde_json::from_str(text) {
+        Ok(push) => Some(push),
+        Err(e) => {
+            warn!("Error {}, {:?}", e, text);
+            metrics::decode_error(exchange);
+            None
+        }
+    }
+}
+
+/// Opens a new `BookFeed` for every (re)connection
+pub(crate) type Connect<Feed> = Box<dyn Fn() -> BoxFuture<'static, Result<Feed>> + Send + Sync>;
+
+/// `BookFeed::next` as a stream, ending at the first error
+fn updates<T, Feed>(feed: &mut Feed) -> impl Stream<Item = T> + '_
+where
+    T: DeserializeOwned + Send + 'static,
+    Feed: BookFeed,
+{
+    stream::unfold(feed, |feed| async move {
+        match feed.next().await {
+            Ok(update) => Some((update, feed)),
+            Err(e) => {
+                warn!("{:?}", e);
+                None
+            }
+        }
+    })
+}
+
+/// Connect, sync and replay loops of a book kept from a `BookFeed`,
+/// shared by the venues that only differ in their socket
+pub(crate) struct BookDriver<Shared> {
+    exchange: &'static str,
+    status: Arc<Mutex<bool>>,
+    state: StateSender,
+    shared: Arc<RwLock<Shared>>,
+}
+
+impl<Shared> Clone for BookDriver<Shared> {
+    fn clone(&self) -> Self {
+        BookDriver {
+            exchange: self.exchange,
+            status: self.status.clone(),
+            state: self.state.clone(),
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<Shared: Send + Sync + 'static> BookDriver<Shared> {
+    pub fn new(exchange: &'static str, shared: Shared) -> Self {
+        BookDriver {
+            exchange,
+            status: Arc::new(Mutex::new(false)),
+            state: StateSender::new(exchange),
+            shared: Arc::new(RwLock::new(shared)),
+        }
+    }
+
+    /// `depth` of the book once it is ready
+    pub fn snapshot(&self, depth: impl Fn(&Shared) -> Depth) -> Option<Depth> {
+        if *self.status.lock().unwrap() {
+            Some(depth(&self.shared.read().unwrap()))
+        } else {
+            debug!("Data is not ready");
+            None
+        }
+    }
+
+    /// The book, for venues that prepare it before every connection
+    pub fn shared(&self) -> Arc<RwLock<Shared>> {
+        self.shared.clone()
+    }
+
+    pub fn subscribe_state(&self) -> UnboundedReceiver<ConnectionState> {
+        self.state.subscribe()
+    }
+
+    fn set_status(&self, ready: bool) {
+        if let Ok(mut guard) = self.status.lock() {
+            (*guard) = ready;
+        }
+    }
+
+    /// Diff events of the feed synced onto the snapshot of `fetcher`,
+    /// sending `depth` of the book of `symbol` after each of them.
+    /// Reconnects for a new snapshot once an event does not follow,
+    /// failed connections and syncs are retried by `reconnect`
+    pub fn diff_book<Feed, Event, Snapshot, Fetcher>(
+        self,
+        connect: Connect<Feed>,
+        symbol: String,
+        reconnect: ReconnectPolicy,
+        fetcher: Fetcher,
+        depth: impl Fn(&Shared) -> Depth + Send + 'static,
+    ) -> UnboundedReceiver<Depth>
+    where
+        Feed: BookFeed + 'static,
+        Event: DiffEvent + DeserializeOwned + Send + 'static,
+        Snapshot: DiffSnapshot + Send + 'static,
+        Shared: DiffBook<Event, Snapshot = Snapshot>,
+        Fetcher: SnapshotFetcher<Snapshot> + Send + Sync + 'static,
+    {
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            info!("Start OrderBook thread");
+            let synchronizer = DiffBookSynchronizer::new(self.shared.clone());
+            let mut backoff = Backoff::new(reconnect);
+            loop {
+                let mut feed = match connect().await {
+                    Ok(feed) => feed,
+                    Err(e) => {
+                        warn!("connection error {:?}", e);
+                        if !backoff.wait(self.exchange).await {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+
+                let initialized = {
+                    let events = updates::<Event, _>(&mut feed);
+                    pin_mut!(events);
+                    synchronizer.synchronize(&mut events, &fetcher).await
+                };
+
+                match initialized {
+                    Ok(true) => {
+                        backoff.connected();
+                        self.set_status(true);
+                        info!("Overbook initialize success, now keep listening");
+                        self.state.send(ConnectionState::Connected);
+                    }
+                    Ok(false) => {
+                        warn!("All event is not usable, need a new snapshot");
+                        self.state.send(ConnectionState::Resyncing(String::from(
+                            "Buffered events do not match snapshot",
+                        )));
+                        continue;
+                    }
+                    Err(e) => {
+                        error!("{:?}", e);
+                        self.state
+                            .send(ConnectionState::Resyncing(format!("{:?}", e)));
+                        if !backoff.wait(self.exchange).await {
+                            break;
+                        }
+                        continue;
+                    }
+                }
+
+                let snapshot = depth(&self.shared.read().unwrap());
+                metrics::book(self.exchange, &symbol, &snapshot);
+                if sender.send(snapshot).is_err() {
+                    error!("depth send Snapshot error");
+                }
+
+                loop {
+                    let event = match feed.next::<Event>().await {
+                        Ok(event) => event,
+                        Err(e) => {
+                            warn!("{:?}, reconnecting", e);
+                            metrics::reconnect(self.exchange);
+                            break;
+                        }
+                    };
+
+                    let result = synchronizer
+                        .apply(event)
+                        .map(|_| depth(&self.shared.read().unwrap()));
+
+                    match result {
+                        Ok(depth) => {
+                            metrics::book(self.exchange, &symbol, &depth);
+                            if sender.send(depth).is_err() {
+                                error!("depth send Snapshot error");
+                            }
+                        }
+                        Err(e) => {
+                            warn!("All event is not usable, need a new snapshot");
+                            self.set_status(false);
+                            self.state.send(ConnectionState::Resyncing(e.to_string()));
+                            break;
+                        }
+                    }
+                }
+
+                self.set_status(false);
+                if !backoff.wait(self.exchange).await {
+                    break;
+                }
+            }
+        });
+
+        receiver
+    }
+
+    /// Whole books of `symbol` pushed by the feed, `load` replaces the book
+    /// with each of them and gives the depth to send.
+    /// Failed connections are retried by `reconnect`
+    pub fn level_book<Feed, Levels>(
+        self,
+        connect: Connect<Feed>,
+        symbol: String,
+        reconnect: ReconnectPolicy,
+        load: impl Fn(&mut Shared, Levels) -> Depth + Send + 'static,
+    ) -> UnboundedReceiver<Depth>
+    where
+        Feed: BookFeed + 'static,
+        Levels: DeserializeOwned + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            info!("Start Level Buffer maintain thread");
+            let mut backoff = Backoff::new(reconnect);
+            loop {
+                let mut feed = match connect().await {
+                    Ok(feed) => feed,
+                    Err(e) => {
+                        warn!("connection error {:?}", e);
+                        if !backoff.wait(self.exchange).await {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+                backoff.connected();
+
+                loop {
+                    let levels = match feed.next::<Levels>().await {
+                        Ok(levels) => levels,
+                        Err(e) => {
+                            warn!("{:?}, reconnecting", e);
+                            metrics::reconnect(self.exchange);
+                            break;
+                        }
+                    };
+
+                    let depth = load(&mut self.shared.write().unwrap(), levels);
+                    self.set_status(true);
+
+                    metrics::book(self.exchange, &symbol, &depth);
+                    if sender.send(depth).is_err() {
+                        error!("level_depth send Snapshot error");
+                    }
+                }
+
+                self.set_status(false);
+                if !backoff.wait(self.exchange).await {
+                    break;
+                }
+            }
+        });
+
+        receiver
+    }
+
+    /// A snapshot of `symbol` pushed by the feed followed by its deltas,
+    /// `apply` puts each push onto the book, ready after the first one,
+    /// and gives the depth to send, None skips the push.
+    /// Resubscribes for a fresh snapshot once `apply` fails or a push is missed,
+    /// failed connections are retried by `reconnect`
+    pub fn pushed_book<Feed, Push>(
+        self,
+        connect: Connect<Feed>,
+        symbol: String,
+        reconnect: ReconnectPolicy,
+        apply: impl Fn(&mut Shared, Push, bool) -> Option<Result<Depth>> + Send + 'static,
+    ) -> UnboundedReceiver<Depth>
+    where
+        Feed: BookFeed + 'static,
+        Push: DeserializeOwned + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            info!("Start OrderBook thread");
+            let mut backoff = Backoff::new(reconnect);
+            loop {
+                let mut feed = match connect().await {
+                    Ok(feed) => feed,
+                    Err(e) => {
+                        warn!("connection error {:?}", e);
+                        if !backoff.wait(self.exchange).await {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+                backoff.connected();
+
+                loop {
+                    let is_ready = *self.status.lock().unwrap();
+
+                    let result = match feed.next::<Push>().await {
+                        Ok(push) => {
+                            let applied = apply(&mut self.shared.write().unwrap(), push, is_ready);
+                            match applied {
+                                Some(result) => result,
+                                None => continue,
+                            }
+                        }
+                        Err(e) if e.is::<SequenceGap>() => Err(e),
+                        Err(e) => {
+                            warn!("{:?}, reconnecting", e);
+                            metrics::reconnect(self.exchange);
+                            break;
+                        }
+                    };
+
+                    let depth = match result {
+                        Ok(depth) => depth,
+                        Err(e) => {
+                            warn!("{:?}, need a new snapshot", e);
+                            self.set_status(false);
+                            self.state.send(ConnectionState::Resyncing(e.to_string()));
+
+                            if let Err(e) = feed.resubscribe().await {
+                                error!("Resubscribe error {:?}", e);
+                                break;
+                            }
+                            continue;
+                        }
+                    };
+
+                    if !is_ready {
+                        self.set_status(true);
+                        info!("Overbook initialize success, now keep listening");
+                        self.state.send(ConnectionState::Connected);
+                    }
+
+                    metrics::book(self.exchange, &symbol, &depth);
+                    if sender.send(depth).is_err() {
+                        error!("depth send Snapshot error");
+                    }
+                }
+
+                self.set_status(false);
+                if !backoff.wait(self.exchange).await {
+                    break;
+                }
+            }
+        });
+
+        receiver
+    }
+}
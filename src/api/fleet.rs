@@ -0,0 +1,951 @@
+use crate::api::sink::{run_sink, CsvSink, FileSinkConfig, JsonlSink, Sink};
+use crate::api::wire::EventRecord;
+use crate::{
+    AdapterRegistry, ConnectionState, Depth, DepthManager, DepthType, ReconnectPolicy, Ticker,
+    TickerManager,
+};
+use anyhow::{anyhow, Error, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+/// What to subscribe for a symbol
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum StreamKind {
+    /// Book maintained from a snapshot and diffs, needs `limit`
+    Depth,
+    /// Book snapshots pushed by the exchange
+    Level,
+    /// Trades
+    Ticker,
+    /// Best bid and ask of the `Level` book
+    BookTicker,
+}
+
+impl fmt::Display for StreamKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            StreamKind::Depth => "depth",
+            StreamKind::Level => "level",
+            StreamKind::Ticker => "ticker",
+            StreamKind::BookTicker => "bookTicker",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for StreamKind {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        match name {
+            "depth" => Ok(StreamKind::Depth),
+            "level" => Ok(StreamKind::Level),
+            "ticker" => Ok(StreamKind::Ticker),
+            "bookTicker" => Ok(StreamKind::BookTicker),
+            _ => Err(anyhow!("Unsupported stream {}", name)),
+        }
+    }
+}
+
+/// Addresses replacing the ones of the adapter
+#[derive(Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct EndpointOverrides {
+    /// Snapshot address of `depth`
+    pub rest: Option<String>,
+    /// Websocket address of `depth`, `level` and `bookTicker`
+    pub websocket: Option<String>,
+    /// Address of `ticker`
+    pub ticker: Option<String>,
+}
+
+/// Where the updates of a stream go
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SinkConfig {
+    /// `Fleet::recv`
+    Channel,
+    /// `tracing` at info level
+    Log,
+    /// `CsvSink`, one writer for every stream with the same config
+    Csv(FileSinkConfig),
+    /// `JsonlSink`, one writer for every stream with the same config
+    Jsonl(FileSinkConfig),
+}
+
+impl SinkConfig {
+    /// Writer of the `csv` and `jsonl` sinks
+    pub fn writer(&self) -> Option<Box<dyn Sink>> {
+        match self {
+            SinkConfig::Csv(config) => Some(Box::new(CsvSink::new(config.clone()))),
+            SinkConfig::Jsonl(config) => Some(Box::new(JsonlSink::new(config.clone()))),
+            SinkConfig::Channel | SinkConfig::Log => None,
+        }
+    }
+}
+
+/// One exchange with the streams of its symbols
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SubscriptionConfig {
+    /// Adapter name, e.g. "binance"
+    pub exchange: String,
+    /// Canonical symbols, e.g. "BTC_USDT" / "BTC_USDT_SWAP"
+    pub symbols: Vec<String>,
+    pub streams: Vec<StreamKind>,
+    /// Book size of `depth`
+    #[serde(default)]
+    pub limit: Option<i32>,
+    #[serde(default)]
+    pub endpoints: EndpointOverrides,
+    /// Replaces the policy of the fleet
+    #[serde(default)]
+    pub reconnect: Option<ReconnectPolicy>,
+    /// Replaces the sinks of the fleet
+    #[serde(default)]
+    pub sinks: Option<Vec<SinkConfig>>,
+}
+
+/// Every subscription of a process, read from TOML or YAML
+///
+/// ```toml
+/// sinks = [{ type = "channel" }, { type = "jsonl", dir = "records", time = "rfc3339" }]
+///
+/// [reconnect]
+/// initial_delay_ms = 100
+///
+/// [[subscriptions]]
+/// exchange = "binance"
+/// symbols = ["BTC_USDT", "ETH_USDT"]
+/// streams = ["depth", "ticker"]
+/// limit = 1000
+/// ```
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct FleetConfig {
+    #[serde(default)]
+    pub reconnect: ReconnectPolicy,
+    #[serde(default = "default_sinks")]
+    pub sinks: Vec<SinkConfig>,
+    pub subscriptions: Vec<SubscriptionConfig>,
+}
+
+fn default_sinks() -> Vec<SinkConfig> {
+    vec![SinkConfig::Channel]
+}
+
+/// Update of one stream of a `Fleet`
+#[derive(Clone, Debug)]
+pub struct FleetEvent {
+    /// e.g. "binance BTC_USDT depth"
+    pub name: String,
+    pub exchange: String,
+    pub symbol: String,
+    pub stream: StreamKind,
+    pub data: FleetData,
+}
+
+impl FleetEvent {
+    /// JSON document of the event in the `api::wire` schema,
+    /// `{"v1": {..}}` where `type` tells the data:
+    ///
+    /// - "depth" / "bookTicker": `ts`, `lts`, `id`, `asks` and `bids` of [price, amount, orders]
+    /// - "trades": `trades` of `ts`, `lts`, `id`, `price`, `amount` and `direction`
+    /// - "state": `lts` and `state` of the book
+    pub fn to_json(&self) -> Value {
+        self.document(None)
+    }
+
+    /// `to_json` with at most `levels` levels of each side
+    pub(crate) fn document(&self, levels: Option<usize>) -> Value {
+        let record = EventRecord {
+            event: self,
+            levels,
+        };
+        serde_json::to_value(record).expect("records have string keys")
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum FleetData {
+    Depth(Depth),
+    /// `Depth` with only the best bid and ask
+    BookTicker(Depth),
+    Ticker(Vec<Ticker>),
+    /// Notices of the `depth` and `level` books
+    State(ConnectionState),
+}
+
+enum Stream {
+    Depth(DepthManager),
+    BookTicker(DepthManager),
+    Ticker(TickerManager),
+}
+
+/// What the events of an `Entry` are tagged with
+#[derive(Clone)]
+struct Origin {
+    name: String,
+    exchange: String,
+    symbol: String,
+    stream: StreamKind,
+}
+
+/// Where `dispatch` sends the updates of a stream
+#[derive(Clone)]
+enum Output {
+    /// `Fleet::recv` or the writer of a file sink
+    Send(UnboundedSender<FleetEvent>),
+    Log,
+}
+
+/// A stream of the config, built but not subscribed
+struct Entry {
+    origin: Origin,
+    stream: Stream,
+    sinks: Vec<SinkConfig>,
+}
+
+impl FleetConfig {
+    pub fn from_toml(text: &str) -> Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    pub fn from_yaml(text: &str) -> Result<Self> {
+        Ok(serde_yaml::from_str(text)?)
+    }
+
+    /// Format by extension, ".toml" / ".yaml" / ".yml"
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Self::from_toml(&text),
+            Some("yaml") | Some("yml") => Self::from_yaml(&text),
+            _ => Err(anyhow!("Unsupported config format {}", path.display())),
+        }
+    }
+
+    /// Build every manager without connecting,
+    /// errors name the subscription and symbol they come from
+    pub fn validate(&self) -> Result<()> {
+        self.entries().map(|_| ())
+    }
+
+    /// Subscribe every stream, within a tokio runtime
+    pub fn start(&self) -> Result<Fleet> {
+        let entries = self.entries()?;
+        let (sender, events) = mpsc::unbounded_channel();
+
+        let names = entries
+            .iter()
+            .map(|entry| entry.origin.name.clone())
+            .collect();
+        let depths = entries
+            .iter()
+            .filter_map(|entry| match &entry.stream {
+                Stream::Depth(manager) | Stream::BookTicker(manager) => Some((
+                    entry.origin.exchange.clone(),
+                    entry.origin.symbol.clone(),
+                    manager.clone(),
+                )),
+                Stream::Ticker(_) => None,
+            })
+            .collect();
+
+        // One writer for every distinct file sink
+        let mut files: Vec<(SinkConfig, UnboundedSender<FleetEvent>)> = vec![];
+        let mut writers = vec![];
+        for sink in entries.iter().flat_map(|entry| &entry.sinks) {
+            if files.iter().any(|(config, _)| config == sink) {
+                continue;
+            }
+            if let Some(writer) = sink.writer() {
+                let (file, events) = mpsc::unbounded_channel();
+                files.push((sink.clone(), file));
+                writers.push(tokio::spawn(async move {
+                    if let Err(e) = run_sink(writer, events).await {
+                        error!("sink stopped: {}", e);
+                    }
+                }));
+            }
+        }
+
+        let tasks = entries
+            .into_iter()
+            .flat_map(|entry| {
+                let outputs = entry
+                    .sinks
+                    .iter()
+                    .map(|sink| match sink {
+                        SinkConfig::Channel => Output::Send(sender.clone()),
+                        SinkConfig::Log => Output::Log,
+                        SinkConfig::Csv(_) | SinkConfig::Jsonl(_) => {
+                            let (_, file) = files
+                                .iter()
+                                .find(|(config, _)| config == sink)
+                                .expect("writer of every file sink");
+                            Output::Send(file.clone())
+                        }
+                    })
+                    .collect();
+                entry.spawn(outputs)
+            })
+            .collect();
+
+        Ok(Fleet {
+            names,
+            depths,
+            events,
+            tasks,
+            writers,
+        })
+    }
+
+    fn entries(&self) -> Result<Vec<Entry>> {
+        let mut entries = vec![];
+
+        for (index, subscription) in self.subscriptions.iter().enumerate() {
+            let context = format!("subscriptions[{}] ({})", index, subscription.exchange);
+            subscription
+                .check(&self.sinks)
+                .map_err(|e| anyhow!("{}: {}", context, e))?;
+
+            for (position, symbol) in subscription.symbols.iter().enumerate() {
+                for stream in &subscription.streams {
+                    let entry = subscription.entry(self, symbol, *stream).map_err(|e| {
+                        anyhow!(
+                            "{} symbols[{}] {} {}: {}",
+                            context,
+                            position,
+                            symbol,
+                            stream,
+                            e
+                        )
+                    })?;
+                    entries.push(entry);
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+impl SubscriptionConfig {
+    /// Checks that do not depend on the symbol
+    fn check(&self, sinks: &[SinkConfig]) -> Result<()> {
+        if AdapterRegistry::global().get(&self.exchange).is_none() {
+            return Err(anyhow!("Unsupported Exchange {}", self.exchange));
+        }
+        if self.symbols.is_empty() {
+            return Err(anyhow!("No symbols"));
+        }
+        if self.streams.is_empty() {
+            return Err(anyhow!("No streams"));
+        }
+        if self.sinks.as_deref().unwrap_or(sinks).is_empty() {
+            return Err(anyhow!("No sinks"));
+        }
+
+        match self.limit {
+            Some(limit) if limit <= 0 => Err(anyhow!("limit {} is not positive", limit)),
+            None if self.streams.contains(&StreamKind::Depth) => {
+                Err(anyhow!("depth needs a limit"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn entry(&self, fleet: &FleetConfig, symbol: &str, stream: StreamKind) -> Result<Entry> {
+        let adapter = AdapterRegistry::global()
+            .get(&self.exchange)
+            .ok_or_else(|| anyhow!("Unsupported Exchange {}", self.exchange))?;
+
+        let reconnect = self
+            .reconnect
+            .clone()
+            .unwrap_or_else(|| fleet.reconnect.clone());
+        let origin = Origin {
+            name: format!("{} {} {}", self.exchange, symbol, stream),
+            exchange: self.exchange.clone(),
+            symbol: symbol.to_string(),
+            stream,
+        };
+        let stream = match stream {
+            StreamKind::Depth | StreamKind::Level | StreamKind::BookTicker => {
+                let limit = match stream {
+                    StreamKind::Depth => self.limit,
+                    _ => None,
+                };
+                let mut config = adapter.depth_config(symbol, limit)?;
+                config.reconnect = reconnect;
+
+                match &mut config.depth_url {
+                    DepthType::DepthSnapshot(rest, websocket) => {
+                        replace(rest, &self.endpoints.rest);
+                        replace(websocket, &self.endpoints.websocket);
+                    }
+                    DepthType::Depth(websocket) => replace(websocket, &self.endpoints.websocket),
+                }
+
+                let manager = DepthManager::from_config(config)?;
+                match stream {
+                    StreamKind::BookTicker => Stream::BookTicker(manager),
+                    _ => Stream::Depth(manager),
+                }
+            }
+            StreamKind::Ticker => {
+                let mut config = adapter.ticker_config(symbol)?;
+                config.reconnect = reconnect;
+                replace(&mut config.ticker_url, &self.endpoints.ticker);
+
+                Stream::Ticker(TickerManager::from_config(config)?)
+            }
+        };
+
+        Ok(Entry {
+            origin,
+            stream,
+            sinks: self.sinks.clone().unwrap_or_else(|| fleet.sinks.clone()),
+        })
+    }
+}
+
+fn replace(address: &mut String, replacement: &Option<String>) {
+    if let Some(replacement) = replacement {
+        *address = replacement.clone();
+    }
+}
+
+impl Entry {
+    fn spawn(self, outputs: Vec<Output>) -> Vec<JoinHandle<()>> {
+        let Entry { origin, stream, .. } = self;
+
+        match stream {
+            Stream::Depth(manager) => {
+                let states = manager.subscribe_state();
+                vec![
+                    tokio::spawn(forward_states(origin.clone(), states, outputs.clone())),
+                    tokio::spawn(forward(
+                        origin,
+                        manager.subscribe_depth(),
+                        FleetData::Depth,
+                        outputs,
+                    )),
+                ]
+            }
+            Stream::BookTicker(manager) => vec![tokio::spawn(forward(
+                origin,
+                manager.subscribe_depth(),
+                |depth| FleetData::BookTicker(best_levels(depth)),
+                outputs,
+            ))],
+            Stream::Ticker(manager) => vec![tokio::spawn(forward(
+                origin,
+                manager.subscribe(),
+                FleetData::Ticker,
+                outputs,
+            ))],
+        }
+    }
+}
+
+/// Dispatch every update of the stream, which reconnects by the
+/// `ReconnectPolicy` of its config and ends once the retries run out
+async fn forward<T, M>(
+    origin: Origin,
+    mut receiver: UnboundedReceiver<T>,
+    map: M,
+    outputs: Vec<Output>,
+) where
+    M: Fn(T) -> FleetData,
+{
+    while let Some(update) = receiver.recv().await {
+        dispatch(&origin, map(update), &outputs);
+    }
+
+    warn!("{} ended", origin.name);
+}
+
+async fn forward_states(
+    origin: Origin,
+    mut states: UnboundedReceiver<ConnectionState>,
+    outputs: Vec<Output>,
+) {
+    while let Some(state) = states.recv().await {
+        dispatch(&origin, FleetData::State(state), &outputs);
+    }
+}
+
+fn dispatch(origin: &Origin, data: FleetData, outputs: &[Output]) {
+    for output in outputs {
+        match output {
+            Output::Send(sender) => {
+                let event = FleetEvent {
+                    name: origin.name.clone(),
+                    exchange: origin.exchange.clone(),
+                    symbol: origin.symbol.clone(),
+                    stream: origin.stream,
+                    data: data.clone(),
+                };
+                // Nobody listens once the `Fleet` is dropped
+                let _ = sender.send(event);
+            }
+            Output::Log => info!("{} {:?}", origin.name, data),
+        }
+    }
+}
+
+fn best_levels(mut depth: Depth) -> Depth {
+    depth.asks.truncate(1);
+    depth.bids.truncate(1);
+    depth
+}
+
+/// Handle of every stream started by `FleetConfig::start`,
+/// dropping it stops them
+pub struct Fleet {
+    names: Vec<String>,
+    /// Exchange, symbol and manager of the books
+    depths: Vec<(String, String, DepthManager)>,
+    events: UnboundedReceiver<FleetEvent>,
+    tasks: Vec<JoinHandle<()>>,
+    /// Tasks of the `csv` and `jsonl` sinks, ending once the streams stop
+    writers: Vec<JoinHandle<()>>,
+}
+
+impl Fleet {
+    /// Names of the streams, e.g. "binance BTC_USDT depth"
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Manager of the book of `exchange` and `symbol`,
+    /// the one of a `depth` stream before `level` and `bookTicker` ones
+    pub fn depth_manager(&self, exchange: &str, symbol: &str) -> Option<&DepthManager> {
+        self.depths
+            .iter()
+            .filter(|(name, pair, _)| name == exchange && pair == symbol)
+            .map(|(_, _, manager)| manager)
+            .max_by_key(|manager| manager.config.limit.is_some())
+    }
+
+    /// Next update of the streams with a `channel` sink,
+    /// `None` once every stream gave up reconnecting
+    pub async fn recv(&mut self) -> Option<FleetEvent> {
+        self.events.recv().await
+    }
+
+    pub fn stop(&self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+
+    /// Stop the streams and wait for the `csv` and `jsonl` sinks to flush their files
+    pub async fn close(mut self) {
+        self.stop();
+        for task in std::mem::take(&mut self.tasks) {
+            let _ = task.await;
+        }
+        for writer in std::mem::take(&mut self.writers) {
+            let _ = writer.await;
+        }
+    }
+}
+
+impl Drop for Fleet {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::fleet::{FleetConfig, FleetData, FleetEvent, SinkConfig, StreamKind};
+    use crate::api::sink::{FileSinkConfig, TimeFormat};
+    use crate::config::{DepthType, SymbolType};
+    use crate::{
+        AdapterRegistry, ConnectionState, Depth, DepthConfig, DepthT, ExchangeAdapter,
+        OrderDirection, Quote, ReconnectPolicy, Ticker, TickerConfig, TickerT,
+    };
+    use anyhow::{anyhow, Result};
+    use std::fs;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+    struct MockDepth {
+        connected: AtomicBool,
+    }
+
+    impl DepthT for MockDepth {
+        fn new() -> Self {
+            MockDepth {
+                connected: AtomicBool::new(false),
+            }
+        }
+
+        fn depth_snapshot(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
+            self.depth(config)
+        }
+
+        /// One update on the first connection, then every stream ends at once
+        /// as if its retries ran out
+        fn depth(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
+            assert_eq!(config.reconnect.initial_delay_ms, 1);
+
+            let (sender, receiver) = mpsc::unbounded_channel();
+            if !self.connected.swap(true, Ordering::SeqCst) {
+                sender.send(self.snapshot().unwrap())?;
+            }
+            Ok(receiver)
+        }
+
+        fn snapshot(&self) -> Option<Depth> {
+            Some(Depth {
+                ts: 1,
+                lts: 2,
+                id: 3,
+                asks: vec![],
+                bids: vec![],
+            })
+        }
+
+        fn subscribe_state(&self) -> UnboundedReceiver<ConnectionState> {
+            mpsc::unbounded_channel().1
+        }
+    }
+
+    struct MockAdapter;
+
+    impl ExchangeAdapter for MockAdapter {
+        fn name(&self) -> &'static str {
+            "fleet-mock"
+        }
+
+        fn validate_symbol(&self, symbol: &str, _limit: Option<i32>) -> Result<SymbolType> {
+            Ok(SymbolType::Spot(symbol.replace('_', "-")))
+        }
+
+        fn depth_url(&self, symbol_type: &SymbolType, limit: Option<i32>) -> Result<DepthType> {
+            let websocket = format!("ws://localhost/{:?}", symbol_type);
+            Ok(match limit {
+                Some(_) => DepthType::DepthSnapshot(String::from("http://localhost"), websocket),
+                None => DepthType::Depth(websocket),
+            })
+        }
+
+        fn ticker_url(&self, _symbol_type: &SymbolType) -> Result<String> {
+            Err(anyhow!("No trades for mock"))
+        }
+
+        fn depth_connection(&self, _config: &DepthConfig) -> Result<Arc<dyn DepthT + Send + Sync>> {
+            Ok(Arc::new(MockDepth::new()))
+        }
+
+        fn ticker_connection(
+            &self,
+            _config: &TickerConfig,
+        ) -> Result<Arc<dyn TickerT + Send + Sync>> {
+            Err(anyhow!("No trades for mock"))
+        }
+    }
+
+    const TOML: &str = r#"
+sinks = [{ type = "channel" }, { type = "log" }]
+
+[reconnect]
+initial_delay_ms = 10
+max_retries = 3
+
+[[subscriptions]]
+exchange = "binance"
+symbols = ["BTC_USDT", "ETH_USDT"]
+streams = ["depth", "ticker"]
+limit = 1000
+
+[[subscriptions]]
+exchange = "okx"
+symbols = ["BTC_USDT_SWAP"]
+streams = ["level", "bookTicker"]
+endpoints = { websocket = "ws://localhost:8080" }
+"#;
+
+    const YAML: &str = r#"
+sinks:
+  - type: channel
+  - type: log
+reconnect:
+  initial_delay_ms: 10
+  max_retries: 3
+subscriptions:
+  - exchange: binance
+    symbols: [BTC_USDT, ETH_USDT]
+    streams: [depth, ticker]
+    limit: 1000
+  - exchange: okx
+    symbols: [BTC_USDT_SWAP]
+    streams: [level, bookTicker]
+    endpoints:
+      websocket: ws://localhost:8080
+"#;
+
+    #[test]
+    fn parse_formats() {
+        let config = FleetConfig::from_toml(TOML).unwrap();
+        assert_eq!(config, FleetConfig::from_yaml(YAML).unwrap());
+
+        assert_eq!(config.sinks, vec![SinkConfig::Channel, SinkConfig::Log]);
+        assert_eq!(
+            config.reconnect,
+            ReconnectPolicy {
+                initial_delay_ms: 10,
+                max_delay_ms: 30_000,
+                max_retries: Some(3),
+            }
+        );
+        assert_eq!(config.subscriptions.len(), 2);
+        assert_eq!(
+            config.subscriptions[1].streams,
+            vec![StreamKind::Level, StreamKind::BookTicker]
+        );
+        assert_eq!(
+            config.subscriptions[1].endpoints.websocket.as_deref(),
+            Some("ws://localhost:8080")
+        );
+        config.validate().unwrap();
+
+        let unknown = "[[subscriptions]]\nexchange = \"binance\"\nsymbol = [\"BTC_USDT\"]";
+        assert!(FleetConfig::from_toml(unknown).is_err());
+
+        let files = r#"
+sinks = [{ type = "csv", dir = "books", levels = 5, time = "rfc3339" }, { type = "jsonl", dir = "records" }]
+subscriptions = []
+"#;
+        let config = FleetConfig::from_toml(files).unwrap();
+        assert_eq!(
+            config.sinks,
+            vec![
+                SinkConfig::Csv(FileSinkConfig {
+                    levels: Some(5),
+                    time: TimeFormat::Rfc3339,
+                    ..FileSinkConfig::new("books")
+                }),
+                SinkConfig::Jsonl(FileSinkConfig::new("records")),
+            ]
+        );
+        assert!(config.sinks.iter().all(|sink| sink.writer().is_some()));
+        assert!(SinkConfig::Channel.writer().is_none());
+    }
+
+    #[test]
+    fn event_documents() {
+        let event = |data| FleetEvent {
+            name: String::from("binance BTC_USDT depth"),
+            exchange: String::from("binance"),
+            symbol: String::from("BTC_USDT"),
+            stream: StreamKind::Depth,
+            data,
+        };
+
+        let depth = Depth {
+            ts: 1,
+            lts: 2,
+            id: 3,
+            asks: vec![Quote {
+                price: 101.0,
+                amount: 0.5,
+                orders: Some(2),
+            }],
+            bids: vec![Quote {
+                price: 100.0,
+                amount: 1.0,
+                orders: None,
+            }],
+        };
+        assert_eq!(
+            event(FleetData::Depth(depth)).to_json().to_string(),
+            r#"{"v1":{"asks":[[101.0,0.5,2]],"bids":[[100.0,1.0,null]],"exchange":"binance","id":3,"lts":2,"symbol":"BTC_USDT","ts":1,"type":"depth"}}"#
+        );
+
+        let trade = Ticker {
+            lts: 2,
+            ts: 1,
+            price: 100.5,
+            amount: 0.25,
+            direction: OrderDirection::Sell,
+            id: 7,
+        };
+        let document = event(FleetData::Ticker(vec![trade])).to_json();
+        assert_eq!(document["v1"]["type"], "trades");
+        assert_eq!(
+            document["v1"]["trades"].to_string(),
+            r#"[{"amount":0.25,"direction":"sell","id":7,"lts":2,"price":100.5,"ts":1}]"#
+        );
+
+        let document = event(FleetData::State(ConnectionState::Connected)).to_json();
+        assert_eq!(document["v1"]["type"], "state");
+        assert_eq!(document["v1"]["state"], "connected");
+
+        let state = ConnectionState::Resyncing(String::from("gap"));
+        let document = event(FleetData::State(state)).to_json();
+        assert_eq!(
+            document["v1"]["state"].to_string(),
+            r#"{"resyncing":"gap"}"#
+        );
+    }
+
+    #[test]
+    fn stream_names() {
+        for stream in [
+            StreamKind::Depth,
+            StreamKind::Level,
+            StreamKind::Ticker,
+            StreamKind::BookTicker,
+        ] {
+            assert_eq!(stream.to_string().parse::<StreamKind>().unwrap(), stream);
+        }
+        assert!("book_ticker".parse::<StreamKind>().is_err());
+    }
+
+    #[test]
+    fn errors_name_the_entry() {
+        let error = |text: &str| {
+            FleetConfig::from_toml(text)
+                .unwrap()
+                .validate()
+                .unwrap_err()
+                .to_string()
+        };
+
+        let unknown = r#"
+[[subscriptions]]
+exchange = "binance"
+symbols = ["BTC_USDT"]
+streams = ["level"]
+
+[[subscriptions]]
+exchange = "binanc"
+symbols = ["BTC_USDT"]
+streams = ["level"]
+"#;
+        assert_eq!(
+            error(unknown),
+            "subscriptions[1] (binanc): Unsupported Exchange binanc"
+        );
+
+        let no_limit = r#"
+[[subscriptions]]
+exchange = "binance"
+symbols = ["BTC_USDT"]
+streams = ["ticker", "depth"]
+"#;
+        assert_eq!(
+            error(no_limit),
+            "subscriptions[0] (binance): depth needs a limit"
+        );
+
+        let symbol = r#"
+[[subscriptions]]
+exchange = "binance"
+symbols = ["BTC_USDT", "BTCUSDT"]
+streams = ["level"]
+"#;
+        assert!(error(symbol).starts_with("subscriptions[0] (binance) symbols[1] BTCUSDT level: "));
+    }
+
+    #[tokio::test]
+    async fn ends_with_its_connection() {
+        AdapterRegistry::global().register(MockAdapter);
+
+        let config = FleetConfig::from_toml(
+            r#"
+[reconnect]
+initial_delay_ms = 1
+max_retries = 2
+
+[[subscriptions]]
+exchange = "fleet-mock"
+symbols = ["BTC_USDT"]
+streams = ["bookTicker"]
+endpoints = { websocket = "ws://localhost:8080" }
+"#,
+        )
+        .unwrap();
+
+        let mut fleet = config.start().unwrap();
+        assert_eq!(fleet.names(), ["fleet-mock BTC_USDT bookTicker"]);
+
+        let mut updates = 0;
+        while let Some(event) = fleet.recv().await {
+            assert_eq!(event.name, "fleet-mock BTC_USDT bookTicker");
+            assert_eq!(
+                (event.exchange.as_str(), event.symbol.as_str(), event.stream),
+                ("fleet-mock", "BTC_USDT", StreamKind::BookTicker)
+            );
+            assert!(matches!(event.data, FleetData::BookTicker(depth) if depth.id == 3));
+            updates += 1;
+        }
+        assert_eq!(updates, 1);
+    }
+
+    #[tokio::test]
+    async fn file_sinks_write_every_stream() {
+        AdapterRegistry::global().register(MockAdapter);
+        let dir = std::env::temp_dir().join(format!("snapshot-fleet-{}", std::process::id()));
+
+        let config = FleetConfig::from_toml(&format!(
+            r#"
+sinks = [{{ type = "channel" }}, {{ type = "jsonl", dir = "{}" }}]
+
+[reconnect]
+initial_delay_ms = 1
+max_retries = 0
+
+[[subscriptions]]
+exchange = "fleet-mock"
+symbols = ["BTC_USDT", "ETH_USDT"]
+streams = ["bookTicker"]
+endpoints = {{ websocket = "ws://localhost:8080" }}
+"#,
+            dir.display()
+        ))
+        .unwrap();
+
+        let mut fleet = config.start().unwrap();
+        let mut updates = 0;
+        while fleet.recv().await.is_some() {
+            updates += 1;
+        }
+        fleet.close().await;
+        assert_eq!(updates, 2);
+
+        let mut lines = vec![];
+        for entry in fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            assert_eq!(path.extension().unwrap(), "jsonl");
+            lines.extend(fs::read_to_string(path).unwrap().lines().map(String::from));
+        }
+        lines.sort();
+        assert_eq!(
+            lines,
+            [
+                r#"{"v1":{"asks":[],"bids":[],"exchange":"fleet-mock","id":3,"lts":2,"symbol":"BTC_USDT","ts":1,"type":"bookTicker"}}"#,
+                r#"{"v1":{"asks":[],"bids":[],"exchange":"fleet-mock","id":3,"lts":2,"symbol":"ETH_USDT","ts":1,"type":"bookTicker"}}"#,
+            ]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
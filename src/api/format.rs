@@ -0,0 +1,112 @@
+use crate::Quote;
+use serde::de::{IgnoredAny, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+
+/// Levels as the exchanges send them, `[["price", "amount"], ..]`,
+/// for `#[serde(deserialize_with = "quote_tuples")]` on exchange formats.
+///
+/// `Quote` itself is serialized in the schema of `crate::api::wire`
+pub(crate) fn quote_tuples<'de, D>(deserializer: D) -> Result<Vec<Quote>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let tuples = Vec::<QuoteTuple>::deserialize(deserializer)?;
+    Ok(tuples.into_iter().map(|QuoteTuple(quote)| quote).collect())
+}
+
+struct QuoteTuple(Quote);
+
+impl<'de> Deserialize<'de> for QuoteTuple {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(QuoteVisitor).map(QuoteTuple)
+    }
+}
+
+struct QuoteVisitor;
+
+impl<'de> Visitor<'de> for QuoteVisitor {
+    type Value = Quote;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a sequence of [price, amount]")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut price = None;
+        let mut amount = None;
+
+        if let Some(val) = seq.next_element::<&str>()? {
+            match val.parse::<f64>() {
+                Ok(num) => price = Some(num),
+                Err(_) => return Err(serde::de::Error::custom("Fail to convert price str to f64")),
+            }
+        }
+
+        if let Some(val) = seq.next_element::<&str>()? {
+            match val.parse::<f64>() {
+                Ok(num) => amount = Some(num),
+                Err(_) => {
+                    return Err(serde::de::Error::custom(
+                        "Fail to convert amount str to f64",
+                    ))
+                }
+            }
+        }
+
+        if price.is_none() {
+            return Err(serde::de::Error::custom("Missing price field"));
+        }
+
+        if amount.is_none() {
+            return Err(serde::de::Error::custom("Missing amount field"));
+        }
+
+        if seq.next_element::<IgnoredAny>()?.is_some() {
+            return Err(serde::de::Error::invalid_length(3, &self));
+        }
+
+        Ok(Quote {
+            price: price.unwrap(),
+            amount: amount.unwrap(),
+            orders: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::quote_tuples;
+    use crate::Quote;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Levels {
+        #[serde(deserialize_with = "quote_tuples")]
+        asks: Vec<Quote>,
+    }
+
+    fn quotes(text: &str) -> serde_json::Result<Vec<Quote>> {
+        let text = format!(r#"{{"asks":{}}}"#, text);
+        serde_json::from_str::<Levels>(&text).map(|levels| levels.asks)
+    }
+
+    #[test]
+    fn quote_deserialize() {
+        let quote = quotes(r#"[["16593.36","0.04950"]]"#).unwrap()[0];
+        assert_eq!(quote.price, 16593.36);
+        assert_eq!(quote.amount, 0.0495);
+        assert_eq!(quote.orders, None);
+
+        assert!(quotes(r#"[["16593.36"]]"#).is_err());
+        assert!(quotes(r#"[["16593.36","x"]]"#).is_err());
+        // [price, amount, orders] of other exchanges
+        assert!(quotes(r#"[["16593.36","0.04950","3"]]"#).is_err());
+    }
+}
@@ -0,0 +1,672 @@
+use crate::{ConnectionState, DepthManager, FleetConfig, FleetData, FleetEvent, StreamKind};
+use anyhow::Result;
+use axum::extract::ws::{Message, WebSocket};
+use axum::extract::{Path, Query, State, WebSocketUpgrade};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router, Server};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::JoinHandle;
+use tracing::{debug, info};
+
+/// Events kept for websocket clients that fall behind
+const BACKLOG: usize = 4096;
+
+/// Encoding of the messages to a websocket client, `/ws?encoding=msgpack`
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Encoding {
+    /// Text frames
+    #[default]
+    Json,
+    /// Binary frames of MessagePack, with the fields of the JSON ones
+    Msgpack,
+}
+
+impl Encoding {
+    fn encode<T: Serialize>(&self, message: &T) -> Message {
+        match self {
+            Encoding::Json => Message::Text(serde_json::to_string(message).unwrap_or_default()),
+            Encoding::Msgpack => {
+                Message::Binary(rmp_serde::to_vec_named(message).unwrap_or_default())
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Connect {
+    #[serde(default)]
+    encoding: Encoding,
+}
+
+/// `{"op": "subscribe", "topics": ["depth.binance.BTC_USDT"]}`
+#[derive(Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum Request {
+    Subscribe { topics: Vec<String> },
+    Unsubscribe { topics: Vec<String> },
+}
+
+/// Answer to a request of a client
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Reply {
+    /// `{"op": "subscribed", "topics": [..]}`
+    Done {
+        op: &'static str,
+        topics: Vec<String>,
+    },
+    Error {
+        error: String,
+    },
+}
+
+/// `{"topic": .., "data": FleetEvent::to_json}`
+#[derive(Serialize)]
+struct Data<'a> {
+    topic: &'a str,
+    data: &'a FleetEvent,
+}
+
+/// A message of a topic, encoded once in each encoding its subscribers use
+struct Published {
+    topic: String,
+    json: Option<String>,
+    msgpack: Option<Vec<u8>>,
+}
+
+/// What the streams of the fleet have done so far
+#[derive(Default)]
+struct Health {
+    state: Option<ConnectionState>,
+    updates: u64,
+    last_lts: Option<i64>,
+}
+
+/// Line of `GET /health`, `state` in the `api::wire` schema
+#[derive(Serialize)]
+struct StreamHealth {
+    name: String,
+    state: Option<ConnectionState>,
+    updates: u64,
+    last_lts: Option<i64>,
+}
+
+struct Hub {
+    events: broadcast::Sender<Arc<Published>>,
+    topics: BTreeSet<String>,
+    /// Clients subscribed to a topic in an encoding
+    subscribers: Mutex<HashMap<(String, Encoding), usize>>,
+    books: HashMap<(String, String), DepthManager>,
+    health: Mutex<BTreeMap<String, Health>>,
+}
+
+/// Serves the streams of a `FleetConfig` to local clients:
+///
+/// - `GET /ws`: websocket, subscribe to `depth.<exchange>.<symbol>`,
+///   `level.<exchange>.<symbol>`, `bookTicker.<exchange>.<symbol>` or
+///   `trades.<exchange>.<symbol>` to get
+///   `{"topic": .., "data": FleetEvent::to_json}`
+/// - `GET /topics`: topics of the fleet
+/// - `GET /depth/<exchange>/<symbol>`: latest book as a "depth" `FleetEvent::to_json`
+/// - `GET /health`: state and updates of every stream
+pub struct Gateway {
+    hub: Arc<Hub>,
+    pump: JoinHandle<()>,
+}
+
+impl Gateway {
+    /// Start the fleet of `config`, within a tokio runtime
+    pub fn start(config: &FleetConfig) -> Result<Self> {
+        let mut fleet = config.start()?;
+
+        let mut topics = BTreeSet::new();
+        let mut books = HashMap::new();
+        for subscription in &config.subscriptions {
+            for symbol in &subscription.symbols {
+                for stream in &subscription.streams {
+                    topics.insert(topic(*stream, &subscription.exchange, symbol));
+                }
+
+                let pair = (subscription.exchange.clone(), symbol.clone());
+                if let Some(manager) = fleet.depth_manager(&pair.0, &pair.1) {
+                    books.insert(pair, manager.clone());
+                }
+            }
+        }
+
+        let health = fleet
+            .names()
+            .iter()
+            .map(|name| (name.clone(), Health::default()))
+            .collect();
+        let hub = Arc::new(Hub {
+            events: broadcast::channel(BACKLOG).0,
+            topics,
+            subscribers: Mutex::default(),
+            books,
+            health: Mutex::new(health),
+        });
+
+        let pump = tokio::spawn({
+            let hub = hub.clone();
+            async move {
+                while let Some(event) = fleet.recv().await {
+                    hub.publish(&event);
+                }
+                info!("every stream of the gateway ended");
+            }
+        });
+
+        Ok(Gateway { hub, pump })
+    }
+
+    /// Routes of the gateway, to serve along others
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/ws", get(upgrade))
+            .route("/topics", get(topics))
+            .route("/depth/:exchange/:symbol", get(latest_depth))
+            .route("/health", get(health))
+            .with_state(self.hub.clone())
+    }
+
+    /// Serve on `address` until the server fails
+    pub async fn serve(&self, address: SocketAddr) -> Result<()> {
+        let server = Server::try_bind(&address)?.serve(self.router().into_make_service());
+        info!("gateway listening on {}", server.local_addr());
+
+        Ok(server.await?)
+    }
+}
+
+impl Drop for Gateway {
+    fn drop(&mut self) {
+        self.pump.abort();
+    }
+}
+
+/// "depth.binance.BTC_USDT"
+fn topic(stream: StreamKind, exchange: &str, symbol: &str) -> String {
+    let kind = match stream {
+        StreamKind::Depth => "depth",
+        StreamKind::Level => "level",
+        StreamKind::BookTicker => "bookTicker",
+        StreamKind::Ticker => "trades",
+    };
+
+    format!("{}.{}.{}", kind, exchange, symbol)
+}
+
+impl Hub {
+    fn publish(&self, event: &FleetEvent) {
+        if let Some(health) = self.health.lock().unwrap().get_mut(&event.name) {
+            match &event.data {
+                FleetData::State(state) => health.state = Some(state.clone()),
+                FleetData::Depth(depth) | FleetData::BookTicker(depth) => {
+                    health.updates += 1;
+                    health.last_lts = Some(depth.lts);
+                }
+                FleetData::Ticker(tickers) => {
+                    health.updates += 1;
+                    health.last_lts = tickers.last().map(|ticker| ticker.lts);
+                }
+            }
+        }
+
+        let topic = topic(event.stream, &event.exchange, &event.symbol);
+        let (json, msgpack) = {
+            let subscribers = self.subscribers.lock().unwrap();
+            let subscribed = |encoding| subscribers.contains_key(&(topic.clone(), encoding));
+            (subscribed(Encoding::Json), subscribed(Encoding::Msgpack))
+        };
+
+        // Nobody to encode for
+        if !json && !msgpack {
+            return;
+        }
+
+        let message = Data {
+            topic: &topic,
+            data: event,
+        };
+        let published = Published {
+            json: json.then(|| serde_json::to_string(&message).unwrap_or_default()),
+            msgpack: msgpack.then(|| rmp_serde::to_vec_named(&message).unwrap_or_default()),
+            topic,
+        };
+        let _ = self.events.send(Arc::new(published));
+    }
+
+    fn subscribe(&self, topic: &str, encoding: Encoding) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        *subscribers
+            .entry((topic.to_string(), encoding))
+            .or_default() += 1;
+    }
+
+    fn unsubscribe(&self, topic: &str, encoding: Encoding) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        let key = (topic.to_string(), encoding);
+        if let Some(count) = subscribers.get_mut(&key) {
+            *count -= 1;
+            if *count == 0 {
+                subscribers.remove(&key);
+            }
+        }
+    }
+}
+
+async fn upgrade(
+    upgrade: WebSocketUpgrade,
+    Query(connect): Query<Connect>,
+    State(hub): State<Arc<Hub>>,
+) -> Response {
+    upgrade.on_upgrade(move |socket| client(socket, hub, connect.encoding))
+}
+
+/// Answer of a subscribe or unsubscribe request, topics are all known or none is taken
+fn answer(hub: &Hub, subscribed: &mut HashSet<String>, encoding: Encoding, text: &str) -> Reply {
+    let request = match serde_json::from_str::<Request>(text) {
+        Ok(request) => request,
+        Err(e) => {
+            return Reply::Error {
+                error: format!("Bad request {}: {}", text, e),
+            }
+        }
+    };
+
+    match request {
+        Request::Subscribe { topics } => {
+            if let Some(unknown) = topics.iter().find(|topic| !hub.topics.contains(*topic)) {
+                return Reply::Error {
+                    error: format!("Unknown topic {}", unknown),
+                };
+            }
+            for topic in &topics {
+                if subscribed.insert(topic.clone()) {
+                    hub.subscribe(topic, encoding);
+                }
+            }
+            Reply::Done {
+                op: "subscribed",
+                topics,
+            }
+        }
+        Request::Unsubscribe { topics } => {
+            for topic in &topics {
+                if subscribed.remove(topic) {
+                    hub.unsubscribe(topic, encoding);
+                }
+            }
+            Reply::Done {
+                op: "unsubscribed",
+                topics,
+            }
+        }
+    }
+}
+
+async fn client(socket: WebSocket, hub: Arc<Hub>, encoding: Encoding) {
+    let (mut sink, mut requests) = socket.split();
+    let mut events = hub.events.subscribe();
+    let mut subscribed = HashSet::new();
+
+    loop {
+        let message = tokio::select! {
+            request = requests.next() => match request {
+                Some(Ok(Message::Text(text))) => {
+                    encoding.encode(&answer(&hub, &mut subscribed, encoding, &text))
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+            event = events.recv() => match event {
+                Ok(published) if subscribed.contains(&published.topic) => {
+                    match (encoding, &published.json, &published.msgpack) {
+                        (Encoding::Json, Some(json), _) => Message::Text(json.clone()),
+                        (Encoding::Msgpack, _, Some(msgpack)) => Message::Binary(msgpack.clone()),
+                        // Published before this client subscribed
+                        _ => continue,
+                    }
+                }
+                Ok(_) => continue,
+                Err(RecvError::Lagged(missed)) => encoding.encode(&Reply::Error {
+                    error: format!("Missed {} messages", missed),
+                }),
+                Err(RecvError::Closed) => break,
+            },
+        };
+
+        if sink.send(message).await.is_err() {
+            break;
+        }
+    }
+
+    for topic in &subscribed {
+        hub.unsubscribe(topic, encoding);
+    }
+    debug!("websocket client gone");
+}
+
+async fn topics(State(hub): State<Arc<Hub>>) -> Json<Vec<String>> {
+    Json(hub.topics.iter().cloned().collect())
+}
+
+async fn latest_depth(
+    Path((exchange, symbol)): Path<(String, String)>,
+    State(hub): State<Arc<Hub>>,
+) -> Response {
+    let error =
+        |status: StatusCode, error: String| (status, Json(Reply::Error { error })).into_response();
+
+    let manager = match hub.books.get(&(exchange.clone(), symbol.clone())) {
+        Some(manager) => manager,
+        None => {
+            return error(
+                StatusCode::NOT_FOUND,
+                format!("No book of {} {}", exchange, symbol),
+            )
+        }
+    };
+
+    match manager.latest_depth() {
+        Some(depth) => Json(FleetEvent {
+            name: format!("{} {} depth", exchange, symbol),
+            exchange,
+            symbol,
+            stream: StreamKind::Depth,
+            data: FleetData::Depth(depth),
+        })
+        .into_response(),
+        None => error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!("No book of {} {} yet", exchange, symbol),
+        ),
+    }
+}
+
+async fn health(State(hub): State<Arc<Hub>>) -> Json<Vec<StreamHealth>> {
+    let health = hub.health.lock().unwrap();
+
+    Json(
+        health
+            .iter()
+            .map(|(name, health)| StreamHealth {
+                name: name.clone(),
+                state: health.state.clone(),
+                updates: health.updates,
+                last_lts: health.last_lts,
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Encoding, Hub, BACKLOG};
+    use crate::{ConnectionState, FleetConfig, FleetData, FleetEvent, Gateway, StreamKind};
+    use axum::Server;
+    use futures_util::{SinkExt, StreamExt};
+    use serde_json::Value;
+    use std::collections::{BTreeSet, HashMap};
+    use std::net::SocketAddr;
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio::sync::broadcast;
+    use tokio::time::{sleep, timeout};
+    use tokio_tungstenite::tungstenite::Message;
+    use tokio_tungstenite::{accept_async, connect_async, MaybeTlsStream, WebSocketStream};
+
+    const BOOKS5: &str = r#"{"arg":{"channel":"books5","instId":"BTC-USDT"},"data":[{"asks":[["8476.98","415","0","13"],["8477","7","0","2"]],"bids":[["8476.9","256","0","12"]],"instId":"BTC-USDT","ts":"1597026383085"}]}"#;
+
+    const TRADES: &str = r#"{"arg":{"channel":"trades","instId":"BTC-USDT"},"data":[{"instId":"BTC-USDT","tradeId":"130639474","px":"42219.9","sz":"0.12060306","side":"sell","ts":"1630048897897","count":"3"}]}"#;
+
+    type Client = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+    /// Okx on a local port, pushing `books5` or `trades`
+    /// every 20ms to the connections subscribed to them
+    async fn mock_okx() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("ws://{}", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut ws = accept_async(socket).await.unwrap();
+                    let request = ws.next().await.unwrap().unwrap().into_text().unwrap();
+                    let push = if request.contains(r#""channel":"trades""#) {
+                        TRADES
+                    } else {
+                        BOOKS5
+                    };
+
+                    while ws.send(Message::from(push)).await.is_ok() {
+                        sleep(Duration::from_millis(20)).await;
+                    }
+                });
+            }
+        });
+
+        address
+    }
+
+    /// Gateway of the mock for "okx BTC_USDT" level and trades
+    async fn start() -> (Gateway, SocketAddr) {
+        let exchange = mock_okx().await;
+        let config = FleetConfig::from_toml(&format!(
+            r#"
+[[subscriptions]]
+exchange = "okx"
+symbols = ["BTC_USDT"]
+streams = ["level", "ticker"]
+endpoints = {{ websocket = "{0}", ticker = "{0}" }}
+"#,
+            exchange
+        ))
+        .unwrap();
+
+        let gateway = Gateway::start(&config).unwrap();
+        let server = Server::bind(&"127.0.0.1:0".parse().unwrap())
+            .serve(gateway.router().into_make_service());
+        let address = server.local_addr();
+        tokio::spawn(server);
+
+        (gateway, address)
+    }
+
+    /// Answer of the request, data of earlier subscriptions is skipped
+    async fn request(client: &mut Client, text: &str) -> Value {
+        client.send(Message::from(text)).await.unwrap();
+        loop {
+            let message = next_text(client).await;
+            if message.get("topic").is_none() {
+                return message;
+            }
+        }
+    }
+
+    async fn next_text(client: &mut Client) -> Value {
+        let message = client.next().await.unwrap().unwrap();
+        serde_json::from_str(&message.into_text().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn publish_only_subscribed_encodings() {
+        let hub = Hub {
+            events: broadcast::channel(BACKLOG).0,
+            topics: BTreeSet::from(["level.okx.BTC_USDT".to_string()]),
+            subscribers: Mutex::default(),
+            books: HashMap::new(),
+            health: Mutex::default(),
+        };
+        let mut events = hub.events.subscribe();
+        let event = FleetEvent {
+            name: "okx BTC_USDT level".to_string(),
+            exchange: "okx".to_string(),
+            symbol: "BTC_USDT".to_string(),
+            stream: StreamKind::Level,
+            data: FleetData::State(ConnectionState::Connected),
+        };
+
+        hub.publish(&event);
+        assert!(events.try_recv().is_err());
+
+        hub.subscribe("level.okx.BTC_USDT", Encoding::Msgpack);
+        hub.publish(&event);
+        let published = events.try_recv().unwrap();
+        assert_eq!(published.topic, "level.okx.BTC_USDT");
+        assert!(published.json.is_none());
+        assert!(published.msgpack.is_some());
+
+        hub.unsubscribe("level.okx.BTC_USDT", Encoding::Msgpack);
+        hub.publish(&event);
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn websocket_topics() {
+        let (_gateway, address) = start().await;
+        let (mut client, _) = connect_async(format!("ws://{}/ws", address)).await.unwrap();
+
+        timeout(Duration::from_secs(10), async {
+            let answer = request(
+                &mut client,
+                r#"{"op":"subscribe","topics":["level.okx.ETH_USDT"]}"#,
+            )
+            .await;
+            assert_eq!(answer["error"], "Unknown topic level.okx.ETH_USDT");
+
+            let answer = request(
+                &mut client,
+                r#"{"op":"subscribe","topics":["level.okx.BTC_USDT","trades.okx.BTC_USDT"]}"#,
+            )
+            .await;
+            assert_eq!(answer["op"], "subscribed");
+
+            let (mut depth, mut trades) = (false, false);
+            while !(depth && trades) {
+                let message = next_text(&mut client).await;
+                let data = &message["data"]["v1"];
+                assert_eq!(data["exchange"], "okx");
+                assert_eq!(data["symbol"], "BTC_USDT");
+
+                match message["topic"].as_str().unwrap() {
+                    "level.okx.BTC_USDT" if data["type"] == "depth" => {
+                        assert_eq!(data["asks"][0][0], 8476.98);
+                        assert_eq!(data["bids"][0][2], 12);
+                        depth = true;
+                    }
+                    "level.okx.BTC_USDT" => {
+                        assert_eq!(data["type"], "state");
+                        assert_eq!(data["state"], "connected");
+                    }
+                    "trades.okx.BTC_USDT" => {
+                        assert_eq!(data["trades"][0]["price"], 42219.9);
+                        assert_eq!(data["trades"][0]["direction"], "sell");
+                        trades = true;
+                    }
+                    topic => panic!("Not subscribed to {}", topic),
+                }
+            }
+
+            let answer = request(
+                &mut client,
+                r#"{"op":"unsubscribe","topics":["trades.okx.BTC_USDT"]}"#,
+            )
+            .await;
+            assert_eq!(answer["op"], "unsubscribed");
+            let answer = request(&mut client, "subscribe").await;
+            assert!(answer["error"].as_str().unwrap().starts_with("Bad request"));
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn websocket_msgpack() {
+        let (_gateway, address) = start().await;
+        let (mut client, _) = connect_async(format!("ws://{}/ws?encoding=msgpack", address))
+            .await
+            .unwrap();
+
+        timeout(Duration::from_secs(10), async {
+            client
+                .send(Message::from(
+                    r#"{"op":"subscribe","topics":["trades.okx.BTC_USDT"]}"#,
+                ))
+                .await
+                .unwrap();
+
+            let mut answers = vec![];
+            while answers.len() < 2 {
+                match client.next().await.unwrap().unwrap() {
+                    Message::Binary(bytes) => {
+                        answers.push(rmp_serde::from_slice::<Value>(&bytes).unwrap())
+                    }
+                    message => panic!("Not binary {:?}", message),
+                }
+            }
+
+            assert_eq!(answers[0]["op"], "subscribed");
+            assert_eq!(answers[1]["topic"], "trades.okx.BTC_USDT");
+            assert_eq!(answers[1]["data"]["v1"]["trades"][0]["amount"], 0.12060306);
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn http_books_and_health() {
+        let (_gateway, address) = start().await;
+        let get = |path: &str| reqwest::get(format!("http://{}{}", address, path));
+
+        timeout(Duration::from_secs(10), async {
+            let topics: Value = get("/topics").await.unwrap().json().await.unwrap();
+            assert_eq!(
+                topics.to_string(),
+                r#"["level.okx.BTC_USDT","trades.okx.BTC_USDT"]"#
+            );
+
+            let missing = get("/depth/okx/ETH_USDT").await.unwrap();
+            assert_eq!(missing.status(), 404);
+
+            let book = loop {
+                let response = get("/depth/okx/BTC_USDT").await.unwrap();
+                if response.status() == 200 {
+                    break response.json::<Value>().await.unwrap();
+                }
+                assert_eq!(response.status(), 503);
+                sleep(Duration::from_millis(20)).await;
+            };
+            assert_eq!(book["v1"]["type"], "depth");
+            assert_eq!(book["v1"]["exchange"], "okx");
+            assert_eq!(book["v1"]["asks"][1][0], 8477.0);
+            assert_eq!(book["v1"]["bids"][0][0], 8476.9);
+
+            let health = loop {
+                let health: Value = get("/health").await.unwrap().json().await.unwrap();
+                if health[0]["updates"].as_u64() > Some(0)
+                    && health[1]["updates"].as_u64() > Some(0)
+                {
+                    break health;
+                }
+                sleep(Duration::from_millis(20)).await;
+            };
+            assert_eq!(health[0]["name"], "okx BTC_USDT level");
+            assert_eq!(health[0]["state"]["v1"], "connected");
+            assert_eq!(health[1]["name"], "okx BTC_USDT ticker");
+            assert!(health[1]["last_lts"].is_i64());
+        })
+        .await
+        .unwrap();
+    }
+}
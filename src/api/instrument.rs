@@ -0,0 +1,347 @@
+use crate::AdapterRegistry;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::sync::{OnceLock, RwLock};
+use tracing::warn;
+
+/// Trading state of an instrument
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InstrumentStatus {
+    Trading,
+    /// Listed but not trading yet
+    Pending,
+    /// Trading is paused, e.g. a break or an auction
+    Halted,
+    /// Delisted, delivered or settled
+    Closed,
+}
+
+/// What an instrument trades, as the exchange info endpoints list it.
+///
+/// Only the kind: the terms of a contract (margin, expiry, strike) are in
+/// `config::InstrumentType` of a parsed symbol, see `InstrumentType::kind`
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InstrumentKind {
+    Spot,
+    Perpetual,
+    Future,
+    Option,
+}
+
+impl InstrumentKind {
+    pub fn is_spot(&self) -> bool {
+        matches!(self, InstrumentKind::Spot)
+    }
+}
+
+/// Trading rules of one instrument from the exchange info endpoints
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct InstrumentInfo {
+    /// Name in exchange notation, e.g. "BTCUSDT" / "BTCUSD-PERP"
+    pub name: String,
+
+    pub kind: InstrumentKind,
+
+    /// Minimum price step
+    pub tick_size: f64,
+
+    /// Minimum quantity step
+    pub lot_size: f64,
+
+    /// Minimum price * quantity of an order, if the exchange tells
+    #[serde(default)]
+    pub min_notional: Option<f64>,
+
+    /// Value of one contract, `None` for spot
+    #[serde(default)]
+    pub contract_size: Option<f64>,
+
+    /// Delivery time in milliseconds, `None` for spot and perpetuals
+    #[serde(default)]
+    pub expiry: Option<i64>,
+
+    pub status: InstrumentStatus,
+}
+
+/// (name, is spot) => instrument
+type Instruments = HashMap<(String, bool), InstrumentInfo>;
+
+/// Instruments of one exchange
+struct Listing {
+    instruments: Instruments,
+    /// Every instrument of the exchange, not only the bundled ones
+    complete: bool,
+}
+
+/// Instruments bundled with the crate, in the format of `save_file`
+const CACHE: &str = include_str!("instruments.json");
+
+/// Instruments by exchange, used to reject unknown symbols
+/// before a connection is made.
+///
+/// Spot and contracts may share a name (Binance "BTCUSDT"),
+/// so lookups tell which one they want.
+/// Exchanges without loaded instruments are not checked.
+///
+/// The global registry starts from the cache bundled with the crate,
+/// which only holds some instruments, so names missing from it are
+/// logged and let through. `load`, `load_file` or `insert` replace it
+/// with the current instruments, after which unknown names are rejected
+#[derive(Default)]
+pub struct InstrumentRegistry {
+    exchanges: RwLock<HashMap<String, Listing>>,
+}
+
+impl InstrumentRegistry {
+    /// Registry checked by `ExchangeAdapter::depth_config` and `ticker_config`
+    pub fn global() -> &'static InstrumentRegistry {
+        static REGISTRY: OnceLock<InstrumentRegistry> = OnceLock::new();
+
+        REGISTRY.get_or_init(InstrumentRegistry::cached)
+    }
+
+    /// Registry of the instrument cache bundled with the crate
+    pub fn cached() -> InstrumentRegistry {
+        let registry = InstrumentRegistry::default();
+        registry
+            .load_json(CACHE, false)
+            .expect("Bundled instrument cache is valid");
+        registry
+    }
+
+    /// Replaces the instruments of `exchange` with every one it lists
+    pub fn insert(&self, exchange: &str, instruments: Vec<InstrumentInfo>) {
+        self.insert_listing(exchange, instruments, true);
+    }
+
+    fn insert_listing(&self, exchange: &str, instruments: Vec<InstrumentInfo>, complete: bool) {
+        let instruments = instruments
+            .into_iter()
+            .map(|instrument| {
+                let key = (instrument.name.clone(), instrument.kind.is_spot());
+                (key, instrument)
+            })
+            .collect();
+
+        let mut exchanges = self.exchanges.write().unwrap();
+        let listing = Listing {
+            instruments,
+            complete,
+        };
+        exchanges.insert(exchange.to_string(), listing);
+    }
+
+    pub fn get(&self, exchange: &str, name: &str, spot: bool) -> Option<InstrumentInfo> {
+        let exchanges = self.exchanges.read().unwrap();
+        exchanges
+            .get(exchange)?
+            .instruments
+            .get(&(name.to_string(), spot))
+            .cloned()
+    }
+
+    /// Instruments of `exchange` sorted by name, spot first
+    pub fn instruments(&self, exchange: &str) -> Vec<InstrumentInfo> {
+        let exchanges = self.exchanges.read().unwrap();
+        let mut instruments: Vec<_> = exchanges
+            .get(exchange)
+            .map(|listing| listing.instruments.values().cloned().collect())
+            .unwrap_or_default();
+
+        instruments.sort_by_key(|instrument| (instrument.name.clone(), !instrument.kind.is_spot()));
+        instruments
+    }
+
+    /// Sorted names of the exchanges with loaded instruments
+    pub fn exchanges(&self) -> Vec<String> {
+        let mut exchanges: Vec<_> = self.exchanges.read().unwrap().keys().cloned().collect();
+        exchanges.sort();
+        exchanges
+    }
+
+    /// Err if `exchange` is loaded but `name` is not trading,
+    /// or is unknown to instruments that are not only the bundled ones
+    pub fn validate(&self, exchange: &str, name: &str, spot: bool) -> Result<()> {
+        let exchanges = self.exchanges.read().unwrap();
+        let listing = match exchanges.get(exchange) {
+            Some(listing) => listing,
+            None => return Ok(()),
+        };
+
+        match listing.instruments.get(&(name.to_string(), spot)) {
+            Some(instrument) if instrument.status == InstrumentStatus::Trading => Ok(()),
+            Some(instrument) => Err(anyhow!(
+                "Instrument {} of {} is {:?}",
+                name,
+                exchange,
+                instrument.status
+            )),
+            None if listing.complete => Err(anyhow!("Unknown instrument {} of {}", name, exchange)),
+            None => {
+                warn!(
+                    "{} of {} is not in the bundled instruments, \
+                     load the instruments of the exchange to check it",
+                    name, exchange
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Fetch the instruments of `exchange` from its exchange info endpoints,
+    /// returns the number of instruments
+    pub async fn load(&self, exchange: &str) -> Result<usize> {
+        let adapter = AdapterRegistry::global()
+            .get(exchange)
+            .ok_or_else(|| anyhow!("Unsupported Exchange {}", exchange))?;
+
+        let instruments = adapter.instruments().await?;
+        let count = instruments.len();
+        self.insert(exchange, instruments);
+
+        Ok(count)
+    }
+
+    /// Load every exchange of a file written by `save_file`,
+    /// returns the number of instruments
+    pub fn load_file(&self, path: impl AsRef<Path>) -> Result<usize> {
+        self.load_json(&fs::read_to_string(path)?, true)
+    }
+
+    /// Load every exchange of `text` in the format of `save_file`
+    fn load_json(&self, text: &str, complete: bool) -> Result<usize> {
+        let cache: BTreeMap<String, Vec<InstrumentInfo>> = serde_json::from_str(text)?;
+
+        let mut count = 0;
+        for (exchange, instruments) in cache {
+            count += instruments.len();
+            self.insert_listing(&exchange, instruments, complete);
+        }
+
+        Ok(count)
+    }
+
+    /// Write every loaded exchange as JSON, for offline use with `load_file`
+    pub fn save_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        let cache: BTreeMap<String, Vec<InstrumentInfo>> = self
+            .exchanges()
+            .into_iter()
+            .map(|exchange| {
+                let instruments = self.instruments(&exchange);
+                (exchange, instruments)
+            })
+            .collect();
+
+        fs::write(path, serde_json::to_string_pretty(&cache)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::instrument::{
+        InstrumentInfo, InstrumentKind, InstrumentRegistry, InstrumentStatus,
+    };
+    use std::env;
+
+    fn instrument(name: &str, status: InstrumentStatus) -> InstrumentInfo {
+        InstrumentInfo {
+            name: name.to_string(),
+            kind: InstrumentKind::Spot,
+            tick_size: 0.01,
+            lot_size: 0.00001,
+            min_notional: Some(5.0),
+            contract_size: None,
+            expiry: None,
+            status,
+        }
+    }
+
+    #[test]
+    fn validate_loaded_exchanges() {
+        let registry = InstrumentRegistry::default();
+        assert!(registry.validate("binance", "BTCUSTD", true).is_ok());
+
+        let perpetual = InstrumentInfo {
+            kind: InstrumentKind::Perpetual,
+            tick_size: 0.1,
+            ..instrument("BTCUSDT", InstrumentStatus::Trading)
+        };
+        registry.insert(
+            "binance",
+            vec![
+                instrument("BTCUSDT", InstrumentStatus::Trading),
+                instrument("LUNAUSDT", InstrumentStatus::Closed),
+                perpetual,
+            ],
+        );
+
+        assert!(registry.validate("binance", "BTCUSDT", true).is_ok());
+        assert!(registry.validate("binance", "BTCUSTD", true).is_err());
+        assert!(registry.validate("binance", "LUNAUSDT", true).is_err());
+        assert!(registry.validate("binance", "LUNAUSDT", false).is_err());
+        assert!(registry.validate("crypto", "BTC_USTD", true).is_ok());
+
+        let spot = registry.get("binance", "BTCUSDT", true).unwrap();
+        assert_eq!(spot.tick_size, 0.01);
+        let perpetual = registry.get("binance", "BTCUSDT", false).unwrap();
+        assert_eq!(perpetual.tick_size, 0.1);
+    }
+
+    #[test]
+    fn bundled_cache() {
+        let registry = InstrumentRegistry::cached();
+        assert_eq!(registry.exchanges(), vec!["binance", "crypto"]);
+
+        assert!(registry.validate("binance", "BTCUSDT", true).is_ok());
+        assert!(registry.validate("binance", "BTCUSDT", false).is_ok());
+        // Only some instruments are bundled
+        assert!(registry.validate("binance", "AVAXUSDT", true).is_ok());
+        // Delivered at the end of 2022
+        assert!(registry
+            .validate("binance", "BTCUSD_221230", false)
+            .is_err());
+        assert!(registry.validate("crypto", "BTCUSD-PERP", false).is_ok());
+        assert!(registry.validate("crypto", "AVAX_USDT", true).is_ok());
+        assert!(registry.validate("kraken", "BTC/USD", true).is_ok());
+
+        let binance = registry.instruments("binance");
+        registry.insert("binance", binance);
+        assert!(registry.validate("binance", "AVAXUSDT", true).is_err());
+    }
+
+    #[test]
+    fn cache_file() {
+        let registry = InstrumentRegistry::default();
+        registry.insert(
+            "binance",
+            vec![
+                instrument("ETHUSDT", InstrumentStatus::Trading),
+                instrument("BTCUSDT", InstrumentStatus::Trading),
+            ],
+        );
+        registry.insert(
+            "crypto",
+            vec![instrument("BTC_USDT", InstrumentStatus::Halted)],
+        );
+
+        let path = env::temp_dir().join(format!("instruments-{}.json", std::process::id()));
+        registry.save_file(&path).unwrap();
+
+        let loaded = InstrumentRegistry::default();
+        assert_eq!(loaded.load_file(&path).unwrap(), 3);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.exchanges(), vec!["binance", "crypto"]);
+        assert_eq!(
+            loaded.instruments("binance"),
+            registry.instruments("binance")
+        );
+        assert_eq!(loaded.instruments("binance")[0].name, "BTCUSDT");
+        assert!(loaded.validate("crypto", "BTC_USDT", true).is_err());
+        assert!(loaded.validate("binance", "AVAXUSDT", true).is_err());
+    }
+}
@@ -0,0 +1,58 @@
+use crate::binance::BinanceKline;
+use crate::crypto::CryptoKline;
+use crate::{get_kline_config_from, ExchangeType, KlineConfig, KlineConnection};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+#[derive(Clone)]
+pub struct KlineManager {
+    pub config: KlineConfig,
+    connection: KlineConnection,
+}
+
+impl KlineManager {
+    /// interval: "1m" / "1h" / "1d"
+    pub fn new(exchange: &str, symbol: &str, interval: &str) -> Self {
+        let config = get_kline_config_from(exchange, symbol, interval);
+
+        assert!(config.is_correct(), "Unsupported config {:?}", config);
+
+        let connection = match config.exchange_type {
+            ExchangeType::Binance => KlineConnection::Binance(BinanceKline::new()),
+            ExchangeType::Crypto => KlineConnection::Crypto(CryptoKline::new()),
+            other => panic!("Kline is unsupported for {}", other.name()),
+        };
+
+        Self { config, connection }
+    }
+
+    /// Get bar stream
+    pub fn subscribe(&self) -> UnboundedReceiver<Bar> {
+        let config = self.config.clone();
+        match &self.connection {
+            KlineConnection::Binance(connection) => connection.connect(config).unwrap(),
+            KlineConnection::Crypto(connection) => connection.connect(config).unwrap(),
+        }
+    }
+}
+
+/// OHLCV bar, either from an exchange kline stream
+/// or built locally by `BarAggregator`
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Bar {
+    /// Open time, first trade time for tick and volume bars
+    pub open_ts: i64,
+    /// Close time, last trade time for tick and volume bars
+    pub close_ts: i64,
+    /// Receive time
+    pub lts: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    /// Base asset volume
+    pub volume: f64,
+    /// Number of trades, `None` if exchange does not tell
+    pub trades: Option<u64>,
+    /// `false` while the bar may still be updated
+    pub closed: bool,
+}
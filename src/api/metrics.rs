@@ -0,0 +1,251 @@
+//! Counters and histograms of every connection, recorded through the
+//! `metrics` facade so they cost nothing until the application installs a
+//! recorder, e.g. `install_prometheus` of the "prometheus" feature.
+//!
+//! Messages/s and bytes/s are the rates of `MESSAGES` and `BYTES`.
+use crate::{Depth, Ticker};
+use metrics::{counter, histogram};
+use std::time::Duration;
+
+/// Websocket text frames received, by `exchange`
+pub const MESSAGES: &str = "snapshot_messages_total";
+/// Bytes of the websocket text frames, by `exchange`
+pub const BYTES: &str = "snapshot_bytes_total";
+/// Frames that did not decode, by `exchange`
+pub const DECODE_ERRORS: &str = "snapshot_decode_errors_total";
+/// Websockets connected again after they failed or closed, by `exchange`
+pub const RECONNECTS: &str = "snapshot_reconnects_total";
+/// Books dropped and rebuilt from a fresh snapshot, by `exchange`
+pub const RESYNCS: &str = "snapshot_resyncs_total";
+/// Seconds to fetch a REST snapshot, by `exchange`
+pub const SNAPSHOT_FETCH: &str = "snapshot_fetch_seconds";
+/// `lts - ts` of the books and trades sent by the connections, by `exchange` and `symbol`
+pub const LATENCY: &str = "snapshot_latency_milliseconds";
+
+pub(crate) fn frame(exchange: &'static str, bytes: usize) {
+    counter!(MESSAGES, "exchange" => exchange).increment(1);
+    counter!(BYTES, "exchange" => exchange).increment(bytes as u64);
+}
+
+pub(crate) fn decode_error(exchange: &'static str) {
+    counter!(DECODE_ERRORS, "exchange" => exchange).increment(1);
+}
+
+pub(crate) fn reconnect(exchange: &'static str) {
+    counter!(RECONNECTS, "exchange" => exchange).increment(1);
+}
+
+pub(crate) fn resync(exchange: &'static str) {
+    counter!(RESYNCS, "exchange" => exchange).increment(1);
+}
+
+pub(crate) fn snapshot_fetch(exchange: &'static str, elapsed: Duration) {
+    histogram!(SNAPSHOT_FETCH, "exchange" => exchange).record(elapsed.as_secs_f64());
+}
+
+/// Latency of a book of `symbol`, the exchange's own symbol
+pub(crate) fn book(exchange: &'static str, symbol: &str, depth: &Depth) {
+    latency(exchange, symbol, depth.ts, depth.lts);
+}
+
+/// Latency of every trade
+pub(crate) fn trades(exchange: &'static str, symbol: &str, tickers: &[Ticker]) {
+    for ticker in tickers {
+        latency(exchange, symbol, ticker.ts, ticker.lts);
+    }
+}
+
+/// Exchanges without a send time report `ts == lts`, a latency of 0
+fn latency(exchange: &'static str, symbol: &str, ts: i64, lts: i64) {
+    histogram!(LATENCY, "exchange" => exchange, "symbol" => symbol.to_string())
+        .record((lts - ts) as f64);
+}
+
+/// Serve every metric at `http://<listen>/metrics` in the Prometheus text format,
+/// within a tokio runtime or on a thread of its own.
+/// Installs the global recorder, only once per process
+#[cfg(feature = "prometheus")]
+pub fn install_prometheus(listen: std::net::SocketAddr) -> anyhow::Result<()> {
+    use metrics::{describe_counter, describe_histogram, Unit};
+    use metrics_exporter_prometheus::{Matcher, PrometheusBuilder};
+
+    const LATENCY_BUCKETS: [f64; 12] = [
+        1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0, 2000.0, 5000.0,
+    ];
+    const FETCH_BUCKETS: [f64; 8] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+    PrometheusBuilder::new()
+        .with_http_listener(listen)
+        .set_buckets_for_metric(Matcher::Full(LATENCY.to_string()), &LATENCY_BUCKETS)
+        .and_then(|builder| {
+            builder
+                .set_buckets_for_metric(Matcher::Full(SNAPSHOT_FETCH.to_string()), &FETCH_BUCKETS)
+        })
+        .and_then(|builder| builder.install())
+        .map_err(|e| anyhow::anyhow!("Prometheus exporter on {}: {}", listen, e))?;
+
+    describe_counter!(MESSAGES, "Websocket text frames received");
+    describe_counter!(BYTES, Unit::Bytes, "Bytes of the websocket text frames");
+    describe_counter!(DECODE_ERRORS, "Frames that did not decode");
+    describe_counter!(
+        RECONNECTS,
+        "Websockets connected again after they failed or closed"
+    );
+    describe_counter!(RESYNCS, "Books rebuilt from a fresh snapshot");
+    describe_histogram!(
+        SNAPSHOT_FETCH,
+        Unit::Seconds,
+        "Time to fetch a REST snapshot"
+    );
+    describe_histogram!(
+        LATENCY,
+        Unit::Milliseconds,
+        "Receive time minus exchange send time"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::metrics::{
+        self, BYTES, DECODE_ERRORS, LATENCY, MESSAGES, RESYNCS, SNAPSHOT_FETCH,
+    };
+    use crate::{ConnectionState, Depth, OrderDirection, RawFrames, StateSender, Ticker};
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use std::time::Duration;
+
+    /// Name, labels and value of a recorded metric
+    type Recorded = (String, Vec<(String, String)>, DebugValue);
+
+    /// Value of `name` with labels `labels`, e.g. `[("exchange", "binance")]`
+    fn value<'a>(
+        values: &'a [Recorded],
+        name: &str,
+        labels: &[(&str, &str)],
+    ) -> Option<&'a DebugValue> {
+        values
+            .iter()
+            .find(|(key, pairs, _)| {
+                key == name
+                    && pairs.len() == labels.len()
+                    && labels.iter().all(|(label, value)| {
+                        pairs.contains(&(label.to_string(), value.to_string()))
+                    })
+            })
+            .map(|(_, _, value)| value)
+    }
+
+    fn ticker(ts: i64, lts: i64) -> Ticker {
+        Ticker {
+            ts,
+            lts,
+            id: 1,
+            price: 100.0,
+            amount: 1.0,
+            direction: OrderDirection::Buy,
+        }
+    }
+
+    #[test]
+    fn connection_metrics() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+
+        ::metrics::with_local_recorder(&recorder, || {
+            let frames = RawFrames::default();
+            frames.publish("binance", "0123456789");
+            frames.publish("binance", "01234");
+            frames.publish("okx", "0");
+            metrics::decode_error("okx");
+
+            let state = StateSender::new("okx");
+            state.send(ConnectionState::Connected);
+            state.send(ConnectionState::Resyncing(String::from("gap")));
+            metrics::snapshot_fetch("binance", Duration::from_millis(250));
+            let depth = Depth {
+                ts: 1_000,
+                lts: 1_012,
+                id: 1,
+                asks: vec![],
+                bids: vec![],
+            };
+            metrics::book("binance", "BTCUSDT", &depth);
+            metrics::trades("binance", "BTCUSDT", &[ticker(1_000, 1_003)]);
+        });
+
+        let values = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _, _, value)| {
+                let (_, key) = key.into_parts();
+                let labels = key
+                    .labels()
+                    .map(|label| (label.key().to_string(), label.value().to_string()))
+                    .collect();
+                (key.name().to_string(), labels, value)
+            })
+            .collect::<Vec<Recorded>>();
+
+        let binance = [("exchange", "binance")];
+        assert_eq!(
+            value(&values, MESSAGES, &binance),
+            Some(&DebugValue::Counter(2))
+        );
+        assert_eq!(
+            value(&values, BYTES, &binance),
+            Some(&DebugValue::Counter(15))
+        );
+        assert_eq!(
+            value(&values, DECODE_ERRORS, &[("exchange", "okx")]),
+            Some(&DebugValue::Counter(1))
+        );
+        assert_eq!(value(&values, DECODE_ERRORS, &binance), None);
+        assert_eq!(
+            value(&values, RESYNCS, &[("exchange", "okx")]),
+            Some(&DebugValue::Counter(1))
+        );
+        assert_eq!(
+            value(&values, SNAPSHOT_FETCH, &binance),
+            Some(&DebugValue::Histogram(vec![0.25.into()]))
+        );
+        assert_eq!(
+            value(
+                &values,
+                LATENCY,
+                &[("exchange", "binance"), ("symbol", "BTCUSDT")]
+            ),
+            Some(&DebugValue::Histogram(vec![12.0.into(), 3.0.into()]))
+        );
+    }
+
+    #[cfg(feature = "prometheus")]
+    #[tokio::test]
+    async fn prometheus_endpoint() {
+        let listen = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        metrics::install_prometheus(listen).unwrap();
+        assert!(metrics::install_prometheus(listen).is_err());
+
+        RawFrames::default().publish("prometheus-test", "{}");
+        metrics::trades("prometheus-test", "BTCUSDT", &[ticker(1_000, 1_004)]);
+
+        let address = format!("http://{}/metrics", listen);
+        let mut text = String::new();
+        for _ in 0..50 {
+            if let Ok(respond) = reqwest::get(&address).await {
+                text = respond.text().await.unwrap();
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        assert!(text.contains(r#"snapshot_messages_total{exchange="prometheus-test"} 1"#));
+        assert!(text.contains(r#"snapshot_bytes_total{exchange="prometheus-test"} 2"#));
+        assert!(text.contains(
+            r#"snapshot_latency_milliseconds_bucket{exchange="prometheus-test",symbol="BTCUSDT",le="5"} 1"#
+        ));
+    }
+}
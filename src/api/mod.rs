@@ -1,5 +1,53 @@
+pub mod adapter;
+pub mod bar;
+pub mod book;
+pub mod checksum;
+#[cfg(feature = "parquet")]
+pub mod columnar;
 pub mod depth;
+pub(crate) mod driver;
+pub mod fleet;
+pub(crate) mod format;
+#[cfg(feature = "gateway")]
+pub mod gateway;
+pub mod instrument;
+pub mod kline;
+pub mod metrics;
+#[cfg(test)]
+pub(crate) mod mock;
+pub mod raw;
+pub mod reconnect;
+pub mod sink;
+pub mod state;
+pub(crate) mod sync;
 pub mod ticker;
+pub mod time;
+pub mod wire;
 
-pub use depth::{Depth, DepthManager, ExchangeType, Quote};
-pub use ticker::{OrderDirection, Ticker, TickerManager};
+pub use adapter::{AdapterRegistry, ExchangeAdapter};
+pub use bar::{BarAggregator, BarKind};
+pub use book::{BookDiff, BookLevel, BookSide, OrderBook};
+pub use checksum::{ChecksumVerifier, Crc32Checksum};
+#[cfg(feature = "parquet")]
+pub use columnar::{
+    convert_records, Conversion, DepthLayout, DepthParquetWriter, ParquetOptions,
+    TickerParquetWriter,
+};
+pub use depth::{Depth, DepthManager, DepthT, ExchangeType, Quote};
+pub use fleet::{
+    EndpointOverrides, Fleet, FleetConfig, FleetData, FleetEvent, SinkConfig, StreamKind,
+    SubscriptionConfig,
+};
+#[cfg(feature = "gateway")]
+pub use gateway::Gateway;
+pub use instrument::{InstrumentInfo, InstrumentKind, InstrumentRegistry, InstrumentStatus};
+pub use kline::{Bar, KlineManager};
+pub use raw::{RawFrame, RawFrames};
+pub(crate) use reconnect::Backoff;
+pub use reconnect::ReconnectPolicy;
+pub use sink::{run_sink, CsvSink, FileSinkConfig, JsonlSink, Sink, TimeFormat};
+pub use state::ConnectionState;
+pub(crate) use state::StateSender;
+pub use ticker::{OrderDirection, Ticker, TickerManager, TickerT};
+pub(crate) use time::{format_time, parse_time};
+pub use wire::SCHEMA_VERSION;
@@ -0,0 +1,88 @@
+use crate::api::metrics;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+/// A websocket text frame as the exchange sent it, before decoding
+#[derive(Clone, Debug, PartialEq)]
+pub struct RawFrame {
+    /// Registry name of the exchange, e.g. "binance"
+    pub exchange: &'static str,
+    /// Receive time
+    pub lts: i64,
+    pub text: String,
+}
+
+/// Text frames of every connection, for recording what the exchanges send,
+/// frames are only copied while somebody subscribes
+#[derive(Default)]
+pub struct RawFrames {
+    senders: Mutex<Vec<UnboundedSender<RawFrame>>>,
+    active: AtomicBool,
+}
+
+impl RawFrames {
+    pub fn global() -> &'static RawFrames {
+        static FRAMES: OnceLock<RawFrames> = OnceLock::new();
+
+        FRAMES.get_or_init(RawFrames::default)
+    }
+
+    /// Frames received from now on, of every exchange
+    pub fn subscribe(&self) -> UnboundedReceiver<RawFrame> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        let mut senders = self.senders.lock().unwrap();
+        senders.push(sender);
+        self.active.store(true, Ordering::Release);
+
+        receiver
+    }
+
+    /// Subscribers that are gone are dropped,
+    /// every frame is counted in `api::metrics` whether anybody subscribes or not
+    pub(crate) fn publish(&self, exchange: &'static str, text: &str) {
+        metrics::frame(exchange, text.len());
+
+        if !self.active.load(Ordering::Acquire) {
+            return;
+        }
+
+        let lts = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        let frame = RawFrame {
+            exchange,
+            lts,
+            text: text.to_string(),
+        };
+
+        let mut senders = self.senders.lock().unwrap();
+        senders.retain(|sender| sender.send(frame.clone()).is_ok());
+        self.active.store(!senders.is_empty(), Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::raw::RawFrames;
+
+    #[test]
+    fn frames_reach_subscribers() {
+        let frames = RawFrames::default();
+        frames.publish("binance", "dropped");
+
+        let mut receiver = frames.subscribe();
+        frames.publish("binance", "{}");
+        let frame = receiver.try_recv().unwrap();
+        assert_eq!(frame.exchange, "binance");
+        assert_eq!(frame.text, "{}");
+        assert!(receiver.try_recv().is_err());
+
+        drop(receiver);
+        frames.publish("binance", "{}");
+        assert!(frames.senders.lock().unwrap().is_empty());
+    }
+}
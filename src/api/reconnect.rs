@@ -0,0 +1,156 @@
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::time::{sleep, Instant};
+use tracing::warn;
+
+/// How often a connection that failed is opened again,
+/// the delay doubles from `initial_delay_ms` up to `max_delay_ms`
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReconnectPolicy {
+    pub initial_delay_ms: u64,
+    pub max_delay_ms: u64,
+    /// `None` retries forever
+    pub max_retries: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        ReconnectPolicy {
+            initial_delay_ms: 100,
+            max_delay_ms: 30_000,
+            max_retries: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Delay before retry `attempt`, counted from 1
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 1u64
+            .checked_shl(attempt.saturating_sub(1))
+            .unwrap_or(u64::MAX);
+        let delay = self.initial_delay_ms.saturating_mul(factor);
+
+        Duration::from_millis(delay.min(self.max_delay_ms))
+    }
+}
+
+/// A connection open this long worked,
+/// a server that accepts and drops at once keeps backing off
+const MIN_CONNECTED: Duration = Duration::from_secs(10);
+
+/// Failed connections in a row of a connection loop
+pub(crate) struct Backoff {
+    policy: ReconnectPolicy,
+    attempt: u32,
+    /// Open time of the current connection
+    connected: Option<Instant>,
+}
+
+impl Backoff {
+    pub fn new(policy: ReconnectPolicy) -> Self {
+        Backoff {
+            policy,
+            attempt: 0,
+            connected: None,
+        }
+    }
+
+    /// Connected, the next failure waits `initial_delay_ms` again
+    /// if the connection lasts `MIN_CONNECTED`
+    pub fn connected(&mut self) {
+        self.connected = Some(Instant::now());
+    }
+
+    /// Wait before connecting again, after a failed connection or a dropped one,
+    /// false once `max_retries` ran out and the loop should end
+    pub async fn wait(&mut self, exchange: &str) -> bool {
+        if let Some(connected) = self.connected.take() {
+            if connected.elapsed() >= MIN_CONNECTED {
+                self.attempt = 0;
+            }
+        }
+
+        self.attempt += 1;
+        if self
+            .policy
+            .max_retries
+            .is_some_and(|retries| self.attempt > retries)
+        {
+            warn!("{} gave up after {} retries", exchange, self.attempt - 1);
+            return false;
+        }
+
+        sleep(self.policy.delay(self.attempt)).await;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::reconnect::{Backoff, ReconnectPolicy, MIN_CONNECTED};
+    use std::time::Duration;
+    use tokio::time::{sleep, Instant};
+
+    #[test]
+    fn delays() {
+        let policy = ReconnectPolicy {
+            initial_delay_ms: 100,
+            max_delay_ms: 1000,
+            max_retries: None,
+        };
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(3), Duration::from_millis(400));
+        assert_eq!(policy.delay(5), Duration::from_millis(1000));
+        assert_eq!(policy.delay(100), Duration::from_millis(1000));
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_retries() {
+        let mut backoff = Backoff::new(ReconnectPolicy {
+            initial_delay_ms: 1,
+            max_delay_ms: 1,
+            max_retries: Some(2),
+        });
+        assert!(backoff.wait("binance").await);
+        assert!(backoff.wait("binance").await);
+        assert!(!backoff.wait("binance").await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn short_connections_keep_backing_off() {
+        let mut backoff = Backoff::new(ReconnectPolicy {
+            initial_delay_ms: 100,
+            max_delay_ms: 30_000,
+            max_retries: Some(2),
+        });
+        assert!(backoff.wait("binance").await);
+
+        // Accepted and dropped at once
+        backoff.connected();
+        let start = Instant::now();
+        assert!(backoff.wait("binance").await);
+        assert_eq!(start.elapsed(), Duration::from_millis(200));
+
+        backoff.connected();
+        assert!(!backoff.wait("binance").await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn long_connections_start_over() {
+        let mut backoff = Backoff::new(ReconnectPolicy {
+            initial_delay_ms: 100,
+            max_delay_ms: 30_000,
+            max_retries: Some(2),
+        });
+        assert!(backoff.wait("binance").await);
+        assert!(backoff.wait("binance").await);
+
+        backoff.connected();
+        sleep(MIN_CONNECTED).await;
+        let start = Instant::now();
+        assert!(backoff.wait("binance").await);
+        assert_eq!(start.elapsed(), Duration::from_millis(100));
+    }
+}
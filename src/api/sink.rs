@@ -0,0 +1,651 @@
+use crate::api::format_time;
+use crate::{Depth, FleetData, FleetEvent, OrderDirection, RawFrame, Ticker};
+use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::fs::{self, File};
+use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::time::{interval, MissedTickBehavior};
+
+/// Levels of each side in CSV rows if `FileSinkConfig::levels` is not set
+const CSV_LEVELS: usize = 10;
+
+/// How long `run_sink` keeps events buffered
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Writer of fleet events, shared by the `csv` / `jsonl` sinks of a `FleetConfig`,
+/// `snapshot-record` and user code.
+///
+/// Implementations return `Box::pin(async move { .. })`
+pub trait Sink: Send {
+    /// Write one event, it may stay buffered until `flush`
+    fn write<'a>(&'a mut self, event: &'a FleetEvent) -> BoxFuture<'a, Result<()>>;
+
+    fn flush(&mut self) -> BoxFuture<'_, Result<()>>;
+}
+
+impl<S: Sink + ?Sized> Sink for Box<S> {
+    fn write<'a>(&'a mut self, event: &'a FleetEvent) -> BoxFuture<'a, Result<()>> {
+        (**self).write(event)
+    }
+
+    fn flush(&mut self) -> BoxFuture<'_, Result<()>> {
+        (**self).flush()
+    }
+}
+
+/// Write `events` until every sender is gone, flushing every second
+pub async fn run_sink(
+    mut sink: impl Sink,
+    mut events: UnboundedReceiver<FleetEvent>,
+) -> Result<()> {
+    let mut flush = interval(FLUSH_INTERVAL);
+    flush.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Some(event) => sink.write(&event).await?,
+                None => break,
+            },
+            _ = flush.tick() => sink.flush().await?,
+        }
+    }
+
+    sink.flush().await
+}
+
+/// Times of `CsvSink` and `JsonlSink`
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TimeFormat {
+    /// Milliseconds since the epoch
+    #[default]
+    Millis,
+    /// UTC time, e.g. "2019-08-14T20:42:27.265Z"
+    Rfc3339,
+}
+
+impl FromStr for TimeFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        match text {
+            "millis" => Ok(TimeFormat::Millis),
+            "rfc3339" => Ok(TimeFormat::Rfc3339),
+            _ => Err(anyhow!("Unknown time format {}", text)),
+        }
+    }
+}
+
+impl TimeFormat {
+    fn text(&self, time: i64) -> String {
+        match self {
+            TimeFormat::Millis => time.to_string(),
+            TimeFormat::Rfc3339 => format_time(time),
+        }
+    }
+
+    /// `ts` and `lts` of a `FleetEvent::to_json` record and of its trades
+    fn apply(&self, document: &mut Value) {
+        if *self == TimeFormat::Millis {
+            return;
+        }
+
+        for key in ["ts", "lts"] {
+            if let Some(time) = document.get(key).and_then(Value::as_i64) {
+                document[key] = json!(self.text(time));
+            }
+        }
+        if let Some(trades) = document.get_mut("trades").and_then(Value::as_array_mut) {
+            trades.iter_mut().for_each(|trade| self.apply(trade));
+        }
+    }
+}
+
+/// Files of a `CsvSink` or `JsonlSink`, one file per stream named
+/// "<exchange>_<symbol>_<stream>.<start ms>.<sequence>.<csv|jsonl>"
+///
+/// ```toml
+/// sinks = [{ type = "csv", dir = "books", levels = 5, time = "rfc3339" }]
+/// ```
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct FileSinkConfig {
+    pub dir: PathBuf,
+    /// Best levels of each side written,
+    /// every level in JSON Lines and 10 in CSV if not set
+    #[serde(default)]
+    pub levels: Option<usize>,
+    #[serde(default)]
+    pub time: TimeFormat,
+    /// Start a new file after this many seconds
+    #[serde(default)]
+    pub rotate_secs: Option<u64>,
+    /// Start a new file after this many bytes
+    #[serde(default)]
+    pub rotate_bytes: Option<u64>,
+}
+
+impl FileSinkConfig {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        FileSinkConfig {
+            dir: dir.into(),
+            levels: None,
+            time: TimeFormat::default(),
+            rotate_secs: None,
+            rotate_bytes: None,
+        }
+    }
+}
+
+/// "binance BTC_USDT:USD depth" => "binance_BTC_USDT-USD_depth"
+pub(crate) fn file_prefix(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '.' => c,
+            ' ' => '_',
+            _ => '-',
+        })
+        .collect()
+}
+
+/// Buffered lines of one stream, in a new file once it is too big or too old
+struct SinkFile {
+    dir: PathBuf,
+    prefix: String,
+    extension: &'static str,
+    /// First line of every file
+    header: Option<String>,
+    max_bytes: Option<u64>,
+    max_age: Option<Duration>,
+    file: Option<BufWriter<File>>,
+    written: u64,
+    opened: Instant,
+    sequence: u64,
+}
+
+impl SinkFile {
+    fn new(config: &FileSinkConfig, name: &str, extension: &'static str) -> Self {
+        SinkFile {
+            dir: config.dir.clone(),
+            prefix: file_prefix(name),
+            extension,
+            header: None,
+            max_bytes: config.rotate_bytes,
+            max_age: config.rotate_secs.map(Duration::from_secs),
+            file: None,
+            written: 0,
+            opened: Instant::now(),
+            sequence: 0,
+        }
+    }
+
+    fn with_header(mut self, header: String) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    async fn write_line(&mut self, line: &str) -> io::Result<()> {
+        let full = self.max_bytes.is_some_and(|max| self.written >= max)
+            || self.max_age.is_some_and(|age| self.opened.elapsed() >= age);
+        let file = match self.file.as_mut() {
+            Some(file) if !full => file,
+            _ => self.rotate().await?,
+        };
+
+        file.write_all(line.as_bytes()).await?;
+        file.write_all(b"\n").await?;
+        self.written += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    async fn rotate(&mut self) -> io::Result<&mut BufWriter<File>> {
+        self.flush().await?;
+
+        let start = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis();
+        let name = format!(
+            "{}.{}.{}.{}",
+            self.prefix, start, self.sequence, self.extension
+        );
+        fs::create_dir_all(&self.dir).await?;
+        let mut file = BufWriter::new(File::create(self.dir.join(name)).await?);
+        self.sequence += 1;
+        self.written = 0;
+        self.opened = Instant::now();
+
+        if let Some(header) = &self.header {
+            file.write_all(header.as_bytes()).await?;
+            file.write_all(b"\n").await?;
+            self.written += header.len() as u64 + 1;
+        }
+
+        Ok(self.file.insert(file))
+    }
+
+    async fn flush(&mut self) -> io::Result<()> {
+        match self.file.as_mut() {
+            Some(file) => file.flush().await,
+            None => Ok(()),
+        }
+    }
+}
+
+async fn flush_all(files: &mut HashMap<String, SinkFile>) -> Result<()> {
+    for file in files.values_mut() {
+        file.flush().await?;
+    }
+    Ok(())
+}
+
+/// Every event as a line of `FleetEvent::to_json`,
+/// with at most `levels` levels and times as `time`.
+/// Raw frames go to "raw <exchange>" files as `{"lts": .., "text": ..}` lines
+pub struct JsonlSink {
+    config: FileSinkConfig,
+    files: HashMap<String, SinkFile>,
+}
+
+impl JsonlSink {
+    pub fn new(config: FileSinkConfig) -> Self {
+        JsonlSink {
+            config,
+            files: HashMap::new(),
+        }
+    }
+
+    /// Write one websocket frame, it may stay buffered until `flush`
+    pub async fn write_raw(&mut self, frame: &RawFrame) -> Result<()> {
+        let name = format!("raw {}", frame.exchange);
+        let line = json!({ "lts": frame.lts, "text": frame.text });
+
+        let config = &self.config;
+        let file = self
+            .files
+            .entry(name)
+            .or_insert_with_key(|name| SinkFile::new(config, name, "jsonl"));
+        Ok(file.write_line(&line.to_string()).await?)
+    }
+}
+
+impl Sink for JsonlSink {
+    fn write<'a>(&'a mut self, event: &'a FleetEvent) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let mut document = event.document(self.config.levels);
+            if let Some(record) = document.get_mut("v1") {
+                self.config.time.apply(record);
+            }
+
+            let config = &self.config;
+            let file = self
+                .files
+                .entry(event.name.clone())
+                .or_insert_with(|| SinkFile::new(config, &event.name, "jsonl"));
+            Ok(file.write_line(&document.to_string()).await?)
+        })
+    }
+
+    fn flush(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(flush_all(&mut self.files))
+    }
+}
+
+/// Books as rows of `ts,lts,id,ask_price_0,ask_amount_0,bid_price_0,bid_amount_0,..`
+/// for the best `levels` levels (1 of `bookTicker`), missing levels left empty,
+/// and trades as rows of `ts,lts,id,price,amount,direction`.
+/// States of the books are not written
+pub struct CsvSink {
+    config: FileSinkConfig,
+    files: HashMap<String, SinkFile>,
+}
+
+impl CsvSink {
+    pub fn new(config: FileSinkConfig) -> Self {
+        CsvSink {
+            config,
+            files: HashMap::new(),
+        }
+    }
+
+    fn file(&mut self, event: &FleetEvent, header: impl FnOnce() -> String) -> &mut SinkFile {
+        let config = &self.config;
+        self.files
+            .entry(event.name.clone())
+            .or_insert_with(|| SinkFile::new(config, &event.name, "csv").with_header(header()))
+    }
+
+    fn book_row(&self, depth: &Depth, levels: usize) -> String {
+        let time = self.config.time;
+        let mut row = format!(
+            "{},{},{}",
+            time.text(depth.ts),
+            time.text(depth.lts),
+            depth.id
+        );
+
+        for level in 0..levels {
+            for quote in [depth.asks.get(level), depth.bids.get(level)] {
+                match quote {
+                    Some(quote) => row += &format!(",{},{}", quote.price, quote.amount),
+                    None => row += ",,",
+                }
+            }
+        }
+        row
+    }
+
+    fn trade_row(&self, ticker: &Ticker) -> String {
+        let time = self.config.time;
+        let direction = match ticker.direction {
+            OrderDirection::Buy => "buy",
+            OrderDirection::Sell => "sell",
+        };
+
+        format!(
+            "{},{},{},{},{},{}",
+            time.text(ticker.ts),
+            time.text(ticker.lts),
+            ticker.id,
+            ticker.price,
+            ticker.amount,
+            direction
+        )
+    }
+}
+
+fn book_header(levels: usize) -> String {
+    let mut header = String::from("ts,lts,id");
+    for level in 0..levels {
+        for column in ["ask_price", "ask_amount", "bid_price", "bid_amount"] {
+            header += &format!(",{}_{}", column, level);
+        }
+    }
+    header
+}
+
+impl Sink for CsvSink {
+    fn write<'a>(&'a mut self, event: &'a FleetEvent) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            match &event.data {
+                FleetData::Depth(depth) | FleetData::BookTicker(depth) => {
+                    let levels = match &event.data {
+                        FleetData::BookTicker(_) => 1,
+                        _ => self.config.levels.unwrap_or(CSV_LEVELS),
+                    };
+                    let row = self.book_row(depth, levels);
+                    self.file(event, || book_header(levels))
+                        .write_line(&row)
+                        .await?;
+                }
+                FleetData::Ticker(tickers) => {
+                    let rows = tickers
+                        .iter()
+                        .map(|ticker| self.trade_row(ticker))
+                        .collect::<Vec<_>>();
+                    let file = self.file(event, || "ts,lts,id,price,amount,direction".to_string());
+                    for row in rows {
+                        file.write_line(&row).await?;
+                    }
+                }
+                FleetData::State(_) => {}
+            }
+            Ok(())
+        })
+    }
+
+    fn flush(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(flush_all(&mut self.files))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::sink::{
+        file_prefix, run_sink, CsvSink, FileSinkConfig, JsonlSink, Sink, TimeFormat,
+    };
+    use crate::{
+        ConnectionState, Depth, FleetData, FleetEvent, OrderDirection, Quote, RawFrame,
+        StreamKind, Ticker,
+    };
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use tokio::sync::mpsc;
+
+    fn temp_dir(test: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("snapshot-sink-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    /// Contents of the files in `dir`, in order of name
+    fn contents(dir: &Path) -> Vec<(String, String)> {
+        let mut files = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| {
+                let path = entry.unwrap().path();
+                let name = path.file_name().unwrap().to_string_lossy().to_string();
+                (name, fs::read_to_string(&path).unwrap())
+            })
+            .collect::<Vec<_>>();
+        files.sort_by_key(|(name, _)| {
+            (
+                name.split('.').next().unwrap().to_string(),
+                name.rsplit('.').nth(1).map(String::from),
+            )
+        });
+        files
+    }
+
+    fn event(stream: StreamKind, data: FleetData) -> FleetEvent {
+        FleetEvent {
+            name: format!("binance BTC_USDT {}", stream),
+            exchange: String::from("binance"),
+            symbol: String::from("BTC_USDT"),
+            stream,
+            data,
+        }
+    }
+
+    fn quote(price: f64, amount: f64) -> Quote {
+        Quote {
+            price,
+            amount,
+            orders: None,
+        }
+    }
+
+    fn depth() -> FleetEvent {
+        event(
+            StreamKind::Depth,
+            FleetData::Depth(Depth {
+                ts: 1565815347265,
+                lts: 1565815347300,
+                id: 3,
+                asks: vec![quote(101.0, 0.5), quote(102.0, 1.5), quote(103.0, 2.0)],
+                bids: vec![quote(100.0, 1.0)],
+            }),
+        )
+    }
+
+    fn trades() -> FleetEvent {
+        let trade = Ticker {
+            lts: 1565815347300,
+            ts: 1565815347265,
+            price: 100.5,
+            amount: 0.25,
+            direction: OrderDirection::Sell,
+            id: 7,
+        };
+        let buy = Ticker {
+            id: 8,
+            direction: OrderDirection::Buy,
+            ..trade
+        };
+        event(StreamKind::Ticker, FleetData::Ticker(vec![trade, buy]))
+    }
+
+    #[tokio::test]
+    async fn csv_rows() {
+        let dir = temp_dir("csv");
+        let mut sink = CsvSink::new(FileSinkConfig {
+            levels: Some(2),
+            time: TimeFormat::Rfc3339,
+            ..FileSinkConfig::new(&dir)
+        });
+
+        sink.write(&depth()).await.unwrap();
+        sink.write(&trades()).await.unwrap();
+        let state = FleetData::State(ConnectionState::Connected);
+        sink.write(&event(StreamKind::Depth, state)).await.unwrap();
+        sink.flush().await.unwrap();
+
+        let files = contents(&dir);
+        assert_eq!(files.len(), 2);
+        assert!(
+            files[0].0.starts_with("binance_BTC_USDT_depth.") && files[0].0.ends_with(".0.csv")
+        );
+        assert_eq!(
+            files[0].1,
+            "ts,lts,id,ask_price_0,ask_amount_0,bid_price_0,bid_amount_0,\
+             ask_price_1,ask_amount_1,bid_price_1,bid_amount_1\n\
+             2019-08-14T20:42:27.265Z,2019-08-14T20:42:27.300Z,3,101,0.5,100,1,102,1.5,,\n"
+        );
+        assert_eq!(
+            files[1].1,
+            "ts,lts,id,price,amount,direction\n\
+             2019-08-14T20:42:27.265Z,2019-08-14T20:42:27.300Z,7,100.5,0.25,sell\n\
+             2019-08-14T20:42:27.265Z,2019-08-14T20:42:27.300Z,8,100.5,0.25,buy\n"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn jsonl_lines() {
+        let dir = temp_dir("jsonl");
+        let mut sink = JsonlSink::new(FileSinkConfig {
+            levels: Some(1),
+            time: TimeFormat::Rfc3339,
+            ..FileSinkConfig::new(&dir)
+        });
+
+        sink.write(&depth()).await.unwrap();
+        sink.write(&trades()).await.unwrap();
+        sink.flush().await.unwrap();
+
+        let files = contents(&dir);
+        assert!(files[0].0.ends_with(".jsonl"));
+        assert_eq!(
+            files[0].1,
+            r#"{"v1":{"asks":[[101.0,0.5,null]],"bids":[[100.0,1.0,null]],"exchange":"binance","id":3,"lts":"2019-08-14T20:42:27.300Z","symbol":"BTC_USDT","ts":"2019-08-14T20:42:27.265Z","type":"depth"}}"#
+                .to_string()
+                + "\n"
+        );
+        let trades: serde_json::Value = serde_json::from_str(files[1].1.trim()).unwrap();
+        assert_eq!(trades["v1"]["trades"][1]["ts"], "2019-08-14T20:42:27.265Z");
+        assert_eq!(trades["v1"]["trades"][1]["direction"], "buy");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn rotation_repeats_header() {
+        let dir = temp_dir("rotate");
+        let mut sink = CsvSink::new(FileSinkConfig {
+            levels: Some(1),
+            rotate_bytes: Some(1),
+            ..FileSinkConfig::new(&dir)
+        });
+
+        for _ in 0..3 {
+            sink.write(&depth()).await.unwrap();
+        }
+        sink.flush().await.unwrap();
+
+        let files = contents(&dir);
+        assert_eq!(files.len(), 3);
+        for (sequence, (name, text)) in files.iter().enumerate() {
+            assert!(name.ends_with(&format!(".{}.csv", sequence)));
+            assert_eq!(
+                text,
+                "ts,lts,id,ask_price_0,ask_amount_0,bid_price_0,bid_amount_0\n\
+                 1565815347265,1565815347300,3,101,0.5,100,1\n"
+            );
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn raw_frames_rotate_by_size() {
+        assert_eq!(
+            file_prefix("binance BTC_USDT:USD depth"),
+            "binance_BTC_USDT-USD_depth"
+        );
+
+        let dir = temp_dir("raw");
+        let mut sink = JsonlSink::new(FileSinkConfig {
+            rotate_bytes: Some(30),
+            ..FileSinkConfig::new(&dir)
+        });
+
+        for text in ["{}", "[]", "{\"a\":1}"] {
+            let frame = RawFrame {
+                exchange: "okx",
+                lts: 1,
+                text: text.to_string(),
+            };
+            sink.write_raw(&frame).await.unwrap();
+        }
+        sink.flush().await.unwrap();
+
+        let files = contents(&dir);
+        assert!(files.iter().all(|(name, _)| name.starts_with("raw_okx.")));
+        let texts = files.iter().map(|(_, text)| text.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            texts,
+            [
+                "{\"lts\":1,\"text\":\"{}\"}\n{\"lts\":1,\"text\":\"[]\"}\n",
+                "{\"lts\":1,\"text\":\"{\\\"a\\\":1}\"}\n"
+            ]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn sink_runs_until_senders_drop() {
+        let dir = temp_dir("run");
+        let (sender, events) = mpsc::unbounded_channel();
+        let task = tokio::spawn(run_sink(JsonlSink::new(FileSinkConfig::new(&dir)), events));
+
+        for _ in 0..2 {
+            sender.send(trades()).unwrap();
+        }
+        drop(sender);
+        task.await.unwrap().unwrap();
+
+        let files = contents(&dir);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].1.lines().count(), 2);
+
+        assert_eq!(
+            "rfc3339".parse::<TimeFormat>().unwrap(),
+            TimeFormat::Rfc3339
+        );
+        assert!("iso".parse::<TimeFormat>().is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
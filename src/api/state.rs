@@ -0,0 +1,51 @@
+use crate::api::metrics;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+
+/// Notices about the health of a maintained order book
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConnectionState {
+    /// Order book is initialized and being updated
+    Connected,
+    /// Order book is dropped and rebuilt from a fresh snapshot
+    Resyncing(String),
+    /// Self audit found levels different from the exchange snapshot,
+    /// counted per side
+    Diverged { id: i64, bids: usize, asks: usize },
+}
+
+/// Fan out `ConnectionState` to every subscriber
+#[derive(Clone)]
+pub(crate) struct StateSender {
+    /// Registry name of the exchange, label of the resync metric
+    exchange: &'static str,
+    senders: Arc<Mutex<Vec<UnboundedSender<ConnectionState>>>>,
+}
+
+impl StateSender {
+    pub fn new(exchange: &'static str) -> Self {
+        StateSender {
+            exchange,
+            senders: Arc::default(),
+        }
+    }
+
+    pub fn subscribe(&self) -> UnboundedReceiver<ConnectionState> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.senders.lock().unwrap().push(sender);
+        receiver
+    }
+
+    /// Subscribers that are gone are dropped
+    pub fn send(&self, state: ConnectionState) {
+        if let ConnectionState::Resyncing(_) = state {
+            metrics::resync(self.exchange);
+        }
+
+        self.senders
+            .lock()
+            .unwrap()
+            .retain(|sender| sender.send(state.clone()).is_ok());
+    }
+}
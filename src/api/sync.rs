@@ -0,0 +1,526 @@
+use crate::api::metrics;
+use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
+use futures_util::{stream, SinkExt, Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use std::collections::VecDeque;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::time::{interval_at, Instant};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tracing::{debug, info, warn};
+
+/// Events buffered before the snapshot is fetched
+const BUFFER_EVENTS: usize = 5;
+
+type WebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Diff event placed by its update ids against a book at `id`
+pub(crate) trait DiffEvent {
+    /// Holds the update right after `id`
+    fn matches(&self, id: i64) -> bool;
+    /// Already in the book
+    fn behind(&self, id: i64) -> bool;
+    /// Starts right after `id`
+    fn equals(&self, id: i64) -> bool;
+}
+
+/// Snapshot a diff stream is synced onto
+pub(crate) trait DiffSnapshot {
+    fn id(&self) -> i64;
+}
+
+/// Book kept from a `Snapshot` and the diff events after it
+pub(crate) trait DiffBook<Event> {
+    type Snapshot: DiffSnapshot;
+
+    /// Update id of the last change in the book
+    fn id(&self) -> i64;
+
+    fn load_snapshot(&mut self, snapshot: &Self::Snapshot);
+
+    fn add_event(&mut self, event: Event);
+}
+
+/// Source of the snapshot a diff stream is synced onto
+pub(crate) trait SnapshotFetcher<Snapshot> {
+    fn fetch(&self) -> BoxFuture<'_, Result<Snapshot>>;
+}
+
+/// Snapshot from a REST endpoint, fetch times are recorded in `api::metrics`
+pub(crate) struct RestSnapshot {
+    exchange: &'static str,
+    address: String,
+}
+
+impl RestSnapshot {
+    pub fn new(exchange: &'static str, address: String) -> Self {
+        RestSnapshot { exchange, address }
+    }
+}
+
+impl<Snapshot: DeserializeOwned + Send> SnapshotFetcher<Snapshot> for RestSnapshot {
+    fn fetch(&self) -> BoxFuture<'_, Result<Snapshot>> {
+        Box::pin(async move {
+            let start = Instant::now();
+            let snapshot = reqwest::get(&self.address).await?.json().await?;
+            metrics::snapshot_fetch(self.exchange, start.elapsed());
+            info!("Successfully connected to {}", self.address);
+            Ok(snapshot)
+        })
+    }
+}
+
+/// How diff events line up with a book at `id`
+pub(crate) trait ContinuityRule<Event> {
+    /// `event` is already in the book
+    fn stale(&self, event: &Event, id: i64) -> bool;
+
+    /// `event` holds the update right after a snapshot at `id`
+    fn bridges(&self, event: &Event, id: i64) -> bool;
+
+    /// `event` directly follows the last event applied to the book
+    fn follows(&self, event: &Event, id: i64) -> bool;
+}
+
+/// Continuity told by the update ids of `DiffEvent`
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct EventContinuity;
+
+impl<Event: DiffEvent> ContinuityRule<Event> for EventContinuity {
+    fn stale(&self, event: &Event, id: i64) -> bool {
+        event.behind(id)
+    }
+
+    fn bridges(&self, event: &Event, id: i64) -> bool {
+        event.matches(id)
+    }
+
+    fn follows(&self, event: &Event, id: i64) -> bool {
+        event.equals(id)
+    }
+}
+
+/// How a websocket is kept open, server pings are always answered
+pub(crate) trait Keepalive {
+    /// Interval of client pings, `None` when only the server pings
+    fn interval(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Next client ping
+    fn ping(&mut self) -> Message {
+        Message::Ping(Vec::new())
+    }
+}
+
+/// Only answer the pings of the server
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct ServerPing;
+
+impl Keepalive for ServerPing {}
+
+/// Events `decode`d from the text frames of `socket`, kept open by `keepalive`,
+/// frames `decode` gives nothing for are skipped.
+///
+/// Ends when the socket closes or fails
+pub(crate) fn socket_events<'a, Event, Decode, Alive>(
+    socket: &'a mut WebSocket,
+    keepalive: Alive,
+    decode: Decode,
+) -> impl Stream<Item = Event> + 'a
+where
+    Event: 'a,
+    Decode: FnMut(&str) -> Option<Event> + 'a,
+    Alive: Keepalive + 'a,
+{
+    let ping = keepalive
+        .interval()
+        .map(|period| interval_at(Instant::now() + period, period));
+
+    stream::unfold(
+        (socket, keepalive, ping, decode),
+        |(socket, mut keepalive, mut ping, mut decode)| async move {
+            loop {
+                let message = match ping.as_mut() {
+                    Some(ping) => tokio::select! {
+                        message = socket.next() => message,
+                        _ = ping.tick() => {
+                            if let Err(e) = socket.send(keepalive.ping()).await {
+                                warn!("Send ping error {:?}", e);
+                                return None;
+                            }
+                            continue;
+                        }
+                    },
+                    None => socket.next().await,
+                };
+
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Ping(payload))) => {
+                        debug!("Receiving ping message");
+                        if let Err(e) = socket.send(Message::Pong(payload)).await {
+                            warn!("Send pong error {:?}", e);
+                            return None;
+                        }
+                        continue;
+                    }
+                    Some(Ok(Message::Close(frame))) => {
+                        warn!("Connection closed {:?}", frame);
+                        return None;
+                    }
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => {
+                        warn!("Connection error {:?}", e);
+                        return None;
+                    }
+                    None => return None,
+                };
+
+                match decode(&text) {
+                    Some(event) => return Some((event, (socket, keepalive, ping, decode))),
+                    None => warn!("Message decode error {:?}", text),
+                }
+            }
+        },
+    )
+}
+
+/// Sync a stream of diff events onto a snapshot and keep `shared` in line.
+///
+/// The first events are buffered before the snapshot is fetched,
+/// stale events are dropped, the book starts from the event bridging the
+/// snapshot and every later event has to follow the one before
+pub(crate) struct DiffBookSynchronizer<Shared, Rule = EventContinuity> {
+    shared: Arc<RwLock<Shared>>,
+    rule: Rule,
+    buffer_events: usize,
+}
+
+impl<Shared> DiffBookSynchronizer<Shared> {
+    pub fn new(shared: Arc<RwLock<Shared>>) -> Self {
+        DiffBookSynchronizer {
+            shared,
+            rule: EventContinuity,
+            buffer_events: BUFFER_EVENTS,
+        }
+    }
+}
+
+impl<Shared, Rule> DiffBookSynchronizer<Shared, Rule> {
+    /// Load the snapshot of `fetcher` into the book and bring it up to
+    /// date with `events`.
+    ///
+    /// `Ok(false)` when `events` do not line up with the snapshot,
+    /// Err when the snapshot fails or `events` end first
+    pub async fn synchronize<Event, Snapshot, Events, Fetcher>(
+        &self,
+        events: &mut Events,
+        fetcher: &Fetcher,
+    ) -> Result<bool>
+    where
+        Shared: DiffBook<Event, Snapshot = Snapshot>,
+        Snapshot: DiffSnapshot,
+        Rule: ContinuityRule<Event>,
+        Events: Stream<Item = Event> + Unpin,
+        Fetcher: SnapshotFetcher<Snapshot>,
+    {
+        let mut buffer = VecDeque::with_capacity(self.buffer_events);
+        while buffer.len() < self.buffer_events {
+            match events.next().await {
+                Some(event) => buffer.push_back(event),
+                None => return Err(anyhow!("Event stream closed before the snapshot")),
+            }
+        }
+
+        let snapshot = fetcher.fetch().await?;
+        let id = snapshot.id();
+
+        loop {
+            let event = match buffer.pop_front() {
+                Some(event) => event,
+                None => match events.next().await {
+                    Some(event) => event,
+                    None => return Err(anyhow!("Event stream closed before the snapshot")),
+                },
+            };
+
+            if self.rule.stale(&event, id) {
+                continue;
+            }
+
+            if !self.rule.bridges(&event, id) {
+                debug!("Event is a head of snapshot {}", id);
+                return Ok(false);
+            }
+
+            let mut orderbook = self.shared.write().unwrap();
+            orderbook.load_snapshot(&snapshot);
+            orderbook.add_event(event);
+            break;
+        }
+
+        while let Some(event) = buffer.pop_front() {
+            if self.apply(event).is_err() {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Apply `event` to a synchronized book, Err when it does not follow
+    pub fn apply<Event>(&self, event: Event) -> Result<()>
+    where
+        Shared: DiffBook<Event>,
+        Rule: ContinuityRule<Event>,
+    {
+        let mut orderbook = self.shared.write().unwrap();
+        if !self.rule.follows(&event, orderbook.id()) {
+            return Err(anyhow!("Event does not follow order book"));
+        }
+
+        orderbook.add_event(event);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DiffBook, DiffBookSynchronizer, DiffEvent, DiffSnapshot, EventContinuity, SnapshotFetcher,
+    };
+    use anyhow::Result;
+    use futures_util::future::{self, BoxFuture};
+    use futures_util::stream;
+    use proptest::prelude::*;
+    use std::collections::{BTreeMap, HashMap};
+    use std::sync::{Arc, RwLock};
+    use tokio::runtime::Builder;
+
+    type Levels = BTreeMap<u8, u8>;
+
+    /// Update ids `first..=last` changing `levels`, an amount of 0 removes the level
+    #[derive(Clone, Debug)]
+    struct Event {
+        first: i64,
+        last: i64,
+        levels: Vec<(u8, u8)>,
+    }
+
+    impl DiffEvent for Event {
+        fn matches(&self, id: i64) -> bool {
+            self.first <= id + 1 && id < self.last
+        }
+
+        fn behind(&self, id: i64) -> bool {
+            self.last <= id
+        }
+
+        fn equals(&self, id: i64) -> bool {
+            self.first == id + 1
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    struct Snapshot {
+        id: i64,
+        levels: Levels,
+    }
+
+    impl DiffSnapshot for Snapshot {
+        fn id(&self) -> i64 {
+            self.id
+        }
+    }
+
+    #[derive(Default)]
+    struct Book {
+        id: i64,
+        levels: Levels,
+    }
+
+    fn apply(levels: &mut Levels, changes: &[(u8, u8)]) {
+        for &(price, amount) in changes {
+            if amount == 0 {
+                levels.remove(&price);
+            } else {
+                levels.insert(price, amount);
+            }
+        }
+    }
+
+    impl DiffBook<Event> for Book {
+        type Snapshot = Snapshot;
+
+        fn id(&self) -> i64 {
+            self.id
+        }
+
+        fn load_snapshot(&mut self, snapshot: &Snapshot) {
+            self.id = snapshot.id;
+            self.levels = snapshot.levels.clone();
+        }
+
+        fn add_event(&mut self, event: Event) {
+            apply(&mut self.levels, &event.levels);
+            self.id = event.last;
+        }
+    }
+
+    struct Fixed(Snapshot);
+
+    impl SnapshotFetcher<Snapshot> for Fixed {
+        fn fetch(&self) -> BoxFuture<'_, Result<Snapshot>> {
+            Box::pin(future::ready(Ok(self.0.clone())))
+        }
+    }
+
+    /// Events with consecutive update ids and the book after each of them,
+    /// the book before the first event is at id 0
+    fn history(updates: &[(i64, Vec<(u8, u8)>)]) -> (Vec<Event>, HashMap<i64, Levels>) {
+        let mut events = Vec::new();
+        let mut books = HashMap::from([(0, Levels::new())]);
+        let mut levels = Levels::new();
+        let mut last = 0;
+
+        for (span, changes) in updates {
+            let event = Event {
+                first: last + 1,
+                last: last + span,
+                levels: changes.clone(),
+            };
+            last = event.last;
+            apply(&mut levels, changes);
+            books.insert(last, levels.clone());
+            events.push(event);
+        }
+
+        (events, books)
+    }
+
+    fn updates() -> impl Strategy<Value = Vec<(i64, Vec<(u8, u8)>)>> {
+        prop::collection::vec(
+            (1..4i64, prop::collection::vec((0..20u8, 0..4u8), 0..4)),
+            1..30,
+        )
+    }
+
+    /// Sync onto the book after `snapshot_at` events, receiving from event `start` on
+    /// without `dropped`, then apply the rest.
+    ///
+    /// Returns whether the book got synchronized and the ids it was at,
+    /// checking every one of them against `books`
+    fn replay(
+        events: &[Event],
+        books: &HashMap<i64, Levels>,
+        snapshot_at: usize,
+        start: usize,
+        dropped: Option<usize>,
+        buffer_events: usize,
+    ) -> (bool, Vec<i64>) {
+        let id = match snapshot_at {
+            0 => 0,
+            at => events[at - 1].last,
+        };
+        let snapshot = Snapshot {
+            id,
+            levels: books[&id].clone(),
+        };
+
+        let mut received = events[start..]
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| Some(start + index) != dropped)
+            .map(|(_, event)| event.clone())
+            .collect::<Vec<_>>()
+            .into_iter();
+
+        let shared = Arc::new(RwLock::new(Book::default()));
+        let synchronizer = DiffBookSynchronizer {
+            shared: shared.clone(),
+            rule: EventContinuity,
+            buffer_events,
+        };
+
+        let runtime = Builder::new_current_thread().build().unwrap();
+        let synchronized = runtime.block_on(async {
+            let mut stream = stream::iter(received.by_ref());
+            synchronizer
+                .synchronize(&mut stream, &Fixed(snapshot))
+                .await
+        });
+
+        let mut ids = Vec::new();
+        if let Ok(true) = synchronized {
+            let check = |ids: &mut Vec<i64>| {
+                let book = shared.read().unwrap();
+                assert_eq!(
+                    Some(&book.levels),
+                    books.get(&book.id),
+                    "book at {}",
+                    book.id
+                );
+                ids.push(book.id);
+            };
+
+            check(&mut ids);
+            for event in received {
+                if synchronizer.apply(event).is_err() {
+                    break;
+                }
+                check(&mut ids);
+            }
+        }
+
+        (matches!(synchronized, Ok(true)), ids)
+    }
+
+    proptest! {
+        /// Every event arrives: the book syncs whenever the stream reaches
+        /// back to the snapshot and ends at the last event
+        #[test]
+        fn syncs_complete_stream(
+            updates in updates(),
+            snapshot_at in any::<prop::sample::Index>(),
+            start in any::<prop::sample::Index>(),
+            buffer_events in 0..8usize,
+        ) {
+            let (events, books) = history(&updates);
+            let snapshot_at = snapshot_at.index(events.len());
+            let start = start.index(events.len());
+
+            let (synchronized, ids) = replay(&events, &books, snapshot_at, start, None, buffer_events);
+
+            let reachable = start <= snapshot_at && buffer_events <= events.len() - start;
+            prop_assert_eq!(synchronized, reachable);
+            if synchronized {
+                prop_assert_eq!(ids.last(), Some(&events.last().unwrap().last));
+            }
+        }
+
+        /// A missing event after the snapshot is noticed,
+        /// the book never gets past it
+        #[test]
+        fn notices_dropped_event(
+            updates in updates(),
+            snapshot_at in any::<prop::sample::Index>(),
+            dropped in any::<prop::sample::Index>(),
+            buffer_events in 0..8usize,
+        ) {
+            let (events, books) = history(&updates);
+            let snapshot_at = snapshot_at.index(events.len());
+            let dropped = dropped.index(events.len());
+
+            let (_, ids) = replay(&events, &books, snapshot_at, 0, Some(dropped), buffer_events);
+
+            let dropped_id = events[dropped].last;
+            if dropped >= snapshot_at {
+                prop_assert!(ids.iter().all(|&id| id < dropped_id));
+            }
+        }
+    }
+}
@@ -1,38 +1,52 @@
-use crate::binance::BinanceTicker;
-use crate::crypto::CryptoTicker;
-use crate::{get_ticker_config_from, ExchangeType, TickerConfig, TickerConnection};
+use crate::api::adapter::get_adapter;
+use crate::{AdapterRegistry, TickerConfig};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use tokio::sync::mpsc::UnboundedReceiver;
 #[derive(Clone)]
 pub struct TickerManager {
     pub config: TickerConfig,
-    connection: TickerConnection,
+    connection: Arc<dyn TickerT + Send + Sync>,
 }
 
 impl TickerManager {
     pub fn new(exchange: &str, symbol: &str) -> Self {
-        let config = get_ticker_config_from(exchange, symbol, None);
+        let config = get_adapter(exchange).ticker_config(symbol).unwrap();
 
-        assert!(config.is_correct(), "Unsupported config {:?}", config);
+        Self::from_config(config).unwrap()
+    }
+
+    /// Manager of a config made by `ExchangeAdapter::ticker_config`,
+    /// e.g. with its address replaced
+    pub fn from_config(config: TickerConfig) -> Result<Self> {
+        if !config.is_correct() {
+            return Err(anyhow!("Unsupported config {:?}", config));
+        }
 
-        let connection = match config.exchange_type {
-            ExchangeType::Binance => TickerConnection::Binance(BinanceTicker::new()),
-            ExchangeType::Crypto => TickerConnection::Crypto(CryptoTicker::new()),
-        };
+        let exchange = config.exchange_type.name();
+        let adapter = AdapterRegistry::global()
+            .get(exchange)
+            .ok_or_else(|| anyhow!("Unsupported Exchange {}", exchange))?;
+        let connection = adapter.ticker_connection(&config)?;
 
-        Self { config, connection }
+        Ok(Self { config, connection })
     }
 
     /// Get snapshot stream
     pub fn subscribe(&self) -> UnboundedReceiver<Vec<Ticker>> {
         let config = self.config.clone();
-        match &self.connection {
-            TickerConnection::Binance(connection) => connection.connect(config).unwrap(),
-            TickerConnection::Crypto(connection) => connection.connect(config).unwrap(),
-        }
+        self.connection.connect(config).unwrap()
     }
 }
 
-#[derive(Clone, Debug, Copy)]
+/// Trade stream of one exchange
+pub trait TickerT {
+    fn connect(&self, config: TickerConfig) -> Result<UnboundedReceiver<Vec<Ticker>>>;
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
 pub enum OrderDirection {
     Buy,
     Sell,
@@ -0,0 +1,106 @@
+use anyhow::{anyhow, Result};
+
+/// RFC 3339 UTC time, e.g. "2019-08-14T20:42:27.265123Z",
+/// in milliseconds
+pub fn parse_time(time: &str) -> Result<i64> {
+    let bad = || anyhow!("Bad time {}", time);
+
+    let (date, clock) = time
+        .strip_suffix('Z')
+        .and_then(|time| time.split_once('T'))
+        .ok_or_else(bad)?;
+    let (clock, fraction) = clock.split_once('.').unwrap_or((clock, "0"));
+
+    let fields = |text: &str, separator: char| -> Result<Vec<i64>> {
+        let fields = text
+            .split(separator)
+            .map(|field| field.parse::<i64>())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if fields.len() == 3 {
+            Ok(fields)
+        } else {
+            Err(bad())
+        }
+    };
+
+    let (year, month, day) = match fields(date, '-')?[..] {
+        [year, month, day] => (year, month, day),
+        _ => return Err(bad()),
+    };
+    let (hour, minute, second) = match fields(clock, ':')?[..] {
+        [hour, minute, second] => (hour, minute, second),
+        _ => return Err(bad()),
+    };
+    let millis = format!("{:0<3}", fraction)[..3].parse::<i64>()?;
+
+    // Days since 1970-01-01 of the proleptic Gregorian calendar
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146097 + day_of_era - 719468;
+
+    Ok((((days * 24 + hour) * 60 + minute) * 60 + second) * 1000 + millis)
+}
+
+/// Milliseconds as RFC 3339 UTC time, e.g. "2019-08-14T20:42:27.265Z"
+pub fn format_time(time: i64) -> String {
+    let (seconds, millis) = (time.div_euclid(1000), time.rem_euclid(1000));
+    let (days, clock) = (seconds.div_euclid(86400), seconds.rem_euclid(86400));
+
+    // Inverse of the day count of `parse_time`
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = (shifted_month + 2) % 12 + 1;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        clock / 3600,
+        clock / 60 % 60,
+        clock % 60,
+        millis
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::time::{format_time, parse_time};
+
+    #[test]
+    fn times() {
+        assert_eq!(
+            parse_time("2019-08-14T20:42:27.265Z").unwrap(),
+            1565815347265
+        );
+        assert_eq!(
+            parse_time("2014-11-07T08:19:27.028459Z").unwrap(),
+            1415348367028
+        );
+        assert_eq!(parse_time("2000-02-29T00:00:00Z").unwrap(), 951782400000);
+        assert!(parse_time("2000-02-29 00:00:00").is_err());
+    }
+
+    #[test]
+    fn formatted_times() {
+        assert_eq!(format_time(1565815347265), "2019-08-14T20:42:27.265Z");
+        assert_eq!(format_time(951782400000), "2000-02-29T00:00:00.000Z");
+        assert_eq!(format_time(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(format_time(-1), "1969-12-31T23:59:59.999Z");
+
+        for time in [1415348367028, 1709251199999, 4102444800000] {
+            assert_eq!(parse_time(&format_time(time)).unwrap(), time);
+        }
+    }
+}
@@ -0,0 +1,473 @@
+//! Serialized form of the public data types, the same for JSON, bincode
+//! and MessagePack.
+//!
+//! Records (`Depth`, `Ticker`, `Bar`, `BookDiff`, `ConnectionState` and
+//! `FleetEvent`) are written inside the tag of their schema version,
+//! `{"v1": {..}}` in JSON, so readers can tell records of a later schema
+//! instead of misreading them.
+//! `Quote`, `OrderDirection` and `BookSide` only appear inside records and
+//! keep the shape of the record version they are written in.
+//!
+//! Exchange formats (e.g. Binance `["price", "qty"]` string tuples) are
+//! parsed by the exchange modules and never reach this schema.
+use crate::{
+    Bar, BookDiff, ConnectionState, Depth, FleetData, FleetEvent, OrderDirection, Quote, Ticker,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Latest schema version, the one records are written in
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Record tagged with its schema version
+#[derive(Serialize, Deserialize)]
+pub(crate) enum Versioned<V1> {
+    #[serde(rename = "v1")]
+    V1(V1),
+}
+
+/// `[price, amount, orders]`, orders is `null` if the exchange does not tell
+impl Serialize for Quote {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.price, self.amount, self.orders).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Quote {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (price, amount, orders) = Deserialize::deserialize(deserializer)?;
+        Ok(Quote {
+            price,
+            amount,
+            orders,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct DepthV1<'a> {
+    ts: i64,
+    lts: i64,
+    id: i64,
+    asks: Cow<'a, [Quote]>,
+    bids: Cow<'a, [Quote]>,
+}
+
+impl<'a> DepthV1<'a> {
+    /// At most `levels` levels of each side
+    fn new(depth: &'a Depth, levels: Option<usize>) -> Self {
+        let best = |quotes: &'a [Quote]| {
+            Cow::Borrowed(&quotes[..levels.unwrap_or(usize::MAX).min(quotes.len())])
+        };
+
+        DepthV1 {
+            ts: depth.ts,
+            lts: depth.lts,
+            id: depth.id,
+            asks: best(&depth.asks),
+            bids: best(&depth.bids),
+        }
+    }
+}
+
+impl Serialize for Depth {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Versioned::V1(DepthV1::new(self, None)).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Depth {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let Versioned::V1(depth) = Versioned::<DepthV1>::deserialize(deserializer)?;
+        Ok(Depth {
+            ts: depth.ts,
+            lts: depth.lts,
+            id: depth.id,
+            asks: depth.asks.into_owned(),
+            bids: depth.bids.into_owned(),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct TickerV1 {
+    ts: i64,
+    lts: i64,
+    id: u64,
+    price: f64,
+    amount: f64,
+    direction: OrderDirection,
+}
+
+impl From<&Ticker> for TickerV1 {
+    fn from(ticker: &Ticker) -> Self {
+        TickerV1 {
+            ts: ticker.ts,
+            lts: ticker.lts,
+            id: ticker.id,
+            price: ticker.price,
+            amount: ticker.amount,
+            direction: ticker.direction,
+        }
+    }
+}
+
+impl Serialize for Ticker {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Versioned::V1(TickerV1::from(self)).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Ticker {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let Versioned::V1(ticker) = Versioned::<TickerV1>::deserialize(deserializer)?;
+        Ok(Ticker {
+            lts: ticker.lts,
+            ts: ticker.ts,
+            price: ticker.price,
+            amount: ticker.amount,
+            direction: ticker.direction,
+            id: ticker.id,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct BarV1 {
+    open_ts: i64,
+    close_ts: i64,
+    lts: i64,
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    volume: f64,
+    trades: Option<u64>,
+    closed: bool,
+}
+
+impl Serialize for Bar {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Versioned::V1(BarV1 {
+            open_ts: self.open_ts,
+            close_ts: self.close_ts,
+            lts: self.lts,
+            open: self.open,
+            high: self.high,
+            low: self.low,
+            close: self.close,
+            volume: self.volume,
+            trades: self.trades,
+            closed: self.closed,
+        })
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bar {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let Versioned::V1(bar) = Versioned::<BarV1>::deserialize(deserializer)?;
+        Ok(Bar {
+            open_ts: bar.open_ts,
+            close_ts: bar.close_ts,
+            lts: bar.lts,
+            open: bar.open,
+            high: bar.high,
+            low: bar.low,
+            close: bar.close,
+            volume: bar.volume,
+            trades: bar.trades,
+            closed: bar.closed,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct BookDiffV1<'a> {
+    bids: Cow<'a, [Quote]>,
+    asks: Cow<'a, [Quote]>,
+}
+
+impl Serialize for BookDiff {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Versioned::V1(BookDiffV1 {
+            bids: Cow::Borrowed(&self.bids),
+            asks: Cow::Borrowed(&self.asks),
+        })
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for BookDiff {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let Versioned::V1(diff) = Versioned::<BookDiffV1>::deserialize(deserializer)?;
+        Ok(BookDiff {
+            bids: diff.bids.into_owned(),
+            asks: diff.asks.into_owned(),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum ConnectionStateV1<'a> {
+    Connected,
+    Resyncing(Cow<'a, str>),
+    Diverged { id: i64, bids: u64, asks: u64 },
+}
+
+impl<'a> From<&'a ConnectionState> for ConnectionStateV1<'a> {
+    fn from(state: &'a ConnectionState) -> Self {
+        match state {
+            ConnectionState::Connected => ConnectionStateV1::Connected,
+            ConnectionState::Resyncing(reason) => {
+                ConnectionStateV1::Resyncing(Cow::Borrowed(reason))
+            }
+            ConnectionState::Diverged { id, bids, asks } => ConnectionStateV1::Diverged {
+                id: *id,
+                bids: *bids as u64,
+                asks: *asks as u64,
+            },
+        }
+    }
+}
+
+impl Serialize for ConnectionState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Versioned::V1(ConnectionStateV1::from(self)).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ConnectionState {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let Versioned::V1(state) = Versioned::<ConnectionStateV1>::deserialize(deserializer)?;
+        Ok(match state {
+            ConnectionStateV1::Connected => ConnectionState::Connected,
+            ConnectionStateV1::Resyncing(reason) => ConnectionState::Resyncing(reason.into_owned()),
+            ConnectionStateV1::Diverged { id, bids, asks } => ConnectionState::Diverged {
+                id,
+                bids: bids as usize,
+                asks: asks as usize,
+            },
+        })
+    }
+}
+
+/// `type` tells the data, "state" records are stamped with the time they are written
+#[derive(Serialize)]
+#[serde(tag = "type")]
+enum FleetEventV1<'a> {
+    #[serde(rename = "depth")]
+    Depth {
+        exchange: &'a str,
+        symbol: &'a str,
+        #[serde(flatten)]
+        depth: DepthV1<'a>,
+    },
+    #[serde(rename = "bookTicker")]
+    BookTicker {
+        exchange: &'a str,
+        symbol: &'a str,
+        #[serde(flatten)]
+        depth: DepthV1<'a>,
+    },
+    #[serde(rename = "trades")]
+    Trades {
+        exchange: &'a str,
+        symbol: &'a str,
+        trades: Vec<TickerV1>,
+    },
+    #[serde(rename = "state")]
+    State {
+        exchange: &'a str,
+        symbol: &'a str,
+        lts: i64,
+        state: ConnectionStateV1<'a>,
+    },
+}
+
+/// `FleetEvent` with at most `levels` levels of each side of its book
+pub(crate) struct EventRecord<'a> {
+    pub event: &'a FleetEvent,
+    pub levels: Option<usize>,
+}
+
+impl Serialize for EventRecord<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let exchange = self.event.exchange.as_str();
+        let symbol = self.event.symbol.as_str();
+
+        let event = match &self.event.data {
+            FleetData::Depth(depth) => FleetEventV1::Depth {
+                exchange,
+                symbol,
+                depth: DepthV1::new(depth, self.levels),
+            },
+            FleetData::BookTicker(depth) => FleetEventV1::BookTicker {
+                exchange,
+                symbol,
+                depth: DepthV1::new(depth, self.levels),
+            },
+            FleetData::Ticker(tickers) => FleetEventV1::Trades {
+                exchange,
+                symbol,
+                trades: tickers.iter().map(TickerV1::from).collect(),
+            },
+            FleetData::State(state) => FleetEventV1::State {
+                exchange,
+                symbol,
+                lts: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis() as i64,
+                state: ConnectionStateV1::from(state),
+            },
+        };
+        Versioned::V1(event).serialize(serializer)
+    }
+}
+
+impl Serialize for FleetEvent {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        EventRecord {
+            event: self,
+            levels: None,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Bar, BookDiff, BookSide, ConnectionState, Depth, OrderDirection, Quote, Ticker};
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use std::fmt::Debug;
+
+    fn quote(price: f64, amount: f64, orders: Option<u64>) -> Quote {
+        Quote {
+            price,
+            amount,
+            orders,
+        }
+    }
+
+    fn depth() -> Depth {
+        Depth {
+            ts: 1,
+            lts: 2,
+            id: 3,
+            asks: vec![quote(101.5, 2.0, Some(4)), quote(102.0, 0.5, None)],
+            bids: vec![quote(100.25, 1.0, None)],
+        }
+    }
+
+    fn ticker() -> Ticker {
+        Ticker {
+            lts: 6,
+            ts: 5,
+            price: 100.5,
+            amount: 0.25,
+            direction: OrderDirection::Sell,
+            id: 7,
+        }
+    }
+
+    /// Same value back from JSON, bincode and MessagePack,
+    /// compared through `Debug` as not every type is `PartialEq`
+    fn round_trip<T: Serialize + DeserializeOwned + Debug>(value: &T) -> T {
+        let expected = format!("{:?}", value);
+
+        let json: T = serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap();
+        assert_eq!(format!("{:?}", json), expected);
+
+        let bincode: T = bincode::deserialize(&bincode::serialize(value).unwrap()).unwrap();
+        assert_eq!(format!("{:?}", bincode), expected);
+
+        let compact: T = rmp_serde::from_slice(&rmp_serde::to_vec(value).unwrap()).unwrap();
+        assert_eq!(format!("{:?}", compact), expected);
+
+        let named: T = rmp_serde::from_slice(&rmp_serde::to_vec_named(value).unwrap()).unwrap();
+        assert_eq!(format!("{:?}", named), expected);
+
+        json
+    }
+
+    #[test]
+    fn json_schema() {
+        assert_eq!(
+            serde_json::to_string(&depth()).unwrap(),
+            r#"{"v1":{"ts":1,"lts":2,"id":3,"asks":[[101.5,2.0,4],[102.0,0.5,null]],"bids":[[100.25,1.0,null]]}}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&ticker()).unwrap(),
+            r#"{"v1":{"ts":5,"lts":6,"id":7,"price":100.5,"amount":0.25,"direction":"sell"}}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&ConnectionState::Resyncing("gap".into())).unwrap(),
+            r#"{"v1":{"resyncing":"gap"}}"#
+        );
+        assert_eq!(serde_json::to_string(&BookSide::Ask).unwrap(), r#""ask""#);
+
+        assert!(serde_json::from_str::<Depth>(r#"{"v2":{}}"#).is_err());
+        assert!(
+            serde_json::from_str::<Depth>(r#"{"ts":1,"lts":2,"id":3,"asks":[],"bids":[]}"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn data_round_trips() {
+        let depth = round_trip(&depth());
+        assert_eq!(
+            depth.asks,
+            [quote(101.5, 2.0, Some(4)), quote(102.0, 0.5, None)]
+        );
+        assert_eq!(depth.bids, [quote(100.25, 1.0, None)]);
+
+        round_trip(&vec![
+            ticker(),
+            Ticker {
+                direction: OrderDirection::Buy,
+                ..ticker()
+            },
+        ]);
+        round_trip(&quote(1.0, 2.0, Some(3)));
+
+        let bar = Bar {
+            open_ts: 60_000,
+            close_ts: 119_999,
+            lts: 120_001,
+            open: 1.0,
+            high: 3.0,
+            low: 0.5,
+            close: 2.0,
+            volume: 10.0,
+            trades: Some(12),
+            closed: true,
+        };
+        assert_eq!(round_trip(&bar), bar);
+
+        let diff = BookDiff {
+            bids: vec![quote(99.0, 0.0, None)],
+            asks: vec![quote(101.0, 3.0, Some(1))],
+        };
+        assert_eq!(round_trip(&diff), diff);
+
+        for state in [
+            ConnectionState::Connected,
+            ConnectionState::Resyncing("checksum mismatch".into()),
+            ConnectionState::Diverged {
+                id: 9,
+                bids: 1,
+                asks: 2,
+            },
+        ] {
+            assert_eq!(round_trip(&state), state);
+        }
+        for side in [BookSide::Bid, BookSide::Ask] {
+            assert_eq!(round_trip(&side), side);
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use anyhow::Result;
+use clap::Parser;
+use snapshot::{FleetConfig, Gateway};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use tracing::info;
+
+/// Serve the books and trades of a fleet over a local WebSocket and HTTP
+#[derive(Parser, Debug)]
+#[command(name = "snapshot-gateway")]
+struct Args {
+    /// Fleet config (.toml / .yaml)
+    config: PathBuf,
+
+    /// Address of the WebSocket (/ws) and HTTP (/depth, /health, /topics) endpoints
+    #[arg(long, default_value = "127.0.0.1:8900")]
+    listen: SocketAddr,
+
+    /// Serve Prometheus metrics on this address, e.g. 127.0.0.1:9100
+    #[cfg(feature = "prometheus")]
+    #[arg(long)]
+    metrics: Option<SocketAddr>,
+}
+
+/// SIGINT, or SIGTERM on unix
+async fn shutdown() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut terminate = signal(SignalKind::terminate()).expect("SIGTERM handler");
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        }
+    }
+
+    #[cfg(not(unix))]
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let args = Args::parse();
+    let config = FleetConfig::load(&args.config)?;
+    config.validate()?;
+
+    #[cfg(feature = "prometheus")]
+    if let Some(listen) = args.metrics {
+        snapshot::metrics::install_prometheus(listen)?;
+        info!("metrics on http://{}/metrics", listen);
+    }
+
+    let gateway = Gateway::start(&config)?;
+    info!("serving on {}", args.listen);
+
+    tokio::select! {
+        served = gateway.serve(args.listen) => served?,
+        _ = shutdown() => info!("shutting down"),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Args;
+    use clap::Parser;
+
+    #[test]
+    fn parse_args() {
+        let args = Args::parse_from(["snapshot-gateway", "fleet.toml"]);
+        assert_eq!(args.listen.to_string(), "127.0.0.1:8900");
+
+        let args = Args::parse_from(["snapshot-gateway", "fleet.yaml", "--listen", "0.0.0.0:80"]);
+        assert_eq!(args.config.to_str(), Some("fleet.yaml"));
+        assert_eq!(args.listen.port(), 80);
+
+        assert!(Args::try_parse_from(["snapshot-gateway"]).is_err());
+    }
+}
@@ -0,0 +1,158 @@
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use snapshot::{convert_records, DepthLayout, ParquetOptions};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::info;
+
+/// Convert snapshot-record JSON Lines files to Parquet
+#[derive(Parser, Debug)]
+#[command(name = "snapshot-parquet")]
+struct Args {
+    /// Record files, or directories of them
+    #[arg(required = true)]
+    inputs: Vec<PathBuf>,
+
+    /// Directory of the Parquet files
+    #[arg(long, default_value = "parquet")]
+    out: PathBuf,
+
+    /// Levels of each side flattened into columns
+    #[arg(long, default_value_t = 10, conflicts_with = "long")]
+    levels: usize,
+
+    /// One row per level (ts, side, level, price, amount) instead of level columns
+    #[arg(long)]
+    long: bool,
+
+    /// Start a new file once rows are received this many seconds after its first row
+    #[arg(long, default_value_t = 3600)]
+    rotate_secs: u64,
+
+    /// Start a new file after this many bytes
+    #[arg(long, default_value_t = 256 * 1024 * 1024)]
+    rotate_bytes: u64,
+}
+
+impl Args {
+    fn options(&self) -> ParquetOptions {
+        let layout = match self.long {
+            true => DepthLayout::Long,
+            false => DepthLayout::Wide(self.levels),
+        };
+
+        ParquetOptions {
+            dir: self.out.clone(),
+            layout,
+            max_age: Duration::from_secs(self.rotate_secs),
+            max_bytes: self.rotate_bytes,
+        }
+    }
+}
+
+/// Files of the inputs, in recording order
+fn record_files(inputs: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for input in inputs {
+        if input.is_dir() {
+            for entry in fs::read_dir(input)? {
+                let path = entry?.path();
+                if path
+                    .extension()
+                    .is_some_and(|extension| extension == "jsonl")
+                {
+                    files.push(path);
+                }
+            }
+        } else if input.is_file() {
+            files.push(input.clone());
+        } else {
+            return Err(anyhow!("No record file {}", input.display()));
+        }
+    }
+
+    files.sort_by_key(|path| record_order(path));
+    files.dedup();
+    Ok(files)
+}
+
+/// "<prefix>.<start ms>.<sequence>.jsonl" => (start ms, sequence, name)
+fn record_order(path: &Path) -> (i64, u64, String) {
+    let name = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let mut parts = name.rsplit('.').skip(1);
+    let sequence = parts.next().and_then(|part| part.parse().ok());
+    let start = parts.next().and_then(|part| part.parse().ok());
+
+    (start.unwrap_or(0), sequence.unwrap_or(0), name)
+}
+
+fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let args = Args::parse();
+    let files = record_files(&args.inputs)?;
+    fs::create_dir_all(&args.out)?;
+
+    let conversion = convert_records(&files, &args.options())?;
+    info!(
+        "{} books and {} trades of {} files to {} parquet files, {} lines skipped",
+        conversion.books,
+        conversion.trades,
+        files.len(),
+        conversion.files.len(),
+        conversion.skipped
+    );
+    for file in conversion.files {
+        println!("{}", file.display());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{record_order, Args};
+    use clap::Parser;
+    use snapshot::DepthLayout;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn parse_args() {
+        let args = Args::parse_from(["snapshot-parquet", "records", "--levels", "5"]);
+        assert_eq!(args.inputs, [PathBuf::from("records")]);
+        assert_eq!(args.options().layout, DepthLayout::Wide(5));
+
+        let args = Args::parse_from(["snapshot-parquet", "a.jsonl", "--long"]);
+        assert_eq!(args.options().layout, DepthLayout::Long);
+
+        assert!(Args::try_parse_from(["snapshot-parquet"]).is_err());
+        assert!(
+            Args::try_parse_from(["snapshot-parquet", "a", "--long", "--levels", "5"]).is_err()
+        );
+    }
+
+    #[test]
+    fn files_in_recording_order() {
+        let mut paths = [
+            "records/binance_BTC_USDT_depth.1700000000000.1.jsonl",
+            "records/binance_BTC_USDT_depth.1600000000000.0.jsonl",
+            "records/binance_BTC_USDT_ticker.1600000000000.0.jsonl",
+        ]
+        .map(PathBuf::from);
+        paths.sort_by_key(|path| record_order(path));
+
+        assert_eq!(
+            paths[0],
+            Path::new("records/binance_BTC_USDT_depth.1600000000000.0.jsonl")
+        );
+        assert_eq!(
+            paths[2],
+            Path::new("records/binance_BTC_USDT_depth.1700000000000.1.jsonl")
+        );
+    }
+}
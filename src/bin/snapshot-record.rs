@@ -0,0 +1,332 @@
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+use snapshot::{
+    ConnectionState, CsvSink, EndpointOverrides, FileSinkConfig, FleetConfig, FleetData,
+    FleetEvent, JsonlSink, RawFrame, RawFrames, ReconnectPolicy, Sink, SinkConfig, StreamKind,
+    SubscriptionConfig, TimeFormat,
+};
+use std::collections::BTreeMap;
+use std::fs;
+use std::future::pending;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::time::{interval_at, MissedTickBehavior};
+use tracing::info;
+
+/// Format of the recorded books and trades
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Jsonl,
+    Csv,
+}
+
+/// Record normalised books and trades to rotating JSON Lines or CSV files
+#[derive(Parser, Debug)]
+#[command(name = "snapshot-record")]
+struct Args {
+    /// Fleet config (.toml / .yaml) instead of --exchange and --symbol,
+    /// streams with a `channel` sink are recorded
+    #[arg(long, conflicts_with_all = ["exchange", "symbols"])]
+    config: Option<PathBuf>,
+
+    /// Exchange name, e.g. binance
+    #[arg(long, required_unless_present = "config")]
+    exchange: Option<String>,
+
+    /// Canonical symbols, e.g. BTC_USDT,ETH_USDT
+    #[arg(
+        long = "symbol",
+        value_delimiter = ',',
+        required_unless_present = "config"
+    )]
+    symbols: Vec<String>,
+
+    /// depth, level, ticker or bookTicker
+    #[arg(long = "stream", value_delimiter = ',', default_value = "depth,ticker")]
+    streams: Vec<StreamKind>,
+
+    /// Book size of the depth streams
+    #[arg(long, default_value_t = 1000)]
+    limit: i32,
+
+    /// Directory of the records
+    #[arg(long, default_value = "records")]
+    out: PathBuf,
+
+    /// Format of the books and trades, raw frames are always JSON Lines
+    #[arg(long, value_enum, default_value = "jsonl")]
+    format: Format,
+
+    /// Best levels of each side recorded, every level in jsonl and 10 in csv if not set
+    #[arg(long)]
+    levels: Option<usize>,
+
+    /// millis or rfc3339
+    #[arg(long, default_value = "millis")]
+    time: TimeFormat,
+
+    /// Start a new file after this many seconds
+    #[arg(long, default_value_t = 3600)]
+    rotate_secs: u64,
+
+    /// Start a new file after this many bytes
+    #[arg(long, default_value_t = 256 * 1024 * 1024)]
+    rotate_bytes: u64,
+
+    /// Also record the websocket frames as the exchanges send them
+    #[arg(long)]
+    raw: bool,
+
+    /// Seconds between status lines
+    #[arg(long, default_value_t = 10)]
+    status_secs: u64,
+
+    /// Serve Prometheus metrics on this address, e.g. 127.0.0.1:9100
+    #[cfg(feature = "prometheus")]
+    #[arg(long)]
+    metrics: Option<std::net::SocketAddr>,
+}
+
+impl Args {
+    fn fleet(&self) -> Result<FleetConfig> {
+        if let Some(path) = &self.config {
+            return FleetConfig::load(path);
+        }
+
+        Ok(FleetConfig {
+            reconnect: ReconnectPolicy::default(),
+            sinks: vec![SinkConfig::Channel],
+            subscriptions: vec![SubscriptionConfig {
+                exchange: self.exchange.clone().unwrap_or_default(),
+                symbols: self.symbols.clone(),
+                streams: self.streams.clone(),
+                limit: Some(self.limit),
+                endpoints: EndpointOverrides::default(),
+                reconnect: None,
+                sinks: None,
+            }],
+        })
+    }
+
+    fn files(&self) -> FileSinkConfig {
+        FileSinkConfig {
+            dir: self.out.clone(),
+            levels: self.levels,
+            time: self.time,
+            rotate_secs: Some(self.rotate_secs),
+            rotate_bytes: Some(self.rotate_bytes),
+        }
+    }
+
+    fn sink(&self) -> Box<dyn Sink> {
+        match self.format {
+            Format::Jsonl => Box::new(JsonlSink::new(self.files())),
+            Format::Csv => Box::new(CsvSink::new(self.files())),
+        }
+    }
+}
+
+/// Messages and the latest state of one stream since the last status line
+#[derive(Default)]
+struct Status {
+    messages: u64,
+    state: Option<ConnectionState>,
+}
+
+struct Recorder {
+    args: Args,
+    sink: Box<dyn Sink>,
+    /// Raw frames, always JSON Lines
+    raw: JsonlSink,
+    status: BTreeMap<String, Status>,
+    raw_frames: u64,
+    since: Instant,
+}
+
+impl Recorder {
+    fn new(args: Args, names: &[String]) -> Self {
+        let status = names
+            .iter()
+            .map(|name| (name.clone(), Status::default()))
+            .collect();
+
+        Recorder {
+            sink: args.sink(),
+            raw: JsonlSink::new(args.files()),
+            args,
+            status,
+            raw_frames: 0,
+            since: Instant::now(),
+        }
+    }
+
+    async fn record(&mut self, event: FleetEvent) -> Result<()> {
+        let status = self.status.entry(event.name.clone()).or_default();
+        if let FleetData::State(state) = &event.data {
+            status.state = Some(state.clone());
+        } else {
+            status.messages += 1;
+        }
+
+        self.sink.write(&event).await
+    }
+
+    async fn record_raw(&mut self, frame: RawFrame) -> Result<()> {
+        self.raw_frames += 1;
+        self.raw.write_raw(&frame).await
+    }
+
+    /// Log the message rates and connection states, then flush
+    async fn status(&mut self) -> Result<()> {
+        let seconds = self.since.elapsed().as_secs_f64().max(f64::EPSILON);
+        self.since = Instant::now();
+
+        let mut streams = self
+            .status
+            .iter_mut()
+            .map(|(name, status)| {
+                let rate = status.messages as f64 / seconds;
+                status.messages = 0;
+                match &status.state {
+                    Some(state) => format!("{} {:.1}/s {:?}", name, rate, state),
+                    None => format!("{} {:.1}/s", name, rate),
+                }
+            })
+            .collect::<Vec<_>>();
+        if self.args.raw {
+            streams.push(format!("raw {:.1}/s", self.raw_frames as f64 / seconds));
+            self.raw_frames = 0;
+        }
+        info!("{}", streams.join(" | "));
+
+        self.flush().await
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.sink.flush().await?;
+        self.raw.flush().await
+    }
+}
+
+async fn next_raw(raw: &mut Option<UnboundedReceiver<RawFrame>>) -> Option<RawFrame> {
+    match raw {
+        Some(raw) => raw.recv().await,
+        None => pending().await,
+    }
+}
+
+/// SIGINT, or SIGTERM on unix
+async fn shutdown() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut terminate = signal(SignalKind::terminate()).expect("SIGTERM handler");
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        }
+    }
+
+    #[cfg(not(unix))]
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let args = Args::parse();
+    let config = args.fleet()?;
+    config.validate()?;
+
+    #[cfg(feature = "prometheus")]
+    if let Some(listen) = args.metrics {
+        snapshot::metrics::install_prometheus(listen)?;
+        info!("metrics on http://{}/metrics", listen);
+    }
+    fs::create_dir_all(&args.out)?;
+
+    let mut raw = args.raw.then(|| RawFrames::global().subscribe());
+    let mut fleet = config.start()?;
+    info!("recording {:?} to {}", fleet.names(), args.out.display());
+
+    let period = Duration::from_secs(args.status_secs.max(1));
+    let mut status = interval_at(tokio::time::Instant::now() + period, period);
+    status.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let mut recorder = Recorder::new(args, fleet.names());
+    let shutdown = shutdown();
+    tokio::pin!(shutdown);
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => {
+                info!("shutting down");
+                break;
+            }
+            event = fleet.recv() => match event {
+                Some(event) => recorder.record(event).await?,
+                None => {
+                    info!("every stream ended");
+                    break;
+                }
+            },
+            Some(frame) = next_raw(&mut raw) => recorder.record_raw(frame).await?,
+            _ = status.tick() => recorder.status().await?,
+        }
+    }
+
+    fleet.close().await;
+    recorder.flush().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Args, Format};
+    use clap::Parser;
+    use snapshot::{StreamKind, TimeFormat};
+
+    #[test]
+    fn parse_args() {
+        let args = Args::parse_from([
+            "snapshot-record",
+            "--exchange",
+            "binance",
+            "--symbol",
+            "BTC_USDT,ETH_USDT",
+            "--stream",
+            "level,bookTicker",
+        ]);
+        let fleet = args.fleet().unwrap();
+        let subscription = &fleet.subscriptions[0];
+        assert_eq!(subscription.symbols, ["BTC_USDT", "ETH_USDT"]);
+        assert_eq!(
+            subscription.streams,
+            [StreamKind::Level, StreamKind::BookTicker]
+        );
+
+        assert_eq!(args.format, Format::Jsonl);
+        assert_eq!(args.time, TimeFormat::Millis);
+
+        let args = Args::parse_from([
+            "snapshot-record",
+            "--config",
+            "fleet.toml",
+            "--format",
+            "csv",
+            "--levels",
+            "5",
+            "--time",
+            "rfc3339",
+        ]);
+        assert_eq!(args.format, Format::Csv);
+        assert_eq!(args.levels, Some(5));
+        assert_eq!(args.time, TimeFormat::Rfc3339);
+
+        assert!(Args::try_parse_from(["snapshot-record", "--exchange", "binance"]).is_err());
+        assert!(Args::try_parse_from(["snapshot-record", "--config", "a.toml"]).is_ok());
+    }
+}
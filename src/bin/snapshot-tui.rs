@@ -0,0 +1,540 @@
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use snapshot::{
+    AdapterRegistry, ConnectionState, Depth, DepthManager, OrderDirection, Quote, Ticker,
+    TickerManager,
+};
+use std::collections::VecDeque;
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::time::interval;
+
+/// Trades kept per book
+const TAPE: usize = 200;
+
+/// Window of the update rate and latency
+const WINDOW: Duration = Duration::from_secs(1);
+
+const REDRAW: Duration = Duration::from_millis(200);
+
+/// Live ladders, trades and connection health of order books, `q` quits
+#[derive(Parser, Debug)]
+#[command(name = "snapshot-tui")]
+struct Args {
+    /// Exchange name, e.g. binance
+    exchange: String,
+
+    /// Canonical symbol, e.g. BTC_USDT
+    symbol: String,
+
+    /// More books side by side, e.g. okx/BTC_USDT_SWAP
+    #[arg(long, value_parser = parse_book)]
+    compare: Vec<(String, String)>,
+
+    /// Book size kept from a snapshot and diffs,
+    /// the level stream of the exchange if unset
+    #[arg(long)]
+    limit: Option<i32>,
+
+    /// Levels shown per side
+    #[arg(long, default_value_t = 10)]
+    levels: usize,
+}
+
+fn parse_book(book: &str) -> Result<(String, String), String> {
+    match book.split_once('/') {
+        Some((exchange, symbol)) if !exchange.is_empty() && !symbol.is_empty() => {
+            Ok((exchange.to_string(), symbol.to_string()))
+        }
+        _ => Err(format!("{} is not EXCHANGE/SYMBOL", book)),
+    }
+}
+
+enum Update {
+    Depth(Depth),
+    Trades(Vec<Ticker>),
+    State(ConnectionState),
+    NoTrades(String),
+    Ended,
+}
+
+/// What is shown of one exchange and symbol
+struct Book {
+    name: String,
+    depth: Option<Depth>,
+    /// Newest first
+    trades: VecDeque<Ticker>,
+    /// Why the trades are missing
+    no_trades: Option<String>,
+    state: String,
+    /// Receive time and `lts - ts` of the updates within `WINDOW`
+    recent: VecDeque<(Instant, i64)>,
+}
+
+impl Book {
+    fn new(name: String) -> Self {
+        Book {
+            name,
+            depth: None,
+            trades: VecDeque::new(),
+            no_trades: None,
+            state: String::from("Connecting"),
+            recent: VecDeque::new(),
+        }
+    }
+
+    fn apply(&mut self, update: Update, now: Instant) {
+        match update {
+            Update::Depth(depth) => {
+                self.recent.push_back((now, depth.lts - depth.ts));
+                self.depth = Some(depth);
+            }
+            Update::Trades(trades) => {
+                for trade in trades {
+                    self.trades.push_front(trade);
+                }
+                self.trades.truncate(TAPE);
+            }
+            Update::State(state) => self.state = format!("{:?}", state),
+            Update::NoTrades(reason) => self.no_trades = Some(reason),
+            Update::Ended => self.state = String::from("Ended"),
+        }
+        self.prune(now);
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while let Some((received, _)) = self.recent.front() {
+            if now.duration_since(*received) <= WINDOW {
+                break;
+            }
+            self.recent.pop_front();
+        }
+    }
+
+    /// Updates per second
+    fn rate(&self) -> f64 {
+        self.recent.len() as f64 / WINDOW.as_secs_f64()
+    }
+
+    /// Mean exchange to local latency in milliseconds
+    fn latency(&self) -> Option<i64> {
+        let count = self.recent.len() as i64;
+        (count > 0).then(|| self.recent.iter().map(|(_, latency)| latency).sum::<i64>() / count)
+    }
+}
+
+/// Subscribe the book and trades, updates go to `sender` tagged with `index`
+fn start(
+    index: usize,
+    exchange: &str,
+    symbol: &str,
+    limit: Option<i32>,
+    sender: &UnboundedSender<(usize, Update)>,
+) -> Result<()> {
+    let adapter = AdapterRegistry::global()
+        .get(exchange)
+        .ok_or_else(|| anyhow!("Unsupported Exchange {}", exchange))?;
+    let depth = DepthManager::from_config(adapter.depth_config(symbol, limit)?)?;
+
+    let mut states = depth.subscribe_state();
+    let updates = sender.clone();
+    tokio::spawn(async move {
+        while let Some(state) = states.recv().await {
+            if updates.send((index, Update::State(state))).is_err() {
+                return;
+            }
+        }
+    });
+
+    let mut depths = depth.subscribe_depth();
+    let updates = sender.clone();
+    tokio::spawn(async move {
+        while let Some(depth) = depths.recv().await {
+            if updates.send((index, Update::Depth(depth))).is_err() {
+                return;
+            }
+        }
+        let _ = updates.send((index, Update::Ended));
+    });
+
+    let ticker = adapter
+        .ticker_config(symbol)
+        .and_then(TickerManager::from_config);
+    match ticker {
+        Ok(ticker) => {
+            let mut trades = ticker.subscribe();
+            let updates = sender.clone();
+            tokio::spawn(async move {
+                while let Some(trades) = trades.recv().await {
+                    if updates.send((index, Update::Trades(trades))).is_err() {
+                        return;
+                    }
+                }
+            });
+        }
+        Err(e) => {
+            let _ = sender.send((index, Update::NoTrades(e.to_string())));
+        }
+    }
+
+    Ok(())
+}
+
+/// Terminal events are read on a thread of their own, they block
+fn keys() -> UnboundedReceiver<Event> {
+    let (sender, receiver) = mpsc::unbounded_channel();
+
+    thread::spawn(move || {
+        while let Ok(event) = event::read() {
+            if sender.send(event).is_err() {
+                return;
+            }
+        }
+    });
+
+    receiver
+}
+
+fn quits(event: &Event) -> bool {
+    match event {
+        Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => true,
+            KeyCode::Char('c') => key.modifiers.contains(KeyModifiers::CONTROL),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+async fn run(
+    terminal: &mut DefaultTerminal,
+    books: &mut [Book],
+    mut updates: UnboundedReceiver<(usize, Update)>,
+    levels: usize,
+) -> Result<()> {
+    let mut keys = keys();
+    let mut redraw = interval(REDRAW);
+
+    loop {
+        tokio::select! {
+            Some((index, update)) = updates.recv() => books[index].apply(update, Instant::now()),
+            Some(event) = keys.recv() => {
+                if quits(&event) {
+                    return Ok(());
+                }
+            }
+            _ = redraw.tick() => {
+                let now = Instant::now();
+                books.iter_mut().for_each(|book| book.prune(now));
+                terminal.draw(|frame| draw(frame, books, levels))?;
+            }
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, books: &[Book], levels: usize) {
+    let columns = Layout::horizontal(vec![Constraint::Fill(1); books.len()]).split(frame.area());
+
+    for (book, area) in books.iter().zip(columns.iter()) {
+        draw_book(frame, book, levels, *area);
+    }
+}
+
+fn draw_book(frame: &mut Frame, book: &Book, levels: usize, area: Rect) {
+    let block = Block::bordered().title(book.name.as_str());
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let ladder_height = (levels * 2 + 1) as u16;
+    let [header, ladder, tape] = Layout::vertical([
+        Constraint::Length(2),
+        Constraint::Length(ladder_height),
+        Constraint::Fill(1),
+    ])
+    .areas(inner);
+
+    frame.render_widget(Paragraph::new(header_lines(book)), header);
+    frame.render_widget(
+        Paragraph::new(ladder_lines(book, levels, ladder.width as usize)),
+        ladder,
+    );
+    frame.render_widget(
+        Paragraph::new(tape_lines(book, tape.height as usize))
+            .block(Block::new().borders(Borders::TOP).title("trades")),
+        tape,
+    );
+}
+
+fn header_lines(book: &Book) -> Vec<Line<'_>> {
+    let color = match book.state.as_str() {
+        "Connected" => Color::Green,
+        "Connecting" => Color::Yellow,
+        _ => Color::Red,
+    };
+    let latency = match book.latency() {
+        Some(latency) => format!("latency {} ms", latency),
+        None => String::from("latency -"),
+    };
+
+    let health = Line::from(vec![
+        Span::styled(book.state.as_str(), Style::default().fg(color)),
+        Span::raw(format!("  {:.0} upd/s  {}", book.rate(), latency)),
+    ]);
+    let prices = match book.depth.as_ref().and_then(spread) {
+        Some((mid, spread, decimals)) => Line::from(format!(
+            "mid {:.prec$}  spread {:.decimals$} ({:.2} bps)",
+            mid,
+            spread,
+            spread / mid * 10_000.0,
+            prec = decimals + 1,
+        )),
+        None => Line::from("no book yet"),
+    };
+
+    vec![health, prices]
+}
+
+/// Mid, spread and the decimals of the best prices
+fn spread(depth: &Depth) -> Option<(f64, f64, usize)> {
+    let ask = depth.asks.first()?.price;
+    let bid = depth.bids.first()?.price;
+    let decimals = decimals(ask).max(decimals(bid));
+
+    Some(((ask + bid) / 2.0, ask - bid, decimals))
+}
+
+fn decimals(price: f64) -> usize {
+    price
+        .to_string()
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len())
+}
+
+/// Asks above bids, best prices in the middle, with size bars of `width`
+fn ladder_lines(book: &Book, levels: usize, width: usize) -> Vec<Line<'static>> {
+    let depth = match &book.depth {
+        Some(depth) => depth,
+        None => return vec![],
+    };
+
+    let asks = &depth.asks[..levels.min(depth.asks.len())];
+    let bids = &depth.bids[..levels.min(depth.bids.len())];
+    let bar_width = width.saturating_sub(26);
+    let largest = asks
+        .iter()
+        .chain(bids)
+        .map(|quote| quote.amount)
+        .fold(0.0, f64::max);
+
+    let line = |quote: &Quote, color: Color| {
+        Line::from(vec![
+            Span::styled(format!("{:>12} ", quote.price), Style::default().fg(color)),
+            Span::raw(format!("{:>12} ", quote.amount)),
+            Span::styled(
+                "█".repeat(bar(quote.amount, largest, bar_width)),
+                Style::default().fg(color),
+            ),
+        ])
+    };
+
+    let mut lines = vec![Line::default(); levels - asks.len()];
+    lines.extend(asks.iter().rev().map(|quote| line(quote, Color::Red)));
+    lines.push(Line::from("-".repeat(width.min(26))));
+    lines.extend(bids.iter().map(|quote| line(quote, Color::Green)));
+    lines
+}
+
+/// Bar length of `amount` when `largest` fills `width`, non-empty levels get one at least
+fn bar(amount: f64, largest: f64, width: usize) -> usize {
+    if amount <= 0.0 || largest <= 0.0 {
+        return 0;
+    }
+
+    ((amount / largest * width as f64).round() as usize).clamp(1, width.max(1))
+}
+
+fn tape_lines(book: &Book, height: usize) -> Vec<Line<'_>> {
+    if let Some(reason) = &book.no_trades {
+        return vec![Line::from(reason.as_str())];
+    }
+
+    book.trades
+        .iter()
+        .take(height)
+        .map(|trade| {
+            let color = match trade.direction {
+                OrderDirection::Buy => Color::Green,
+                OrderDirection::Sell => Color::Red,
+            };
+            Line::styled(
+                format!(
+                    "{} {:>12} {:>12}",
+                    clock(trade.ts),
+                    trade.price,
+                    trade.amount
+                ),
+                Style::default().fg(color),
+            )
+        })
+        .collect()
+}
+
+/// "HH:MM:SS.mmm" in UTC of milliseconds since the epoch
+fn clock(ms: i64) -> String {
+    let seconds = ms.div_euclid(1000);
+    let day = seconds.rem_euclid(86_400);
+
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        day / 3600,
+        day % 3600 / 60,
+        day % 60,
+        ms.rem_euclid(1000)
+    )
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let sources = [(args.exchange.clone(), args.symbol.clone())]
+        .into_iter()
+        .chain(args.compare.iter().cloned())
+        .collect::<Vec<_>>();
+
+    let (sender, updates) = mpsc::unbounded_channel();
+    let mut books = vec![];
+    for (index, (exchange, symbol)) in sources.iter().enumerate() {
+        start(index, exchange, symbol, args.limit, &sender)
+            .map_err(|e| anyhow!("{} {}: {}", exchange, symbol, e))?;
+        books.push(Book::new(format!("{} {}", exchange, symbol)));
+    }
+
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut books, updates, args.levels.max(1)).await;
+    ratatui::restore();
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bar, clock, draw, parse_book, spread, Book, Update};
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+    use snapshot::{ConnectionState, Depth, OrderDirection, Quote, Ticker};
+    use std::time::{Duration, Instant};
+
+    fn quote(price: f64, amount: f64) -> Quote {
+        Quote {
+            price,
+            amount,
+            orders: None,
+        }
+    }
+
+    fn depth(ts: i64, lts: i64) -> Depth {
+        Depth {
+            ts,
+            lts,
+            id: 1,
+            asks: vec![quote(100.5, 2.0), quote(101.0, 4.0)],
+            bids: vec![quote(100.0, 1.0), quote(99.5, 0.0)],
+        }
+    }
+
+    #[test]
+    fn parse_books() {
+        assert_eq!(
+            parse_book("okx/BTC_USDT_SWAP"),
+            Ok((String::from("okx"), String::from("BTC_USDT_SWAP")))
+        );
+        assert!(parse_book("okx").is_err());
+        assert!(parse_book("/BTC_USDT").is_err());
+    }
+
+    #[test]
+    fn prices_and_bars() {
+        assert_eq!(spread(&depth(0, 0)), Some((100.25, 0.5, 1)));
+
+        assert_eq!(bar(4.0, 4.0, 10), 10);
+        assert_eq!(bar(2.0, 4.0, 10), 5);
+        assert_eq!(bar(0.01, 4.0, 10), 1);
+        assert_eq!(bar(0.0, 4.0, 10), 0);
+        assert_eq!(bar(1.0, 4.0, 0), 1);
+
+        assert_eq!(clock(1_671_000_000_123), "06:40:00.123");
+    }
+
+    #[test]
+    fn rate_and_latency_window() {
+        let start = Instant::now();
+        let mut book = Book::new(String::from("binance BTC_USDT"));
+        assert_eq!(book.latency(), None);
+
+        book.apply(Update::Depth(depth(100, 130)), start);
+        book.apply(Update::Depth(depth(200, 210)), start);
+        assert_eq!(book.rate(), 2.0);
+        assert_eq!(book.latency(), Some(20));
+
+        book.apply(
+            Update::Depth(depth(300, 340)),
+            start + Duration::from_millis(1500),
+        );
+        assert_eq!(book.rate(), 1.0);
+        assert_eq!(book.latency(), Some(40));
+
+        book.apply(Update::State(ConnectionState::Connected), start);
+        assert_eq!(book.state, "Connected");
+    }
+
+    #[test]
+    fn render_books() {
+        let mut book = Book::new(String::from("binance BTC_USDT"));
+        book.apply(Update::Depth(depth(100, 130)), Instant::now());
+        book.apply(
+            Update::Trades(vec![Ticker {
+                lts: 2,
+                ts: 1,
+                price: 100.25,
+                amount: 0.75,
+                direction: OrderDirection::Buy,
+                id: 7,
+            }]),
+            Instant::now(),
+        );
+        let mut other = Book::new(String::from("okx BTC_USDT_SWAP"));
+        other.apply(Update::NoTrades(String::from("No trades")), Instant::now());
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 24)).unwrap();
+        terminal
+            .draw(|frame| draw(frame, &[book, other], 3))
+            .unwrap();
+
+        let screen = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect::<String>();
+        for text in [
+            "binance BTC_USDT",
+            "okx BTC_USDT_SWAP",
+            "spread 0.5 (49.88 bps)",
+            "101",
+            "00:00:00.001",
+            "0.75",
+            "No trades",
+            "no book yet",
+        ] {
+            assert!(screen.contains(text), "{} missing", text);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use crate::binance::connection::binance_perpetual_coin::BinanceSpotOrderBookPerpetualCoin;
+use crate::binance::connection::binance_perpetual_usdt::BinanceSpotOrderBookPerpetualUSDT;
+use crate::binance::connection::binance_spot::BinanceOrderBookSpot;
+use crate::binance::connection::instrument::fetch_instruments;
+use crate::binance::BinanceTicker;
+use crate::config::{
+    exchange_info_addresses_binance, set_addr_for_binance, validate_symbol_binance, DepthType,
+    Method, SymbolType,
+};
+use crate::config::{instrument_binance, symbol_type_binance, Instrument};
+use crate::{
+    DepthConfig, DepthT, ExchangeAdapter, ExchangeType, InstrumentInfo, TickerConfig, TickerT,
+};
+use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
+use std::sync::Arc;
+
+/// Spot, USDT and coin margined contracts of Binance
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BinanceAdapter;
+
+impl ExchangeAdapter for BinanceAdapter {
+    fn name(&self) -> &'static str {
+        "binance"
+    }
+
+    fn exchange_type(&self) -> ExchangeType {
+        ExchangeType::Binance
+    }
+
+    fn validate_symbol(&self, symbol: &str, _limit: Option<i32>) -> Result<SymbolType> {
+        validate_symbol_binance(symbol)
+    }
+
+    fn depth_url(&self, symbol_type: &SymbolType, limit: Option<i32>) -> Result<DepthType> {
+        let (rest_address, depth_address, level_depth_address) =
+            set_addr_for_binance(symbol_type.clone(), limit, Method::Depth);
+
+        DepthType::new(rest_address, depth_address, level_depth_address)
+            .ok_or_else(|| anyhow!("depth url is empty"))
+    }
+
+    fn ticker_url(&self, symbol_type: &SymbolType) -> Result<String> {
+        let (_, _, ticker_url) = set_addr_for_binance(symbol_type.clone(), None, Method::Ticker);
+
+        ticker_url.ok_or_else(|| anyhow!("ticker url is empty"))
+    }
+
+    fn depth_connection(&self, config: &DepthConfig) -> Result<Arc<dyn DepthT + Send + Sync>> {
+        let connection: Arc<dyn DepthT + Send + Sync> = match &config.symbol_type {
+            SymbolType::Spot(_) => Arc::new(BinanceOrderBookSpot::new()),
+            SymbolType::ContractUSDT(_) => Arc::new(BinanceSpotOrderBookPerpetualUSDT::new()),
+            SymbolType::ContractCoin(_) => Arc::new(BinanceSpotOrderBookPerpetualCoin::new()),
+            other => return Err(anyhow!("Unsupported symbol {:?} for binance", other)),
+        };
+
+        Ok(connection)
+    }
+
+    fn ticker_connection(&self, _config: &TickerConfig) -> Result<Arc<dyn TickerT + Send + Sync>> {
+        Ok(Arc::new(BinanceTicker::new()))
+    }
+
+    fn native_symbol(&self, instrument: &Instrument) -> Result<SymbolType> {
+        symbol_type_binance(instrument)
+    }
+
+    fn instrument_of(&self, symbol_type: &SymbolType) -> Result<Instrument> {
+        instrument_binance(symbol_type)
+    }
+
+    /// "btcusd_221230" => "BTCUSD_221230"
+    fn instrument_name(&self, symbol_type: &SymbolType) -> Option<String> {
+        match symbol_type {
+            SymbolType::Spot(inner)
+            | SymbolType::ContractUSDT(inner)
+            | SymbolType::ContractCoin(inner) => Some(inner.to_uppercase()),
+            SymbolType::Option(_) => None,
+        }
+    }
+
+    fn instruments(&self) -> BoxFuture<'_, Result<Vec<InstrumentInfo>>> {
+        Box::pin(fetch_instruments(exchange_info_addresses_binance()))
+    }
+}
@@ -6,7 +6,8 @@ use crate::binance::format::binance_perpetual_coin::{
     StreamEventPerpetualCoin, StreamLevelEventPerpetualCoin,
 };
 use crate::binance::format::SharedT;
-use crate::{Depth, DepthConfig, DepthT};
+use crate::metrics;
+use crate::{Backoff, ConnectionState, Depth, DepthConfig, DepthT, StateSender};
 
 
 use anyhow::anyhow;
@@ -19,6 +20,7 @@ use tracing::{debug, error, info};
 #[derive(Clone)]
 pub struct BinanceSpotOrderBookPerpetualCoin {
     status: Arc<Mutex<bool>>,
+    state: StateSender,
     pub(crate) shared: Arc<RwLock<SharedPerpetualCoin>>,
 }
 
@@ -41,6 +43,7 @@ impl DepthT for BinanceSpotOrderBookPerpetualCoin {
     fn new() -> Self {
         BinanceSpotOrderBookPerpetualCoin {
             status: Arc::new(Mutex::new(false)),
+            state: StateSender::new("binance"),
             shared: Arc::new(RwLock::new(SharedPerpetualCoin::new())),
         }
     }
@@ -49,12 +52,17 @@ impl DepthT for BinanceSpotOrderBookPerpetualCoin {
     fn depth_snapshot(&self, config: DepthConfig ) -> Result<UnboundedReceiver<Depth>> {
         let shared = self.shared.clone();
         let status = self.status.clone();
+        let state = self.state.clone();
+        let audit_interval = config.audit_interval;
+        let symbol = config.get_symbol();
+        let reconnect = config.reconnect.clone();
         let (rest_address, depth_address) = config.get_depth_snapshot_addresses();
         let (sender, receiver) = mpsc::unbounded_channel();
         let sender = sender.clone();
         // Thread to maintain Order Book
         let _ = tokio::spawn(async move {
             info!("Start OrderBook thread");
+            let mut backoff = Backoff::new(reconnect);
             loop {
                 let res = try_get_connection::<
                     EventPerpetualCoin,
@@ -65,14 +73,24 @@ impl DepthT for BinanceSpotOrderBookPerpetualCoin {
                     sender.clone(),
                     rest_address.clone(),
                     depth_address.clone(),
+                    &symbol,
                     status.clone(),
                     shared.clone(),
+                    state.clone(),
+                    audit_interval,
+                    &mut backoff,
                 )
                 .await;
 
                 match res {
-                    Ok(false) => error!("Try get connection failed retrying"),
-                    Err(e) => error!("Error happen when try get connection {:?}", e),
+                    Ok(false) => {
+                        error!("Try get connection failed retrying");
+                        metrics::reconnect("binance");
+                    }
+                    Err(e) => {
+                        error!("Error happen when try get connection {:?}", e);
+                        break;
+                    }
                     _ => unreachable!(),
                 }
             }
@@ -84,6 +102,8 @@ impl DepthT for BinanceSpotOrderBookPerpetualCoin {
     fn depth(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
         let shared = self.shared.clone();
         let level_address = config.get_depth_addresses();
+        let symbol = config.get_symbol();
+        let reconnect = config.reconnect.clone();
         // This is not actually used
         let status = self.status.clone();
 
@@ -91,17 +111,19 @@ impl DepthT for BinanceSpotOrderBookPerpetualCoin {
 
         let _ = tokio::spawn(async move {
             info!("Start Level OrderBook thread");
+            let mut backoff = Backoff::new(reconnect);
             loop {
-                if let Ok(mut guard) = status.lock() {
-                    (*guard) = false;
-                }
                 let mut stream = match socket_stream(&level_address).await {
                     Ok(stream) => stream,
                     Err(e) => {
                         error!("Error calling {}, {:?}", level_address, e);
+                        if !backoff.wait("binance").await {
+                            break;
+                        }
                         continue;
                     }
                 };
+                backoff.connected();
 
                 info!("Successfully connected to {}", level_address);
 
@@ -134,6 +156,7 @@ impl DepthT for BinanceSpotOrderBookPerpetualCoin {
                         (*guard).set_level_event(level_event);
 
                         let snapshot = (*guard).get_snapshot().depth();
+                        metrics::book("binance", &symbol, &snapshot);
                         if let Err(_) = sender.send(snapshot) {
                             error!("level_depth Send Snapshot error");
                         };
@@ -141,6 +164,14 @@ impl DepthT for BinanceSpotOrderBookPerpetualCoin {
                         error!("SharedSpot is busy");
                     }
                 }
+                metrics::reconnect("binance");
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = false;
+                }
+                if !backoff.wait("binance").await {
+                    break;
+                }
             }
         });
 
@@ -164,34 +195,38 @@ impl DepthT for BinanceSpotOrderBookPerpetualCoin {
             None
         }
     }
+
+    fn subscribe_state(&self) -> UnboundedReceiver<ConnectionState> {
+        self.state.subscribe()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::binance::connection::BinanceSpotOrderBookPerpetualCoin;
-    use crate::config::{DepthConfig, Method, SymbolType};
-    use crate::ExchangeType;
-    use std::sync::{Arc, Mutex, RwLock};
+    use crate::binance::connection::binance_perpetual_coin::BinanceSpotOrderBookPerpetualCoin;
+    use crate::config::{DepthConfig, DepthType, SymbolType};
+    use crate::{DepthT, ExchangeType, ReconnectPolicy};
     use tokio::runtime::Runtime;
-    const DEPTH_URL: &str = "wss://dstream.binance.com/stream?streams=btcusdt_221230@depth@100m";
-    const REST: &str = "https://dapi.binance.com/dapi/v1/depth?symbol=BTCUSDT_221230&limit=1000";
+    const DEPTH_URL: &str = "wss://dstream.binance.com/stream?streams=btcusd_perp@depth@100ms";
+    const REST: &str = "https://dapi.binance.com/dapi/v1/depth?symbol=BTCUSD_PERP&limit=1000";
     #[test]
+    #[ignore = "requires live exchange connection"]
     fn binance_perpetual_coin_function() {
         let config = DepthConfig {
-            rest_url: Some(DEPTH_URL.to_string()),
-            depth_url: Some(REST.to_string()),
-            level_depth_url: None,
-            symbol_type: SymbolType::ContractCoin(String::from("BTCUSD-PERP")),
+            depth_url: DepthType::DepthSnapshot(REST.to_string(), DEPTH_URL.to_string()),
+            symbol_type: SymbolType::ContractCoin(String::from("btcusd_perp")),
             exchange_type: ExchangeType::Binance,
+            limit: Some(1000),
+            checksum: None,
+            audit_interval: None,
+            reconnect: ReconnectPolicy::default(),
         };
 
         tracing_subscriber::fmt::init();
 
         Runtime::new().unwrap().block_on(async {
-            let ticker = BinanceSpotOrderBookPerpetualCoin::new();
-            let mut recv = ticker
-                .depth(REST.to_string(), DEPTH_URL.to_string())
-                .unwrap();
+            let book = BinanceSpotOrderBookPerpetualCoin::new();
+            let mut recv = book.depth_snapshot(config).unwrap();
 
             let depth = recv.recv().await;
             assert!(depth.is_some());
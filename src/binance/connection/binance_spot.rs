@@ -3,7 +3,8 @@ use crate::binance::format::binance_spot::{
     BinanceSnapshotSpot, EventSpot, LevelEventSpot, SharedSpot,
 };
 use crate::binance::format::SharedT;
-use crate::{Depth, DepthConfig, DepthT};
+use crate::metrics;
+use crate::{Backoff, ConnectionState, Depth, DepthConfig, DepthT, StateSender};
 
 
 use anyhow::anyhow;
@@ -16,6 +17,7 @@ use tracing::{debug, error, info};
 #[derive(Clone)]
 pub struct BinanceOrderBookSpot {
     status: Arc<Mutex<bool>>,
+    state: StateSender,
     shared: Arc<RwLock<SharedSpot>>,
 }
 
@@ -46,6 +48,7 @@ impl DepthT for BinanceOrderBookSpot {
     fn new() -> Self {
         BinanceOrderBookSpot {
             status: Arc::new(Mutex::new(false)),
+            state: StateSender::new("binance"),
             shared: Arc::new(RwLock::new(SharedSpot::new())),
         }
     }
@@ -53,26 +56,41 @@ impl DepthT for BinanceOrderBookSpot {
     fn depth_snapshot(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
         let shared = self.shared.clone();
         let status = self.status.clone();
+        let state = self.state.clone();
+        let audit_interval = config.audit_interval;
+        let symbol = config.get_symbol();
+        let reconnect = config.reconnect.clone();
         let (rest_address, depth_address) = config.get_depth_snapshot_addresses();
         let (sender, receiver) = mpsc::unbounded_channel();
         let sender = sender.clone();
         // Thread to maintain Order Book
         let _ = tokio::spawn(async move {
             info!("Start OrderBook thread");
+            let mut backoff = Backoff::new(reconnect);
             loop {
                 let res =
                     try_get_connection::<EventSpot, BinanceSnapshotSpot, SharedSpot, EventSpot>(
                         sender.clone(),
                         rest_address.clone(),
                         depth_address.clone(),
+                        &symbol,
                         status.clone(),
                         shared.clone(),
+                        state.clone(),
+                        audit_interval,
+                        &mut backoff,
                     )
                     .await;
 
                 match res {
-                    Ok(false) => error!("Try get connection failed retrying"),
-                    Err(e) => error!("Error happen when try get connection {:?}", e),
+                    Ok(false) => {
+                        error!("Try get connection failed retrying");
+                        metrics::reconnect("binance");
+                    }
+                    Err(e) => {
+                        error!("Error happen when try get connection {:?}", e);
+                        break;
+                    }
                     _ => unreachable!(),
                 }
             }
@@ -84,23 +102,27 @@ impl DepthT for BinanceOrderBookSpot {
     fn depth(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
         let shared = self.shared.clone();
         let level_address = config.get_depth_addresses();
+        let symbol = config.get_symbol();
+        let reconnect = config.reconnect.clone();
         // This is not actually used
         let status = self.status.clone();
         let (sender, receiver) = mpsc::unbounded_channel();
 
         let _ = tokio::spawn(async move {
             info!("Start Level Buffer maintain thread");
+            let mut backoff = Backoff::new(reconnect);
             loop {
-                if let Ok(mut guard) = status.lock() {
-                    (*guard) = false;
-                }
                 let mut stream = match socket_stream(&level_address).await {
                     Ok(stream) => stream,
                     Err(e) => {
                         error!("Error calling {}, {:?}", level_address, e);
+                        if !backoff.wait("binance").await {
+                            break;
+                        }
                         continue;
                     }
                 };
+                backoff.connected();
 
                 info!("Successfully connected to {}", level_address);
                 if let Ok(mut guard) = status.lock() {
@@ -126,6 +148,7 @@ impl DepthT for BinanceOrderBookSpot {
 
                         let snapshot = (*guard).get_snapshot().depth();
 
+                        metrics::book("binance", &symbol, &snapshot);
                         if let Err(_) = sender.send(snapshot) {
                             error!("level_depth send Snapshot error");
                         };
@@ -133,6 +156,14 @@ impl DepthT for BinanceOrderBookSpot {
                         error!("SharedSpot is busy");
                     }
                 }
+                metrics::reconnect("binance");
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = false;
+                }
+                if !backoff.wait("binance").await {
+                    break;
+                }
             }
         });
 
@@ -155,4 +186,8 @@ impl DepthT for BinanceOrderBookSpot {
             None
         }
     }
+
+    fn subscribe_state(&self) -> UnboundedReceiver<ConnectionState> {
+        self.state.subscribe()
+    }
 }
@@ -1,13 +1,18 @@
-use crate::binance::format::{EventT, SharedT, SnapshotT, StreamEventT};
-use crate::Depth;
+use crate::binance::format::{SharedT, StreamEventT};
+use crate::api::sync::{
+    socket_events, DiffBook, DiffBookSynchronizer, DiffEvent, DiffSnapshot, RestSnapshot, ServerPing,
+};
+use crate::metrics;
+use crate::{Backoff, ConnectionState, Depth, Quote, RawFrames, StateSender};
 
 use anyhow::{anyhow, Result};
-use futures_util::{SinkExt, StreamExt};
+use futures_util::{pin_mut, SinkExt, StreamExt};
 use serde::de::DeserializeOwned;
 use std::collections::VecDeque;
 use std::sync::{Arc, Mutex, RwLock};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::net::TcpStream;
+use tokio::sync::mpsc;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::time::sleep;
 use tokio_tungstenite::{connect_async, tungstenite, MaybeTlsStream, WebSocketStream};
@@ -15,7 +20,11 @@ use tracing::{debug, error, info, warn};
 use tungstenite::Message;
 use url::Url;
 
-const MAX_BUFFER_EVENTS: usize = 5;
+/// Events kept to replay on top of an audit snapshot
+const MAX_AUDIT_EVENTS: usize = 1000;
+
+/// Levels per side compared by the audit
+const AUDIT_LEVELS: usize = 20;
 
 pub type BinanceWebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
@@ -28,17 +37,22 @@ pub async fn socket_stream(address: &str) -> Result<BinanceWebSocket, String> {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn try_get_connection<
-    Event: DeserializeOwned + EventT,
-    Snapshot: SnapshotT + DeserializeOwned,
-    Shard: SharedT<Event, BinanceSnapshot = Snapshot>,
+    Event: DeserializeOwned + DiffEvent + Clone,
+    Snapshot: DiffSnapshot + DeserializeOwned + Send + 'static,
+    Shard: SharedT<Event> + DiffBook<Event, Snapshot = Snapshot> + Default,
     StreamEvent: StreamEventT + DeserializeOwned + StreamEventT<Event = Event>,
 >(
     sender: UnboundedSender<Depth>,
     rest_address: String,
     depth_address: String,
+    symbol: &str,
     status: Arc<Mutex<bool>>,
     shared: Arc<RwLock<Shard>>,
+    state: StateSender,
+    audit_interval: Option<Duration>,
+    backoff: &mut Backoff,
 ) -> Result<bool> {
     if let Ok(mut guard) = status.lock() {
         (*guard) = false;
@@ -47,90 +61,207 @@ pub async fn try_get_connection<
         Ok(stream) => stream,
         Err(e) => {
             error!("Error calling {}, {:?}", depth_address, e);
-            sleep(Duration::from_millis(100)).await;
+            if !backoff.wait("binance").await {
+                return Err(anyhow!("Gave up connecting to {}", depth_address));
+            }
             return Ok(false);
         }
     };
 
     info!("Successfully connected to {}", depth_address);
-    match initialize::<Event, Snapshot, Shard, StreamEvent>(
-        &mut stream,
-        rest_address.clone(),
-        shared.clone(),
-    )
-    .await
-    {
+    let events = socket_events(&mut stream, ServerPing, |text| {
+        RawFrames::global().publish("binance", text);
+        match serde_json::from_str::<StreamEvent>(text) {
+            Ok(event) => Some(event.event()),
+            Err(_) => {
+                metrics::decode_error("binance");
+                None
+            }
+        }
+    });
+    pin_mut!(events);
+
+    let synchronizer = DiffBookSynchronizer::new(shared.clone());
+    let rest_snapshot = RestSnapshot::new("binance", rest_address.clone());
+    match synchronizer.synchronize(&mut events, &rest_snapshot).await {
         Ok(overbook_setup) => {
             if overbook_setup {
+                backoff.connected();
                 if let Ok(mut guard) = status.lock() {
                     (*guard) = true;
                 };
+                state.send(ConnectionState::Connected);
             } else {
                 warn!("All event is not usable, need a new snapshot");
+                state.send(ConnectionState::Resyncing(String::from(
+                    "Buffered events do not match snapshot",
+                )));
                 return Ok(false);
             }
         }
         Err(e) => {
             error!("{:?}", e);
+            state.send(ConnectionState::Resyncing(format!("{:?}", e)));
+            if !backoff.wait("binance").await {
+                return Err(anyhow!("Gave up syncing {}", depth_address));
+            }
             return Ok(false);
         }
     };
 
     info!(" Overbook initialize success, now keep listening ");
 
-    while let Ok(message) = stream.next().await.unwrap() {
-        if message.is_ping() {
-            debug!("Receiving ping message");
-            let inner = message.clone().into_data();
-            match stream.send(Message::Pong(inner.clone())).await {
-                Ok(_) => continue,
-                Err(e) => {
-                    warn!("Send pong error {:?}", e);
-                    let _ = stream.send(Message::Pong(inner.clone())).await;
+    let (audit_sender, mut audit_receiver) = mpsc::unbounded_channel();
+    let audit = audit_interval.map(|interval| {
+        tokio::spawn(fetch_snapshots::<Snapshot>(
+            rest_address.clone(),
+            interval,
+            audit_sender,
+        ))
+    });
+    let mut audit_events = VecDeque::new();
+
+    loop {
+        let event = tokio::select! {
+            event = events.next() => match event {
+                Some(event) => event,
+                None => break,
+            },
+            Some(snapshot) = audit_receiver.recv() => {
+                if let Some((id, bids, asks)) =
+                    audit_orderbook::<Event, Snapshot, Shard>(&snapshot, &audit_events, &shared)
+                {
+                    warn!("Order book {} diverged, bids {} asks {}", id, bids, asks);
+                    state.send(ConnectionState::Diverged { id, bids, asks });
                 }
-            };
+                continue;
+            }
+        };
+
+        let audit_event = audit.as_ref().map(|_| event.clone());
+        if let Err(e) = synchronizer.apply(event) {
+            warn!("All event is not usable, need a new snapshot");
+            state.send(ConnectionState::Resyncing(e.to_string()));
+            break;
         }
 
-        let event = deserialize_event::<StreamEvent>(message.clone());
-        if event.is_none() {
-            warn!("Message decode error {:?}", message);
-            continue;
+        if let Some(event) = audit_event {
+            if audit_events.len() == MAX_AUDIT_EVENTS {
+                audit_events.pop_front();
+            }
+            audit_events.push_back(event);
         }
-        let event = event.unwrap().event();
 
-        let mut orderbook = shared.write().unwrap();
-        if event.equals(orderbook.id()) {
-            orderbook.add_event(event);
+        let snapshot = shared.read().unwrap().get_snapshot().depth();
 
-            let snapshot = orderbook.get_snapshot();
+        metrics::book("binance", symbol, &snapshot);
+        if sender.send(snapshot).is_err() {
+            error!("depth send Snapshot error");
+        };
+    }
 
-            if let Err(_) = sender.send(snapshot.depth()) {
-                error!("depth send Snapshot error");
-            };
-        } else {
-            warn!("All event is not usable, need a new snapshot");
-            break;
-        }
+    if let Some(audit) = audit {
+        audit.abort();
+    }
+
+    if let Ok(mut guard) = status.lock() {
+        (*guard) = false;
+    }
+    if !backoff.wait("binance").await {
+        return Err(anyhow!("Gave up reconnecting to {}", depth_address));
     }
     Ok(false)
 }
 
-fn deserialize_event<StreamEvent: DeserializeOwned>(message: Message) -> Option<StreamEvent> {
-    if !message.is_text() {
+/// Fetch a REST snapshot every `interval` for `audit_orderbook`
+async fn fetch_snapshots<Snapshot: DeserializeOwned>(
+    rest_address: String,
+    interval: Duration,
+    sender: UnboundedSender<Snapshot>,
+) {
+    loop {
+        sleep(interval).await;
+
+        let start = Instant::now();
+        let snapshot = match reqwest::get(&rest_address).await {
+            Ok(respond) => respond.json::<Snapshot>().await,
+            Err(e) => Err(e),
+        };
+
+        match snapshot {
+            Ok(snapshot) => {
+                metrics::snapshot_fetch("binance", start.elapsed());
+                if sender.send(snapshot).is_err() {
+                    break;
+                }
+            }
+            Err(e) => warn!("Audit snapshot error {:?}", e),
+        }
+    }
+}
+
+/// Replay `events` on top of `snapshot` and compare the result with
+/// the maintained order book, return `(id, bids, asks)` with the number
+/// of differing levels if there are any.
+///
+/// Returns `None` as well when the audit can not be done, e.g. `events`
+/// do not reach back to `snapshot`
+fn audit_orderbook<
+    Event: DiffEvent + Clone,
+    Snapshot: DiffSnapshot,
+    Shard: SharedT<Event> + DiffBook<Event, Snapshot = Snapshot> + Default,
+>(
+    snapshot: &Snapshot,
+    events: &VecDeque<Event>,
+    shared: &Arc<RwLock<Shard>>,
+) -> Option<(i64, usize, usize)> {
+    let snap_shot_id = snapshot.id();
+    let mut audit = Shard::default();
+    audit.load_snapshot(snapshot);
+
+    let mut matched = false;
+    for event in events {
+        if !matched {
+            if event.behind(snap_shot_id) {
+                continue;
+            }
+            if !event.matches(snap_shot_id) {
+                debug!("Audit events do not reach back to snapshot {}", snap_shot_id);
+                return None;
+            }
+            matched = true;
+        }
+        audit.add_event(event.clone());
+    }
+
+    let orderbook = shared.read().unwrap();
+    if orderbook.id() != audit.id() {
+        debug!(
+            "Audit snapshot {} is not comparable with order book {}",
+            audit.id(),
+            orderbook.id()
+        );
         return None;
     }
 
-    let text = match message.into_text() {
-        Ok(e) => e,
-        Err(_) => return None,
-    };
+    let expected = audit.get_snapshot();
+    let current = orderbook.get_snapshot();
 
-    let event: StreamEvent = match serde_json::from_str(&text) {
-        Ok(e) => e,
-        Err(_) => return None,
-    };
+    fn different(expected: &[Quote], current: &[Quote]) -> usize {
+        (0..AUDIT_LEVELS)
+            .filter(|&level| expected.get(level) != current.get(level))
+            .count()
+    }
 
-    Some(event)
+    let bids = different(&expected.bids, &current.bids);
+    let asks = different(&expected.asks, &current.asks);
+
+    if bids == 0 && asks == 0 {
+        debug!("Audit order book {} success", current.last_update_id);
+        None
+    } else {
+        Some((current.last_update_id, bids, asks))
+    }
 }
 
 pub async fn deserialize_event_with_stream<StreamEvent: DeserializeOwned>(
@@ -161,116 +292,16 @@ pub async fn deserialize_event_with_stream<StreamEvent: DeserializeOwned>(
             return None;
         }
     };
+    RawFrames::global().publish("binance", &text);
 
     let event: StreamEvent = match serde_json::from_str(&text) {
         Ok(e) => e,
         Err(e) => {
             warn!("Error {}, {:?}", e, message);
+            metrics::decode_error("binance");
             return None;
         }
     };
 
     Some(event)
 }
-
-fn add_event_to_orderbook<
-    Event: EventT,
-    Snapshot: SnapshotT,
-    Shard: SharedT<Event, BinanceSnapshot = Snapshot>,
->(
-    event: Event,
-    shared: Arc<RwLock<Shard>>,
-    snapshot: &Snapshot,
-) -> Result<bool> {
-    let snap_shot_id = snapshot.id();
-    if event.behind(snap_shot_id) {
-        return Ok(false);
-    }
-
-    if event.matches(snap_shot_id) {
-        let mut orderbook = shared.write().unwrap();
-        orderbook.load_snapshot(&snapshot);
-        orderbook.add_event(event);
-        return Ok(true);
-    }
-
-    if event.ahead(snap_shot_id) {
-        return Err(anyhow!(" Event is a head of snapshot"));
-    }
-
-    Ok(false)
-}
-
-async fn initialize<
-    Event: DeserializeOwned + EventT,
-    Snapshot: SnapshotT + DeserializeOwned,
-    Shard: SharedT<Event, BinanceSnapshot = Snapshot>,
-    StreamEvent: StreamEventT + DeserializeOwned + StreamEventT<Event = Event>,
->(
-    stream: &mut BinanceWebSocket,
-    rest_address: String,
-    shared: Arc<RwLock<Shard>>,
-) -> Result<bool> {
-    let mut buffer_events = VecDeque::new();
-
-    while let Ok(message) = stream.next().await.unwrap() {
-        let event = deserialize_event::<StreamEvent>(message).unwrap();
-        let event = event.event();
-        buffer_events.push_back(event);
-
-        if buffer_events.len() == MAX_BUFFER_EVENTS {
-            break;
-        }
-    }
-
-    // Wait for a while to collect event into buffer
-    let snapshot: Snapshot = reqwest::get(&rest_address).await?.json().await?;
-
-    info!("Successfully connected to {}", rest_address);
-
-    let mut overbook_setup = false;
-    let shared_clone = shared.clone();
-    while let Some(event) = buffer_events.pop_front() {
-        if let Ok(add_success) =
-            add_event_to_orderbook::<Event, Snapshot, Shard>(event, shared_clone.clone(), &snapshot)
-        {
-            if add_success {
-                overbook_setup = true;
-            } // else: event behind snap_shot wait for next message
-        } else {
-            // Add event to order book error, need a new https snapshot
-            return Ok(false);
-        };
-    }
-
-    if overbook_setup {
-        while let Some(event) = buffer_events.pop_front() {
-            let mut orderbook = shared.write().unwrap();
-            orderbook.add_event(event);
-        }
-        // default exit return Ok(true)
-    } else {
-        info!(" Try to wait new events for out snapshot");
-
-        while let Ok(message) = stream.next().await.unwrap() {
-            let event = deserialize_event::<StreamEvent>(message).unwrap();
-            let event = event.event();
-
-            if let Ok(add_success) = add_event_to_orderbook::<Event, Snapshot, Shard>(
-                event,
-                shared_clone.clone(),
-                &snapshot,
-            ) {
-                if add_success {
-                    // default exit return Ok(true)
-                    break;
-                } // else: event behind snap_shot wait for next message
-            } else {
-                warn!("All event is not usable, need a new snapshot");
-                return Ok(false);
-            };
-        }
-    }
-
-    return Ok(true);
-}
@@ -0,0 +1,49 @@
+use crate::binance::format::exchange_info::ExchangeInfoRespond;
+use crate::InstrumentInfo;
+use anyhow::Result;
+
+/// Instruments of every `exchangeInfo` address, e.g. spot, fapi and dapi
+pub async fn fetch_instruments(addresses: Vec<String>) -> Result<Vec<InstrumentInfo>> {
+    let mut instruments = Vec::new();
+    for address in addresses {
+        let respond: ExchangeInfoRespond = reqwest::get(&address).await?.json().await?;
+        instruments.extend(respond.instruments()?);
+    }
+
+    Ok(instruments)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::mock::serve_json;
+    use crate::binance::connection::instrument::fetch_instruments;
+    use tokio::runtime::Runtime;
+
+    const SPOT: &str = r#"{"symbols":[{"symbol":"BTCUSDT","status":"TRADING","filters":[{"filterType":"PRICE_FILTER","tickSize":"0.01000000"},{"filterType":"LOT_SIZE","stepSize":"0.00001000"}]}]}"#;
+
+    const FAPI: &str = r#"{"symbols":[{"symbol":"BTCUSDT","status":"TRADING","contractType":"PERPETUAL","deliveryDate":4133404800000,"filters":[{"filterType":"PRICE_FILTER","tickSize":"0.10"},{"filterType":"LOT_SIZE","stepSize":"0.001"},{"filterType":"MIN_NOTIONAL","notional":"100"}]}]}"#;
+
+    #[test]
+    fn instruments_from_mock_server() {
+        Runtime::new().unwrap().block_on(async {
+            let rest = serve_json(vec![
+                ("/api/v3/exchangeInfo", SPOT.to_string()),
+                ("/fapi/v1/exchangeInfo", FAPI.to_string()),
+            ])
+            .await;
+
+            let addresses = vec![
+                format!("{}/api/v3/exchangeInfo", rest),
+                format!("{}/fapi/v1/exchangeInfo", rest),
+            ];
+            let instruments = fetch_instruments(addresses).await.unwrap();
+            assert_eq!(instruments.len(), 2);
+            assert_eq!(instruments[0].tick_size, 0.01);
+            assert_eq!(instruments[1].tick_size, 0.1);
+            assert_eq!(instruments[1].min_notional, Some(100.0));
+
+            let missing = vec![format!("{}/dapi/v1/exchangeInfo", rest)];
+            assert!(fetch_instruments(missing).await.is_err());
+        })
+    }
+}
@@ -0,0 +1,87 @@
+use super::connect::{deserialize_event_with_stream, socket_stream};
+use crate::binance::format::kline::StreamEventKline;
+use crate::metrics;
+use crate::{Backoff, Bar, KlineConfig};
+use anyhow::Result;
+use futures_util::StreamExt;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tracing::{error, info, warn};
+
+#[derive(Clone)]
+pub struct BinanceKline {
+    status: Arc<Mutex<bool>>,
+}
+
+impl BinanceKline {
+    pub fn new() -> Self {
+        Self {
+            status: Arc::new(Mutex::new(false)),
+        }
+    }
+
+    pub fn connect(&self, config: KlineConfig) -> Result<UnboundedReceiver<Bar>> {
+        let kline_address = config.kline_url.clone();
+        let reconnect = config.reconnect.clone();
+        let status = self.status.clone();
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            info!("Start Kline maintain thread");
+            let mut backoff = Backoff::new(reconnect);
+            loop {
+                let mut stream = match socket_stream(&kline_address).await {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        warn!("connection error {:?}", e);
+                        if !backoff.wait("binance").await {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+                backoff.connected();
+                info!("Connect to {} success", &kline_address);
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = true;
+                }
+
+                while let Some(Ok(message)) = stream.next().await {
+                    let event = match deserialize_event_with_stream::<StreamEventKline>(
+                        message,
+                        &mut stream,
+                    )
+                    .await
+                    {
+                        Some(event) => event,
+                        None => continue,
+                    };
+
+                    match event.event().add_timestamp_transform_to_bar() {
+                        Ok(bar) => {
+                            if sender.send(bar).is_err() {
+                                error!("Binance Kline send Bar error");
+                            }
+                        }
+                        Err(e) => {
+                            warn!("Binance Kline decode error {:?}", e);
+                            metrics::decode_error("binance");
+                        }
+                    }
+                }
+                metrics::reconnect("binance");
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = false;
+                }
+                if !backoff.wait("binance").await {
+                    break;
+                }
+            }
+        });
+
+        Ok(receiver)
+    }
+}
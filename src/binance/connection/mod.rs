@@ -3,16 +3,18 @@ pub mod binance_perpetual_usdt;
 pub mod binance_spot;
 
 mod connect;
+pub(crate) mod instrument;
+mod kline;
 mod ticker;
 
+pub use kline::BinanceKline;
 pub use ticker::BinanceTicker;
 
+use crate::api::format::quote_tuples;
 use crate::Depth;
 use crate::Quote;
 
-use anyhow::Result;
 use serde::Deserialize;
-use tokio::sync::mpsc::UnboundedReceiver;
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct BinanceOrderBookSnapshot {
@@ -21,7 +23,9 @@ pub struct BinanceOrderBookSnapshot {
     pub create_time: i64,
     pub send_time: i64,
     pub receive_time: i64,
+    #[serde(deserialize_with = "quote_tuples")]
     pub bids: Vec<Quote>,
+    #[serde(deserialize_with = "quote_tuples")]
     pub asks: Vec<Quote>,
 }
 
@@ -80,19 +84,3 @@ impl BinanceOrderBookSnapshot {
         }
     }
 }
-
-pub trait BinanceDepthT {
-    fn new() -> Self
-    where
-        Self: Sized;
-
-    fn depth_snapshot(
-        &self,
-        rest_address: String,
-        depth_address: String,
-    ) -> Result<UnboundedReceiver<Depth>>;
-
-    fn depth(&self, level_address: String) -> Result<UnboundedReceiver<Depth>>;
-
-    fn snapshot(&self) -> Option<Depth>;
-}
@@ -1,12 +1,11 @@
 use crate::binance::format::ticker::EventTicker;
-use crate::{Ticker, TickerConfig};
+use crate::metrics;
+use crate::{Backoff, RawFrames, Ticker, TickerConfig, TickerT};
 use anyhow::{Error, Result};
 use futures_util::{SinkExt, StreamExt};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::UnboundedReceiver;
-use tokio::time::sleep;
 use tokio_tungstenite::connect_async;
 use tokio_tungstenite::tungstenite::Message;
 use tracing::{debug, error, info, warn};
@@ -23,15 +22,20 @@ impl BinanceTicker {
             status: Arc::new(Mutex::new(false)),
         }
     }
+}
 
+impl TickerT for BinanceTicker {
     #[allow(unreachable_code)]
-    pub fn connect(&self, config: TickerConfig) -> Result<UnboundedReceiver<Vec<Ticker>>> {
+    fn connect(&self, config: TickerConfig) -> Result<UnboundedReceiver<Vec<Ticker>>> {
         let level_address = config.ticker_url.clone();
+        let symbol = config.get_symbol();
+        let reconnect = config.reconnect.clone();
         let status = self.status.clone();
         let (sender, receiver) = mpsc::unbounded_channel();
 
         let _ = tokio::spawn(async move {
             info!("Start Level Buffer maintain thread");
+            let mut backoff = Backoff::new(reconnect);
             loop {
                 let result: Result<()> = {
                     let url = Url::parse(&level_address).expect("Bad URL");
@@ -39,10 +43,13 @@ impl BinanceTicker {
                         Ok((connection, _)) => connection,
                         Err(e) => {
                             warn!("connection error {:?}", e);
-                            sleep(Duration::from_millis(25)).await;
+                            if !backoff.wait("binance").await {
+                                break;
+                            }
                             continue;
                         }
                     };
+                    backoff.connected();
                     info!("Connect to {} success", &level_address);
 
                     if let Ok(mut guard) = status.lock() {
@@ -73,16 +80,19 @@ impl BinanceTicker {
                                 continue;
                             }
                         };
+                        RawFrames::global().publish("binance", &text);
 
                         let response: EventTicker = match serde_json::from_str(&text) {
                             Ok(response) => response,
                             Err(e) => {
                                 warn!("Error {}, {:?}", e, message);
+                                metrics::decode_error("binance");
                                 continue;
                             }
                         };
 
                         if let Some(ticks) = response.add_timestamp_transform_to_ticks() {
+                            metrics::trades("binance", &symbol, &ticks);
                             if let Err(_) = sender.send(ticks) {
                                 error!("Binance Ticker send Snapshot error");
                             };
@@ -90,6 +100,10 @@ impl BinanceTicker {
                             warn!("Binance Received empty ticks")
                         }
                     }
+
+                    if let Ok(mut guard) = status.lock() {
+                        (*guard) = false;
+                    }
                     Ok(())
                 };
 
@@ -97,6 +111,11 @@ impl BinanceTicker {
                     Ok(_) => (),
                     Err(e) => error!("Error happen when running level_depth: {:?}", e),
                 }
+                metrics::reconnect("binance");
+
+                if !backoff.wait("binance").await {
+                    break;
+                }
             }
             Ok::<(), Error>(())
         });
@@ -109,18 +128,20 @@ impl BinanceTicker {
 mod tests {
     use crate::binance::connection::ticker::BinanceTicker;
     use crate::config::{Method, SymbolType, TickerConfig};
-    use crate::ExchangeType;
+    use crate::{ExchangeType, ReconnectPolicy, TickerT};
     use std::sync::{Arc, Mutex, RwLock};
     use tokio::runtime::Runtime;
 
     const TICKER_URL: &str = "wss://stream.binance.com:9443/ws/bnbbtc@trade";
 
     #[test]
+    #[ignore = "requires live exchange connection"]
     fn binance_ticker_function() {
         let config = TickerConfig {
             ticker_url: TICKER_URL.to_string(),
             symbol_type: SymbolType::Spot(String::from("BTCUSD-PERP")),
             exchange_type: ExchangeType::Binance,
+            reconnect: ReconnectPolicy::default(),
         };
 
         tracing_subscriber::fmt::init();
@@ -1,10 +1,10 @@
+use crate::api::format::quote_tuples;
+use crate::api::sync::{DiffBook, DiffEvent, DiffSnapshot};
 use crate::binance::connection::BinanceOrderBookSnapshot;
-use crate::binance::format::{EventT, SharedT, SnapshotT, StreamEventT};
-use crate::Quote;
+use crate::binance::format::{SharedT, StreamEventT};
+use crate::{BookSide, OrderBook, Quote};
 
-use ordered_float::OrderedFloat;
 use serde::Deserialize;
-use std::collections::BTreeMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::debug;
 
@@ -84,15 +84,15 @@ pub struct EventPerpetualCoin {
     pub last_message_last_update_id: i64,
 
     /// Difference in bids
-    #[serde(rename = "b")]
+    #[serde(rename = "b", deserialize_with = "quote_tuples")]
     pub bids: Vec<Quote>,
 
     /// Difference in asks
-    #[serde(rename = "a")]
+    #[serde(rename = "a", deserialize_with = "quote_tuples")]
     pub asks: Vec<Quote>,
 }
 
-impl EventT for EventPerpetualCoin {
+impl DiffEvent for EventPerpetualCoin {
     /// only for contract_U
     /// Rule: `U<= id <= u`
     /// [E.U,..,S.u,..,E.u]
@@ -119,18 +119,6 @@ impl EventT for EventPerpetualCoin {
         self.last_update_id < snap_shot_id
     }
 
-    /// S.u [E.U,..,E.u]
-    fn ahead(&self, snap_shot_id: i64) -> bool {
-        debug!(
-            "order book {}, Event {}-{}({})",
-            snap_shot_id,
-            self.first_update_id,
-            self.last_update_id,
-            self.last_message_last_update_id
-        );
-        self.first_update_id > snap_shot_id
-    }
-
     fn equals(&self, snap_shot_id: i64) -> bool {
         debug!(
             "order book {}, Event {}-{}({})",
@@ -182,11 +170,11 @@ pub struct LevelEventPerpetualCoin {
     pub last_message_last_update_id: i64,
 
     /// Difference in bids
-    #[serde(rename = "b")]
+    #[serde(rename = "b", deserialize_with = "quote_tuples")]
     pub bids: Vec<Quote>,
 
     /// Difference in asks
-    #[serde(rename = "a")]
+    #[serde(rename = "a", deserialize_with = "quote_tuples")]
     pub asks: Vec<Quote>,
 }
 
@@ -207,23 +195,17 @@ pub struct BinanceSnapshotPerpetualCoin {
 
     pub pair: String,
 
+    #[serde(deserialize_with = "quote_tuples")]
     pub bids: Vec<Quote>,
 
+    #[serde(deserialize_with = "quote_tuples")]
     pub asks: Vec<Quote>,
 }
 
-impl SnapshotT for BinanceSnapshotPerpetualCoin {
+impl DiffSnapshot for BinanceSnapshotPerpetualCoin {
     fn id(&self) -> i64 {
         self.last_update_id
     }
-
-    fn bids(&self) -> &Vec<Quote> {
-        &self.bids
-    }
-
-    fn asks(&self) -> &Vec<Quote> {
-        &self.asks
-    }
 }
 
 pub struct SharedPerpetualCoin {
@@ -232,12 +214,11 @@ pub struct SharedPerpetualCoin {
     create_time: i64,
     send_time: i64,
     receive_time: i64,
-    asks: BTreeMap<OrderedFloat<f64>, f64>,
-    bids: BTreeMap<OrderedFloat<f64>, f64>,
+    book: OrderBook,
 }
 
-impl SharedT<EventPerpetualCoin> for SharedPerpetualCoin {
-    type BinanceSnapshot = BinanceSnapshotPerpetualCoin;
+impl DiffBook<EventPerpetualCoin> for SharedPerpetualCoin {
+    type Snapshot = BinanceSnapshotPerpetualCoin;
     /// return last_update_id
     /// or `u`
     fn id(&self) -> i64 {
@@ -245,15 +226,8 @@ impl SharedT<EventPerpetualCoin> for SharedPerpetualCoin {
     }
 
     fn load_snapshot(&mut self, snapshot: &BinanceSnapshotPerpetualCoin) {
-        self.asks.clear();
-        for ask in &snapshot.asks {
-            self.asks.insert(OrderedFloat(ask.price), ask.amount);
-        }
-
-        self.bids.clear();
-        for bid in &snapshot.bids {
-            self.bids.insert(OrderedFloat(bid.price), bid.amount);
-        }
+        self.book
+            .apply_snapshot(snapshot.bids.iter().copied(), snapshot.asks.iter().copied());
 
         self.last_update_id = snapshot.last_update_id;
         self.send_time = snapshot.event_time;
@@ -262,60 +236,36 @@ impl SharedT<EventPerpetualCoin> for SharedPerpetualCoin {
 
     /// Only used for "Event"
     fn add_event(&mut self, event: EventPerpetualCoin) {
-        for ask in event.asks {
-            if ask.amount == 0.0 {
-                self.asks.remove(&OrderedFloat(ask.price));
-            } else {
-                self.asks.insert(OrderedFloat(ask.price), ask.amount);
-            }
-        }
-
-        for bid in event.bids {
-            if bid.amount == 0.0 {
-                self.bids.remove(&OrderedFloat(bid.price));
-            } else {
-                self.bids.insert(OrderedFloat(bid.price), bid.amount);
-            }
-        }
+        self.book.apply_levels(BookSide::Ask, event.asks);
+        self.book.apply_levels(BookSide::Bid, event.bids);
         let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
         self.last_update_id = event.last_update_id;
         self.create_time = event.create_time;
         self.send_time = event.event_time;
         self.receive_time = time.as_millis() as i64;
     }
+}
 
+impl SharedT<EventPerpetualCoin> for SharedPerpetualCoin {
     fn get_snapshot(&self) -> BinanceOrderBookSnapshot {
-        let asks = self
-            .asks
-            .iter()
-            .map(|(price, amount)| Quote {
-                price: price.into_inner(),
-                amount: *amount,
-            })
-            .collect();
-
-        let bids = self
-            .bids
-            .iter()
-            .rev()
-            .map(|(price, amount)| Quote {
-                price: price.into_inner(),
-                amount: *amount,
-            })
-            .collect();
-
         BinanceOrderBookSnapshot {
             symbol: self.symbol.clone(),
             last_update_id: self.last_update_id,
             create_time: self.create_time,
             send_time: self.send_time,
             receive_time: self.receive_time,
-            asks,
-            bids,
+            asks: self.book.quotes(BookSide::Ask),
+            bids: self.book.quotes(BookSide::Bid),
         }
     }
 }
 
+impl Default for SharedPerpetualCoin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SharedPerpetualCoin {
     pub fn new() -> Self {
         SharedPerpetualCoin {
@@ -324,22 +274,13 @@ impl SharedPerpetualCoin {
             create_time: 0,
             send_time: 0,
             receive_time: 0,
-            asks: BTreeMap::new(),
-            bids: BTreeMap::new(),
+            book: OrderBook::new(),
         }
     }
 
     /// Only used for "LevelEvent"
     pub fn set_level_event(&mut self, level_event: LevelEventPerpetualCoin) {
-        self.asks.clear();
-        for ask in level_event.asks {
-            self.asks.insert(OrderedFloat(ask.price), ask.amount);
-        }
-
-        self.bids.clear();
-        for bid in level_event.bids {
-            self.bids.insert(OrderedFloat(bid.price), bid.amount);
-        }
+        self.book.apply_snapshot(level_event.bids, level_event.asks);
 
         let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
         self.last_update_id = level_event.last_update_id;
@@ -354,10 +295,12 @@ fn depth_row() {
     let a = Quote {
         amount: 1.0,
         price: 2.0,
+        orders: None,
     };
     let b = Quote {
         amount: 1.0,
         price: 2.0,
+        orders: None,
     };
     assert_eq!(a, b);
 }
@@ -1,10 +1,10 @@
+use crate::api::format::quote_tuples;
+use crate::api::sync::{DiffBook, DiffEvent, DiffSnapshot};
 use crate::binance::connection::BinanceOrderBookSnapshot;
-use crate::binance::format::{EventT, SharedT, SnapshotT, StreamEventT};
-use crate::Quote;
+use crate::binance::format::{SharedT, StreamEventT};
+use crate::{BookSide, OrderBook, Quote};
 
-use ordered_float::OrderedFloat;
 use serde::Deserialize;
-use std::collections::BTreeMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::debug;
 
@@ -79,15 +79,15 @@ pub struct EventPerpetualUSDT {
     pub last_message_last_update_id: i64,
 
     /// Difference in bids
-    #[serde(rename = "b")]
+    #[serde(rename = "b", deserialize_with = "quote_tuples")]
     pub bids: Vec<Quote>,
 
     /// Difference in asks
-    #[serde(rename = "a")]
+    #[serde(rename = "a", deserialize_with = "quote_tuples")]
     pub asks: Vec<Quote>,
 }
 
-impl EventT for EventPerpetualUSDT {
+impl DiffEvent for EventPerpetualUSDT {
     /// only for contract_U
     /// Rule: `U<= id <= u`
     /// [E.U,..,S.u,..,E.u]
@@ -114,18 +114,6 @@ impl EventT for EventPerpetualUSDT {
         self.last_update_id < snap_shot_id
     }
 
-    /// S.u [E.U,..,E.u]
-    fn ahead(&self, snap_shot_id: i64) -> bool {
-        debug!(
-            "order book {}, Event {}-{}({})",
-            snap_shot_id,
-            self.first_update_id,
-            self.last_update_id,
-            self.last_message_last_update_id
-        );
-        self.first_update_id > snap_shot_id
-    }
-
     fn equals(&self, snap_shot_id: i64) -> bool {
         debug!(
             "order book {}, Event {}-{}({})",
@@ -173,11 +161,11 @@ pub struct LevelEventPerpetualUSDT {
     pub last_message_last_update_id: i64,
 
     /// Difference in bids
-    #[serde(rename = "b")]
+    #[serde(rename = "b", deserialize_with = "quote_tuples")]
     pub bids: Vec<Quote>,
 
     /// Difference in asks
-    #[serde(rename = "a")]
+    #[serde(rename = "a", deserialize_with = "quote_tuples")]
     pub asks: Vec<Quote>,
 }
 
@@ -194,23 +182,17 @@ pub struct BinanceSnapshotPerpetualUSDT {
     #[serde(rename = "T")]
     pub create_time: i64,
 
+    #[serde(deserialize_with = "quote_tuples")]
     pub bids: Vec<Quote>,
 
+    #[serde(deserialize_with = "quote_tuples")]
     pub asks: Vec<Quote>,
 }
 
-impl SnapshotT for BinanceSnapshotPerpetualUSDT {
+impl DiffSnapshot for BinanceSnapshotPerpetualUSDT {
     fn id(&self) -> i64 {
         self.last_update_id
     }
-
-    fn bids(&self) -> &Vec<Quote> {
-        &self.bids
-    }
-
-    fn asks(&self) -> &Vec<Quote> {
-        &self.asks
-    }
 }
 
 pub struct SharedPerpetualUSDT {
@@ -219,12 +201,11 @@ pub struct SharedPerpetualUSDT {
     create_time: i64,
     send_time: i64,
     receive_time: i64,
-    asks: BTreeMap<OrderedFloat<f64>, f64>,
-    bids: BTreeMap<OrderedFloat<f64>, f64>,
+    book: OrderBook,
 }
 
-impl SharedT<EventPerpetualUSDT> for SharedPerpetualUSDT {
-    type BinanceSnapshot = BinanceSnapshotPerpetualUSDT;
+impl DiffBook<EventPerpetualUSDT> for SharedPerpetualUSDT {
+    type Snapshot = BinanceSnapshotPerpetualUSDT;
     /// return last_update_id
     /// or `u`
     fn id(&self) -> i64 {
@@ -232,15 +213,8 @@ impl SharedT<EventPerpetualUSDT> for SharedPerpetualUSDT {
     }
 
     fn load_snapshot(&mut self, snapshot: &BinanceSnapshotPerpetualUSDT) {
-        self.asks.clear();
-        for ask in &snapshot.asks {
-            self.asks.insert(OrderedFloat(ask.price), ask.amount);
-        }
-
-        self.bids.clear();
-        for bid in &snapshot.bids {
-            self.bids.insert(OrderedFloat(bid.price), bid.amount);
-        }
+        self.book
+            .apply_snapshot(snapshot.bids.iter().copied(), snapshot.asks.iter().copied());
 
         self.last_update_id = snapshot.last_update_id;
         self.send_time = snapshot.event_time;
@@ -250,60 +224,36 @@ impl SharedT<EventPerpetualUSDT> for SharedPerpetualUSDT {
     /// Only used for "Event"
     fn add_event(&mut self, event: EventPerpetualUSDT) {
         let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
-        for ask in event.asks {
-            if ask.amount == 0.0 {
-                self.asks.remove(&OrderedFloat(ask.price));
-            } else {
-                self.asks.insert(OrderedFloat(ask.price), ask.amount);
-            }
-        }
-
-        for bid in event.bids {
-            if bid.amount == 0.0 {
-                self.bids.remove(&OrderedFloat(bid.price));
-            } else {
-                self.bids.insert(OrderedFloat(bid.price), bid.amount);
-            }
-        }
+        self.book.apply_levels(BookSide::Ask, event.asks);
+        self.book.apply_levels(BookSide::Bid, event.bids);
 
         self.last_update_id = event.last_update_id;
         self.create_time = event.create_time;
         self.send_time = event.event_time;
         self.receive_time = time.as_millis() as i64;
     }
+}
 
+impl SharedT<EventPerpetualUSDT> for SharedPerpetualUSDT {
     fn get_snapshot(&self) -> BinanceOrderBookSnapshot {
-        let asks = self
-            .asks
-            .iter()
-            .map(|(price, amount)| Quote {
-                price: price.into_inner(),
-                amount: *amount,
-            })
-            .collect();
-
-        let bids = self
-            .bids
-            .iter()
-            .rev()
-            .map(|(price, amount)| Quote {
-                price: price.into_inner(),
-                amount: *amount,
-            })
-            .collect();
-
         BinanceOrderBookSnapshot {
             symbol: self.symbol.clone(),
             last_update_id: self.last_update_id,
             create_time: self.create_time,
             send_time: self.send_time,
             receive_time: self.receive_time,
-            asks,
-            bids,
+            asks: self.book.quotes(BookSide::Ask),
+            bids: self.book.quotes(BookSide::Bid),
         }
     }
 }
 
+impl Default for SharedPerpetualUSDT {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SharedPerpetualUSDT {
     pub fn new() -> Self {
         SharedPerpetualUSDT {
@@ -312,22 +262,13 @@ impl SharedPerpetualUSDT {
             create_time: 0,
             send_time: 0,
             receive_time: 0,
-            asks: BTreeMap::new(),
-            bids: BTreeMap::new(),
+            book: OrderBook::new(),
         }
     }
 
     /// Only used for "LevelEvent"
     pub fn set_level_event(&mut self, level_event: LevelEventPerpetualUSDT) {
-        self.asks.clear();
-        for ask in level_event.asks {
-            self.asks.insert(OrderedFloat(ask.price), ask.amount);
-        }
-
-        self.bids.clear();
-        for bid in level_event.bids {
-            self.bids.insert(OrderedFloat(bid.price), bid.amount);
-        }
+        self.book.apply_snapshot(level_event.bids, level_event.asks);
 
         let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
         self.last_update_id = level_event.last_update_id;
@@ -342,10 +283,12 @@ fn depth_row() {
     let a = Quote {
         amount: 1.0,
         price: 2.0,
+        orders: None,
     };
     let b = Quote {
         amount: 1.0,
         price: 2.0,
+        orders: None,
     };
     assert_eq!(a, b);
 }
@@ -1,14 +1,15 @@
+use crate::api::format::quote_tuples;
+use crate::api::sync::{DiffBook, DiffEvent, DiffSnapshot};
 use crate::binance::connection::BinanceOrderBookSnapshot;
-use crate::binance::format::{EventT, SharedT, SnapshotT, StreamEventT};
-use crate::Quote;
+use crate::binance::format::{SharedT, StreamEventT};
+use crate::{BookSide, OrderBook, Quote};
 
-use ordered_float::OrderedFloat;
 use serde::Deserialize;
-use std::collections::btree_map::BTreeMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::debug;
 
-#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct EventSpot {
     #[serde(rename = "e")]
     pub ttype: String,
@@ -20,28 +21,20 @@ pub struct EventSpot {
     pub first_update_id: i64,
     #[serde(rename = "u")]
     pub last_update_id: i64,
-    #[serde(rename = "b")]
+    #[serde(rename = "b", deserialize_with = "quote_tuples")]
     pub bids: Vec<Quote>,
-    #[serde(rename = "a")]
+    #[serde(rename = "a", deserialize_with = "quote_tuples")]
     pub asks: Vec<Quote>,
 }
 
 impl StreamEventT for EventSpot {
     type Event = EventSpot;
     fn event(&self) -> Self::Event {
-        EventSpot {
-            ttype: self.ttype.clone(),
-            ts: self.ts,
-            pair: self.pair.clone(),
-            first_update_id: self.first_update_id,
-            last_update_id: self.last_update_id,
-            bids: self.bids.clone(),
-            asks: self.bids.clone(),
-        }
+        self.clone()
     }
 }
 
-impl EventT for EventSpot {
+impl DiffEvent for EventSpot {
     /// [E.U,..,S.u,..,E.u]
     fn matches(&self, snap_shot_id: i64) -> bool {
         debug!(
@@ -62,16 +55,6 @@ impl EventT for EventSpot {
         self.last_update_id <= snap_shot_id
     }
 
-    /// S.u [E.U,..,E.u]
-    fn ahead(&self, snap_shot_id: i64) -> bool {
-        debug!(
-            "order book {}, Event {}-{}",
-            snap_shot_id, self.first_update_id, self.last_update_id
-        );
-
-        self.first_update_id > snap_shot_id + 1
-    }
-
     ///
     fn equals(&self, snap_shot_id: i64) -> bool {
         debug!(
@@ -88,11 +71,11 @@ pub struct LevelEventSpot {
     pub last_update_id: i64,
 
     /// Difference in bids
-    #[serde(rename = "bids")]
+    #[serde(rename = "bids", deserialize_with = "quote_tuples")]
     pub bids: Vec<Quote>,
 
     /// Difference in asks
-    #[serde(rename = "asks")]
+    #[serde(rename = "asks", deserialize_with = "quote_tuples")]
     pub asks: Vec<Quote>,
 }
 
@@ -115,22 +98,16 @@ impl StreamEventT for LevelEventSpot {
 #[serde(rename_all = "camelCase")]
 pub struct BinanceSnapshotSpot {
     pub last_update_id: i64,
+    #[serde(deserialize_with = "quote_tuples")]
     pub bids: Vec<Quote>,
+    #[serde(deserialize_with = "quote_tuples")]
     pub asks: Vec<Quote>,
 }
 
-impl SnapshotT for BinanceSnapshotSpot {
+impl DiffSnapshot for BinanceSnapshotSpot {
     fn id(&self) -> i64 {
         self.last_update_id
     }
-
-    fn bids(&self) -> &Vec<Quote> {
-        &self.bids
-    }
-
-    fn asks(&self) -> &Vec<Quote> {
-        &self.asks
-    }
 }
 
 pub struct SharedSpot {
@@ -139,8 +116,13 @@ pub struct SharedSpot {
     create_time: i64,
     send_time: i64,
     receive_time: i64,
-    asks: BTreeMap<OrderedFloat<f64>, f64>,
-    bids: BTreeMap<OrderedFloat<f64>, f64>,
+    book: OrderBook,
+}
+
+impl Default for SharedSpot {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SharedSpot {
@@ -151,22 +133,13 @@ impl SharedSpot {
             create_time: 0,
             send_time: 0,
             receive_time: 0,
-            asks: BTreeMap::new(),
-            bids: BTreeMap::new(),
+            book: OrderBook::new(),
         }
     }
 
     /// Only used for "LevelEvent"
     pub fn set_level_event(&mut self, level_event: LevelEventSpot) {
-        self.asks.clear();
-        for ask in level_event.asks {
-            self.asks.insert(OrderedFloat(ask.price), ask.amount);
-        }
-
-        self.bids.clear();
-        for bid in level_event.bids {
-            self.bids.insert(OrderedFloat(bid.price), bid.amount);
-        }
+        self.book.apply_snapshot(level_event.bids, level_event.asks);
 
         let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
         self.last_update_id = level_event.last_update_id;
@@ -175,79 +148,42 @@ impl SharedSpot {
     }
 }
 
-impl SharedT<EventSpot> for SharedSpot {
-    type BinanceSnapshot = BinanceSnapshotSpot;
+impl DiffBook<EventSpot> for SharedSpot {
+    type Snapshot = BinanceSnapshotSpot;
     /// return last_update_id
     fn id(&self) -> i64 {
         self.last_update_id
     }
 
     fn load_snapshot(&mut self, snapshot: &BinanceSnapshotSpot) {
-        self.asks.clear();
-        for ask in &snapshot.asks {
-            self.asks.insert(OrderedFloat(ask.price), ask.amount);
-        }
-
-        self.bids.clear();
-        for bid in &snapshot.bids {
-            self.bids.insert(OrderedFloat(bid.price), bid.amount);
-        }
+        self.book
+            .apply_snapshot(snapshot.bids.iter().copied(), snapshot.asks.iter().copied());
 
         self.last_update_id = snapshot.last_update_id;
     }
 
     /// Only used for "Event"
     fn add_event(&mut self, event: EventSpot) {
-        for ask in event.asks {
-            if ask.amount == 0.0 {
-                self.asks.remove(&OrderedFloat(ask.price));
-            } else {
-                self.asks.insert(OrderedFloat(ask.price), ask.amount);
-            }
-        }
-
-        for bid in event.bids {
-            if bid.amount == 0.0 {
-                self.bids.remove(&OrderedFloat(bid.price));
-            } else {
-                self.bids.insert(OrderedFloat(bid.price), bid.amount);
-            }
-        }
+        self.book.apply_levels(BookSide::Ask, event.asks);
+        self.book.apply_levels(BookSide::Bid, event.bids);
 
         let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
         self.last_update_id = event.last_update_id;
         self.send_time = event.ts;
         self.receive_time = time.as_millis() as i64;
     }
+}
 
+impl SharedT<EventSpot> for SharedSpot {
     fn get_snapshot(&self) -> BinanceOrderBookSnapshot {
-        let asks = self
-            .asks
-            .iter()
-            .map(|(price, amount)| Quote {
-                price: price.into_inner(),
-                amount: *amount,
-            })
-            .collect();
-
-        let bids = self
-            .bids
-            .iter()
-            .rev()
-            .map(|(price, amount)| Quote {
-                price: price.into_inner(),
-                amount: *amount,
-            })
-            .collect();
-
         BinanceOrderBookSnapshot {
             symbol: self.symbol.clone(),
             last_update_id: self.last_update_id,
             create_time: self.create_time,
             send_time: self.send_time,
             receive_time: self.receive_time,
-            asks,
-            bids,
+            asks: self.book.quotes(BookSide::Ask),
+            bids: self.book.quotes(BookSide::Bid),
         }
     }
 }
@@ -257,10 +193,43 @@ fn depth_row() {
     let a = Quote {
         amount: 1.0,
         price: 2.0,
+        orders: None,
     };
     let b = Quote {
         amount: 1.0,
         price: 2.0,
+        orders: None,
     };
     assert_eq!(a, b);
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::api::sync::DiffBook;
+    use crate::binance::format::binance_spot::{BinanceSnapshotSpot, EventSpot, SharedSpot};
+    use crate::binance::format::{SharedT, StreamEventT};
+
+    const SNAPSHOT: &str = r#"{"lastUpdateId":100,"bids":[["99.0","1"],["98.0","2"]],"asks":[["101.0","1"],["102.0","2"]]}"#;
+
+    const EVENT: &str = r#"{"e":"depthUpdate","E":1672531200100,"s":"BTCUSDT","U":101,"u":102,"b":[["98.0","0"]],"a":[["100.5","3"]]}"#;
+
+    #[test]
+    fn event_keeps_both_sides() {
+        let event: EventSpot = serde_json::from_str(EVENT).unwrap();
+        let event = event.event();
+        assert_eq!(event.bids[0].price, 98.0);
+        assert_eq!(event.asks[0].price, 100.5);
+
+        let snapshot: BinanceSnapshotSpot = serde_json::from_str(SNAPSHOT).unwrap();
+        let mut shared = SharedSpot::new();
+        shared.load_snapshot(&snapshot);
+        shared.add_event(event);
+
+        let snapshot = shared.get_snapshot();
+        assert_eq!(snapshot.last_update_id, 102);
+        assert_eq!(snapshot.send_time, 1672531200100);
+        assert_eq!(snapshot.bids.len(), 1);
+        assert_eq!(snapshot.asks[0].price, 100.5);
+        assert_eq!(snapshot.asks[1].price, 101.0);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{InstrumentInfo, InstrumentKind, InstrumentStatus};
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+
+/// Respond of REST `exchangeInfo`, the same shape for spot, fapi and dapi
+#[derive(Deserialize, Debug)]
+pub struct ExchangeInfoRespond {
+    pub symbols: Vec<SymbolInfo>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolInfo {
+    /// Something like "BTCUSDT" / "BTCUSD_221230"
+    pub symbol: String,
+
+    /// `contractStatus` for dapi
+    #[serde(alias = "contractStatus")]
+    pub status: String,
+
+    /// "PERPETUAL" / "CURRENT_QUARTER" ..., missing for spot
+    #[serde(default)]
+    pub contract_type: Option<String>,
+
+    /// Milliseconds, far in the future for perpetuals
+    #[serde(default)]
+    pub delivery_date: Option<i64>,
+
+    /// Only in dapi
+    #[serde(default)]
+    pub contract_size: Option<f64>,
+
+    pub filters: Vec<Filter>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "filterType")]
+pub enum Filter {
+    #[serde(rename = "PRICE_FILTER", rename_all = "camelCase")]
+    Price { tick_size: String },
+
+    #[serde(rename = "LOT_SIZE", rename_all = "camelCase")]
+    LotSize { step_size: String },
+
+    /// `minNotional` of spot, `notional` of fapi
+    #[serde(rename = "MIN_NOTIONAL", alias = "NOTIONAL")]
+    MinNotional {
+        #[serde(rename = "minNotional", alias = "notional")]
+        min_notional: String,
+    },
+
+    #[serde(other)]
+    Other,
+}
+
+impl SymbolInfo {
+    pub fn instrument(&self) -> Result<InstrumentInfo> {
+        let mut tick_size = None;
+        let mut lot_size = None;
+        let mut min_notional = None;
+
+        for filter in &self.filters {
+            match filter {
+                Filter::Price { tick_size: size } => tick_size = Some(size.parse()?),
+                Filter::LotSize { step_size } => lot_size = Some(step_size.parse()?),
+                Filter::MinNotional { min_notional: size } => min_notional = Some(size.parse()?),
+                Filter::Other => {}
+            }
+        }
+
+        let (kind, expiry) = match self.contract_type.as_deref() {
+            None => (InstrumentKind::Spot, None),
+            Some("") | Some("PERPETUAL") => (InstrumentKind::Perpetual, None),
+            Some(_) => (InstrumentKind::Future, self.delivery_date),
+        };
+
+        let status = match self.status.as_str() {
+            "TRADING" => InstrumentStatus::Trading,
+            "PRE_TRADING" | "PENDING_TRADING" => InstrumentStatus::Pending,
+            "DELIVERING" | "DELIVERED" | "SETTLING" | "CLOSE" => InstrumentStatus::Closed,
+            _ => InstrumentStatus::Halted,
+        };
+
+        Ok(InstrumentInfo {
+            name: self.symbol.clone(),
+            kind,
+            tick_size: tick_size
+                .ok_or_else(|| anyhow!("Missing PRICE_FILTER of {}", self.symbol))?,
+            lot_size: lot_size.ok_or_else(|| anyhow!("Missing LOT_SIZE of {}", self.symbol))?,
+            min_notional,
+            contract_size: self.contract_size,
+            expiry,
+            status,
+        })
+    }
+}
+
+impl ExchangeInfoRespond {
+    pub fn instruments(&self) -> Result<Vec<InstrumentInfo>> {
+        self.symbols.iter().map(SymbolInfo::instrument).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::binance::format::exchange_info::ExchangeInfoRespond;
+    use crate::{InstrumentKind, InstrumentStatus};
+
+    /// Recorded from `api/v3/exchangeInfo?symbol=BTCUSDT`, trimmed
+    const SPOT: &str = r#"{"timezone":"UTC","serverTime":1672531200000,"symbols":[{"symbol":"BTCUSDT","status":"TRADING","baseAsset":"BTC","quoteAsset":"USDT","filters":[{"filterType":"PRICE_FILTER","minPrice":"0.01000000","maxPrice":"1000000.00000000","tickSize":"0.01000000"},{"filterType":"LOT_SIZE","minQty":"0.00001000","maxQty":"9000.00000000","stepSize":"0.00001000"},{"filterType":"ICEBERG_PARTS","limit":10},{"filterType":"NOTIONAL","minNotional":"5.00000000","applyMinToMarket":true,"maxNotional":"9000000.00000000","applyMaxToMarket":false,"avgPriceMins":5}]}]}"#;
+
+    /// Recorded from `dapi/v1/exchangeInfo`, trimmed
+    const DAPI: &str = r#"{"timezone":"UTC","serverTime":1672531200000,"symbols":[{"symbol":"BTCUSD_PERP","pair":"BTCUSD","contractType":"PERPETUAL","deliveryDate":4133404800000,"contractStatus":"TRADING","contractSize":100,"filters":[{"filterType":"PRICE_FILTER","maxPrice":"4520958","minPrice":"1000","tickSize":"0.1"},{"filterType":"LOT_SIZE","stepSize":"1","maxQty":"1000000","minQty":"1"}]},{"symbol":"BTCUSD_221230","pair":"BTCUSD","contractType":"CURRENT_QUARTER","deliveryDate":1672387200000,"contractStatus":"DELIVERING","contractSize":100,"filters":[{"filterType":"PRICE_FILTER","maxPrice":"4520958","minPrice":"1000","tickSize":"0.1"},{"filterType":"LOT_SIZE","stepSize":"1","maxQty":"1000000","minQty":"1"}]}]}"#;
+
+    #[test]
+    fn spot_instrument() {
+        let respond: ExchangeInfoRespond = serde_json::from_str(SPOT).unwrap();
+        let instruments = respond.instruments().unwrap();
+
+        assert_eq!(instruments[0].name, "BTCUSDT");
+        assert_eq!(instruments[0].kind, InstrumentKind::Spot);
+        assert_eq!(instruments[0].tick_size, 0.01);
+        assert_eq!(instruments[0].lot_size, 0.00001);
+        assert_eq!(instruments[0].min_notional, Some(5.0));
+        assert_eq!(instruments[0].expiry, None);
+        assert_eq!(instruments[0].status, InstrumentStatus::Trading);
+    }
+
+    #[test]
+    fn coin_margined_instruments() {
+        let respond: ExchangeInfoRespond = serde_json::from_str(DAPI).unwrap();
+        let instruments = respond.instruments().unwrap();
+
+        assert_eq!(instruments[0].kind, InstrumentKind::Perpetual);
+        assert_eq!(instruments[0].contract_size, Some(100.0));
+        assert_eq!(instruments[0].expiry, None);
+        assert_eq!(instruments[1].name, "BTCUSD_221230");
+        assert_eq!(instruments[1].kind, InstrumentKind::Future);
+        assert_eq!(instruments[1].expiry, Some(1672387200000));
+        assert_eq!(instruments[1].status, InstrumentStatus::Closed);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::Bar;
+use anyhow::Result;
+use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Spot streams send `EventKline` directly,
+/// contract streams wrap it with the stream name
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum StreamEventKline {
+    Stream { stream: String, data: EventKline },
+    Event(EventKline),
+}
+
+impl StreamEventKline {
+    pub fn event(&self) -> &EventKline {
+        match self {
+            StreamEventKline::Stream { data, .. } => data,
+            StreamEventKline::Event(event) => event,
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+pub struct EventKline {
+    #[serde(rename = "e")]
+    pub event_type: String,
+    #[serde(rename = "E")]
+    pub event_time: i64,
+    #[serde(rename = "s")]
+    pub pair: String,
+    #[serde(rename = "k")]
+    pub kline: KlineData,
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+pub struct KlineData {
+    /// Kline start time
+    #[serde(rename = "t")]
+    pub start_time: i64,
+    /// Kline close time
+    #[serde(rename = "T")]
+    pub close_time: i64,
+    #[serde(rename = "i")]
+    pub interval: String,
+    #[serde(rename = "o")]
+    pub open: String,
+    #[serde(rename = "c")]
+    pub close: String,
+    #[serde(rename = "h")]
+    pub high: String,
+    #[serde(rename = "l")]
+    pub low: String,
+    /// Base asset volume
+    #[serde(rename = "v")]
+    pub volume: String,
+    /// Number of trades
+    #[serde(rename = "n")]
+    pub trades: u64,
+    /// Is this kline closed
+    #[serde(rename = "x")]
+    pub closed: bool,
+}
+
+impl EventKline {
+    pub fn add_timestamp_transform_to_bar(&self) -> Result<Bar> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let kline = &self.kline;
+
+        Ok(Bar {
+            open_ts: kline.start_time,
+            close_ts: kline.close_time,
+            lts: now.as_millis() as i64,
+            open: kline.open.parse::<f64>()?,
+            high: kline.high.parse::<f64>()?,
+            low: kline.low.parse::<f64>()?,
+            close: kline.close.parse::<f64>()?,
+            volume: kline.volume.parse::<f64>()?,
+            trades: Some(kline.trades),
+            closed: kline.closed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StreamEventKline;
+
+    const SPOT: &str = r#"{"e":"kline","E":1672515782136,"s":"BNBBTC","k":{"t":1672515780000,"T":1672515839999,"s":"BNBBTC","i":"1m","f":100,"L":200,"o":"0.0010","c":"0.0020","h":"0.0025","l":"0.0015","v":"1000","n":100,"x":false,"q":"1.0000","V":"500","Q":"0.500","B":"123456"}}"#;
+
+    #[test]
+    fn kline_deserialize() {
+        let event: StreamEventKline = serde_json::from_str(SPOT).unwrap();
+        let bar = event.event().add_timestamp_transform_to_bar().unwrap();
+
+        assert_eq!(bar.open_ts, 1672515780000);
+        assert_eq!(bar.close_ts, 1672515839999);
+        assert_eq!(bar.open, 0.001);
+        assert_eq!(bar.high, 0.0025);
+        assert_eq!(bar.low, 0.0015);
+        assert_eq!(bar.close, 0.002);
+        assert_eq!(bar.volume, 1000.0);
+        assert_eq!(bar.trades, Some(100));
+        assert!(!bar.closed);
+
+        let stream = format!(r#"{{"stream":"btcusdt@kline_1m","data":{}}}"#, SPOT);
+        let event: StreamEventKline = serde_json::from_str(&stream).unwrap();
+        assert!(matches!(event, StreamEventKline::Stream { .. }));
+        assert_eq!(event.event().kline.interval, "1m");
+    }
+}
@@ -1,5 +1,7 @@
+mod adapter;
 pub mod connection;
 pub mod format;
 
-pub use connection::BinanceDepthT;
+pub use adapter::BinanceAdapter;
+pub use connection::BinanceKline;
 pub use connection::BinanceTicker;
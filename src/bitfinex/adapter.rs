@@ -0,0 +1,93 @@
+use crate::bitfinex::{BitfinexDepth, BitfinexPrecision, BitfinexTicker};
+use crate::config::{instrument_bitfinex, symbol_type_bitfinex, Instrument};
+use crate::config::{set_addr_for_bitfinex, set_ticker_addr_for_bitfinex};
+use crate::config::{validate_symbol_bitfinex, DepthType, SymbolType};
+use crate::{DepthConfig, DepthT, ExchangeAdapter, ExchangeType, TickerConfig, TickerT};
+use anyhow::{anyhow, Result};
+use std::sync::Arc;
+
+/// Spot pairs and USDT perpetuals of Bitfinex.
+///
+/// Books are `P0` with sequence numbers checked by default,
+/// register a configured adapter to change it, e.g.
+/// `AdapterRegistry::global().register(BitfinexAdapter::new().precision(BitfinexPrecision::R0))`
+#[derive(Clone, Copy, Debug)]
+pub struct BitfinexAdapter {
+    precision: BitfinexPrecision,
+    sequenced: bool,
+}
+
+impl BitfinexAdapter {
+    pub fn new() -> Self {
+        BitfinexAdapter {
+            precision: BitfinexPrecision::default(),
+            sequenced: true,
+        }
+    }
+
+    /// `P0`..`P4` aggregated books, or the `R0` book of orders
+    pub fn precision(mut self, precision: BitfinexPrecision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Ask for sequence numbers with `conf` and resync on gaps
+    pub fn sequenced(mut self, sequenced: bool) -> Self {
+        self.sequenced = sequenced;
+        self
+    }
+}
+
+impl Default for BitfinexAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExchangeAdapter for BitfinexAdapter {
+    fn name(&self) -> &'static str {
+        "bitfinex"
+    }
+
+    fn exchange_type(&self) -> ExchangeType {
+        ExchangeType::Bitfinex
+    }
+
+    fn validate_symbol(&self, symbol: &str, _limit: Option<i32>) -> Result<SymbolType> {
+        validate_symbol_bitfinex(symbol)
+    }
+
+    fn depth_url(&self, symbol_type: &SymbolType, limit: Option<i32>) -> Result<DepthType> {
+        let symbol = match symbol_type {
+            SymbolType::Spot(s) | SymbolType::ContractUSDT(s) => s,
+            other => return Err(anyhow!("Unsupported symbol {:?} for bitfinex", other)),
+        };
+        let (rest_address, depth_address, level_depth_address) =
+            set_addr_for_bitfinex(symbol, self.precision.as_str(), limit);
+
+        DepthType::new(rest_address, depth_address, level_depth_address)
+            .ok_or_else(|| anyhow!("depth url is empty"))
+    }
+
+    fn ticker_url(&self, _symbol_type: &SymbolType) -> Result<String> {
+        Ok(set_ticker_addr_for_bitfinex())
+    }
+
+    fn depth_connection(&self, _config: &DepthConfig) -> Result<Arc<dyn DepthT + Send + Sync>> {
+        let connection = BitfinexDepth::new().with_options(self.precision, self.sequenced);
+
+        Ok(Arc::new(connection))
+    }
+
+    fn ticker_connection(&self, _config: &TickerConfig) -> Result<Arc<dyn TickerT + Send + Sync>> {
+        Ok(Arc::new(BitfinexTicker::new()))
+    }
+
+    fn native_symbol(&self, instrument: &Instrument) -> Result<SymbolType> {
+        symbol_type_bitfinex(instrument)
+    }
+
+    fn instrument_of(&self, symbol_type: &SymbolType) -> Result<Instrument> {
+        instrument_bitfinex(symbol_type)
+    }
+}
@@ -0,0 +1,251 @@
+use crate::api::driver::{BookFeed, SequenceGap};
+use crate::bitfinex::connection::BitfinexWebSocket;
+use crate::bitfinex::format::{
+    conf_message, subscribe_message, unsubscribe_message, EventMessage, Subscription,
+};
+use crate::metrics;
+use crate::RawFrames;
+use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
+use futures_util::{SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::timeout;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, warn};
+use url::Url;
+
+/// Bitfinex sends `hb` every 15 seconds on quiet channels
+const QUIET_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// `conf` flag adding a sequence number to every frame
+const SEQ_ALL: u64 = 65536;
+
+/// `info` code asking clients to reconnect
+const RECONNECT_CODE: i64 = 20051;
+
+/// Data frame of a subscription without channel id and sequence number,
+/// e.g. `[[PRICE, COUNT, AMOUNT], ..]` or `["te", [ID, MTS, AMOUNT, PRICE]]`
+pub struct Frame {
+    pub chan_id: u64,
+    pub body: Vec<Value>,
+}
+
+/// Connection with the subscriptions of its channel ids
+pub struct BitfinexStream {
+    stream: BitfinexWebSocket,
+    /// Sent, waiting for `subscribed`
+    pending: Vec<Subscription>,
+    channels: HashMap<u64, Subscription>,
+    /// Whether `conf` asked for sequence numbers
+    sequenced: bool,
+    /// Sequence number of the last frame
+    sequence: Option<u64>,
+}
+
+impl BitfinexStream {
+    /// Connect and subscribe, with sequence numbers checked if `sequenced`
+    pub async fn connect(
+        address: &str,
+        subscription: Subscription,
+        sequenced: bool,
+    ) -> Result<Self> {
+        let url = Url::parse(address)?;
+
+        let stream = match connect_async(url).await {
+            Ok((connection, _)) => connection,
+            Err(e) => return Err(anyhow!("{:?}", e)),
+        };
+        debug!("Connect to {} success", address);
+
+        let mut stream = BitfinexStream {
+            stream,
+            pending: Vec::new(),
+            channels: HashMap::new(),
+            sequenced,
+            sequence: None,
+        };
+
+        if sequenced {
+            stream
+                .stream
+                .send(Message::from(conf_message(SEQ_ALL)))
+                .await?;
+        }
+        stream.subscribe(subscription).await?;
+
+        Ok(stream)
+    }
+
+    async fn subscribe(&mut self, subscription: Subscription) -> Result<()> {
+        self.stream
+            .send(Message::from(subscribe_message(subscription.clone())))
+            .await?;
+
+        debug!("Subscribe to {:?} success", subscription);
+        self.pending.push(subscription);
+
+        Ok(())
+    }
+
+    /// Unsubscribe the channels of `subscription` and subscribe again
+    /// to get a fresh snapshot on a new channel id
+    pub async fn resubscribe(&mut self, subscription: Subscription) -> Result<()> {
+        let chan_ids = self
+            .channels
+            .iter()
+            .filter(|(_, subscribed)| **subscribed == subscription)
+            .map(|(chan_id, _)| *chan_id)
+            .collect::<Vec<_>>();
+
+        for chan_id in chan_ids {
+            self.channels.remove(&chan_id);
+            self.stream
+                .send(Message::from(unsubscribe_message(chan_id)))
+                .await?;
+        }
+
+        self.subscribe(subscription).await
+    }
+
+    /// Subscription of a channel id
+    pub fn subscription(&self, chan_id: u64) -> Option<&Subscription> {
+        self.channels.get(&chan_id)
+    }
+
+    /// Next data frame of a subscribed channel,
+    /// events and `hb` are handled on the way.
+    ///
+    /// Err if the stream is quiet for `QUIET_TIMEOUT`
+    /// or Bitfinex asks to reconnect, `SequenceGap` once a frame is missed
+    pub async fn next_frame(&mut self) -> Result<Frame> {
+        loop {
+            let message = match timeout(QUIET_TIMEOUT, self.stream.next()).await {
+                Ok(Some(message)) => message?,
+                Ok(None) => return Err(anyhow!("Connection closed")),
+                Err(_) => return Err(anyhow!("Nothing received in {:?}", QUIET_TIMEOUT)),
+            };
+
+            let text = match message {
+                Message::Text(text) => {
+                    RawFrames::global().publish("bitfinex", &text);
+                    text
+                }
+                Message::Ping(payload) => {
+                    self.stream.send(Message::Pong(payload)).await?;
+                    continue;
+                }
+                Message::Close(frame) => return Err(anyhow!("Connection closed {:?}", frame)),
+                _ => continue,
+            };
+
+            if text.starts_with('{') {
+                self.handle_event(serde_json::from_str(&text)?)?;
+                continue;
+            }
+
+            let mut body: Vec<Value> = serde_json::from_str(&text)?;
+            if body.is_empty() {
+                continue;
+            }
+            let chan_id = body.remove(0).as_u64().unwrap_or_default();
+
+            if self.sequenced && body.len() >= 2 {
+                if let Some(received) = body.last().and_then(Value::as_u64) {
+                    body.pop();
+                    if let Some(last) = self.sequence.replace(received) {
+                        if received != last + 1 {
+                            return Err(SequenceGap { last, received }.into());
+                        }
+                    }
+                }
+            }
+
+            if body.first().and_then(Value::as_str) == Some("hb") {
+                continue;
+            }
+
+            if !self.channels.contains_key(&chan_id) {
+                debug!("Skip frame of channel {}", chan_id);
+                continue;
+            }
+
+            return Ok(Frame { chan_id, body });
+        }
+    }
+
+    fn handle_event(&mut self, event: EventMessage) -> Result<()> {
+        match event.event.as_str() {
+            "subscribed" => {
+                let index = self
+                    .pending
+                    .iter()
+                    .position(|subscription| subscription.is_answered_by(&event));
+
+                match (index, event.chan_id) {
+                    (Some(index), Some(chan_id)) => {
+                        let subscription = self.pending.remove(index);
+                        debug!("Channel {} is {:?}", chan_id, subscription);
+                        self.channels.insert(chan_id, subscription);
+                    }
+                    _ => warn!("Unexpected {:?}", event),
+                }
+            }
+            "error" => {
+                return Err(anyhow!(
+                    "Bitfinex error {:?}: {}",
+                    event.code,
+                    event.msg.unwrap_or_default()
+                ))
+            }
+            "info" if event.code == Some(RECONNECT_CODE) => {
+                return Err(anyhow!("Bitfinex asks to reconnect"))
+            }
+            "conf" if event.status.as_deref() != Some("OK") => {
+                return Err(anyhow!("Bitfinex refused conf {:?}", event))
+            }
+            _ => debug!("Receive {:?}", event),
+        }
+
+        Ok(())
+    }
+}
+
+impl BookFeed for BitfinexStream {
+    /// First element of the next data frame
+    fn next<T: DeserializeOwned + Send + 'static>(&mut self) -> BoxFuture<'_, Result<T>> {
+        Box::pin(async move {
+            loop {
+                let frame = self.next_frame().await?;
+                let data = frame.body.into_iter().next().unwrap_or(Value::Null);
+                match serde_json::from_value(data) {
+                    Ok(data) => return Ok(data),
+                    Err(e) => {
+                        warn!("Error {} of channel {}", e, frame.chan_id);
+                        metrics::decode_error("bitfinex");
+                    }
+                }
+            }
+        })
+    }
+
+    /// Resubscribe every subscription of the stream
+    fn resubscribe(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            let mut subscriptions = std::mem::take(&mut self.pending);
+            for subscription in self.channels.values() {
+                if !subscriptions.contains(subscription) {
+                    subscriptions.push(subscription.clone());
+                }
+            }
+
+            for subscription in subscriptions {
+                BitfinexStream::resubscribe(self, subscription).await?;
+            }
+            Ok(())
+        })
+    }
+}
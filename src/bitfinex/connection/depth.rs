@@ -0,0 +1,234 @@
+use crate::api::driver::{BookDriver, Connect};
+use crate::bitfinex::connection::abstraction::BitfinexStream;
+use crate::bitfinex::format::{BitfinexPrecision, BookShared, Subscription};
+use crate::config::length_bitfinex;
+use crate::{ConnectionState, Depth, DepthConfig, DepthT};
+use anyhow::Result;
+use serde_json::Value;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tracing::debug;
+
+#[derive(Clone)]
+pub struct BitfinexDepth {
+    driver: BookDriver<BookShared>,
+    precision: BitfinexPrecision,
+    /// Check the sequence numbers of `conf`
+    sequenced: bool,
+}
+
+/// Connect to `address` and subscribe `subscription`,
+/// with sequence numbers checked if `sequenced`
+fn connect(
+    address: String,
+    subscription: Subscription,
+    sequenced: bool,
+) -> Connect<BitfinexStream> {
+    Box::new(move || {
+        let address = address.clone();
+        let subscription = subscription.clone();
+        Box::pin(async move { BitfinexStream::connect(&address, subscription, sequenced).await })
+    })
+}
+
+/// Put a `book` frame onto `shared`,
+/// the first frame of a channel is the snapshot
+fn apply(shared: &mut BookShared, data: Value, is_ready: bool) -> Option<Result<Depth>> {
+    let is_snapshot = data
+        .as_array()
+        .is_some_and(|entries| entries.iter().all(Value::is_array));
+
+    if is_snapshot {
+        Some(shared.set_snapshot(data).map(|_| shared.get_snapshot()))
+    } else if is_ready {
+        Some(shared.add_update(data).map(|_| shared.get_snapshot()))
+    } else {
+        // Updates before the fresh snapshot
+        debug!("Skip update before snapshot");
+        None
+    }
+}
+
+impl BitfinexDepth {
+    pub fn with_options(mut self, precision: BitfinexPrecision, sequenced: bool) -> Self {
+        self.precision = precision;
+        self.sequenced = sequenced;
+        self
+    }
+
+    /// Keep the book in line with the `book` frames of `subscription`,
+    /// resubscribe for a fresh snapshot once a sequence gap is found
+    fn maintain(
+        &self,
+        address: String,
+        subscription: Subscription,
+        config: DepthConfig,
+    ) -> UnboundedReceiver<Depth> {
+        self.driver
+            .shared()
+            .write()
+            .unwrap()
+            .set_precision(self.precision);
+
+        self.driver.clone().pushed_book(
+            connect(address, subscription, self.sequenced),
+            config.get_symbol(),
+            config.reconnect,
+            apply,
+        )
+    }
+}
+
+impl DepthT for BitfinexDepth {
+    fn new() -> Self {
+        BitfinexDepth {
+            driver: BookDriver::new("bitfinex", BookShared::new()),
+            precision: BitfinexPrecision::default(),
+            sequenced: true,
+        }
+    }
+
+    /// acquire a order book of `limit` levels (orders for `R0`)
+    fn depth_snapshot(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
+        let (_, depth_address) = config.get_depth_snapshot_addresses();
+        let length = length_bitfinex(config.limit);
+        let subscription = Subscription::book(&config.get_symbol(), self.precision, length);
+
+        Ok(self.maintain(depth_address, subscription, config))
+    }
+
+    /// acquire a order book of 25 levels (orders for `R0`)
+    fn depth(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
+        let level_address = config.get_depth_addresses();
+        let length = length_bitfinex(None);
+        let subscription = Subscription::book(&config.get_symbol(), self.precision, length);
+
+        Ok(self.maintain(level_address, subscription, config))
+    }
+
+    /// Get the snapshot of the current Order Book
+    fn snapshot(&self) -> Option<Depth> {
+        self.driver.snapshot(BookShared::get_snapshot)
+    }
+
+    fn subscribe_state(&self) -> UnboundedReceiver<ConnectionState> {
+        self.driver.subscribe_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bitfinex::connection::BitfinexDepth;
+    use crate::bitfinex::format::BitfinexPrecision;
+    use crate::config::{DepthConfig, DepthType, SymbolType};
+    use crate::{ConnectionState, DepthT, ExchangeType, ReconnectPolicy};
+    use futures_util::{SinkExt, StreamExt};
+    use std::time::Duration;
+    use tokio::net::TcpListener;
+    use tokio::runtime::Runtime;
+    use tokio::time::timeout;
+    use tokio_tungstenite::accept_async;
+    use tokio_tungstenite::tungstenite::Message;
+
+    const REST: &str = "https://api-pub.bitfinex.com/v2/book/tBTCUSD/P0?len=25";
+
+    const INFO: &str = r#"{"event":"info","version":2,"serverId":"1a2b","platform":{"status":1}}"#;
+
+    const CONF: &str = r#"{"event":"conf","status":"OK","flags":65536}"#;
+
+    const SUBSCRIBED: &str = r#"{"event":"subscribed","channel":"book","chanId":17082,"symbol":"tBTCUSD","prec":"P0","freq":"F0","len":"25","pair":"BTCUSD"}"#;
+
+    const RESUBSCRIBED: &str = r#"{"event":"subscribed","channel":"book","chanId":17083,"symbol":"tBTCUSD","prec":"P0","freq":"F0","len":"25","pair":"BTCUSD"}"#;
+
+    #[test]
+    fn book_from_mock_server() {
+        Runtime::new().unwrap().block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let address = format!("ws://{}", listener.local_addr().unwrap());
+
+            let server = tokio::spawn(async move {
+                let (socket, _) = listener.accept().await.unwrap();
+                let mut ws = accept_async(socket).await.unwrap();
+
+                let request = ws.next().await.unwrap().unwrap().into_text().unwrap();
+                assert_eq!(request, r#"{"event":"conf","flags":65536}"#);
+                let request = ws.next().await.unwrap().unwrap().into_text().unwrap();
+                assert!(request.contains(r#""prec":"P0""#));
+
+                for frame in [
+                    INFO,
+                    CONF,
+                    SUBSCRIBED,
+                    "[17082,[[7254.7,3,3.3],[7254.6,2,2.2],[7254.8,1,-1.5]],1]",
+                    "[17082,\"hb\",2]",
+                    "[17082,[7254.7,0,1],3]",
+                    // Frame 4 is lost
+                    "[17082,[7254.8,2,-2.5],5]",
+                ] {
+                    ws.send(Message::from(frame)).await.unwrap();
+                }
+
+                let request = ws.next().await.unwrap().unwrap().into_text().unwrap();
+                assert_eq!(request, r#"{"event":"unsubscribe","chanId":17082}"#);
+                let request = ws.next().await.unwrap().unwrap().into_text().unwrap();
+                assert!(request.contains(r#""event":"subscribe""#));
+
+                for frame in [
+                    // Of the old channel, ignored
+                    "[17082,[7254.9,1,-1],6]",
+                    RESUBSCRIBED,
+                    "[17083,[[7254.7,3,3.3],[7254.8,2,-2.5]],7]",
+                ] {
+                    ws.send(Message::from(frame)).await.unwrap();
+                }
+                ws
+            });
+
+            let config = DepthConfig {
+                depth_url: DepthType::DepthSnapshot(REST.to_string(), address),
+                symbol_type: SymbolType::Spot(String::from("tBTCUSD")),
+                exchange_type: ExchangeType::Bitfinex,
+                limit: Some(25),
+                checksum: None,
+                audit_interval: None,
+                reconnect: ReconnectPolicy::default(),
+            };
+
+            let book = BitfinexDepth::new().with_options(BitfinexPrecision::P0, true);
+            let mut state = book.subscribe_state();
+            let mut recv = book.depth_snapshot(config).unwrap();
+
+            timeout(Duration::from_secs(10), async {
+                let depth = recv.recv().await.unwrap();
+                assert_eq!(depth.id, 1);
+                assert_eq!(depth.bids.len(), 2);
+                assert_eq!(depth.asks[0].orders, Some(1));
+                assert!(matches!(
+                    state.recv().await,
+                    Some(ConnectionState::Connected)
+                ));
+
+                let depth = recv.recv().await.unwrap();
+                assert_eq!(depth.id, 2);
+                assert_eq!(depth.bids[0].price, 7254.6);
+
+                assert!(matches!(
+                    state.recv().await,
+                    Some(ConnectionState::Resyncing(_))
+                ));
+
+                let depth = recv.recv().await.unwrap();
+                assert_eq!(depth.id, 1);
+                assert_eq!(depth.bids[0].price, 7254.7);
+                assert_eq!(depth.asks[0].amount, 2.5);
+                assert!(matches!(
+                    state.recv().await,
+                    Some(ConnectionState::Connected)
+                ));
+            })
+            .await
+            .unwrap();
+
+            server.await.unwrap();
+        })
+    }
+}
@@ -0,0 +1,102 @@
+use crate::bitfinex::connection::abstraction::BitfinexStream;
+use crate::bitfinex::format::{Subscription, TradeData};
+use crate::metrics;
+use crate::{Backoff, Ticker, TickerConfig, TickerT};
+use anyhow::Result;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tracing::{debug, error, info, warn};
+
+#[derive(Clone)]
+pub struct BitfinexTicker {
+    status: Arc<Mutex<bool>>,
+}
+
+impl BitfinexTicker {
+    pub fn new() -> Self {
+        BitfinexTicker {
+            status: Arc::new(Mutex::new(false)),
+        }
+    }
+}
+
+impl TickerT for BitfinexTicker {
+    fn connect(&self, config: TickerConfig) -> Result<UnboundedReceiver<Vec<Ticker>>> {
+        let address = config.ticker_url.clone();
+        let subscription = Subscription::trades(&config.get_symbol());
+
+        let status = self.status.clone();
+
+        let symbol = config.get_symbol();
+        let reconnect = config.reconnect.clone();
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            info!("Start Bitfinex trades thread");
+            let mut backoff = Backoff::new(reconnect);
+            loop {
+                let mut stream =
+                    match BitfinexStream::connect(&address, subscription.clone(), false).await {
+                        Ok(connection) => connection,
+                        Err(e) => {
+                            warn!("connection error {:?}", e);
+                            if !backoff.wait("bitfinex").await {
+                                break;
+                            }
+                            continue;
+                        }
+                    };
+                backoff.connected();
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = true;
+                }
+
+                loop {
+                    let frame = match stream.next_frame().await {
+                        Ok(frame) => frame,
+                        Err(e) => {
+                            warn!("{:?}, reconnecting", e);
+                            metrics::reconnect("bitfinex");
+                            break;
+                        }
+                    };
+
+                    // Only "te", "tu" repeats the trade and the snapshot is history
+                    let trade = match &frame.body[..] {
+                        [Value::String(kind), trade] if kind == "te" => trade.clone(),
+                        _ => {
+                            debug!("Skip frame of {:?}", stream.subscription(frame.chan_id));
+                            continue;
+                        }
+                    };
+
+                    let tick = match serde_json::from_value::<TradeData>(trade) {
+                        Ok(trade) => trade.tick(),
+                        Err(e) => {
+                            warn!("Bad trade {:?}", e);
+                            metrics::decode_error("bitfinex");
+                            continue;
+                        }
+                    };
+
+                    let ticks = vec![tick];
+                    metrics::trades("bitfinex", &symbol, &ticks);
+                    if sender.send(ticks).is_err() {
+                        error!("Bitfinex Ticker send error");
+                    }
+                }
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = false;
+                }
+                if !backoff.wait("bitfinex").await {
+                    break;
+                }
+            }
+        });
+
+        Ok(receiver)
+    }
+}
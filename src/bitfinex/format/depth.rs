@@ -0,0 +1,270 @@
+use crate::{BookSide, Depth, OrderBook, Quote};
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Price aggregation of a Bitfinex book,
+/// `P0` has 5 significant figures and every step after one less,
+/// `R0` is the raw book of single orders
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BitfinexPrecision {
+    #[default]
+    P0,
+    P1,
+    P2,
+    P3,
+    P4,
+    R0,
+}
+
+impl BitfinexPrecision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BitfinexPrecision::P0 => "P0",
+            BitfinexPrecision::P1 => "P1",
+            BitfinexPrecision::P2 => "P2",
+            BitfinexPrecision::P3 => "P3",
+            BitfinexPrecision::P4 => "P4",
+            BitfinexPrecision::R0 => "R0",
+        }
+    }
+
+    pub fn is_raw(&self) -> bool {
+        matches!(self, BitfinexPrecision::R0)
+    }
+}
+
+/// `[PRICE, COUNT, AMOUNT]` of `P0`..`P4`,
+/// count 0 removes the level, a negative amount is an ask
+#[derive(Deserialize, Debug, Clone, Copy)]
+struct LevelEntry(f64, u64, f64);
+
+/// `[ORDER_ID, PRICE, AMOUNT]` of `R0`,
+/// price 0 removes the order, a negative amount is an ask
+#[derive(Deserialize, Debug, Clone, Copy)]
+struct OrderEntry(u64, f64, f64);
+
+/// One resting order of the raw book
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Order {
+    pub price: f64,
+
+    /// Negative for asks
+    pub amount: f64,
+}
+
+/// Order level book of `R0`, orders by id
+#[derive(Default)]
+pub struct RawBook {
+    orders: HashMap<u64, Order>,
+}
+
+impl RawBook {
+    fn apply(&mut self, OrderEntry(id, price, amount): OrderEntry) {
+        if price == 0.0 {
+            self.orders.remove(&id);
+        } else {
+            self.orders.insert(id, Order { price, amount });
+        }
+    }
+
+    /// Orders summed up by price into levels with the number of orders
+    fn aggregate(&self) -> OrderBook {
+        let mut book: OrderBook = OrderBook::new();
+
+        for order in self.orders.values() {
+            let side = side(order.amount);
+            let level = book.get(side, order.price).copied().unwrap_or_default();
+
+            let level = Quote {
+                price: order.price,
+                amount: level.amount + order.amount.abs(),
+                orders: Some(level.orders.unwrap_or_default() + 1),
+            };
+            book.apply_level(side, level);
+        }
+
+        book
+    }
+}
+
+/// A negative amount is an ask
+fn side(amount: f64) -> BookSide {
+    if amount < 0.0 {
+        BookSide::Ask
+    } else {
+        BookSide::Bid
+    }
+}
+
+/// Bitfinex sends no update id with `book`,
+/// so `Depth.id` is a local sequence instead:
+/// `1` for the snapshot, then increased by one for every applied update.
+///
+/// Books carry no time either, `ts` is the receive time
+pub struct BookShared {
+    precision: BitfinexPrecision,
+    sequence: i64,
+    receive_time: i64,
+    /// Levels of `P0`..`P4`
+    book: OrderBook,
+    orders: RawBook,
+}
+
+impl BookShared {
+    pub fn new() -> Self {
+        BookShared {
+            precision: BitfinexPrecision::default(),
+            sequence: 0,
+            receive_time: 0,
+            book: OrderBook::new(),
+            orders: RawBook::default(),
+        }
+    }
+
+    pub fn set_precision(&mut self, precision: BitfinexPrecision) {
+        self.precision = precision;
+    }
+
+    /// Array of entries, replaces the whole book
+    pub fn set_snapshot(&mut self, data: Value) -> Result<()> {
+        self.book.clear();
+        self.orders = RawBook::default();
+        self.sequence = 0;
+
+        if self.precision.is_raw() {
+            let entries: Vec<OrderEntry> = serde_json::from_value(data)?;
+            entries
+                .into_iter()
+                .for_each(|entry| self.orders.apply(entry));
+        } else {
+            let entries: Vec<LevelEntry> = serde_json::from_value(data)?;
+            entries
+                .into_iter()
+                .for_each(|entry| self.apply_level(entry));
+        }
+
+        self.applied();
+        Ok(())
+    }
+
+    /// One entry
+    pub fn add_update(&mut self, data: Value) -> Result<()> {
+        if self.sequence == 0 {
+            return Err(anyhow!("Update before snapshot"));
+        }
+
+        if self.precision.is_raw() {
+            self.orders.apply(serde_json::from_value(data)?);
+        } else {
+            self.apply_level(serde_json::from_value(data)?);
+        }
+
+        self.applied();
+        Ok(())
+    }
+
+    fn apply_level(&mut self, LevelEntry(price, count, amount): LevelEntry) {
+        let level = Quote {
+            price,
+            amount: if count == 0 { 0.0 } else { amount.abs() },
+            orders: Some(count),
+        };
+
+        self.book.apply_level(side(amount), level);
+    }
+
+    fn applied(&mut self) {
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        self.sequence += 1;
+        self.receive_time = time.as_millis() as i64;
+    }
+
+    /// Levels with the number of orders, aggregated from the orders for `R0`
+    pub fn get_snapshot(&self) -> Depth {
+        let aggregated;
+        let book = if self.precision.is_raw() {
+            aggregated = self.orders.aggregate();
+            &aggregated
+        } else {
+            &self.book
+        };
+
+        Depth {
+            id: self.sequence,
+            ts: self.receive_time,
+            lts: self.receive_time,
+            asks: book.quotes(BookSide::Ask),
+            bids: book.quotes(BookSide::Bid),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bitfinex::format::{BitfinexPrecision, BookShared};
+    use serde_json::Value;
+
+    fn value(text: &str) -> Value {
+        serde_json::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn aggregated_book() {
+        let mut shared = BookShared::new();
+        shared.set_precision(BitfinexPrecision::P0);
+        assert!(shared.add_update(value("[7254.7,3,3.3]")).is_err());
+
+        let snapshot = "[[7254.7,3,3.3],[7254.6,2,2.2],[7254.8,1,-1.5],[7255,2,-4]]";
+        shared.set_snapshot(value(snapshot)).unwrap();
+
+        let depth = shared.get_snapshot();
+        assert_eq!(depth.id, 1);
+        assert_eq!(depth.bids[0].price, 7254.7);
+        assert_eq!(depth.bids[0].orders, Some(3));
+        assert_eq!(depth.asks[0].price, 7254.8);
+        assert_eq!(depth.asks[1].amount, 4.0);
+
+        shared.add_update(value("[7254.7,0,1]")).unwrap();
+        shared.add_update(value("[7254.8,2,-2.5]")).unwrap();
+
+        let depth = shared.get_snapshot();
+        assert_eq!(depth.id, 3);
+        assert_eq!(depth.bids.len(), 1);
+        assert_eq!(depth.bids[0].price, 7254.6);
+        assert_eq!(depth.asks[0].amount, 2.5);
+        assert_eq!(depth.asks[0].orders, Some(2));
+    }
+
+    #[test]
+    fn raw_book() {
+        let mut shared = BookShared::new();
+        shared.set_precision(BitfinexPrecision::R0);
+
+        let snapshot = "[[34006738527,7254.7,0.5],[34006738528,7254.7,0.25],[34006738529,7254.5,1],[34006738530,7254.9,-0.75],[34006738531,7254.9,-0.25]]";
+        shared.set_snapshot(value(snapshot)).unwrap();
+
+        let depth = shared.get_snapshot();
+        assert_eq!(depth.bids[0].price, 7254.7);
+        assert_eq!(depth.bids[0].amount, 0.75);
+        assert_eq!(depth.bids[0].orders, Some(2));
+        assert_eq!(depth.asks[0].amount, 1.0);
+
+        // Removed, then moved to another price
+        shared.add_update(value("[34006738527,0,0.5]")).unwrap();
+        shared
+            .add_update(value("[34006738530,7255.1,-0.75]"))
+            .unwrap();
+
+        let depth = shared.get_snapshot();
+        assert_eq!(depth.id, 3);
+        assert_eq!(depth.bids[0].amount, 0.25);
+        assert_eq!(depth.bids[0].orders, Some(1));
+        assert_eq!(depth.asks.len(), 2);
+        assert_eq!(depth.asks[0].price, 7254.9);
+        assert_eq!(depth.asks[0].amount, 0.25);
+        assert_eq!(depth.asks[1].price, 7255.1);
+    }
+}
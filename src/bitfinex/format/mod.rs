@@ -0,0 +1,9 @@
+mod depth;
+mod request;
+mod stream;
+mod ticker;
+
+pub use depth::{BitfinexPrecision, BookShared};
+pub use request::{conf_message, subscribe_message, unsubscribe_message, Subscription};
+pub use stream::EventMessage;
+pub use ticker::TradeData;
@@ -0,0 +1,130 @@
+use crate::bitfinex::format::{BitfinexPrecision, EventMessage};
+use serde::Serialize;
+
+/// One subscription, e.g. `book` of "tBTCUSD" at "P0" with length "25"
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Subscription {
+    pub channel: String,
+
+    pub symbol: String,
+
+    /// Only for `book`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prec: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub freq: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub len: Option<String>,
+}
+
+impl Subscription {
+    /// Realtime `book` of `length` levels, or orders for `R0`
+    pub fn book(symbol: &str, precision: BitfinexPrecision, length: i32) -> Self {
+        Subscription {
+            channel: "book".to_string(),
+            symbol: symbol.to_string(),
+            prec: Some(precision.as_str().to_string()),
+            freq: Some("F0".to_string()),
+            len: Some(length.to_string()),
+        }
+    }
+
+    pub fn trades(symbol: &str) -> Self {
+        Subscription {
+            channel: "trades".to_string(),
+            symbol: symbol.to_string(),
+            prec: None,
+            freq: None,
+            len: None,
+        }
+    }
+
+    /// Whether `subscribed` answers this subscription
+    pub fn is_answered_by(&self, subscribed: &EventMessage) -> bool {
+        subscribed.channel.as_deref() == Some(self.channel.as_str())
+            && subscribed.symbol.as_deref() == Some(self.symbol.as_str())
+            && (self.prec.is_none() || subscribed.prec == self.prec)
+    }
+}
+
+#[derive(Serialize)]
+struct SubscribeRequest {
+    event: &'static str,
+
+    #[serde(flatten)]
+    subscription: Subscription,
+}
+
+#[derive(Serialize)]
+struct UnsubscribeRequest {
+    event: &'static str,
+
+    #[serde(rename = "chanId")]
+    chan_id: u64,
+}
+
+#[derive(Serialize)]
+struct ConfRequest {
+    event: &'static str,
+
+    flags: u64,
+}
+
+pub fn subscribe_message(subscription: Subscription) -> String {
+    let inner = SubscribeRequest {
+        event: "subscribe",
+        subscription,
+    };
+    serde_json::to_string(&inner).unwrap()
+}
+
+pub fn unsubscribe_message(chan_id: u64) -> String {
+    let inner = UnsubscribeRequest {
+        event: "unsubscribe",
+        chan_id,
+    };
+    serde_json::to_string(&inner).unwrap()
+}
+
+pub fn conf_message(flags: u64) -> String {
+    let inner = ConfRequest {
+        event: "conf",
+        flags,
+    };
+    serde_json::to_string(&inner).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bitfinex::format::{
+        conf_message, subscribe_message, unsubscribe_message, BitfinexPrecision, EventMessage,
+        Subscription,
+    };
+
+    #[test]
+    fn event_messages() {
+        let book = Subscription::book("tBTCUSD", BitfinexPrecision::R0, 25);
+        assert_eq!(
+            subscribe_message(book.clone()),
+            r#"{"event":"subscribe","channel":"book","symbol":"tBTCUSD","prec":"R0","freq":"F0","len":"25"}"#
+        );
+        assert_eq!(
+            subscribe_message(Subscription::trades("tBTCUSD")),
+            r#"{"event":"subscribe","channel":"trades","symbol":"tBTCUSD"}"#
+        );
+        assert_eq!(
+            unsubscribe_message(17082),
+            r#"{"event":"unsubscribe","chanId":17082}"#
+        );
+        assert_eq!(conf_message(65536), r#"{"event":"conf","flags":65536}"#);
+
+        let subscribed: EventMessage = serde_json::from_str(r#"{"event":"subscribed","channel":"book","chanId":17082,"symbol":"tBTCUSD","prec":"R0","freq":"F0","len":"25","pair":"BTCUSD"}"#).unwrap();
+        assert_eq!(subscribed.chan_id, Some(17082));
+        assert!(book.is_answered_by(&subscribed));
+        assert!(
+            !Subscription::book("tBTCUSD", BitfinexPrecision::P0, 25).is_answered_by(&subscribed)
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use serde::Deserialize;
+
+/// Object frames of Bitfinex: `info`, `conf`, `subscribed`, `unsubscribed` and `error`,
+/// data comes in array frames led by the channel id
+#[derive(Deserialize, Debug)]
+pub struct EventMessage {
+    pub event: String,
+
+    #[serde(rename = "chanId", default)]
+    pub chan_id: Option<u64>,
+
+    #[serde(default)]
+    pub channel: Option<String>,
+
+    #[serde(default)]
+    pub symbol: Option<String>,
+
+    #[serde(default)]
+    pub prec: Option<String>,
+
+    /// e.g. 20051 for "reconnect now" in `info`
+    #[serde(default)]
+    pub code: Option<i64>,
+
+    #[serde(default)]
+    pub msg: Option<String>,
+
+    /// "OK" for accepted `conf` / `unsubscribe`
+    #[serde(default)]
+    pub status: Option<String>,
+}
@@ -0,0 +1,46 @@
+use crate::{OrderDirection, Ticker};
+use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// `[ID, MTS, AMOUNT, PRICE]`, a negative amount is a taker sell
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct TradeData(pub u64, pub i64, pub f64, pub f64);
+
+impl TradeData {
+    pub fn tick(&self) -> Ticker {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let TradeData(id, ts, amount, price) = *self;
+
+        Ticker {
+            lts: now.as_millis() as i64,
+            ts,
+            price,
+            amount: amount.abs(),
+            direction: if amount > 0.0 {
+                OrderDirection::Buy
+            } else {
+                OrderDirection::Sell
+            },
+            id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bitfinex::format::TradeData;
+    use crate::OrderDirection;
+
+    #[test]
+    fn trade_to_ticker() {
+        let trade: TradeData =
+            serde_json::from_str("[401597395,1574694478808,-0.005,7245.3]").unwrap();
+
+        let tick = trade.tick();
+        assert_eq!(tick.id, 401597395);
+        assert_eq!(tick.ts, 1574694478808);
+        assert_eq!(tick.price, 7245.3);
+        assert_eq!(tick.amount, 0.005);
+        assert!(matches!(tick.direction, OrderDirection::Sell));
+    }
+}
@@ -0,0 +1,8 @@
+mod adapter;
+pub mod connection;
+pub mod format;
+
+pub use adapter::BitfinexAdapter;
+pub use connection::BitfinexDepth;
+pub use connection::BitfinexTicker;
+pub use format::BitfinexPrecision;
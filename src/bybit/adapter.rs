@@ -0,0 +1,54 @@
+use crate::bybit::{BybitDepth, BybitTicker};
+use crate::config::{instrument_bybit, symbol_type_bybit, Instrument};
+use crate::config::{set_addr_for_bybit, validate_symbol_bybit, DepthType, SymbolType};
+use crate::{DepthConfig, DepthT, ExchangeAdapter, ExchangeType, TickerConfig, TickerT};
+use anyhow::{anyhow, Result};
+use std::sync::Arc;
+
+/// Spot, linear and inverse categories of Bybit v5
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BybitAdapter;
+
+impl ExchangeAdapter for BybitAdapter {
+    fn name(&self) -> &'static str {
+        "bybit"
+    }
+
+    fn exchange_type(&self) -> ExchangeType {
+        ExchangeType::Bybit
+    }
+
+    fn validate_symbol(&self, symbol: &str, _limit: Option<i32>) -> Result<SymbolType> {
+        validate_symbol_bybit(symbol)
+    }
+
+    fn depth_url(&self, symbol_type: &SymbolType, limit: Option<i32>) -> Result<DepthType> {
+        let (rest_address, depth_address, level_depth_address) =
+            set_addr_for_bybit(symbol_type, limit);
+
+        DepthType::new(rest_address, depth_address, level_depth_address)
+            .ok_or_else(|| anyhow!("depth url is empty"))
+    }
+
+    fn ticker_url(&self, symbol_type: &SymbolType) -> Result<String> {
+        let (_, _, ticker_url) = set_addr_for_bybit(symbol_type, None);
+
+        ticker_url.ok_or_else(|| anyhow!("ticker url is empty"))
+    }
+
+    fn depth_connection(&self, _config: &DepthConfig) -> Result<Arc<dyn DepthT + Send + Sync>> {
+        Ok(Arc::new(BybitDepth::new()))
+    }
+
+    fn ticker_connection(&self, _config: &TickerConfig) -> Result<Arc<dyn TickerT + Send + Sync>> {
+        Ok(Arc::new(BybitTicker::new()))
+    }
+
+    fn native_symbol(&self, instrument: &Instrument) -> Result<SymbolType> {
+        symbol_type_bybit(instrument)
+    }
+
+    fn instrument_of(&self, symbol_type: &SymbolType) -> Result<Instrument> {
+        instrument_bybit(symbol_type)
+    }
+}
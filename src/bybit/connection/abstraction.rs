@@ -0,0 +1,134 @@
+use crate::api::driver::{decode, BookFeed};
+use crate::bybit::connection::BybitWebSocket;
+use crate::bybit::format::{ping_message, subscribe_message, unsubscribe_message, OpRespond};
+use crate::RawFrames;
+use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
+use futures_util::{SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+use tokio::time::{interval, Interval, MissedTickBehavior};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::debug;
+use url::Url;
+
+/// Bybit drops clients without a `ping` in this long
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(20);
+
+/// Unlike Binance, Bybit does not ping,
+/// tick the returned heartbeat in `next_push` to send ours
+pub async fn bybit_initialize(address: &str, topic: String) -> Result<(BybitWebSocket, Interval)> {
+    let url = Url::parse(address)?;
+
+    let mut stream = match connect_async(url).await {
+        Ok((connection, _)) => connection,
+        Err(e) => return Err(anyhow!("{:?}", e)),
+    };
+    debug!("Connect to {} success", address);
+
+    stream
+        .send(Message::from(subscribe_message(topic.clone())))
+        .await?;
+
+    debug!("Subscribe to topic {} success", topic);
+
+    let mut heartbeat = interval(HEARTBEAT_INTERVAL);
+    heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    // The first tick completes at once
+    heartbeat.tick().await;
+
+    Ok((stream, heartbeat))
+}
+
+/// Subscribe again on the same connection to get a fresh snapshot
+pub async fn bybit_resubscribe(stream: &mut BybitWebSocket, topic: String) -> Result<()> {
+    stream
+        .send(Message::from(unsubscribe_message(topic.clone())))
+        .await?;
+    stream
+        .send(Message::from(subscribe_message(topic.clone())))
+        .await?;
+
+    debug!("Resubscribe to topic {} success", topic);
+
+    Ok(())
+}
+
+/// Next push data of the stream, sending `{"op":"ping"}` on every heartbeat tick.
+///
+/// `op` responds are skipped, Err for unsuccessful ones
+pub async fn next_push(stream: &mut BybitWebSocket, heartbeat: &mut Interval) -> Result<String> {
+    loop {
+        let message = tokio::select! {
+            _ = heartbeat.tick() => {
+                stream.send(Message::from(ping_message())).await?;
+                continue;
+            }
+            message = stream.next() => match message {
+                Some(message) => message?,
+                None => return Err(anyhow!("Connection closed")),
+            },
+        };
+
+        let text = match message {
+            Message::Text(text) => {
+                RawFrames::global().publish("bybit", &text);
+                text
+            }
+            Message::Ping(payload) => {
+                stream.send(Message::Pong(payload)).await?;
+                continue;
+            }
+            Message::Close(frame) => return Err(anyhow!("Connection closed {:?}", frame)),
+            _ => continue,
+        };
+
+        if let Ok(respond) = serde_json::from_str::<OpRespond>(&text) {
+            if respond.success == Some(false) {
+                return Err(anyhow!("Bybit {} failed: {}", respond.op, respond.ret_msg));
+            }
+
+            debug!("Receive {:?}", respond);
+            continue;
+        }
+
+        return Ok(text);
+    }
+}
+
+/// Connection subscribed to the book `topic`
+pub struct BybitStream {
+    stream: BybitWebSocket,
+    heartbeat: Interval,
+    topic: String,
+}
+
+impl BybitStream {
+    pub async fn connect(address: &str, topic: String) -> Result<Self> {
+        let (stream, heartbeat) = bybit_initialize(address, topic.clone()).await?;
+
+        Ok(BybitStream {
+            stream,
+            heartbeat,
+            topic,
+        })
+    }
+}
+
+impl BookFeed for BybitStream {
+    fn next<T: DeserializeOwned + Send + 'static>(&mut self) -> BoxFuture<'_, Result<T>> {
+        Box::pin(async move {
+            loop {
+                let text = next_push(&mut self.stream, &mut self.heartbeat).await?;
+                if let Some(push) = decode("bybit", &text) {
+                    return Ok(push);
+                }
+            }
+        })
+    }
+
+    fn resubscribe(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(bybit_resubscribe(&mut self.stream, self.topic.clone()))
+    }
+}
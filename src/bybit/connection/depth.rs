@@ -0,0 +1,89 @@
+use crate::api::driver::{BookDriver, Connect};
+use crate::bybit::connection::abstraction::BybitStream;
+use crate::bybit::format::{BookEventStream, BookShared};
+use crate::config::depth_topic_bybit;
+use crate::{ConnectionState, Depth, DepthConfig, DepthT};
+use anyhow::Result;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tracing::{debug, warn};
+
+#[derive(Clone)]
+pub struct BybitDepth {
+    driver: BookDriver<BookShared>,
+}
+
+/// Connect to `address` and subscribe `topic`
+fn connect(address: String, topic: String) -> Connect<BybitStream> {
+    Box::new(move || {
+        let address = address.clone();
+        let topic = topic.clone();
+        Box::pin(async move { BybitStream::connect(&address, topic).await })
+    })
+}
+
+/// Put a snapshot or delta of the topic onto `shared`,
+/// Err once a gap of `u` is found
+fn apply(shared: &mut BookShared, push: BookEventStream, is_ready: bool) -> Option<Result<Depth>> {
+    match push.ttype.as_str() {
+        "snapshot" => {
+            shared.set_snapshot(push);
+            Some(Ok(shared.get_snapshot()))
+        }
+        "delta" if is_ready => Some(shared.add_delta(push).map(|_| {
+            debug!("Apply delta, seq {}", shared.seq());
+            shared.get_snapshot()
+        })),
+        // Deltas before the fresh snapshot
+        "delta" => {
+            debug!("Skip delta before snapshot");
+            None
+        }
+        other => {
+            warn!("Unknown type {}", other);
+            None
+        }
+    }
+}
+
+impl DepthT for BybitDepth {
+    fn new() -> Self {
+        BybitDepth {
+            driver: BookDriver::new("bybit", BookShared::new()),
+        }
+    }
+
+    /// acquire a order book of `limit` levels
+    fn depth_snapshot(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
+        let (_, depth_address) = config.get_depth_snapshot_addresses();
+        let topic = depth_topic_bybit(&config.symbol_type, config.limit);
+
+        Ok(self.driver.clone().pushed_book(
+            connect(depth_address, topic),
+            config.get_symbol(),
+            config.reconnect,
+            apply,
+        ))
+    }
+
+    /// acquire a order book of 50 levels
+    fn depth(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
+        let level_address = config.get_depth_addresses();
+        let topic = depth_topic_bybit(&config.symbol_type, None);
+
+        Ok(self.driver.clone().pushed_book(
+            connect(level_address, topic),
+            config.get_symbol(),
+            config.reconnect,
+            apply,
+        ))
+    }
+
+    /// Get the snapshot of the current Order Book
+    fn snapshot(&self) -> Option<Depth> {
+        self.driver.snapshot(BookShared::get_snapshot)
+    }
+
+    fn subscribe_state(&self) -> UnboundedReceiver<ConnectionState> {
+        self.driver.subscribe_state()
+    }
+}
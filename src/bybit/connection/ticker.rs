@@ -0,0 +1,106 @@
+use crate::bybit::connection::abstraction::{bybit_initialize, next_push};
+use crate::bybit::format::TradeEventStream;
+use crate::config::trade_topic_bybit;
+use crate::metrics;
+use crate::{Backoff, Ticker, TickerConfig, TickerT};
+use anyhow::Result;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tracing::{error, info, warn};
+
+#[derive(Clone)]
+pub struct BybitTicker {
+    status: Arc<Mutex<bool>>,
+}
+
+impl BybitTicker {
+    pub fn new() -> Self {
+        BybitTicker {
+            status: Arc::new(Mutex::new(false)),
+        }
+    }
+}
+
+impl TickerT for BybitTicker {
+    fn connect(&self, config: TickerConfig) -> Result<UnboundedReceiver<Vec<Ticker>>> {
+        let address = config.ticker_url.clone();
+        let topic = trade_topic_bybit(&config.symbol_type);
+
+        let status = self.status.clone();
+
+        let symbol = config.get_symbol();
+        let reconnect = config.reconnect.clone();
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            info!("Start Bybit trades thread");
+            let mut backoff = Backoff::new(reconnect);
+            loop {
+                let (mut stream, mut heartbeat) =
+                    match bybit_initialize(&address, topic.clone()).await {
+                        Ok(connection) => connection,
+                        Err(e) => {
+                            warn!("connection error {:?}", e);
+                            if !backoff.wait("bybit").await {
+                                break;
+                            }
+                            continue;
+                        }
+                    };
+                backoff.connected();
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = true;
+                }
+
+                loop {
+                    let text = match next_push(&mut stream, &mut heartbeat).await {
+                        Ok(text) => text,
+                        Err(e) => {
+                            warn!("{:?}, reconnecting", e);
+                            metrics::reconnect("bybit");
+                            break;
+                        }
+                    };
+
+                    let event: TradeEventStream = match serde_json::from_str(&text) {
+                        Ok(event) => event,
+                        Err(e) => {
+                            warn!("Error {}, {:?}", e, text);
+                            metrics::decode_error("bybit");
+                            continue;
+                        }
+                    };
+
+                    let ticks = event
+                        .data
+                        .iter()
+                        .filter_map(|trade| match trade.tick() {
+                            Ok(tick) => Some(tick),
+                            Err(e) => {
+                                warn!("Bad trade {:?}, {:?}", trade, e);
+                                None
+                            }
+                        })
+                        .collect::<Vec<_>>();
+
+                    metrics::trades("bybit", &symbol, &ticks);
+                    if ticks.is_empty() {
+                        warn!("Bybit Received empty ticks");
+                    } else if sender.send(ticks).is_err() {
+                        error!("Bybit Ticker send error");
+                    }
+                }
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = false;
+                }
+                if !backoff.wait("bybit").await {
+                    break;
+                }
+            }
+        });
+
+        Ok(receiver)
+    }
+}
@@ -0,0 +1,157 @@
+use crate::api::format::quote_tuples;
+use crate::bybit::format::BookEventStream;
+use crate::{BookSide, Depth, OrderBook, Quote};
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[allow(dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct BookData {
+    /// Something like "BTCUSDT"
+    #[serde(rename = "s")]
+    pub symbol: String,
+
+    #[serde(rename = "b", deserialize_with = "quote_tuples")]
+    pub bids: Vec<Quote>,
+
+    #[serde(rename = "a", deserialize_with = "quote_tuples")]
+    pub asks: Vec<Quote>,
+
+    /// Update id, increases by one every push,
+    /// `1` means Bybit restarted and sends a new snapshot
+    #[serde(rename = "u")]
+    pub update_id: i64,
+
+    /// Cross sequence, shared with other channels of the symbol
+    pub seq: i64,
+}
+
+/// `Depth.id` is the update id `u`
+pub struct BookShared {
+    update_id: i64,
+    seq: i64,
+    send_time: i64,
+    receive_time: i64,
+    book: OrderBook,
+}
+
+impl BookShared {
+    pub fn new() -> Self {
+        BookShared {
+            update_id: 0,
+            seq: 0,
+            send_time: 0,
+            receive_time: 0,
+            book: OrderBook::new(),
+        }
+    }
+
+    /// "snapshot", replaces the whole book
+    pub fn set_snapshot(&mut self, event: BookEventStream) {
+        self.book.clear();
+
+        self.apply(event);
+    }
+
+    /// "delta", Err if `u` does not follow the last applied one
+    pub fn add_delta(&mut self, event: BookEventStream) -> Result<()> {
+        let update_id = event.data.update_id;
+
+        if update_id == 1 {
+            self.set_snapshot(event);
+            return Ok(());
+        }
+
+        if update_id != self.update_id + 1 {
+            return Err(anyhow!(
+                "Sequence gap, order book {}, update {}",
+                self.update_id,
+                update_id
+            ));
+        }
+
+        self.apply(event);
+        Ok(())
+    }
+
+    fn apply(&mut self, event: BookEventStream) {
+        let data = event.data;
+
+        self.book.apply_levels(BookSide::Ask, data.asks);
+        self.book.apply_levels(BookSide::Bid, data.bids);
+
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        self.update_id = data.update_id;
+        self.seq = data.seq;
+        self.send_time = event.ts;
+        self.receive_time = time.as_millis() as i64;
+    }
+
+    /// Cross sequence of the last applied push
+    pub fn seq(&self) -> i64 {
+        self.seq
+    }
+
+    pub fn get_snapshot(&self) -> Depth {
+        Depth {
+            id: self.update_id,
+            ts: self.send_time,
+            lts: self.receive_time,
+            asks: self.book.quotes(BookSide::Ask),
+            bids: self.book.quotes(BookSide::Bid),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bybit::format::{BookEventStream, BookShared};
+
+    /// Recorded from `orderbook.50.BTCUSDT` of the linear stream, trimmed to 3 levels
+    const SNAPSHOT: &str = r#"{"topic":"orderbook.50.BTCUSDT","type":"snapshot","ts":1672304484978,"data":{"s":"BTCUSDT","b":[["16493.50","0.006"],["16493.00","0.100"],["16492.50","0.250"]],"a":[["16611.00","0.029"],["16612.00","0.213"],["16613.00","0.080"]],"u":18521288,"seq":7961638724},"cts":1672304484976}"#;
+
+    const DELTA: &str = r#"{"topic":"orderbook.50.BTCUSDT","type":"delta","ts":1672304485018,"data":{"s":"BTCUSDT","b":[["16493.50","0"],["16493.20","1.500"]],"a":[["16611.00","0.040"]],"u":18521289,"seq":7961638730},"cts":1672304485016}"#;
+
+    const GAP: &str = r#"{"topic":"orderbook.50.BTCUSDT","type":"delta","ts":1672304485118,"data":{"s":"BTCUSDT","b":[],"a":[],"u":18521291,"seq":7961638790},"cts":1672304485116}"#;
+
+    const RESTART: &str = r#"{"topic":"orderbook.50.BTCUSDT","type":"delta","ts":1672304486018,"data":{"s":"BTCUSDT","b":[["16490.00","2.000"]],"a":[["16600.00","1.000"]],"u":1,"seq":7961639000},"cts":1672304486016}"#;
+
+    #[test]
+    fn snapshot_and_delta() {
+        let mut shared = BookShared::new();
+
+        let snapshot: BookEventStream = serde_json::from_str(SNAPSHOT).unwrap();
+        assert_eq!(snapshot.ttype, "snapshot");
+        shared.set_snapshot(snapshot);
+
+        let depth = shared.get_snapshot();
+        assert_eq!(depth.id, 18521288);
+        assert_eq!(depth.ts, 1672304484978);
+        assert_eq!(depth.bids[0].price, 16493.5);
+        assert_eq!(depth.asks.len(), 3);
+
+        let delta: BookEventStream = serde_json::from_str(DELTA).unwrap();
+        shared.add_delta(delta).unwrap();
+
+        let depth = shared.get_snapshot();
+        assert_eq!(depth.id, 18521289);
+        assert_eq!(shared.seq(), 7961638730);
+        assert_eq!(depth.bids[0].price, 16493.2);
+        assert_eq!(depth.bids[0].amount, 1.5);
+        assert_eq!(depth.bids.len(), 3);
+        assert_eq!(depth.asks[0].amount, 0.04);
+
+        let gap: BookEventStream = serde_json::from_str(GAP).unwrap();
+        assert!(shared.add_delta(gap).is_err());
+        assert_eq!(shared.get_snapshot().id, 18521289);
+
+        let restart: BookEventStream = serde_json::from_str(RESTART).unwrap();
+        shared.add_delta(restart).unwrap();
+
+        let depth = shared.get_snapshot();
+        assert_eq!(depth.id, 1);
+        assert_eq!(depth.bids.len(), 1);
+        assert_eq!(depth.asks[0].price, 16600.0);
+    }
+}
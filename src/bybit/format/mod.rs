@@ -0,0 +1,9 @@
+mod depth;
+mod request;
+mod stream;
+mod ticker;
+
+pub use depth::{BookData, BookShared};
+pub use request::{ping_message, subscribe_message, unsubscribe_message};
+pub use stream::{BookEventStream, OpRespond, TradeEventStream};
+pub use ticker::TradeData;
@@ -0,0 +1,61 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct OpRequest<'a> {
+    op: &'a str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    args: Vec<String>,
+}
+
+/// e.g. `{"op":"subscribe","args":["orderbook.50.BTCUSDT"]}`
+pub fn subscribe_message(topic: String) -> String {
+    let inner = OpRequest {
+        op: "subscribe",
+        args: vec![topic],
+    };
+    serde_json::to_string(&inner).unwrap()
+}
+
+pub fn unsubscribe_message(topic: String) -> String {
+    let inner = OpRequest {
+        op: "unsubscribe",
+        args: vec![topic],
+    };
+    serde_json::to_string(&inner).unwrap()
+}
+
+/// Heartbeat Bybit expects from clients every 20 seconds
+pub fn ping_message() -> String {
+    let inner = OpRequest {
+        op: "ping",
+        args: vec![],
+    };
+    serde_json::to_string(&inner).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bybit::format::{ping_message, subscribe_message, OpRespond};
+
+    #[test]
+    fn op_messages() {
+        assert_eq!(ping_message(), r#"{"op":"ping"}"#);
+        assert_eq!(
+            subscribe_message(String::from("orderbook.50.BTCUSDT")),
+            r#"{"op":"subscribe","args":["orderbook.50.BTCUSDT"]}"#
+        );
+
+        // `pong` of spot and of derivatives
+        let spot: OpRespond = serde_json::from_str(
+            r#"{"success":true,"ret_msg":"pong","conn_id":"0970e817","req_id":"","op":"ping"}"#,
+        )
+        .unwrap();
+        assert_eq!(spot.success, Some(true));
+
+        let linear: OpRespond = serde_json::from_str(
+            r#"{"op":"pong","args":["1675418560633"],"conn_id":"cfcb4ocsvfriu23r3er0"}"#,
+        )
+        .unwrap();
+        assert_eq!(linear.success, None);
+    }
+}
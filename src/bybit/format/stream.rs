@@ -0,0 +1,45 @@
+use crate::bybit::format::{BookData, TradeData};
+use serde::Deserialize;
+
+/// Respond of `subscribe` / `unsubscribe` / `ping`
+#[derive(Deserialize, Debug)]
+pub struct OpRespond {
+    pub op: String,
+
+    /// Missing in the `pong` of derivative streams
+    #[serde(default)]
+    pub success: Option<bool>,
+
+    #[serde(default)]
+    pub ret_msg: String,
+}
+
+/// Push data of `orderbook.{depth}.{symbol}`
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+pub struct BookEventStream {
+    pub topic: String,
+
+    /// "snapshot" / "delta"
+    #[serde(rename = "type")]
+    pub ttype: String,
+
+    /// Time the data is generated
+    pub ts: i64,
+
+    pub data: BookData,
+}
+
+/// Push data of `publicTrade.{symbol}`
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+pub struct TradeEventStream {
+    pub topic: String,
+
+    #[serde(rename = "type")]
+    pub ttype: String,
+
+    pub ts: i64,
+
+    pub data: Vec<TradeData>,
+}
@@ -0,0 +1,90 @@
+use crate::{OrderDirection, Ticker};
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[allow(dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct TradeData {
+    #[serde(rename = "T")]
+    pub trade_time: i64,
+
+    #[serde(rename = "s")]
+    pub symbol: String,
+
+    /// "Buy" / "Sell", direction of the taker
+    #[serde(rename = "S")]
+    pub side: String,
+
+    #[serde(rename = "v")]
+    pub amount: String,
+
+    #[serde(rename = "p")]
+    pub price: String,
+
+    /// Numeric for spot, an UUID for derivatives
+    #[serde(rename = "i")]
+    pub trade_id: String,
+}
+
+impl TradeData {
+    pub fn tick(&self) -> Result<Ticker> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let direction = match self.side.as_str() {
+            "Buy" => OrderDirection::Buy,
+            "Sell" => OrderDirection::Sell,
+            other => return Err(anyhow!("Unknown side {}", other)),
+        };
+
+        Ok(Ticker {
+            lts: now.as_millis() as i64,
+            ts: self.trade_time,
+            price: self.price.parse()?,
+            amount: self.amount.parse()?,
+            direction,
+            id: self.id()?,
+        })
+    }
+
+    /// Spot ids as they are, the first 64 bits of derivative UUIDs
+    fn id(&self) -> Result<u64> {
+        if let Ok(id) = self.trade_id.parse::<u64>() {
+            return Ok(id);
+        }
+
+        let hex = self.trade_id.replace('-', "");
+        let head = hex
+            .get(..16)
+            .ok_or_else(|| anyhow!("Bad trade id {}", self.trade_id))?;
+
+        Ok(u64::from_str_radix(head, 16)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bybit::format::TradeEventStream;
+    use crate::OrderDirection;
+
+    /// Recorded from `publicTrade.BTCUSDT` of the linear stream
+    const LINEAR_TRADES: &str = r#"{"topic":"publicTrade.BTCUSDT","type":"snapshot","ts":1672304486868,"data":[{"T":1672304486865,"s":"BTCUSDT","S":"Buy","v":"0.001","p":"16578.50","L":"PlusTick","i":"20f43950-d8dd-5b31-9112-a178eb6023af","BT":false}]}"#;
+
+    /// Recorded from `publicTrade.BTCUSDT` of the spot stream
+    const SPOT_TRADES: &str = r#"{"topic":"publicTrade.BTCUSDT","ts":1672304486868,"type":"snapshot","data":[{"i":"2290000000007764263","T":1672304486865,"p":"16578.5","v":"0.0123","S":"Sell","s":"BTCUSDT","BT":false}]}"#;
+
+    #[test]
+    fn trade_to_ticker() {
+        let event: TradeEventStream = serde_json::from_str(LINEAR_TRADES).unwrap();
+        let tick = event.data[0].tick().unwrap();
+        assert_eq!(tick.id, 0x20f43950d8dd5b31);
+        assert_eq!(tick.ts, 1672304486865);
+        assert_eq!(tick.price, 16578.5);
+        assert!(matches!(tick.direction, OrderDirection::Buy));
+
+        let event: TradeEventStream = serde_json::from_str(SPOT_TRADES).unwrap();
+        let tick = event.data[0].tick().unwrap();
+        assert_eq!(tick.id, 2290000000007764263);
+        assert_eq!(tick.amount, 0.0123);
+        assert!(matches!(tick.direction, OrderDirection::Sell));
+    }
+}
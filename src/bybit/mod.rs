@@ -0,0 +1,7 @@
+mod adapter;
+pub mod connection;
+pub mod format;
+
+pub use adapter::BybitAdapter;
+pub use connection::BybitDepth;
+pub use connection::BybitTicker;
@@ -0,0 +1,59 @@
+use crate::coinbase::{CoinbaseDepth, CoinbaseTicker};
+use crate::config::{instrument_coinbase, symbol_type_coinbase, Instrument};
+use crate::config::{
+    set_addr_for_coinbase, set_ticker_addr_for_coinbase, validate_symbol_coinbase,
+};
+use crate::config::{DepthType, SymbolType};
+use crate::{DepthConfig, DepthT, ExchangeAdapter, ExchangeType, TickerConfig, TickerT};
+use anyhow::{anyhow, Result};
+use std::sync::Arc;
+
+/// Spot products of the Coinbase Exchange feed
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CoinbaseAdapter;
+
+impl ExchangeAdapter for CoinbaseAdapter {
+    fn name(&self) -> &'static str {
+        "coinbase"
+    }
+
+    fn exchange_type(&self) -> ExchangeType {
+        ExchangeType::Coinbase
+    }
+
+    fn validate_symbol(&self, symbol: &str, _limit: Option<i32>) -> Result<SymbolType> {
+        validate_symbol_coinbase(symbol)
+    }
+
+    fn depth_url(&self, symbol_type: &SymbolType, limit: Option<i32>) -> Result<DepthType> {
+        let product_id = match symbol_type {
+            SymbolType::Spot(s) => s,
+            other => return Err(anyhow!("Unsupported symbol {:?} for coinbase", other)),
+        };
+        let (rest_address, depth_address, level_depth_address) =
+            set_addr_for_coinbase(product_id, limit);
+
+        DepthType::new(rest_address, depth_address, level_depth_address)
+            .ok_or_else(|| anyhow!("depth url is empty"))
+    }
+
+    fn ticker_url(&self, _symbol_type: &SymbolType) -> Result<String> {
+        Ok(set_ticker_addr_for_coinbase())
+    }
+
+    fn depth_connection(&self, _config: &DepthConfig) -> Result<Arc<dyn DepthT + Send + Sync>> {
+        Ok(Arc::new(CoinbaseDepth::new()))
+    }
+
+    fn ticker_connection(&self, _config: &TickerConfig) -> Result<Arc<dyn TickerT + Send + Sync>> {
+        Ok(Arc::new(CoinbaseTicker::new()))
+    }
+
+    fn native_symbol(&self, instrument: &Instrument) -> Result<SymbolType> {
+        symbol_type_coinbase(instrument)
+    }
+
+    fn instrument_of(&self, symbol_type: &SymbolType) -> Result<Instrument> {
+        instrument_coinbase(symbol_type)
+    }
+}
@@ -0,0 +1,180 @@
+use crate::api::driver::BookFeed;
+use crate::coinbase::connection::CoinbaseWebSocket;
+use crate::coinbase::format::{subscribe_message, unsubscribe_message, FeedMessage};
+use crate::metrics;
+use crate::RawFrames;
+use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
+use futures_util::{SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, warn};
+use url::Url;
+
+pub async fn coinbase_initialize(
+    address: &str,
+    product_id: String,
+    channel: String,
+) -> Result<CoinbaseWebSocket> {
+    let url = Url::parse(address)?;
+
+    let mut stream = match connect_async(url).await {
+        Ok((connection, _)) => connection,
+        Err(e) => return Err(anyhow!("{:?}", e)),
+    };
+    debug!("Connect to {} success", address);
+
+    stream
+        .send(Message::from(subscribe_message(
+            product_id.clone(),
+            channel.clone(),
+        )))
+        .await?;
+
+    debug!("Subscribe to {} of {} success", channel, product_id);
+
+    Ok(stream)
+}
+
+/// Subscribe again on the same connection to get a fresh snapshot
+pub async fn coinbase_resubscribe(
+    stream: &mut CoinbaseWebSocket,
+    product_id: String,
+    channel: String,
+) -> Result<()> {
+    stream
+        .send(Message::from(unsubscribe_message(
+            product_id.clone(),
+            channel.clone(),
+        )))
+        .await?;
+    stream
+        .send(Message::from(subscribe_message(
+            product_id.clone(),
+            channel.clone(),
+        )))
+        .await?;
+
+    debug!("Resubscribe to {} of {} success", channel, product_id);
+
+    Ok(())
+}
+
+/// Types of the messages of `level2_batch`
+const BOOK_TYPES: [&str; 2] = ["snapshot", "l2update"];
+
+/// Next text message of the stream, answering pings on the way
+async fn next_text(stream: &mut CoinbaseWebSocket) -> Result<String> {
+    loop {
+        let message = match stream.next().await {
+            Some(message) => message?,
+            None => return Err(anyhow!("Connection closed")),
+        };
+
+        match message {
+            Message::Text(text) => {
+                RawFrames::global().publish("coinbase", &text);
+                return Ok(text);
+            }
+            Message::Ping(payload) => {
+                stream.send(Message::Pong(payload)).await?;
+                continue;
+            }
+            Message::Close(frame) => return Err(anyhow!("Connection closed {:?}", frame)),
+            _ => continue,
+        }
+    }
+}
+
+/// Next data message of the feed,
+/// `subscriptions` and other control messages are skipped, Err for `error`
+pub async fn next_message(stream: &mut CoinbaseWebSocket) -> Result<FeedMessage> {
+    loop {
+        let text = next_text(stream).await?;
+
+        match serde_json::from_str(&text) {
+            Ok(FeedMessage::Error { message, reason }) => {
+                return Err(anyhow!("Coinbase error {}: {}", message, reason))
+            }
+            Ok(FeedMessage::Other) => debug!("Skip {}", text),
+            Ok(message) => return Ok(message),
+            Err(e) => {
+                warn!("Error {}, {:?}", e, text);
+                metrics::decode_error("coinbase");
+            }
+        }
+    }
+}
+
+/// Connection subscribed to `channel` of `product_id`
+pub struct CoinbaseStream {
+    stream: CoinbaseWebSocket,
+    product_id: String,
+    channel: String,
+}
+
+impl CoinbaseStream {
+    pub async fn connect(address: &str, product_id: String, channel: String) -> Result<Self> {
+        let stream = coinbase_initialize(address, product_id.clone(), channel.clone()).await?;
+
+        Ok(CoinbaseStream {
+            stream,
+            product_id,
+            channel,
+        })
+    }
+}
+
+impl BookFeed for CoinbaseStream {
+    /// Next `snapshot` or `l2update`, Err for `error`
+    fn next<T: DeserializeOwned + Send + 'static>(&mut self) -> BoxFuture<'_, Result<T>> {
+        Box::pin(async move {
+            loop {
+                let text = next_text(&mut self.stream).await?;
+
+                let message: Value = match serde_json::from_str(&text) {
+                    Ok(message) => message,
+                    Err(e) => {
+                        warn!("Error {}, {:?}", e, text);
+                        metrics::decode_error("coinbase");
+                        continue;
+                    }
+                };
+
+                match message["type"].as_str() {
+                    Some("error") => {
+                        return Err(anyhow!(
+                            "Coinbase error {}: {}",
+                            message["message"],
+                            message["reason"]
+                        ))
+                    }
+                    Some(ttype) if BOOK_TYPES.contains(&ttype) => {}
+                    _ => {
+                        debug!("Skip {}", text);
+                        continue;
+                    }
+                }
+
+                // `Quote` borrows its strings, so decode from a reference
+                match T::deserialize(&message) {
+                    Ok(message) => return Ok(message),
+                    Err(e) => {
+                        warn!("Error {}, {:?}", e, text);
+                        metrics::decode_error("coinbase");
+                    }
+                }
+            }
+        })
+    }
+
+    fn resubscribe(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(coinbase_resubscribe(
+            &mut self.stream,
+            self.product_id.clone(),
+            self.channel.clone(),
+        ))
+    }
+}
@@ -0,0 +1,204 @@
+use crate::api::driver::{BookDriver, Connect};
+use crate::coinbase::connection::abstraction::CoinbaseStream;
+use crate::coinbase::format::{BookShared, FeedMessage};
+use crate::{ConnectionState, Depth, DepthConfig, DepthT};
+use anyhow::Result;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tracing::{debug, warn};
+
+const CHANNEL: &str = "level2_batch";
+
+/// Levels sent in Level Mode
+const LEVEL_DEPTH: usize = 20;
+
+#[derive(Clone)]
+pub struct CoinbaseDepth {
+    driver: BookDriver<BookShared>,
+    /// Levels of each side in `snapshot()`
+    levels: Arc<Mutex<usize>>,
+}
+
+/// Connect to `address` and subscribe `level2_batch` of `product_id`
+fn connect(address: String, product_id: String) -> Connect<CoinbaseStream> {
+    Box::new(move || {
+        let address = address.clone();
+        let product_id = product_id.clone();
+        Box::pin(
+            async move { CoinbaseStream::connect(&address, product_id, CHANNEL.to_string()).await },
+        )
+    })
+}
+
+/// Put a message of `level2_batch` onto `shared`,
+/// giving the best `levels` of each side
+fn apply(
+    shared: &mut BookShared,
+    message: FeedMessage,
+    is_ready: bool,
+    levels: usize,
+) -> Option<Result<Depth>> {
+    match message {
+        FeedMessage::Snapshot(snapshot) => {
+            shared.set_snapshot(snapshot);
+            Some(Ok(shared.get_snapshot(levels)))
+        }
+        FeedMessage::L2update(update) if is_ready => Some(
+            shared
+                .add_update(update)
+                .map(|_| shared.get_snapshot(levels)),
+        ),
+        // Updates before the fresh snapshot
+        FeedMessage::L2update(_) => {
+            debug!("Skip l2update before snapshot");
+            None
+        }
+        other => {
+            warn!("Unexpected message {:?}", other);
+            None
+        }
+    }
+}
+
+impl CoinbaseDepth {
+    /// Keep the book in line with `level2_batch`,
+    /// sending the best `levels` of each side after every message
+    fn maintain(
+        &self,
+        address: String,
+        levels: usize,
+        config: DepthConfig,
+    ) -> UnboundedReceiver<Depth> {
+        *self.levels.lock().unwrap() = levels;
+
+        self.driver.clone().pushed_book(
+            connect(address, config.get_symbol()),
+            config.get_symbol(),
+            config.reconnect,
+            move |shared, message, is_ready| apply(shared, message, is_ready, levels),
+        )
+    }
+}
+
+impl DepthT for CoinbaseDepth {
+    fn new() -> Self {
+        CoinbaseDepth {
+            driver: BookDriver::new("coinbase", BookShared::new()),
+            levels: Arc::new(Mutex::new(LEVEL_DEPTH)),
+        }
+    }
+
+    /// acquire the best `limit` levels of the whole book
+    fn depth_snapshot(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
+        let (_, depth_address) = config.get_depth_snapshot_addresses();
+        let levels = config.limit.map_or(LEVEL_DEPTH, |limit| limit as usize);
+
+        Ok(self.maintain(depth_address, levels, config))
+    }
+
+    /// acquire the best 20 levels of the whole book
+    fn depth(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
+        let level_address = config.get_depth_addresses();
+
+        Ok(self.maintain(level_address, LEVEL_DEPTH, config))
+    }
+
+    /// Get the snapshot of the current Order Book
+    fn snapshot(&self) -> Option<Depth> {
+        let levels = *self.levels.lock().unwrap();
+        self.driver.snapshot(|shared| shared.get_snapshot(levels))
+    }
+
+    fn subscribe_state(&self) -> UnboundedReceiver<ConnectionState> {
+        self.driver.subscribe_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::coinbase::connection::CoinbaseDepth;
+    use crate::config::{DepthConfig, DepthType, SymbolType};
+    use crate::{ConnectionState, DepthT, ExchangeType, ReconnectPolicy};
+    use futures_util::{SinkExt, StreamExt};
+    use std::time::Duration;
+    use tokio::net::TcpListener;
+    use tokio::runtime::Runtime;
+    use tokio::time::timeout;
+    use tokio_tungstenite::accept_async;
+    use tokio_tungstenite::tungstenite::Message;
+
+    const REST: &str = "https://api.exchange.coinbase.com/products/BTC-USD/book?level=2";
+
+    const SUBSCRIPTIONS: &str = r#"{"type":"subscriptions","channels":[{"name":"level2_batch","product_ids":["BTC-USD"]}]}"#;
+
+    const EARLY_UPDATE: &str = r#"{"type":"l2update","product_id":"BTC-USD","changes":[["buy","16000.00","1.0"]],"time":"2022-12-29T09:01:24.900000Z"}"#;
+
+    const SNAPSHOT: &str = r#"{"type":"snapshot","product_id":"BTC-USD","bids":[["16539.10","0.5"],["16539.00","1.2"],["16538.50","0.3"]],"asks":[["16540.20","0.8"],["16540.50","2.0"],["16541.00","0.1"]],"time":"2022-12-29T09:01:25.000000Z"}"#;
+
+    const UPDATE: &str = r#"{"type":"l2update","product_id":"BTC-USD","changes":[["buy","16539.10","0"],["sell","16540.00","0.4"]],"time":"2022-12-29T09:01:25.050000Z"}"#;
+
+    #[test]
+    fn snapshot_then_update_from_mock_server() {
+        Runtime::new().unwrap().block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let address = format!("ws://{}", listener.local_addr().unwrap());
+
+            let server = tokio::spawn(async move {
+                let (socket, _) = listener.accept().await.unwrap();
+                let mut ws = accept_async(socket).await.unwrap();
+
+                let request = ws.next().await.unwrap().unwrap().into_text().unwrap();
+                assert!(request.contains(r#""type":"subscribe""#));
+                assert!(request.contains(r#""product_ids":["BTC-USD"]"#));
+                assert!(request.contains(r#""channels":["level2_batch"]"#));
+
+                for frame in [SUBSCRIPTIONS, EARLY_UPDATE, SNAPSHOT, UPDATE] {
+                    ws.send(Message::from(frame)).await.unwrap();
+                }
+                ws
+            });
+
+            let config = DepthConfig {
+                depth_url: DepthType::DepthSnapshot(REST.to_string(), address),
+                symbol_type: SymbolType::Spot(String::from("BTC-USD")),
+                exchange_type: ExchangeType::Coinbase,
+                limit: Some(2),
+                checksum: None,
+                audit_interval: None,
+                reconnect: ReconnectPolicy::default(),
+            };
+
+            let book = CoinbaseDepth::new();
+            let mut state = book.subscribe_state();
+            let mut recv = book.depth_snapshot(config).unwrap();
+
+            timeout(Duration::from_secs(10), async {
+                let depth = recv.recv().await.unwrap();
+                assert_eq!(depth.id, 1);
+                assert_eq!(depth.ts, 1672304485000);
+                assert_eq!(depth.bids.len(), 2);
+                assert_eq!(depth.bids[0].price, 16539.1);
+                assert_eq!(depth.asks[0].price, 16540.2);
+                assert!(matches!(
+                    state.recv().await,
+                    Some(ConnectionState::Connected)
+                ));
+
+                let depth = recv.recv().await.unwrap();
+                assert_eq!(depth.id, 2);
+                assert_eq!(depth.ts, 1672304485050);
+                assert_eq!(depth.bids[0].price, 16539.0);
+                assert_eq!(depth.bids[1].price, 16538.5);
+                assert_eq!(depth.asks[0].price, 16540.0);
+                assert_eq!(depth.asks[0].amount, 0.4);
+                assert_eq!(depth.asks.len(), 2);
+
+                assert_eq!(book.snapshot().unwrap().id, 2);
+            })
+            .await
+            .unwrap();
+
+            server.await.unwrap();
+        })
+    }
+}
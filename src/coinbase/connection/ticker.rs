@@ -0,0 +1,99 @@
+use crate::coinbase::connection::abstraction::{coinbase_initialize, next_message};
+use crate::coinbase::format::FeedMessage;
+use crate::metrics;
+use crate::{Backoff, Ticker, TickerConfig, TickerT};
+use anyhow::Result;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tracing::{error, info, warn};
+
+const CHANNEL: &str = "matches";
+
+#[derive(Clone)]
+pub struct CoinbaseTicker {
+    status: Arc<Mutex<bool>>,
+}
+
+impl CoinbaseTicker {
+    pub fn new() -> Self {
+        CoinbaseTicker {
+            status: Arc::new(Mutex::new(false)),
+        }
+    }
+}
+
+impl TickerT for CoinbaseTicker {
+    fn connect(&self, config: TickerConfig) -> Result<UnboundedReceiver<Vec<Ticker>>> {
+        let address = config.ticker_url.clone();
+        let product_id = config.get_symbol();
+
+        let status = self.status.clone();
+
+        let reconnect = config.reconnect.clone();
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            info!("Start Coinbase matches thread");
+            let mut backoff = Backoff::new(reconnect);
+            loop {
+                let mut stream =
+                    match coinbase_initialize(&address, product_id.clone(), CHANNEL.to_string())
+                        .await
+                    {
+                        Ok(connection) => connection,
+                        Err(e) => {
+                            warn!("connection error {:?}", e);
+                            if !backoff.wait("coinbase").await {
+                                break;
+                            }
+                            continue;
+                        }
+                    };
+                backoff.connected();
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = true;
+                }
+
+                loop {
+                    let trade = match next_message(&mut stream).await {
+                        // `last_match` is the latest trade before subscribing
+                        Ok(FeedMessage::Match(trade)) | Ok(FeedMessage::LastMatch(trade)) => trade,
+                        Ok(other) => {
+                            warn!("Unexpected message {:?}", other);
+                            continue;
+                        }
+                        Err(e) => {
+                            warn!("{:?}, reconnecting", e);
+                            metrics::reconnect("coinbase");
+                            break;
+                        }
+                    };
+
+                    let tick = match trade.tick() {
+                        Ok(tick) => tick,
+                        Err(e) => {
+                            warn!("Bad trade {:?}, {:?}", trade, e);
+                            continue;
+                        }
+                    };
+
+                    let ticks = vec![tick];
+                    metrics::trades("coinbase", &product_id, &ticks);
+                    if sender.send(ticks).is_err() {
+                        error!("Coinbase Ticker send error");
+                    }
+                }
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = false;
+                }
+                if !backoff.wait("coinbase").await {
+                    break;
+                }
+            }
+        });
+
+        Ok(receiver)
+    }
+}
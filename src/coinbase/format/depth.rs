@@ -0,0 +1,110 @@
+use crate::api::format::quote_tuples;
+use crate::api::parse_time;
+use crate::{BookSide, Depth, OrderBook, Quote};
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[allow(dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct BookSnapshot {
+    /// Something like "BTC-USD"
+    pub product_id: String,
+
+    #[serde(deserialize_with = "quote_tuples")]
+    pub bids: Vec<Quote>,
+
+    #[serde(deserialize_with = "quote_tuples")]
+    pub asks: Vec<Quote>,
+
+    /// Missing in older feeds
+    #[serde(default)]
+    pub time: Option<String>,
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct BookUpdate {
+    pub product_id: String,
+
+    pub time: String,
+
+    pub changes: Vec<Change>,
+}
+
+/// `["buy" / "sell", "price", "size"]`, size `0` removes the level
+#[derive(Deserialize, Debug, Clone)]
+pub struct Change(pub String, pub String, pub String);
+
+/// Coinbase sends no update id with `level2_batch`,
+/// so `Depth.id` is a local sequence instead:
+/// `1` for the snapshot, then increased by one for every applied `l2update`.
+///
+/// It starts over with every snapshot,
+/// ids are only comparable within one subscription
+pub struct BookShared {
+    sequence: i64,
+    send_time: i64,
+    receive_time: i64,
+    book: OrderBook,
+}
+
+impl BookShared {
+    pub fn new() -> Self {
+        BookShared {
+            sequence: 0,
+            send_time: 0,
+            receive_time: 0,
+            book: OrderBook::new(),
+        }
+    }
+
+    pub fn set_snapshot(&mut self, snapshot: BookSnapshot) {
+        self.book.apply_snapshot(snapshot.bids, snapshot.asks);
+
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let receive_time = time.as_millis() as i64;
+
+        self.sequence = 1;
+        self.send_time = snapshot
+            .time
+            .and_then(|time| parse_time(&time).ok())
+            .unwrap_or(receive_time);
+        self.receive_time = receive_time;
+    }
+
+    pub fn add_update(&mut self, update: BookUpdate) -> Result<()> {
+        for Change(side, price, size) in update.changes {
+            let side = match side.as_str() {
+                "buy" => BookSide::Bid,
+                "sell" => BookSide::Ask,
+                other => return Err(anyhow!("Unknown side {}", other)),
+            };
+
+            let quote = Quote {
+                price: price.parse()?,
+                amount: size.parse()?,
+                orders: None,
+            };
+            self.book.apply_level(side, quote);
+        }
+
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        self.sequence += 1;
+        self.send_time = parse_time(&update.time)?;
+        self.receive_time = time.as_millis() as i64;
+
+        Ok(())
+    }
+
+    /// The best `levels` of each side
+    pub fn get_snapshot(&self, levels: usize) -> Depth {
+        Depth {
+            id: self.sequence,
+            ts: self.send_time,
+            lts: self.receive_time,
+            asks: self.book.top(BookSide::Ask, levels),
+            bids: self.book.top(BookSide::Bid, levels),
+        }
+    }
+}
@@ -0,0 +1,9 @@
+mod depth;
+mod request;
+mod stream;
+mod ticker;
+
+pub use depth::{BookShared, BookSnapshot, BookUpdate};
+pub use request::{subscribe_message, unsubscribe_message};
+pub use stream::FeedMessage;
+pub use ticker::MatchData;
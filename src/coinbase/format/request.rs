@@ -0,0 +1,28 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct ChannelRequest<'a> {
+    #[serde(rename = "type")]
+    ttype: &'a str,
+    product_ids: Vec<String>,
+    channels: Vec<String>,
+}
+
+/// e.g. `{"type":"subscribe","product_ids":["BTC-USD"],"channels":["level2_batch"]}`
+pub fn subscribe_message(product_id: String, channel: String) -> String {
+    let inner = ChannelRequest {
+        ttype: "subscribe",
+        product_ids: vec![product_id],
+        channels: vec![channel],
+    };
+    serde_json::to_string(&inner).unwrap()
+}
+
+pub fn unsubscribe_message(product_id: String, channel: String) -> String {
+    let inner = ChannelRequest {
+        ttype: "unsubscribe",
+        product_ids: vec![product_id],
+        channels: vec![channel],
+    };
+    serde_json::to_string(&inner).unwrap()
+}
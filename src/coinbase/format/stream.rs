@@ -0,0 +1,48 @@
+use crate::coinbase::format::{BookSnapshot, BookUpdate, MatchData};
+use serde::Deserialize;
+
+/// Messages of the feed, told apart by `type`
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FeedMessage {
+    /// First message of `level2_batch`, the whole book
+    Snapshot(BookSnapshot),
+
+    /// Changed levels of `level2_batch`, batched every 50ms
+    L2update(BookUpdate),
+
+    Match(MatchData),
+
+    /// Latest trade, sent once after subscribing `matches`
+    LastMatch(MatchData),
+
+    Error {
+        message: String,
+        #[serde(default)]
+        reason: String,
+    },
+
+    /// `subscriptions` respond, `heartbeat` ...
+    #[serde(other)]
+    Other,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::coinbase::format::FeedMessage;
+
+    #[test]
+    fn feed_messages() {
+        let subscriptions = r#"{"type":"subscriptions","channels":[{"name":"level2_batch","product_ids":["BTC-USD"]}]}"#;
+        assert!(matches!(
+            serde_json::from_str(subscriptions).unwrap(),
+            FeedMessage::Other
+        ));
+
+        let error = r#"{"type":"error","message":"Failed to subscribe","reason":"BTC-XYZ is not a valid product"}"#;
+        assert!(matches!(
+            serde_json::from_str(error).unwrap(),
+            FeedMessage::Error { .. }
+        ));
+    }
+}
@@ -0,0 +1,69 @@
+use crate::api::parse_time;
+use crate::{OrderDirection, Ticker};
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[allow(dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct MatchData {
+    pub trade_id: u64,
+
+    pub sequence: i64,
+
+    /// Something like "BTC-USD"
+    pub product_id: String,
+
+    pub price: String,
+
+    pub size: String,
+
+    /// "buy" / "sell", direction of the maker
+    pub side: String,
+
+    pub time: String,
+}
+
+impl MatchData {
+    pub fn tick(&self) -> Result<Ticker> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        // The taker trades against the maker
+        let direction = match self.side.as_str() {
+            "buy" => OrderDirection::Sell,
+            "sell" => OrderDirection::Buy,
+            other => return Err(anyhow!("Unknown side {}", other)),
+        };
+
+        Ok(Ticker {
+            lts: now.as_millis() as i64,
+            ts: parse_time(&self.time)?,
+            price: self.price.parse()?,
+            amount: self.size.parse()?,
+            direction,
+            id: self.trade_id,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::coinbase::format::FeedMessage;
+    use crate::OrderDirection;
+
+    const MATCH: &str = r#"{"type":"match","trade_id":10,"sequence":50,"maker_order_id":"ac928c66-ca53-498f-9c13-a110027a60e8","taker_order_id":"132fb6ae-456b-4654-b4e0-d681ac05cea1","time":"2014-11-07T08:19:27.028459Z","product_id":"BTC-USD","size":"5.23512","price":"400.23","side":"sell"}"#;
+
+    #[test]
+    fn match_to_ticker() {
+        let data = match serde_json::from_str(MATCH).unwrap() {
+            FeedMessage::Match(data) => data,
+            other => panic!("Unexpected {:?}", other),
+        };
+
+        let tick = data.tick().unwrap();
+        assert_eq!(tick.id, 10);
+        assert_eq!(tick.ts, 1415348367028);
+        assert_eq!(tick.price, 400.23);
+        assert_eq!(tick.amount, 5.23512);
+        assert!(matches!(tick.direction, OrderDirection::Buy));
+    }
+}
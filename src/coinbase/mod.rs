@@ -0,0 +1,7 @@
+mod adapter;
+pub mod connection;
+pub mod format;
+
+pub use adapter::CoinbaseAdapter;
+pub use connection::CoinbaseDepth;
+pub use connection::CoinbaseTicker;
@@ -1,4 +1,5 @@
-use crate::config::{Method, SymbolType};
+use crate::config::instrument::{split_pair, valid_expiry};
+use crate::config::{Instrument, InstrumentType, Margin, Method, SymbolType};
 use anyhow::{anyhow, Result};
 
 #[allow(unused_assignments)]
@@ -76,46 +77,120 @@ pub fn set_addr_for_binance(
                 "wss://dstream.binance.com/stream?streams={}@trade",
                 inner
             )),
+            (SymbolType::Option(_), _) => panic!("Unsupported {:?} for binance", symbol_type),
         };
     }
     (rest_address, depth_address, level_depth_address)
 }
 
-/// Inputs are BTC_USDT / BTC_USDT_SWAP / BTC_USDT_221230_SWAP,
-/// Binance output: btcusd_221230/ btcusdt/ bnbbtc (lower cases)
+/// Inputs are BTC_USDT / BTC_USDT_SWAP / BTC_USD_SWAP / BTC_USD_221230_SWAP,
+/// Binance output: bnbbtc / btcusdt / btcusd_perp / btcusd_221230 (lower cases)
 pub fn validate_symbol_binance(symbol: &str) -> Result<SymbolType> {
-    let splits = symbol.split("_").collect::<Vec<_>>();
-    if splits.len() > 4 || splits.len() < 2 {
-        return Err(anyhow!("Unsupported Symbol {} for binance", symbol));
-    }
+    let instrument = symbol
+        .parse::<Instrument>()
+        .map_err(|_| anyhow!("Unsupported Symbol {} for binance", symbol))?;
 
-    let is_contract = { symbol.ends_with("_SWAP") && splits.len() <= 4 };
-    let is_spot = !symbol.contains("SWAP");
-    let is_contract_coin = { splits.len() == 4 && is_contract };
+    symbol_type_binance(&instrument)
+}
 
-    let symbol_inner = {
-        let mut splits = splits;
+/// Spot on `api`, linear contracts on `fapi`, inverse contracts on `dapi`
+pub fn symbol_type_binance(instrument: &Instrument) -> Result<SymbolType> {
+    let pair = format!("{}{}", instrument.base, instrument.quote).to_lowercase();
 
-        if is_contract {
-            splits.pop();
+    let result = match (&instrument.kind, instrument.margin()) {
+        // e.g. "bnbbtc"
+        (InstrumentType::Spot, _) => SymbolType::Spot(pair),
+        // e.g. "btcusdt"
+        (InstrumentType::Perpetual { .. }, Some(Margin::Linear)) => SymbolType::ContractUSDT(pair),
+        // e.g. "btcusd_perp"
+        (InstrumentType::Perpetual { .. }, _) => SymbolType::ContractCoin(format!("{}_perp", pair)),
+        // e.g. "btcusdt_230331"
+        (InstrumentType::Future { expiry }, Some(Margin::Linear)) => {
+            SymbolType::ContractUSDT(format!("{}_{}", pair, expiry))
         }
+        // e.g. "btcusd_221230"
+        (InstrumentType::Future { expiry }, _) => {
+            SymbolType::ContractCoin(format!("{}_{}", pair, expiry))
+        }
+        _ => return Err(anyhow!("Unsupported Symbol {} for binance", instrument)),
+    };
+
+    Ok(result)
+}
+
+/// Inverse of `symbol_type_binance`
+pub fn instrument_binance(symbol_type: &SymbolType) -> Result<Instrument> {
+    let unsupported = || anyhow!("Unsupported symbol {:?} for binance", symbol_type);
 
-        if splits.len() == 3 {
-            format!("{}{}_{}", splits[0], splits[1], splits[2]).to_lowercase()
-        } else {
-            format!("{}{}", splits[0], splits[1]).to_lowercase()
+    let (name, linear) = match symbol_type {
+        SymbolType::Spot(name) => {
+            let (base, quote) = split_pair(name).ok_or_else(unsupported)?;
+            return Ok(Instrument::spot(&base, &quote));
         }
+        SymbolType::ContractUSDT(name) => (name, true),
+        SymbolType::ContractCoin(name) => (name, false),
+        SymbolType::Option(_) => return Err(unsupported()),
     };
 
-    let result = match (is_contract, is_contract_coin, is_spot) {
-        // e.g. "btcusd_221230_swap"
-        (true, true, false) => SymbolType::ContractCoin(symbol_inner),
-        // e.g. "btcusdt_swap"
-        (true, false, false) => SymbolType::ContractUSDT(symbol_inner),
-        // e.g. "bnbbtc"
-        (false, false, true) => SymbolType::Spot(symbol_inner),
-        _ => return Err(anyhow!("Unsupported Symbol {} for binance", symbol)),
+    let (pair, expiry) = match name.split_once('_') {
+        Some((pair, expiry)) => (pair, Some(expiry)),
+        None => (name.as_str(), None),
     };
+    let (base, quote) = split_pair(pair).ok_or_else(unsupported)?;
 
-    Ok(result)
+    let instrument = match (expiry, linear) {
+        (None, true) => Instrument::perpetual(&base, &quote),
+        (Some(expiry), false) if expiry.eq_ignore_ascii_case("perp") => {
+            Instrument::perpetual(&base, &quote)
+        }
+        (Some(expiry), _) if valid_expiry(expiry) => Instrument::future(&base, &quote, expiry),
+        _ => return Err(unsupported()),
+    };
+
+    let settle = if linear { quote } else { base };
+    Ok(instrument.settled_in(&settle))
+}
+
+/// REST `exchangeInfo` of spot, USDT and coin margined contracts
+pub fn exchange_info_addresses_binance() -> Vec<String> {
+    vec![
+        String::from("https://api.binance.com/api/v3/exchangeInfo"),
+        String::from("https://fapi.binance.com/fapi/v1/exchangeInfo"),
+        String::from("https://dapi.binance.com/dapi/v1/exchangeInfo"),
+    ]
+}
+
+/// Kline stream of `symbol_type`, e.g.
+/// "wss://stream.binance.com:9443/ws/bnbbtc@kline_1m"
+pub fn set_kline_addr_for_binance(symbol_type: SymbolType, interval: &str) -> String {
+    match symbol_type {
+        SymbolType::Spot(inner) => format!(
+            "wss://stream.binance.com:9443/ws/{}@kline_{}",
+            inner, interval
+        ),
+        SymbolType::ContractUSDT(inner) => format!(
+            "wss://fstream.binance.com/stream?streams={}@kline_{}",
+            inner, interval
+        ),
+        SymbolType::ContractCoin(inner) => format!(
+            "wss://dstream.binance.com/stream?streams={}@kline_{}",
+            inner, interval
+        ),
+        SymbolType::Option(_) => panic!("Kline is unsupported for {:?}", symbol_type),
+    }
+}
+
+/// Inputs are 1m / 1h / 1d ...,
+/// Binance output: the same string if Binance supports it
+pub fn validate_interval_binance(interval: &str) -> Result<String> {
+    const INTERVALS: [&str; 16] = [
+        "1s", "1m", "3m", "5m", "15m", "30m", "1h", "2h", "4h", "6h", "8h", "12h", "1d", "3d",
+        "1w", "1M",
+    ];
+
+    if INTERVALS.contains(&interval) {
+        Ok(interval.to_string())
+    } else {
+        Err(anyhow!("Unsupported interval {} for binance", interval))
+    }
 }
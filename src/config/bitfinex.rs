@@ -0,0 +1,126 @@
+use crate::config::{Instrument, InstrumentType, SymbolType};
+use anyhow::{anyhow, Result};
+
+const WS_ADDRESS: &str = "wss://api-pub.bitfinex.com/ws/2";
+
+/// Book length in Level Mode
+const LEVEL_LENGTH: i32 = 25;
+
+/// Book lengths Bitfinex publishes
+const LENGTHS: [i32; 4] = [1, 25, 100, 250];
+
+/// Bitfinex names of some assets, e.g. "UST" for Tether
+const ASSET_ALIASES: [(&str, &str); 1] = [("USDT", "UST")];
+
+/// Both modes maintain a `book` subscription,
+/// Depth Mode also gets `rest_address` of the same book
+pub fn set_addr_for_bitfinex(
+    symbol: &str,
+    precision: &str,
+    limit: Option<i32>,
+) -> (Option<String>, Option<String>, Option<String>) {
+    match limit {
+        Some(limit) => {
+            let rest_address = format!(
+                "https://api-pub.bitfinex.com/v2/book/{}/{}?len={}",
+                symbol,
+                precision,
+                length_bitfinex(Some(limit))
+            );
+
+            (Some(rest_address), Some(WS_ADDRESS.to_string()), None)
+        }
+        None => (None, None, Some(WS_ADDRESS.to_string())),
+    }
+}
+
+/// `trades` share the endpoint with the books
+pub fn set_ticker_addr_for_bitfinex() -> String {
+    WS_ADDRESS.to_string()
+}
+
+/// `limit` rounded up to a book length Bitfinex publishes
+pub fn length_bitfinex(limit: Option<i32>) -> i32 {
+    let limit = limit.unwrap_or(LEVEL_LENGTH);
+
+    LENGTHS
+        .iter()
+        .copied()
+        .find(|length| *length >= limit)
+        .unwrap_or(LENGTHS[LENGTHS.len() - 1])
+}
+
+/// Inputs: BTC_USD / BTC_USDT / DOGE_USD / BTC_USDT_SWAP
+/// Bitfinex output: tBTCUSD / tBTCUST / tDOGE:USD (Spot) / tBTCF0:USTF0 (ContractUSDT)
+pub fn validate_symbol_bitfinex(symbol: &str) -> Result<SymbolType> {
+    let instrument = symbol
+        .parse::<Instrument>()
+        .map_err(|_| anyhow!("Unsupported Symbol {} for bitfinex", symbol))?;
+
+    symbol_type_bitfinex(&instrument)
+}
+
+/// Only USDT perpetuals are listed among the contracts
+pub fn symbol_type_bitfinex(instrument: &Instrument) -> Result<SymbolType> {
+    let (base, quote) = (
+        bitfinex_asset(&instrument.base),
+        bitfinex_asset(&instrument.quote),
+    );
+
+    match (&instrument.kind, instrument.settle.as_str()) {
+        (InstrumentType::Spot, _) => {
+            // A colon is needed once a name is longer than 3
+            if base.len() == 3 && quote.len() == 3 {
+                Ok(SymbolType::Spot(format!("t{}{}", base, quote)))
+            } else {
+                Ok(SymbolType::Spot(format!("t{}:{}", base, quote)))
+            }
+        }
+        (InstrumentType::Perpetual { .. }, "USDT") if instrument.quote == "USDT" => {
+            Ok(SymbolType::ContractUSDT(format!("t{}F0:USTF0", base)))
+        }
+        _ => Err(anyhow!("Unsupported Symbol {} for bitfinex", instrument)),
+    }
+}
+
+/// Inverse of `symbol_type_bitfinex`
+pub fn instrument_bitfinex(symbol_type: &SymbolType) -> Result<Instrument> {
+    let unsupported = || anyhow!("Unsupported symbol {:?} for bitfinex", symbol_type);
+
+    let (name, spot) = match symbol_type {
+        SymbolType::Spot(name) => (name, true),
+        SymbolType::ContractUSDT(name) => (name, false),
+        _ => return Err(unsupported()),
+    };
+
+    let pair = name.strip_prefix('t').ok_or_else(unsupported)?;
+    let (base, quote) = match pair.split_once(':') {
+        Some(split) => split,
+        None if pair.len() == 6 && pair.is_ascii() => pair.split_at(3),
+        None => return Err(unsupported()),
+    };
+
+    match (spot, base.strip_suffix("F0"), quote) {
+        (true, _, _) if !base.is_empty() && !quote.is_empty() => {
+            Ok(Instrument::spot(common_asset(base), common_asset(quote)))
+        }
+        (false, Some(base), "USTF0") if !base.is_empty() => {
+            Ok(Instrument::perpetual(common_asset(base), "USDT"))
+        }
+        _ => Err(unsupported()),
+    }
+}
+
+fn bitfinex_asset(asset: &str) -> &str {
+    ASSET_ALIASES
+        .iter()
+        .find(|(common, _)| *common == asset)
+        .map_or(asset, |(_, bitfinex)| bitfinex)
+}
+
+fn common_asset(asset: &str) -> &str {
+    ASSET_ALIASES
+        .iter()
+        .find(|(_, bitfinex)| *bitfinex == asset)
+        .map_or(asset, |(common, _)| common)
+}
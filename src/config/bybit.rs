@@ -0,0 +1,173 @@
+use crate::config::instrument::{split_pair, valid_expiry};
+use crate::config::{Instrument, InstrumentType, Margin, SymbolType};
+use anyhow::{anyhow, Result};
+
+/// Topic depth in Level Mode
+const LEVEL_DEPTH: i32 = 50;
+
+/// Futures month codes, "F" for January
+const MONTH_CODES: &str = "FGHJKMNQUVXZ";
+
+/// "spot" / "linear" / "inverse" / "option"
+pub fn category_bybit(symbol_type: &SymbolType) -> &'static str {
+    match symbol_type {
+        SymbolType::Spot(_) => "spot",
+        SymbolType::ContractUSDT(_) => "linear",
+        SymbolType::ContractCoin(_) => "inverse",
+        SymbolType::Option(_) => "option",
+    }
+}
+
+/// Level Mode (limit is none): `orderbook.50` on the public stream,
+/// only need `level_depth_address`
+///
+/// Depth Mode (limit is some): `orderbook.{limit}`,
+/// need `rest_address` and `depth_address`
+pub fn set_addr_for_bybit(
+    symbol_type: &SymbolType,
+    limit: Option<i32>,
+) -> (Option<String>, Option<String>, Option<String>) {
+    let category = category_bybit(symbol_type);
+    let address = format!("wss://stream.bybit.com/v5/public/{}", category);
+
+    match limit {
+        Some(limit) => {
+            let rest_address = format!(
+                "https://api.bybit.com/v5/market/orderbook?category={}&symbol={}&limit={}",
+                category,
+                symbol_bybit(symbol_type),
+                limit
+            );
+
+            (Some(rest_address), Some(address), None)
+        }
+        None => (None, None, Some(address)),
+    }
+}
+
+/// e.g. "orderbook.50.BTCUSDT",
+/// `limit` is rounded up to a depth Bybit publishes
+pub fn depth_topic_bybit(symbol_type: &SymbolType, limit: Option<i32>) -> String {
+    let depths: &[i32] = match symbol_type {
+        SymbolType::Spot(_) => &[1, 50, 200],
+        _ => &[1, 50, 200, 500],
+    };
+
+    let limit = limit.unwrap_or(LEVEL_DEPTH);
+    let depth = depths
+        .iter()
+        .copied()
+        .find(|depth| *depth >= limit)
+        .unwrap_or(depths[depths.len() - 1]);
+
+    format!("orderbook.{}.{}", depth, symbol_bybit(symbol_type))
+}
+
+/// e.g. "publicTrade.BTCUSDT"
+pub fn trade_topic_bybit(symbol_type: &SymbolType) -> String {
+    format!("publicTrade.{}", symbol_bybit(symbol_type))
+}
+
+fn symbol_bybit(symbol_type: &SymbolType) -> &str {
+    match symbol_type {
+        SymbolType::Spot(s) | SymbolType::ContractUSDT(s) | SymbolType::ContractCoin(s) => s,
+        SymbolType::Option(option) => &option.name,
+    }
+}
+
+/// Inputs: BTC_USDT / BTC_USDT_SWAP / BTC_USD_SWAP / BTC_USD_221230_SWAP
+/// Bybit output: BTCUSDT (spot) / BTCUSDT (linear) / BTCUSD (inverse) / BTCUSDZ22 (inverse)
+pub fn validate_symbol_bybit(symbol: &str) -> Result<SymbolType> {
+    let instrument = symbol
+        .parse::<Instrument>()
+        .map_err(|_| anyhow!("Unsupported Symbol {} for bybit", symbol))?;
+
+    symbol_type_bybit(&instrument)
+}
+
+/// Inverse futures are named by month, so only the last Friday of a month is an expiry
+pub fn symbol_type_bybit(instrument: &Instrument) -> Result<SymbolType> {
+    let unsupported = || anyhow!("Unsupported Symbol {} for bybit", instrument);
+    let pair = format!("{}{}", instrument.base, instrument.quote);
+
+    let result = match (&instrument.kind, instrument.margin()) {
+        (InstrumentType::Spot, _) => SymbolType::Spot(pair),
+        (InstrumentType::Perpetual { .. }, Some(Margin::Linear)) => SymbolType::ContractUSDT(pair),
+        // Inverse perpetual, margined in the base coin
+        (InstrumentType::Perpetual { .. }, _) => SymbolType::ContractCoin(pair),
+        // Inverse futures, "221230" => "Z22"
+        (InstrumentType::Future { expiry }, Some(Margin::Inverse)) if valid_expiry(expiry) => {
+            let year = expiry[..2].parse::<i32>().map_err(|_| unsupported())?;
+            let month = expiry[2..4].parse::<usize>().map_err(|_| unsupported())?;
+            let day = expiry[4..].parse::<u32>().map_err(|_| unsupported())?;
+            if day != last_friday(2000 + year, month as u32) {
+                return Err(unsupported());
+            }
+
+            let code = MONTH_CODES.as_bytes()[month - 1] as char;
+            SymbolType::ContractCoin(format!("{}{}{}", pair, code, &expiry[..2]))
+        }
+        _ => return Err(unsupported()),
+    };
+
+    Ok(result)
+}
+
+/// Inverse of `symbol_type_bybit`
+pub fn instrument_bybit(symbol_type: &SymbolType) -> Result<Instrument> {
+    let unsupported = || anyhow!("Unsupported symbol {:?} for bybit", symbol_type);
+
+    match symbol_type {
+        SymbolType::Spot(name) => {
+            let (base, quote) = split_pair(name).ok_or_else(unsupported)?;
+            Ok(Instrument::spot(&base, &quote))
+        }
+        SymbolType::ContractUSDT(name) => {
+            let (base, quote) = split_pair(name).ok_or_else(unsupported)?;
+            Ok(Instrument::perpetual(&base, &quote).settled_in(&quote))
+        }
+        SymbolType::ContractCoin(name) => {
+            let (base, quote) = match split_pair(name) {
+                Some(pair) => pair,
+                None => {
+                    // e.g. "BTCUSDZ22"
+                    let (pair, code) = name.split_at(name.len().saturating_sub(3));
+                    let (base, quote) = split_pair(pair).ok_or_else(unsupported)?;
+                    let month = code
+                        .get(..1)
+                        .and_then(|letter| MONTH_CODES.find(letter))
+                        .ok_or_else(unsupported)? as u32
+                        + 1;
+                    let year = code[1..].parse::<i32>().map_err(|_| unsupported())?;
+
+                    let expiry = format!(
+                        "{:02}{:02}{:02}",
+                        year,
+                        month,
+                        last_friday(2000 + year, month)
+                    );
+                    return Ok(Instrument::future(&base, &quote, &expiry).settled_in(&base));
+                }
+            };
+
+            Ok(Instrument::perpetual(&base, &quote).settled_in(&base))
+        }
+        SymbolType::Option(_) => Err(unsupported()),
+    }
+}
+
+/// Day of the last Friday of `month`, when quarterly futures expire
+fn last_friday(year: i32, month: u32) -> u32 {
+    const DAYS: [u32; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+    const OFFSETS: [i32; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let last = DAYS[month as usize - 1] + u32::from(leap && month == 2);
+
+    // Sakamoto's method, 0 is Sunday
+    let y = if month < 3 { year - 1 } else { year };
+    let weekday =
+        (y + y / 4 - y / 100 + y / 400 + OFFSETS[month as usize - 1] + last as i32).rem_euclid(7);
+
+    last - ((weekday - 5).rem_euclid(7) as u32)
+}
@@ -0,0 +1,62 @@
+use crate::config::{Instrument, SymbolType};
+use anyhow::{anyhow, Result};
+
+const FEED_ADDRESS: &str = "wss://ws-feed.exchange.coinbase.com";
+
+/// Both modes maintain the `level2_batch` book,
+/// Depth Mode also gets `rest_address` of the same book
+pub fn set_addr_for_coinbase(
+    product_id: &str,
+    limit: Option<i32>,
+) -> (Option<String>, Option<String>, Option<String>) {
+    match limit {
+        Some(_) => {
+            let rest_address = format!(
+                "https://api.exchange.coinbase.com/products/{}/book?level=2",
+                product_id
+            );
+
+            (Some(rest_address), Some(FEED_ADDRESS.to_string()), None)
+        }
+        None => (None, None, Some(FEED_ADDRESS.to_string())),
+    }
+}
+
+/// `matches` share the feed with the books
+pub fn set_ticker_addr_for_coinbase() -> String {
+    FEED_ADDRESS.to_string()
+}
+
+/// Inputs: BTC_USD / BTC_USDT
+/// Coinbase output: BTC-USD / BTC-USDT, there are no contracts
+pub fn validate_symbol_coinbase(symbol: &str) -> Result<SymbolType> {
+    let instrument = symbol
+        .parse::<Instrument>()
+        .map_err(|_| anyhow!("Unsupported Symbol {} for coinbase", symbol))?;
+
+    symbol_type_coinbase(&instrument)
+}
+
+pub fn symbol_type_coinbase(instrument: &Instrument) -> Result<SymbolType> {
+    if !instrument.is_spot() {
+        return Err(anyhow!("Unsupported Symbol {} for coinbase", instrument));
+    }
+
+    Ok(SymbolType::Spot(format!(
+        "{}-{}",
+        instrument.base, instrument.quote
+    )))
+}
+
+/// Inverse of `symbol_type_coinbase`
+pub fn instrument_coinbase(symbol_type: &SymbolType) -> Result<Instrument> {
+    match symbol_type {
+        SymbolType::Spot(name) => match name.split_once('-') {
+            Some((base, quote)) if !base.is_empty() && !quote.is_empty() => {
+                Ok(Instrument::spot(base, quote))
+            }
+            _ => Err(anyhow!("Unsupported symbol {:?} for coinbase", symbol_type)),
+        },
+        _ => Err(anyhow!("Unsupported symbol {:?} for coinbase", symbol_type)),
+    }
+}
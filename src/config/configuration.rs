@@ -1,11 +1,21 @@
-use crate::ExchangeType;
+use crate::{ChecksumVerifier, ExchangeType, ReconnectPolicy};
 use anyhow::{anyhow, Result};
+use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Clone, Debug)]
 pub struct DepthConfig {
     pub depth_url: DepthType,
     pub symbol_type: SymbolType,
     pub exchange_type: ExchangeType,
+    /// Book size asked for, `None` in Level Mode
+    pub limit: Option<i32>,
+    /// Verify exchange checksums after every update
+    pub checksum: Option<Arc<dyn ChecksumVerifier>>,
+    /// Compare the maintained book with a REST snapshot this often
+    pub audit_interval: Option<Duration>,
+    /// Backoff and retries of the connection
+    pub reconnect: ReconnectPolicy,
 }
 
 #[derive(Clone, Debug)]
@@ -42,15 +52,50 @@ pub enum SymbolType {
     Spot(String),
     ContractUSDT(String),
     ContractCoin(String),
+    Option(OptionContract),
+}
+
+/// Right of an option
+#[derive(Clone, Copy, Debug, PartialOrd, PartialEq, Eq)]
+pub enum OptionKind {
+    Call,
+    Put,
+}
+
+/// Option in exchange notation with the terms it is named by
+#[derive(Clone, Debug, PartialOrd, PartialEq)]
+pub struct OptionContract {
+    /// Instrument name of the exchange, e.g. "BTC-30DEC22-20000-C"
+    pub name: String,
+    /// e.g. "BTC"
+    pub underlying: String,
+    /// "yymmdd", e.g. "221230"
+    pub expiry: String,
+    pub strike: f64,
+    pub kind: OptionKind,
 }
 
 impl DepthConfig {
-    /// Binance Spot ContractUSDT ContractCoin, Crypto Spot ContractUSDT
+    /// Binance Spot ContractUSDT ContractCoin, Crypto Spot ContractUSDT,
+    /// Okx and Bybit Spot ContractUSDT ContractCoin, Coinbase and Kraken Spot,
+    /// Deribit ContractCoin Option, Bitfinex Spot ContractUSDT, KuCoin and Gate.io Spot
     pub fn is_correct(&self) -> bool {
         match (&self.symbol_type, &self.exchange_type) {
             (_, ExchangeType::Binance) => true,
             (SymbolType::Spot(_), ExchangeType::Crypto) => true,
             (SymbolType::ContractUSDT(_), ExchangeType::Crypto) => true,
+            (_, ExchangeType::Okx) => true,
+            (_, ExchangeType::Bybit) => true,
+            (SymbolType::Spot(_), ExchangeType::Coinbase) => true,
+            (SymbolType::Spot(_), ExchangeType::Kraken) => true,
+            (SymbolType::ContractCoin(_), ExchangeType::Deribit) => true,
+            (SymbolType::Option(_), ExchangeType::Deribit) => true,
+            (SymbolType::Spot(_), ExchangeType::Bitfinex) => true,
+            (SymbolType::ContractUSDT(_), ExchangeType::Bitfinex) => true,
+            (SymbolType::Spot(_), ExchangeType::Kucoin) => true,
+            (SymbolType::Spot(_), ExchangeType::Gateio) => true,
+            // Checked by its adapter
+            (_, ExchangeType::Other(_)) => true,
             _ => false,
         }
     }
@@ -125,6 +170,7 @@ impl DepthConfig {
             SymbolType::Spot(symbol) => symbol.clone(),
             SymbolType::ContractCoin(symbol) => symbol.clone(),
             SymbolType::ContractUSDT(symbol) => symbol.clone(),
+            SymbolType::Option(option) => option.name.clone(),
         }
     }
 
@@ -154,15 +200,31 @@ pub struct TickerConfig {
     pub ticker_url: String,
     pub symbol_type: SymbolType,
     pub exchange_type: ExchangeType,
+    /// Backoff and retries of the connection
+    pub reconnect: ReconnectPolicy,
 }
 
 impl TickerConfig {
-    /// Binance Spot, Crypto Spot ContractUSDT
+    /// Binance Spot, Crypto Spot ContractUSDT,
+    /// Okx and Bybit Spot ContractUSDT ContractCoin, Coinbase and Kraken Spot,
+    /// Deribit ContractCoin Option, Bitfinex Spot ContractUSDT, KuCoin and Gate.io Spot
     pub fn is_correct(&self) -> bool {
         match (&self.symbol_type, &self.exchange_type) {
             (SymbolType::Spot(_), ExchangeType::Binance) => true,
             (SymbolType::Spot(_), ExchangeType::Crypto) => true,
             (SymbolType::ContractUSDT(_), ExchangeType::Crypto) => true,
+            (_, ExchangeType::Okx) => true,
+            (_, ExchangeType::Bybit) => true,
+            (SymbolType::Spot(_), ExchangeType::Coinbase) => true,
+            (SymbolType::Spot(_), ExchangeType::Kraken) => true,
+            (SymbolType::ContractCoin(_), ExchangeType::Deribit) => true,
+            (SymbolType::Option(_), ExchangeType::Deribit) => true,
+            (SymbolType::Spot(_), ExchangeType::Bitfinex) => true,
+            (SymbolType::ContractUSDT(_), ExchangeType::Bitfinex) => true,
+            (SymbolType::Spot(_), ExchangeType::Kucoin) => true,
+            (SymbolType::Spot(_), ExchangeType::Gateio) => true,
+            // Checked by its adapter
+            (_, ExchangeType::Other(_)) => true,
             _ => false,
         }
     }
@@ -207,6 +269,7 @@ impl TickerConfig {
             SymbolType::Spot(symbol) => symbol.clone(),
             SymbolType::ContractCoin(symbol) => symbol.clone(),
             SymbolType::ContractUSDT(symbol) => symbol.clone(),
+            SymbolType::Option(option) => option.name.clone(),
         }
     }
 
@@ -230,3 +293,65 @@ impl TickerConfig {
         }
     }
 }
+
+#[derive(Clone, Debug)]
+pub struct KlineConfig {
+    pub kline_url: String,
+    /// Interval in exchange notation, e.g. "1m" / "1D"
+    pub interval: String,
+    /// Interval length in milliseconds
+    pub interval_ms: i64,
+    pub symbol_type: SymbolType,
+    pub exchange_type: ExchangeType,
+    /// Backoff and retries of the connection
+    pub reconnect: ReconnectPolicy,
+}
+
+impl KlineConfig {
+    /// Binance Spot ContractUSDT ContractCoin, Crypto Spot ContractUSDT
+    pub fn is_correct(&self) -> bool {
+        matches!(
+            (&self.symbol_type, &self.exchange_type),
+            (_, ExchangeType::Binance)
+                | (SymbolType::Spot(_), ExchangeType::Crypto)
+                | (SymbolType::ContractUSDT(_), ExchangeType::Crypto)
+        )
+    }
+
+    pub fn is_binance(&self) -> bool {
+        matches!(self.exchange_type, ExchangeType::Binance)
+    }
+
+    pub fn is_crypto(&self) -> bool {
+        matches!(self.exchange_type, ExchangeType::Crypto)
+    }
+
+    pub fn get_symbol(&self) -> String {
+        match &self.symbol_type {
+            SymbolType::Spot(symbol) => symbol.clone(),
+            SymbolType::ContractCoin(symbol) => symbol.clone(),
+            SymbolType::ContractUSDT(symbol) => symbol.clone(),
+            SymbolType::Option(option) => option.name.clone(),
+        }
+    }
+
+    /// Specialized for crypto exchange, e.g. "candlestick.1m.BTC_USDT"
+    pub fn get_channel(&self) -> Result<String> {
+        match self.exchange_type {
+            ExchangeType::Crypto => {
+                if matches!(self.symbol_type, SymbolType::ContractCoin(_)) {
+                    Err(anyhow!(
+                        "Crypto Channel is unsupported for {:?}",
+                        self.symbol_type
+                    ))
+                } else {
+                    Ok(format!("candlestick.{}.{}", self.interval, self.get_symbol()))
+                }
+            }
+            _ => Err(anyhow!(
+                "Channel is unsupported for {:?}",
+                self.exchange_type
+            )),
+        }
+    }
+}
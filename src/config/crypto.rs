@@ -1,60 +1,116 @@
-use crate::config::SymbolType;
+use crate::config::instrument::split_pair;
+use crate::config::{Instrument, InstrumentType, Margin, SymbolType};
 use anyhow::{anyhow, Result};
 
-#[allow(unused_assignments)]
+/// Level Mode (limit is none): full book snapshots every message,
+/// only need `level_depth_address`
+///
+/// Depth Mode (limit is some): one snapshot followed by `book.update` deltas,
+/// need `rest_address` and `depth_address`
 pub fn set_addr_for_crypto(
-    _instrument: &str,
-    _limit: Option<i32>,
+    instrument: &str,
+    limit: Option<i32>,
 ) -> (Option<String>, Option<String>, Option<String>) {
-    let level_depth_address: Option<String> = Some(format!("wss://stream.crypto.com/v2/market",));
+    match limit {
+        Some(limit) => {
+            let rest_address = format!(
+                "https://api.crypto.com/exchange/v1/public/get-book?instrument_name={}&depth={}",
+                instrument, limit
+            );
+            let depth_address = String::from("wss://stream.crypto.com/exchange/v1/market");
 
-    (None, None, level_depth_address)
-}
+            (Some(rest_address), Some(depth_address), None)
+        }
+        None => {
+            let level_depth_address = String::from("wss://stream.crypto.com/v2/market");
 
-/// Inputs: BTC_USDT / BTC_USDT_SWAP / BTC_USDT_221230_SWAP
-/// Crypto output: BTC_USDT / BTCUSD-PERP / (Unsupported)
-pub fn validate_symbol_crypto(symbol: &str, limit: Option<i32>) -> Result<SymbolType> {
-    let splits = symbol.split("_").collect::<Vec<_>>();
-    if splits.len() > 4 || splits.len() < 2 {
-        return Err(anyhow!("Unsupported Symbol {} for crypto", symbol));
+            (None, None, Some(level_depth_address))
+        }
     }
+}
 
-    let is_contract = { symbol.ends_with("_SWAP") && splits.len() <= 4 };
-    let is_spot = !symbol.contains("SWAP");
-    let is_contract_coin = { splits.len() == 4 && is_contract };
+/// REST list of every instrument
+pub fn instruments_address_crypto() -> String {
+    String::from("https://api.crypto.com/exchange/v1/public/get-instruments")
+}
+
+/// Inputs: BTC_USDT / BTC_USDT_SWAP / BTC_USD_SWAP:USD / BTC_USDT_221230_SWAP
+/// Crypto output: BTC_USDT / BTCUSD-PERP / BTCUSD-PERP / (Unsupported)
+pub fn validate_symbol_crypto(symbol: &str) -> Result<SymbolType> {
+    let instrument = symbol
+        .parse::<Instrument>()
+        .map_err(|_| anyhow!("Unsupported Symbol {} for crypto", symbol))?;
+
+    symbol_type_crypto(&instrument)
+}
 
-    fn usdt_usd(usdt: &str) -> &str {
-        if usdt == "USDT" {
-            &usdt[..3]
-        } else {
-            usdt
+/// Perpetuals are margined and settled in USD,
+/// so USDT perpetuals are taken as their USD ones
+pub fn symbol_type_crypto(instrument: &Instrument) -> Result<SymbolType> {
+    match (&instrument.kind, instrument.margin()) {
+        // e.g. "BTC_USDT"
+        (InstrumentType::Spot, _) => Ok(SymbolType::Spot(format!(
+            "{}_{}",
+            instrument.base, instrument.quote
+        ))),
+        // e.g. "BTCUSD-PERP"
+        (InstrumentType::Perpetual { .. }, Some(Margin::Linear)) => {
+            let quote = match instrument.quote.as_str() {
+                "USDT" => "USD",
+                quote => quote,
+            };
+            Ok(SymbolType::ContractUSDT(format!(
+                "{}{}-PERP",
+                instrument.base, quote
+            )))
         }
+        _ => Err(anyhow!("Unsupported Symbol {} for crypto", instrument)),
     }
+}
+
+/// Inverse of `symbol_type_crypto`, perpetuals come back in USD
+pub fn instrument_crypto(symbol_type: &SymbolType) -> Result<Instrument> {
+    let unsupported = || anyhow!("Unsupported symbol {:?} for crypto", symbol_type);
 
-    let symbol_in = {
-        let mut splits = splits;
+    match symbol_type {
+        SymbolType::Spot(name) => match name.split_once('_') {
+            Some((base, quote)) if !base.is_empty() && !quote.is_empty() => {
+                Ok(Instrument::spot(base, quote))
+            }
+            _ => Err(unsupported()),
+        },
+        SymbolType::ContractUSDT(name) => {
+            let pair = name.strip_suffix("-PERP").ok_or_else(unsupported)?;
+            let (base, quote) = split_pair(pair).ok_or_else(unsupported)?;
 
-        if is_contract {
-            splits.pop();
-            format!("{}{}-PERP", splits[0], usdt_usd(splits[1]))
-        } else {
-            format!("{}_{}", splits[0], splits[1])
+            Ok(Instrument::perpetual(&base, &quote).settled_in(&quote))
         }
-    };
+        _ => Err(unsupported()),
+    }
+}
 
-    let symbol_inner = if limit.is_some() {
-        format!("{}.{}", symbol_in, limit.unwrap())
-    } else {
-        format!("{}", symbol_in)
+/// Book channel of `instrument`, e.g. "book.BTC_USDT.50",
+/// crypto.com publishes books of 10 and 50 levels
+pub fn depth_channel_crypto(instrument: &str, limit: Option<i32>) -> String {
+    let depth = match limit {
+        Some(limit) if limit <= 10 => 10,
+        _ => 50,
     };
 
-    let result = match (is_contract, is_contract_coin, is_spot) {
-        // e.g. "BTCUSD-PERP.50"
-        (true, false, false) => SymbolType::ContractUSDT(symbol_inner.to_string()),
-        // e.g. "BTC_USDT.50"
-        (false, false, true) => SymbolType::Spot(symbol_inner.to_string()),
-        _ => return Err(anyhow!("Unsupported Symbol {} for crypto", symbol)),
+    format!("book.{}.{}", instrument, depth)
+}
+
+/// Inputs are 1m / 1h / 1d ...,
+/// Crypto output: 1m / 1h / 1D ...
+pub fn validate_interval_crypto(interval: &str) -> Result<String> {
+    let result = match interval {
+        "1m" | "5m" | "15m" | "30m" | "1h" | "4h" | "6h" | "12h" => interval,
+        "1d" => "1D",
+        "1w" => "7D",
+        "2w" => "14D",
+        "1M" => "1M",
+        _ => return Err(anyhow!("Unsupported interval {} for crypto", interval)),
     };
 
-    Ok(result)
+    Ok(result.to_string())
 }
@@ -0,0 +1,168 @@
+use crate::config::instrument::valid_expiry;
+use crate::config::{Instrument, InstrumentType, Margin, OptionContract, OptionKind, SymbolType};
+use anyhow::{anyhow, Result};
+
+const WS_ADDRESS: &str = "wss://www.deribit.com/ws/api/v2";
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+/// Both modes maintain `book.{instrument}.100ms` over JSON-RPC,
+/// Depth Mode also gets `rest_address` of the same book
+pub fn set_addr_for_deribit(
+    instrument: &str,
+    limit: Option<i32>,
+) -> (Option<String>, Option<String>, Option<String>) {
+    match limit {
+        Some(limit) => {
+            let rest_address = format!(
+                "https://www.deribit.com/api/v2/public/get_order_book?instrument_name={}&depth={}",
+                instrument, limit
+            );
+
+            (Some(rest_address), Some(WS_ADDRESS.to_string()), None)
+        }
+        None => (None, None, Some(WS_ADDRESS.to_string())),
+    }
+}
+
+/// `trades` share the JSON-RPC endpoint with the books
+pub fn set_ticker_addr_for_deribit() -> String {
+    WS_ADDRESS.to_string()
+}
+
+/// e.g. "book.BTC-PERPETUAL.100ms"
+pub fn depth_channel_deribit(instrument: &str) -> String {
+    format!("book.{}.100ms", instrument)
+}
+
+/// e.g. "trades.BTC-PERPETUAL.100ms"
+pub fn trade_channel_deribit(instrument: &str) -> String {
+    format!("trades.{}.100ms", instrument)
+}
+
+/// Inputs: BTC_USD_SWAP / BTC_USD_221230_SWAP / BTC_USD_221230_20000_C
+/// Deribit output: BTC-PERPETUAL / BTC-30DEC22 (ContractCoin) / BTC-30DEC22-20000-C (Option),
+/// only the coin-margined instruments are supported
+pub fn validate_symbol_deribit(symbol: &str) -> Result<SymbolType> {
+    let instrument = symbol
+        .parse::<Instrument>()
+        .map_err(|_| anyhow!("Unsupported Symbol {} for deribit", symbol))?;
+
+    symbol_type_deribit(&instrument)
+}
+
+pub fn symbol_type_deribit(instrument: &Instrument) -> Result<SymbolType> {
+    let unsupported = || anyhow!("Unsupported Symbol {} for deribit", instrument);
+
+    let base = &instrument.base;
+    if instrument.quote != "USD" || instrument.margin() != Some(Margin::Inverse) {
+        return Err(unsupported());
+    }
+
+    match &instrument.kind {
+        InstrumentType::Perpetual { .. } => {
+            Ok(SymbolType::ContractCoin(format!("{}-PERPETUAL", base)))
+        }
+        InstrumentType::Future { expiry } => Ok(SymbolType::ContractCoin(format!(
+            "{}-{}",
+            base,
+            expiry_deribit(expiry).ok_or_else(unsupported)?
+        ))),
+        InstrumentType::Option {
+            expiry,
+            strike,
+            kind,
+        } => Ok(SymbolType::Option(OptionContract {
+            name: format!(
+                "{}-{}-{}-{}",
+                base,
+                expiry_deribit(expiry).ok_or_else(unsupported)?,
+                strike,
+                if *kind == OptionKind::Call { "C" } else { "P" }
+            ),
+            underlying: base.to_string(),
+            expiry: expiry.to_string(),
+            strike: *strike,
+            kind: *kind,
+        })),
+        InstrumentType::Spot => Err(unsupported()),
+    }
+}
+
+/// Inverse of `symbol_type_deribit`
+pub fn instrument_deribit(symbol_type: &SymbolType) -> Result<Instrument> {
+    let unsupported = || anyhow!("Unsupported symbol {:?} for deribit", symbol_type);
+
+    let name = match symbol_type {
+        SymbolType::ContractCoin(name) => name,
+        SymbolType::Option(option) => &option.name,
+        _ => return Err(unsupported()),
+    };
+
+    let splits = name.split('-').collect::<Vec<_>>();
+    match splits[..] {
+        [base, "PERPETUAL"] if !base.is_empty() => Ok(Instrument::perpetual(base, "USD")),
+        [base, expiry] if !base.is_empty() => Ok(Instrument::future(
+            base,
+            "USD",
+            &expiry_common(expiry).ok_or_else(unsupported)?,
+        )),
+        [base, expiry, strike, kind] if !base.is_empty() => {
+            let kind = match kind {
+                "C" => OptionKind::Call,
+                "P" => OptionKind::Put,
+                _ => return Err(unsupported()),
+            };
+            let strike = strike.parse::<f64>().map_err(|_| unsupported())?;
+
+            Ok(Instrument::option(
+                base,
+                "USD",
+                &expiry_common(expiry).ok_or_else(unsupported)?,
+                strike,
+                kind,
+            ))
+        }
+        _ => Err(unsupported()),
+    }
+}
+
+/// "221230" -> "30DEC22", "230106" -> "6JAN23"
+fn expiry_deribit(expiry: &str) -> Option<String> {
+    if expiry.len() != 6 || !expiry.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let year = &expiry[..2];
+    let month = expiry[2..4].parse::<usize>().ok()?;
+    let day = expiry[4..].parse::<u32>().ok()?;
+    if !(1..=31).contains(&day) {
+        return None;
+    }
+
+    Some(format!(
+        "{}{}{}",
+        day,
+        MONTHS.get(month.checked_sub(1)?)?,
+        year
+    ))
+}
+
+/// "30DEC22" -> "221230", "6JAN23" -> "230106"
+fn expiry_common(expiry: &str) -> Option<String> {
+    let digits = expiry.bytes().take_while(|b| b.is_ascii_digit()).count();
+    if !(1..=2).contains(&digits) || expiry.len() != digits + 5 {
+        return None;
+    }
+
+    let day = expiry[..digits].parse::<u32>().ok()?;
+    let month = MONTHS
+        .iter()
+        .position(|month| *month == &expiry[digits..digits + 3])?;
+    let year = &expiry[digits + 3..];
+
+    let common = format!("{}{:02}{:02}", year, month + 1, day);
+    valid_expiry(&common).then_some(common)
+}
@@ -0,0 +1,66 @@
+use crate::config::{Instrument, SymbolType};
+use anyhow::{anyhow, Result};
+
+const WS_ADDRESS: &str = "wss://api.gateio.ws/ws/v4/";
+
+/// Most levels of a REST snapshot
+const MAX_LIMIT: i32 = 100;
+
+/// Both modes maintain the `spot.order_book_update` book,
+/// Depth Mode also gets `rest_address` with the update id of the book
+pub fn set_addr_for_gateio(
+    currency_pair: &str,
+    limit: Option<i32>,
+) -> (Option<String>, Option<String>, Option<String>) {
+    match limit {
+        Some(limit) => {
+            let rest_address = format!(
+                "https://api.gateio.ws/api/v4/spot/order_book?currency_pair={}&limit={}&with_id=true",
+                currency_pair,
+                limit.clamp(1, MAX_LIMIT)
+            );
+
+            (Some(rest_address), Some(WS_ADDRESS.to_string()), None)
+        }
+        None => (None, None, Some(WS_ADDRESS.to_string())),
+    }
+}
+
+/// `spot.trades` share the endpoint with the books
+pub fn set_ticker_addr_for_gateio() -> String {
+    WS_ADDRESS.to_string()
+}
+
+/// Inputs: BTC_USDT
+/// Gate.io output: BTC_USDT, there are no contracts on the spot feed
+pub fn validate_symbol_gateio(symbol: &str) -> Result<SymbolType> {
+    let instrument = symbol
+        .parse::<Instrument>()
+        .map_err(|_| anyhow!("Unsupported Symbol {} for gateio", symbol))?;
+
+    symbol_type_gateio(&instrument)
+}
+
+pub fn symbol_type_gateio(instrument: &Instrument) -> Result<SymbolType> {
+    if !instrument.is_spot() {
+        return Err(anyhow!("Unsupported Symbol {} for gateio", instrument));
+    }
+
+    Ok(SymbolType::Spot(format!(
+        "{}_{}",
+        instrument.base, instrument.quote
+    )))
+}
+
+/// Inverse of `symbol_type_gateio`
+pub fn instrument_gateio(symbol_type: &SymbolType) -> Result<Instrument> {
+    match symbol_type {
+        SymbolType::Spot(name) => match name.split_once('_') {
+            Some((base, quote)) if !base.is_empty() && !quote.is_empty() => {
+                Ok(Instrument::spot(base, quote))
+            }
+            _ => Err(anyhow!("Unsupported symbol {:?} for gateio", symbol_type)),
+        },
+        _ => Err(anyhow!("Unsupported symbol {:?} for gateio", symbol_type)),
+    }
+}
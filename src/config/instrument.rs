@@ -0,0 +1,369 @@
+use crate::config::OptionKind;
+use crate::InstrumentKind;
+use anyhow::{anyhow, Error, Result};
+use std::fmt;
+use std::str::FromStr;
+
+/// Quote assets recognised in names without a separator, e.g. Binance "BNBBTC",
+/// longer names first so "BUSD" is not read as "USD"
+const QUOTES: [&str; 14] = [
+    "FDUSD", "USDT", "USDC", "BUSD", "TUSD", "USD", "DAI", "EUR", "GBP", "TRY", "BTC", "ETH",
+    "BNB", "JPY",
+];
+
+/// How a contract is margined and settled
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Margin {
+    /// In the quote asset (or another stable coin), e.g. USDT perpetuals
+    Linear,
+    /// In the base coin, e.g. coin margined futures quoted in USD
+    Inverse,
+}
+
+/// What an `Instrument` trades, with the terms of its contract as the
+/// canonical symbol gives them.
+///
+/// `InstrumentKind` is the same without the terms, as the exchange info
+/// endpoints and `InstrumentRegistry` list it
+#[derive(Clone, Debug, PartialEq)]
+pub enum InstrumentType {
+    Spot,
+    Perpetual {
+        margin: Margin,
+    },
+    Future {
+        /// "yymmdd", e.g. "221230"
+        expiry: String,
+    },
+    Option {
+        /// "yymmdd", e.g. "221230"
+        expiry: String,
+        strike: f64,
+        kind: OptionKind,
+    },
+}
+
+impl InstrumentType {
+    pub fn kind(&self) -> InstrumentKind {
+        match self {
+            InstrumentType::Spot => InstrumentKind::Spot,
+            InstrumentType::Perpetual { .. } => InstrumentKind::Perpetual,
+            InstrumentType::Future { .. } => InstrumentKind::Future,
+            InstrumentType::Option { .. } => InstrumentKind::Option,
+        }
+    }
+}
+
+/// A symbol in our notation, independent of any exchange.
+///
+/// The canonical string is
+/// - `BTC_USDT` for spot
+/// - `BTC_USDT_SWAP` for perpetuals
+/// - `BTC_USD_221230_SWAP` for futures
+/// - `BTC_USD_221230_20000_C` for options
+///
+/// Contracts quoted in USD settle in the base coin, the others in the quote,
+/// a different settle asset is appended, e.g. `BTC_USD_SWAP:USD`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Instrument {
+    pub base: String,
+    pub quote: String,
+    pub kind: InstrumentType,
+    /// Asset profits and losses are paid in, the quote for spot
+    pub settle: String,
+}
+
+impl Instrument {
+    pub fn spot(base: &str, quote: &str) -> Self {
+        Instrument {
+            base: base.to_string(),
+            quote: quote.to_string(),
+            kind: InstrumentType::Spot,
+            settle: quote.to_string(),
+        }
+    }
+
+    /// Margined as its canonical string says, see `Instrument`
+    pub fn perpetual(base: &str, quote: &str) -> Self {
+        let settle = default_settle(base, quote);
+        let margin = margin_of(base, settle);
+
+        Instrument {
+            kind: InstrumentType::Perpetual { margin },
+            settle: settle.to_string(),
+            ..Instrument::spot(base, quote)
+        }
+    }
+
+    pub fn future(base: &str, quote: &str, expiry: &str) -> Self {
+        Instrument {
+            kind: InstrumentType::Future {
+                expiry: expiry.to_string(),
+            },
+            settle: default_settle(base, quote).to_string(),
+            ..Instrument::spot(base, quote)
+        }
+    }
+
+    pub fn option(base: &str, quote: &str, expiry: &str, strike: f64, kind: OptionKind) -> Self {
+        Instrument {
+            kind: InstrumentType::Option {
+                expiry: expiry.to_string(),
+                strike,
+                kind,
+            },
+            settle: default_settle(base, quote).to_string(),
+            ..Instrument::spot(base, quote)
+        }
+    }
+
+    /// Replace the settle asset, and the margin of a perpetual with it
+    pub fn settled_in(mut self, settle: &str) -> Self {
+        if let InstrumentType::Perpetual { margin } = &mut self.kind {
+            *margin = margin_of(&self.base, settle);
+        }
+        self.settle = settle.to_string();
+        self
+    }
+
+    pub fn is_spot(&self) -> bool {
+        self.kind == InstrumentType::Spot
+    }
+
+    /// `None` for spot
+    pub fn margin(&self) -> Option<Margin> {
+        match &self.kind {
+            InstrumentType::Spot => None,
+            InstrumentType::Perpetual { margin } => Some(*margin),
+            _ => Some(margin_of(&self.base, &self.settle)),
+        }
+    }
+
+    /// "yymmdd" of futures and options
+    pub fn expiry(&self) -> Option<&str> {
+        match &self.kind {
+            InstrumentType::Future { expiry } | InstrumentType::Option { expiry, .. } => {
+                Some(expiry)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Instrument {
+    type Err = Error;
+
+    fn from_str(symbol: &str) -> Result<Self> {
+        let unsupported = || anyhow!("Unsupported Symbol {}", symbol);
+
+        let (name, settle) = match symbol.split_once(':') {
+            Some((name, settle)) if !settle.is_empty() => (name, Some(settle)),
+            Some(_) => return Err(unsupported()),
+            None => (symbol, None),
+        };
+
+        let splits = name.split('_').collect::<Vec<_>>();
+        if splits.iter().any(|s| s.is_empty()) {
+            return Err(unsupported());
+        }
+
+        let instrument = match splits[..] {
+            [base, quote] if settle.is_none() => Instrument::spot(base, quote),
+            [base, quote, "SWAP"] => Instrument::perpetual(base, quote),
+            [base, quote, expiry, "SWAP"] if valid_expiry(expiry) => {
+                Instrument::future(base, quote, expiry)
+            }
+            [base, quote, expiry, strike, kind] if valid_expiry(expiry) => {
+                let kind = match kind {
+                    "C" => OptionKind::Call,
+                    "P" => OptionKind::Put,
+                    _ => return Err(unsupported()),
+                };
+                let strike = strike
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|strike| strike.is_finite() && *strike > 0.0)
+                    .ok_or_else(unsupported)?;
+
+                Instrument::option(base, quote, expiry, strike, kind)
+            }
+            _ => return Err(unsupported()),
+        };
+
+        Ok(match settle {
+            Some(settle) => instrument.settled_in(settle),
+            None => instrument,
+        })
+    }
+}
+
+impl fmt::Display for Instrument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}_{}", self.base, self.quote)?;
+
+        match &self.kind {
+            InstrumentType::Spot => return Ok(()),
+            InstrumentType::Perpetual { .. } => write!(f, "_SWAP")?,
+            InstrumentType::Future { expiry } => write!(f, "_{}_SWAP", expiry)?,
+            InstrumentType::Option {
+                expiry,
+                strike,
+                kind,
+            } => {
+                let kind = match kind {
+                    OptionKind::Call => "C",
+                    OptionKind::Put => "P",
+                };
+                write!(f, "_{}_{}_{}", expiry, strike, kind)?
+            }
+        }
+
+        if self.settle != default_settle(&self.base, &self.quote) {
+            write!(f, ":{}", self.settle)?;
+        }
+        Ok(())
+    }
+}
+
+/// Contracts quoted in USD settle in the base coin
+fn default_settle<'a>(base: &'a str, quote: &'a str) -> &'a str {
+    if quote == "USD" {
+        base
+    } else {
+        quote
+    }
+}
+
+fn margin_of(base: &str, settle: &str) -> Margin {
+    if base == settle {
+        Margin::Inverse
+    } else {
+        Margin::Linear
+    }
+}
+
+/// "yymmdd" with a real month and day
+pub(crate) fn valid_expiry(expiry: &str) -> bool {
+    if expiry.len() != 6 || !expiry.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+
+    let month = expiry[2..4].parse::<u32>().unwrap_or(0);
+    let day = expiry[4..].parse::<u32>().unwrap_or(0);
+    (1..=12).contains(&month) && (1..=31).contains(&day)
+}
+
+/// "BNBBTC" -> ("BNB", "BTC") by the known quote assets, case insensitive
+pub(crate) fn split_pair(pair: &str) -> Option<(String, String)> {
+    let upper = pair.to_uppercase();
+
+    QUOTES.iter().find_map(|quote| {
+        let base = upper.strip_suffix(quote)?;
+        (!base.is_empty()).then(|| (base.to_string(), quote.to_string()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::instrument::{split_pair, Instrument, InstrumentType, Margin};
+    use crate::config::OptionKind;
+    use crate::InstrumentKind;
+
+    #[test]
+    fn canonical_round_trip() {
+        let symbols = [
+            "BTC_USDT",
+            "BTC_USDT_SWAP",
+            "BTC_USD_SWAP",
+            "BTC_USD_SWAP:USD",
+            "BTC_USDT_221230_SWAP",
+            "BTC_USD_221230_SWAP",
+            "BTC_USD_221230_20000_C",
+            "ETH_USD_230106_1500.5_P",
+        ];
+
+        for symbol in symbols {
+            let instrument: Instrument = symbol.parse().unwrap();
+            assert_eq!(instrument.to_string(), symbol);
+        }
+    }
+
+    #[test]
+    fn parse_terms() {
+        let spot: Instrument = "BNB_BTC".parse().unwrap();
+        assert_eq!(spot, Instrument::spot("BNB", "BTC"));
+        assert_eq!(spot.settle, "BTC");
+        assert_eq!(spot.margin(), None);
+
+        let linear: Instrument = "BTC_USDT_SWAP".parse().unwrap();
+        assert_eq!(
+            linear.kind,
+            InstrumentType::Perpetual {
+                margin: Margin::Linear
+            }
+        );
+        assert_eq!(linear.settle, "USDT");
+
+        let inverse: Instrument = "BTC_USD_SWAP".parse().unwrap();
+        assert_eq!(inverse.margin(), Some(Margin::Inverse));
+        assert_eq!(inverse.settle, "BTC");
+
+        let usd: Instrument = "BTC_USD_SWAP:USD".parse().unwrap();
+        assert_eq!(usd.margin(), Some(Margin::Linear));
+
+        let future: Instrument = "BTC_USD_221230_SWAP".parse().unwrap();
+        assert_eq!(future.expiry(), Some("221230"));
+        assert_eq!(future.margin(), Some(Margin::Inverse));
+        assert_eq!(future.kind.kind(), InstrumentKind::Future);
+
+        let option: Instrument = "BTC_USD_221230_20000_C".parse().unwrap();
+        assert_eq!(
+            option.kind,
+            InstrumentType::Option {
+                expiry: String::from("221230"),
+                strike: 20000.0,
+                kind: OptionKind::Call,
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_symbols() {
+        let symbols = [
+            "BTC",
+            "BTC_",
+            "_USDT",
+            "BTC_USDT_",
+            "BTC_USDT:USD",
+            "BTC_USDT_SWAP:",
+            "BTC_USD_221230",
+            "BTC_USD_221330_SWAP",
+            "BTC_USD_2212_SWAP",
+            "BTC_USD_221230_20000_X",
+            "BTC_USD_221230_-1_C",
+            "BTC_USTD_221230_SWAP_",
+        ];
+
+        for symbol in symbols {
+            assert!(symbol.parse::<Instrument>().is_err(), "{}", symbol);
+        }
+    }
+
+    #[test]
+    fn split_pairs() {
+        assert_eq!(
+            split_pair("bnbbtc"),
+            Some((String::from("BNB"), String::from("BTC")))
+        );
+        assert_eq!(
+            split_pair("BTCUSDT"),
+            Some((String::from("BTC"), String::from("USDT")))
+        );
+        assert_eq!(
+            split_pair("BTCFDUSD"),
+            Some((String::from("BTC"), String::from("FDUSD")))
+        );
+        assert_eq!(split_pair("USDT"), None);
+        assert_eq!(split_pair("BTCXYZ"), None);
+    }
+}
@@ -0,0 +1,34 @@
+use crate::binance::BinanceKline;
+use crate::crypto::CryptoKline;
+use anyhow::{anyhow, Result};
+
+#[derive(Clone)]
+pub enum KlineConnection {
+    Binance(BinanceKline),
+    Crypto(CryptoKline),
+}
+
+/// Inputs are 1s / 1m / 1h / 1d / 1w / 1M,
+/// output is the interval length in milliseconds (a month counts 30 days)
+pub fn interval_millis(interval: &str) -> Result<i64> {
+    let split = interval
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| anyhow!("Missing unit in interval {}", interval))?;
+    let (count, unit) = interval.split_at(split);
+
+    let count = count
+        .parse::<i64>()
+        .map_err(|_| anyhow!("Missing count in interval {}", interval))?;
+
+    let unit = match unit {
+        "s" => 1_000,
+        "m" => 60 * 1_000,
+        "h" => 60 * 60 * 1_000,
+        "d" | "D" => 24 * 60 * 60 * 1_000,
+        "w" => 7 * 24 * 60 * 60 * 1_000,
+        "M" => 30 * 24 * 60 * 60 * 1_000,
+        _ => return Err(anyhow!("Unsupported unit in interval {}", interval)),
+    };
+
+    Ok(count * unit)
+}
@@ -0,0 +1,127 @@
+use crate::config::{Instrument, SymbolType};
+use anyhow::{anyhow, Result};
+
+const WS_ADDRESS: &str = "wss://ws.kraken.com/v2";
+
+/// Book depth in Level Mode
+const LEVEL_DEPTH: i32 = 10;
+
+/// Book depths Kraken publishes
+const DEPTHS: [i32; 5] = [10, 25, 100, 500, 1000];
+
+/// Kraken names of some assets, e.g. "XBT" for Bitcoin,
+/// still used by REST and the v1 feed
+const ASSET_ALIASES: [(&str, &str); 2] = [("BTC", "XBT"), ("DOGE", "XDG")];
+
+/// Level Mode (limit is none): `book` of depth 10,
+/// only need `level_depth_address`
+///
+/// Depth Mode (limit is some): `book` of `depth_kraken(limit)`,
+/// `rest_address` is the pair info the checksum precision comes from
+pub fn set_addr_for_kraken(
+    pair: &str,
+    limit: Option<i32>,
+) -> (Option<String>, Option<String>, Option<String>) {
+    match limit {
+        Some(_) => (
+            Some(asset_pair_address_kraken(pair)),
+            Some(WS_ADDRESS.to_string()),
+            None,
+        ),
+        None => (None, None, Some(WS_ADDRESS.to_string())),
+    }
+}
+
+/// `trade` shares the v2 feed with `book`
+pub fn set_ticker_addr_for_kraken() -> String {
+    WS_ADDRESS.to_string()
+}
+
+/// REST info of `pair`, with `pair_decimals` and `lot_decimals`,
+/// e.g. ".../AssetPairs?pair=XBTUSD" for "BTC/USD"
+pub fn asset_pair_address_kraken(pair: &str) -> String {
+    let altname = kraken_pair_name(pair).replace('/', "");
+
+    format!(
+        "https://api.kraken.com/0/public/AssetPairs?pair={}",
+        altname
+    )
+}
+
+/// `limit` rounded up to a depth Kraken publishes
+pub fn depth_kraken(limit: Option<i32>) -> i32 {
+    let limit = limit.unwrap_or(LEVEL_DEPTH);
+
+    DEPTHS
+        .iter()
+        .copied()
+        .find(|depth| *depth >= limit)
+        .unwrap_or(DEPTHS[DEPTHS.len() - 1])
+}
+
+/// Inputs: BTC_USD / XBT_USD / ETH_BTC
+/// Kraken output: BTC/USD / BTC/USD / ETH/BTC as the v2 feed names them,
+/// there are no contracts on the spot feed
+pub fn validate_symbol_kraken(symbol: &str) -> Result<SymbolType> {
+    let instrument = symbol
+        .parse::<Instrument>()
+        .map_err(|_| anyhow!("Unsupported Symbol {} for kraken", symbol))?;
+
+    symbol_type_kraken(&instrument)
+}
+
+pub fn symbol_type_kraken(instrument: &Instrument) -> Result<SymbolType> {
+    if !instrument.is_spot() {
+        return Err(anyhow!("Unsupported Symbol {} for kraken", instrument));
+    }
+
+    Ok(SymbolType::Spot(format!(
+        "{}/{}",
+        common_asset(&instrument.base),
+        common_asset(&instrument.quote)
+    )))
+}
+
+/// Inverse of `symbol_type_kraken`, also for the "XBT/USD" names of REST
+pub fn instrument_kraken(symbol_type: &SymbolType) -> Result<Instrument> {
+    let pair = match symbol_type {
+        SymbolType::Spot(pair) => pair,
+        _ => return Err(anyhow!("Unsupported symbol {:?} for kraken", symbol_type)),
+    };
+
+    match pair.split_once('/') {
+        Some((base, quote)) if !base.is_empty() && !quote.is_empty() => {
+            Ok(Instrument::spot(common_asset(base), common_asset(quote)))
+        }
+        _ => Err(anyhow!("Unsupported pair {} of kraken", pair)),
+    }
+}
+
+/// Inputs: XBT/USD / BTC/USD / XDG/EUR
+/// output: BTC_USD / BTC_USD / DOGE_EUR
+pub fn normalize_symbol_kraken(pair: &str) -> Result<String> {
+    let instrument = instrument_kraken(&SymbolType::Spot(pair.to_string()))?;
+    Ok(instrument.to_string())
+}
+
+/// "BTC/USD" -> "XBT/USD", the name of REST and the v1 feed
+pub fn kraken_pair_name(pair: &str) -> String {
+    pair.split('/')
+        .map(kraken_asset)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn common_asset(asset: &str) -> &str {
+    ASSET_ALIASES
+        .iter()
+        .find(|(_, kraken)| *kraken == asset)
+        .map_or(asset, |(common, _)| common)
+}
+
+fn kraken_asset(asset: &str) -> &str {
+    ASSET_ALIASES
+        .iter()
+        .find(|(common, _)| *common == asset)
+        .map_or(asset, |(_, kraken)| kraken)
+}
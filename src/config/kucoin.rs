@@ -0,0 +1,74 @@
+use crate::config::{Instrument, SymbolType};
+use anyhow::{anyhow, Result};
+
+/// The websocket endpoint is handed out with a token by this request
+const BULLET_ADDRESS: &str = "https://api.kucoin.com/api/v1/bullet-public";
+
+/// Both modes connect through `bullet-public`, so the websocket address
+/// is the `bullet-public` request, Depth Mode also gets `rest_address`
+/// of the partial book
+pub fn set_addr_for_kucoin(
+    symbol: &str,
+    limit: Option<i32>,
+) -> (Option<String>, Option<String>, Option<String>) {
+    match limit {
+        Some(limit) => {
+            let rest_address = format!(
+                "https://api.kucoin.com/api/v1/market/orderbook/level2_{}?symbol={}",
+                depth_kucoin(limit),
+                symbol
+            );
+
+            (Some(rest_address), Some(BULLET_ADDRESS.to_string()), None)
+        }
+        None => (None, None, Some(BULLET_ADDRESS.to_string())),
+    }
+}
+
+/// `/market/match` shares the endpoint with the books
+pub fn set_ticker_addr_for_kucoin() -> String {
+    BULLET_ADDRESS.to_string()
+}
+
+/// Partial books are public in 20 and 100 levels
+pub fn depth_kucoin(limit: i32) -> i32 {
+    if limit <= 20 {
+        20
+    } else {
+        100
+    }
+}
+
+/// Inputs: BTC_USDT
+/// KuCoin output: BTC-USDT, there are no contracts on the spot feed
+pub fn validate_symbol_kucoin(symbol: &str) -> Result<SymbolType> {
+    let instrument = symbol
+        .parse::<Instrument>()
+        .map_err(|_| anyhow!("Unsupported Symbol {} for kucoin", symbol))?;
+
+    symbol_type_kucoin(&instrument)
+}
+
+pub fn symbol_type_kucoin(instrument: &Instrument) -> Result<SymbolType> {
+    if !instrument.is_spot() {
+        return Err(anyhow!("Unsupported Symbol {} for kucoin", instrument));
+    }
+
+    Ok(SymbolType::Spot(format!(
+        "{}-{}",
+        instrument.base, instrument.quote
+    )))
+}
+
+/// Inverse of `symbol_type_kucoin`
+pub fn instrument_kucoin(symbol_type: &SymbolType) -> Result<Instrument> {
+    match symbol_type {
+        SymbolType::Spot(name) => match name.split_once('-') {
+            Some((base, quote)) if !base.is_empty() && !quote.is_empty() => {
+                Ok(Instrument::spot(base, quote))
+            }
+            _ => Err(anyhow!("Unsupported symbol {:?} for kucoin", symbol_type)),
+        },
+        _ => Err(anyhow!("Unsupported symbol {:?} for kucoin", symbol_type)),
+    }
+}
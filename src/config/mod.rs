@@ -1,18 +1,67 @@
 mod binance;
+mod bitfinex;
+mod bybit;
+mod coinbase;
 mod configuration;
 mod crypto;
 mod depth;
-mod ticker;
-use crate::ExchangeType;
-pub use configuration::{DepthConfig, TickerConfig};
-pub use configuration::{DepthType, Method, SymbolType};
-pub use ticker::TickerConnection;
+mod deribit;
+mod gateio;
+mod instrument;
+mod kline;
+mod kraken;
+mod kucoin;
+mod okx;
+use crate::{ExchangeType, ReconnectPolicy};
+pub use configuration::{DepthConfig, KlineConfig, TickerConfig};
+pub use configuration::{DepthType, Method, OptionContract, OptionKind, SymbolType};
+pub use instrument::{Instrument, InstrumentType, Margin};
+pub use kline::{interval_millis, KlineConnection};
 
-use binance::{set_addr_for_binance, validate_symbol_binance};
-use crypto::{set_addr_for_crypto, validate_symbol_crypto};
+pub(crate) use binance::{
+    exchange_info_addresses_binance, instrument_binance, set_addr_for_binance,
+    set_kline_addr_for_binance, symbol_type_binance, validate_interval_binance,
+    validate_symbol_binance,
+};
+pub(crate) use bitfinex::{
+    instrument_bitfinex, length_bitfinex, set_addr_for_bitfinex, set_ticker_addr_for_bitfinex,
+    symbol_type_bitfinex, validate_symbol_bitfinex,
+};
+pub(crate) use bybit::{
+    depth_topic_bybit, instrument_bybit, set_addr_for_bybit, symbol_type_bybit, trade_topic_bybit,
+    validate_symbol_bybit,
+};
+pub(crate) use coinbase::{
+    instrument_coinbase, set_addr_for_coinbase, set_ticker_addr_for_coinbase,
+    symbol_type_coinbase, validate_symbol_coinbase,
+};
+pub(crate) use crypto::{
+    depth_channel_crypto, instrument_crypto, instruments_address_crypto, set_addr_for_crypto,
+    symbol_type_crypto, validate_interval_crypto, validate_symbol_crypto,
+};
+pub(crate) use deribit::{
+    depth_channel_deribit, instrument_deribit, set_addr_for_deribit, set_ticker_addr_for_deribit,
+    symbol_type_deribit, trade_channel_deribit, validate_symbol_deribit,
+};
+pub(crate) use gateio::{
+    instrument_gateio, set_addr_for_gateio, set_ticker_addr_for_gateio, symbol_type_gateio,
+    validate_symbol_gateio,
+};
+pub(crate) use kraken::{
+    asset_pair_address_kraken, depth_kraken, instrument_kraken, normalize_symbol_kraken,
+    set_addr_for_kraken, set_ticker_addr_for_kraken, symbol_type_kraken, validate_symbol_kraken,
+};
+pub(crate) use kucoin::{
+    instrument_kucoin, set_addr_for_kucoin, set_ticker_addr_for_kucoin, symbol_type_kucoin,
+    validate_symbol_kucoin,
+};
+pub(crate) use okx::{
+    instrument_okx, set_addr_for_okx, set_ticker_addr_for_okx, symbol_type_okx,
+    validate_symbol_okx,
+};
 
-/// Crypto contract should panic
-pub fn get_depth_config_from(exchange: &str, symbol: &str, limit: Option<i32>) -> DepthConfig {
+/// interval: "1m" / "1h" / "1d"
+pub fn get_kline_config_from(exchange: &str, symbol: &str, interval: &str) -> KlineConfig {
     let exchange_type = match exchange {
         "binance" => ExchangeType::Binance,
         "crypto" => ExchangeType::Crypto,
@@ -21,58 +70,37 @@ pub fn get_depth_config_from(exchange: &str, symbol: &str, limit: Option<i32>) -
 
     let symbol_type = match exchange_type {
         ExchangeType::Binance => validate_symbol_binance(symbol).unwrap(),
-        ExchangeType::Crypto => validate_symbol_crypto(symbol, limit).unwrap(),
+        ExchangeType::Crypto => validate_symbol_crypto(symbol).unwrap(),
+        other => panic!("Kline is unsupported for {}", other.name()),
     };
 
-    let (rest_address, depth_address, level_depth_address) = match exchange_type {
-        ExchangeType::Binance => set_addr_for_binance(symbol_type.clone(), limit, Method::Depth),
-        ExchangeType::Crypto => {
-            let symbol = match symbol_type.clone() {
-                SymbolType::Spot(s) => s,
-                SymbolType::ContractUSDT(s) => s,
-                _ => panic!("Crypto is supported for {}", symbol),
-            };
-            set_addr_for_crypto(&symbol, limit)
-        }
-    };
-
-    let depth_url = DepthType::new(rest_address, depth_address, level_depth_address);
-
-    DepthConfig {
-        depth_url: depth_url.expect("depth url is empty"),
-        symbol_type,
-        exchange_type,
-    }
-}
-
-pub fn get_ticker_config_from(exchange: &str, symbol: &str, limit: Option<i32>) -> TickerConfig {
-    let exchange_type = match exchange {
-        "binance" => ExchangeType::Binance,
-        "crypto" => ExchangeType::Crypto,
-        _ => panic!("Unsupported Exchange {}", exchange),
-    };
-
-    let symbol_type = match exchange_type {
-        ExchangeType::Binance => validate_symbol_binance(symbol).unwrap(),
-        ExchangeType::Crypto => validate_symbol_crypto(symbol, limit).unwrap(),
-    };
+    let interval_ms = interval_millis(interval).unwrap();
 
-    let (_, _, ticker_url) = match exchange_type {
-        ExchangeType::Binance => set_addr_for_binance(symbol_type.clone(), limit, Method::Ticker),
+    let (kline_url, interval) = match exchange_type {
+        ExchangeType::Binance => {
+            let interval = validate_interval_binance(interval).unwrap();
+            (set_kline_addr_for_binance(symbol_type.clone(), &interval), interval)
+        }
         ExchangeType::Crypto => {
+            let interval = validate_interval_crypto(interval).unwrap();
             let symbol = match symbol_type.clone() {
                 SymbolType::Spot(s) => s,
                 SymbolType::ContractUSDT(s) => s,
                 _ => panic!("Crypto is supported for {}", symbol),
             };
-            set_addr_for_crypto(&symbol, limit)
+            let (_, _, kline_url) = set_addr_for_crypto(&symbol, None);
+            (kline_url.unwrap(), interval)
         }
+        other => panic!("Kline is unsupported for {}", other.name()),
     };
 
-    TickerConfig {
-        ticker_url: ticker_url.unwrap(),
+    KlineConfig {
+        kline_url,
+        interval,
+        interval_ms,
         symbol_type,
         exchange_type,
+        reconnect: ReconnectPolicy::default(),
     }
 }
 
@@ -121,75 +149,339 @@ pub fn get_ticker_config_from(exchange: &str, symbol: &str, limit: Option<i32>)
 /// https://uat-api.3ona.co/v2/{method} // Backup
 #[cfg(test)]
 mod tests {
-    use crate::config::get_depth_config_from;
+    use crate::api::adapter::get_adapter;
+    use crate::config::DepthConfig;
     use crate::config::validate_symbol_binance;
-    use crate::config::validate_symbol_crypto;
+    use crate::config::{length_bitfinex, validate_symbol_bitfinex};
+    use crate::config::{depth_topic_bybit, validate_symbol_bybit};
+    use crate::config::validate_symbol_coinbase;
+    use crate::config::kraken::kraken_pair_name;
+    use crate::config::{depth_channel_crypto, validate_symbol_crypto};
+    use crate::config::validate_symbol_deribit;
+    use crate::config::{set_addr_for_gateio, validate_symbol_gateio};
+    use crate::config::{OptionContract, OptionKind};
+    use crate::config::{asset_pair_address_kraken, validate_symbol_kraken};
+    use crate::config::{depth_kraken, normalize_symbol_kraken};
+    use crate::config::{set_addr_for_kucoin, validate_symbol_kucoin};
+    use crate::config::validate_symbol_okx;
     use crate::config::Method;
-    use crate::config::SymbolType;
+    use crate::config::{Instrument, SymbolType};
+    use crate::InstrumentRegistry;
+
+    /// Crypto contract should panic
+    fn get_depth_config_from(exchange: &str, symbol: &str, limit: Option<i32>) -> DepthConfig {
+        get_adapter(exchange).depth_config(symbol, limit).unwrap()
+    }
 
     #[test]
     fn match_up_input_test() {
-        assert!(validate_symbol_binance("BTC_USTD_221230_SWAP").is_ok());
-        assert!(validate_symbol_binance("BTC_USTD_SWAP").is_ok());
-        assert!(validate_symbol_binance("BTC_USTD").is_ok());
+        // The bundled instruments as if they were loaded, the cache only warns
+        let cached = InstrumentRegistry::cached();
+        let registry = InstrumentRegistry::default();
+        for exchange in cached.exchanges() {
+            registry.insert(&exchange, cached.instruments(&exchange));
+        }
+
+        // Well formed, but no such instrument
+        let binance = get_adapter("binance");
+        for symbol in ["BTC_USTD_221230_SWAP", "BTC_USTD_SWAP", "BTC_USTD"] {
+            let symbol_type = validate_symbol_binance(symbol).unwrap();
+            assert!(binance.check_instrument(&registry, &symbol_type).is_err());
+        }
+
+        assert!(validate_symbol_crypto("BTC_USTD_221230_SWAP").is_err());
+        let crypto = get_adapter("crypto");
+        for symbol in ["BTC_USTD_SWAP", "BTC_USTD"] {
+            let symbol_type = validate_symbol_crypto(symbol).unwrap();
+            assert!(crypto.check_instrument(&registry, &symbol_type).is_err());
+        }
+    }
+
+    /// Canonical symbol and its name of every supported venue
+    fn native_symbols() -> Vec<(&'static str, &'static str, SymbolType)> {
+        let spot = |name: &str| SymbolType::Spot(name.to_string());
+        let linear = |name: &str| SymbolType::ContractUSDT(name.to_string());
+        let inverse = |name: &str| SymbolType::ContractCoin(name.to_string());
 
-        assert!(validate_symbol_crypto("BTC_USTD_221230_SWAP", None).is_err());
-        assert!(validate_symbol_crypto("BTC_USTD_SWAP", None).is_ok());
-        assert!(validate_symbol_crypto("BTC_USTD", None).is_ok());
+        vec![
+            ("binance", "BNB_BTC", spot("bnbbtc")),
+            ("binance", "BTC_USDT_SWAP", linear("btcusdt")),
+            ("binance", "BTC_USDT_230331_SWAP", linear("btcusdt_230331")),
+            ("binance", "BTC_USD_SWAP", inverse("btcusd_perp")),
+            ("binance", "BTC_USD_221230_SWAP", inverse("btcusd_221230")),
+            ("crypto", "BTC_USDT", spot("BTC_USDT")),
+            ("crypto", "BTC_USD_SWAP:USD", linear("BTCUSD-PERP")),
+            ("okx", "BTC_USDT", spot("BTC-USDT")),
+            ("okx", "BTC_USDT_SWAP", linear("BTC-USDT-SWAP")),
+            ("okx", "BTC_USDT_221230_SWAP", linear("BTC-USDT-221230")),
+            ("okx", "BTC_USD_SWAP", inverse("BTC-USD-SWAP")),
+            ("okx", "BTC_USD_221230_SWAP", inverse("BTC-USD-221230")),
+            ("bybit", "BTC_USDT", spot("BTCUSDT")),
+            ("bybit", "BTC_USDT_SWAP", linear("BTCUSDT")),
+            ("bybit", "BTC_USD_SWAP", inverse("BTCUSD")),
+            ("bybit", "BTC_USD_221230_SWAP", inverse("BTCUSDZ22")),
+            ("bybit", "ETH_USD_230331_SWAP", inverse("ETHUSDH23")),
+            ("coinbase", "BTC_USD", spot("BTC-USD")),
+            ("kraken", "BTC_USD", spot("BTC/USD")),
+            ("kraken", "DOGE_EUR", spot("DOGE/EUR")),
+            ("deribit", "BTC_USD_SWAP", inverse("BTC-PERPETUAL")),
+            ("deribit", "ETH_USD_230106_SWAP", inverse("ETH-6JAN23")),
+            (
+                "deribit",
+                "BTC_USD_221230_20000_C",
+                SymbolType::Option(OptionContract {
+                    name: String::from("BTC-30DEC22-20000-C"),
+                    underlying: String::from("BTC"),
+                    expiry: String::from("221230"),
+                    strike: 20000.0,
+                    kind: OptionKind::Call,
+                }),
+            ),
+            ("bitfinex", "BTC_USD", spot("tBTCUSD")),
+            ("bitfinex", "BTC_USDT", spot("tBTCUST")),
+            ("bitfinex", "DOGE_USD", spot("tDOGE:USD")),
+            ("bitfinex", "BTC_USDT_SWAP", linear("tBTCF0:USTF0")),
+            ("kucoin", "BTC_USDT", spot("BTC-USDT")),
+            ("gateio", "BTC_USDT", spot("BTC_USDT")),
+        ]
     }
 
     #[test]
-    fn valid_symbols() {
+    fn round_trip_symbols() {
+        for (exchange, symbol, native) in native_symbols() {
+            let adapter = get_adapter(exchange);
+            let instrument: Instrument = symbol.parse().unwrap();
+
+            assert_eq!(instrument.to_string(), symbol);
+            assert_eq!(adapter.validate_symbol(symbol, None).unwrap(), native);
+            assert_eq!(adapter.native_symbol(&instrument).unwrap(), native);
+            assert_eq!(
+                adapter.instrument_of(&native).unwrap(),
+                instrument,
+                "{} of {}",
+                symbol,
+                exchange
+            );
+        }
+
+        // USDT perpetuals of crypto.com are its USD ones
         assert_eq!(
-            SymbolType::ContractCoin(String::from("btcusdt_221230")),
-            validate_symbol_binance("BTC_USDT_221230_SWAP").unwrap(),
+            validate_symbol_crypto("BTC_USDT_SWAP").unwrap(),
+            SymbolType::ContractUSDT(String::from("BTCUSD-PERP"))
         );
+    }
+
+    #[test]
+    fn config_test() {
+        let binance_config = get_depth_config_from("binance", "BTC_USD_SWAP", Some(1000));
+
+        assert!(binance_config.is_binance());
+        assert!(binance_config.is_contract_coin());
+
+        // Delivered
+        assert!(get_adapter("binance")
+            .depth_config("BTC_USD_221230_SWAP", Some(1000))
+            .is_err());
+
+        let crypto_config = get_depth_config_from("crypto", "BTC_USDT", None);
+
+        assert!(crypto_config.is_crypto());
+        assert!(crypto_config.is_spot());
 
+        assert_eq!(crypto_config.get_symbol(), String::from("BTC_USDT"));
         assert_eq!(
-            SymbolType::ContractUSDT(String::from("btcusdt")),
-            validate_symbol_binance("BTC_USDT_SWAP").unwrap()
+            depth_channel_crypto(&crypto_config.get_symbol(), crypto_config.limit),
+            "book.BTC_USDT.50"
         );
 
+        let crypto_config = get_depth_config_from("crypto", "BTC_USDT_SWAP", None);
+        assert_eq!(crypto_config.get_symbol(), String::from("BTCUSD-PERP"));
+
+        let crypto_config = get_depth_config_from("crypto", "BTC_USDT", Some(10));
         assert_eq!(
-            SymbolType::Spot(String::from("btcusdt")),
-            validate_symbol_binance("BTC_USDT").unwrap()
+            depth_channel_crypto(&crypto_config.get_symbol(), crypto_config.limit),
+            "book.BTC_USDT.10"
         );
+    }
 
+    #[test]
+    fn okx_symbols() {
         assert_eq!(
-            SymbolType::ContractUSDT(String::from("BTCUSD-PERP.50")),
-            validate_symbol_crypto("BTC_USDT_SWAP", None).unwrap()
+            SymbolType::Spot(String::from("BTC-USDT")),
+            validate_symbol_okx("BTC_USDT").unwrap()
         );
 
         assert_eq!(
-            SymbolType::Spot(String::from("BTC_USDT.50")),
-            validate_symbol_crypto("BTC_USDT", None).unwrap()
+            SymbolType::ContractUSDT(String::from("BTC-USDT-SWAP")),
+            validate_symbol_okx("BTC_USDT_SWAP").unwrap()
         );
 
         assert_eq!(
-            SymbolType::Spot(String::from("BTC_USDT.10")),
-            validate_symbol_crypto("BTC_USDT", Some(10)).unwrap()
+            SymbolType::ContractCoin(String::from("BTC-USD-221230")),
+            validate_symbol_okx("BTC_USD_221230_SWAP").unwrap()
         );
+
+        assert!(validate_symbol_okx("BTC_USD_221230").is_err());
+        assert!(validate_symbol_okx("BTC").is_err());
     }
 
     #[test]
-    fn config_test() {
-        let binance_config = get_depth_config_from("binance", "BTC_USTD_221230_SWAP", Some(1000));
+    fn bybit_symbols() {
+        assert_eq!(
+            SymbolType::Spot(String::from("BTCUSDT")),
+            validate_symbol_bybit("BTC_USDT").unwrap()
+        );
 
-        assert!(binance_config.is_binance());
-        assert!(binance_config.is_contract_coin());
+        assert_eq!(
+            SymbolType::ContractUSDT(String::from("BTCUSDT")),
+            validate_symbol_bybit("BTC_USDT_SWAP").unwrap()
+        );
 
-        let crypto_config = get_depth_config_from("crypto", "BTC_USDT", None);
+        assert_eq!(
+            SymbolType::ContractCoin(String::from("BTCUSD")),
+            validate_symbol_bybit("BTC_USD_SWAP").unwrap()
+        );
 
-        assert!(crypto_config.is_crypto());
-        assert!(crypto_config.is_spot());
+        assert_eq!(
+            SymbolType::ContractCoin(String::from("BTCUSDZ22")),
+            validate_symbol_bybit("BTC_USD_221230_SWAP").unwrap()
+        );
 
-        assert_eq!(crypto_config.get_symbol(), String::from("BTC_USDT.50"));
+        assert!(validate_symbol_bybit("BTC_USD_221330_SWAP").is_err());
+        assert!(validate_symbol_bybit("BTC_USDT_221230_SWAP").is_err());
 
-        let crypto_config = get_depth_config_from("crypto", "BTC_USDT_SWAP", None);
-        assert_eq!(crypto_config.get_symbol(), String::from("BTCUSD-PERP.50"));
+        let spot = validate_symbol_bybit("BTC_USDT").unwrap();
+        assert_eq!(depth_topic_bybit(&spot, None), "orderbook.50.BTCUSDT");
+        assert_eq!(depth_topic_bybit(&spot, Some(100)), "orderbook.200.BTCUSDT");
+        assert_eq!(depth_topic_bybit(&spot, Some(1000)), "orderbook.200.BTCUSDT");
 
-        let crypto_config = get_depth_config_from("crypto", "BTC_USDT", Some(10));
-        assert_eq!(crypto_config.get_symbol(), String::from("BTC_USDT.10"));
+        let linear = validate_symbol_bybit("BTC_USDT_SWAP").unwrap();
+        assert_eq!(depth_topic_bybit(&linear, Some(1000)), "orderbook.500.BTCUSDT");
+    }
+
+    #[test]
+    fn coinbase_symbols() {
+        assert_eq!(
+            SymbolType::Spot(String::from("BTC-USD")),
+            validate_symbol_coinbase("BTC_USD").unwrap()
+        );
+
+        assert!(validate_symbol_coinbase("BTC_USD_SWAP").is_err());
+        assert!(validate_symbol_coinbase("BTC_").is_err());
+    }
+
+    #[test]
+    fn kraken_symbols() {
+        assert_eq!(
+            SymbolType::Spot(String::from("BTC/USD")),
+            validate_symbol_kraken("BTC_USD").unwrap()
+        );
+        assert_eq!(
+            SymbolType::Spot(String::from("BTC/USD")),
+            validate_symbol_kraken("XBT_USD").unwrap()
+        );
+        assert!(validate_symbol_kraken("BTC_USD_SWAP").is_err());
+
+        assert_eq!(normalize_symbol_kraken("XBT/USD").unwrap(), "BTC_USD");
+        assert_eq!(normalize_symbol_kraken("BTC/USD").unwrap(), "BTC_USD");
+        assert_eq!(normalize_symbol_kraken("XDG/EUR").unwrap(), "DOGE_EUR");
+        assert!(normalize_symbol_kraken("XBTUSD").is_err());
+
+        assert_eq!(kraken_pair_name("BTC/USD"), "XBT/USD");
+        assert_eq!(kraken_pair_name("ETH/USD"), "ETH/USD");
+        assert_eq!(
+            asset_pair_address_kraken("BTC/USD"),
+            "https://api.kraken.com/0/public/AssetPairs?pair=XBTUSD"
+        );
+
+        assert_eq!(depth_kraken(None), 10);
+        assert_eq!(depth_kraken(Some(50)), 100);
+        assert_eq!(depth_kraken(Some(5000)), 1000);
+    }
+
+    #[test]
+    fn deribit_symbols() {
+        assert_eq!(
+            SymbolType::ContractCoin(String::from("BTC-PERPETUAL")),
+            validate_symbol_deribit("BTC_USD_SWAP").unwrap()
+        );
+        assert_eq!(
+            SymbolType::ContractCoin(String::from("ETH-6JAN23")),
+            validate_symbol_deribit("ETH_USD_230106_SWAP").unwrap()
+        );
+        assert_eq!(
+            SymbolType::Option(OptionContract {
+                name: String::from("BTC-30DEC22-20000-C"),
+                underlying: String::from("BTC"),
+                expiry: String::from("221230"),
+                strike: 20000.0,
+                kind: OptionKind::Call,
+            }),
+            validate_symbol_deribit("BTC_USD_221230_20000_C").unwrap()
+        );
+
+        assert!(validate_symbol_deribit("BTC_USD").is_err());
+        assert!(validate_symbol_deribit("BTC_USDT_SWAP").is_err());
+        assert!(validate_symbol_deribit("BTC_USD_221330_SWAP").is_err());
+        assert!(validate_symbol_deribit("BTC_USD_221230_20000_X").is_err());
+    }
+
+    #[test]
+    fn bitfinex_symbols() {
+        assert_eq!(
+            SymbolType::Spot(String::from("tBTCUSD")),
+            validate_symbol_bitfinex("BTC_USD").unwrap()
+        );
+        assert_eq!(
+            SymbolType::Spot(String::from("tBTCUST")),
+            validate_symbol_bitfinex("BTC_USDT").unwrap()
+        );
+        assert_eq!(
+            SymbolType::Spot(String::from("tDOGE:USD")),
+            validate_symbol_bitfinex("DOGE_USD").unwrap()
+        );
+        assert_eq!(
+            SymbolType::ContractUSDT(String::from("tBTCF0:USTF0")),
+            validate_symbol_bitfinex("BTC_USDT_SWAP").unwrap()
+        );
+        assert!(validate_symbol_bitfinex("BTC_USD_SWAP").is_err());
+        assert!(validate_symbol_bitfinex("BTC_").is_err());
+
+        assert_eq!(length_bitfinex(None), 25);
+        assert_eq!(length_bitfinex(Some(50)), 100);
+        assert_eq!(length_bitfinex(Some(1000)), 250);
+    }
+
+    #[test]
+    fn kucoin_symbols() {
+        assert_eq!(
+            SymbolType::Spot(String::from("BTC-USDT")),
+            validate_symbol_kucoin("BTC_USDT").unwrap()
+        );
+        assert!(validate_symbol_kucoin("BTC_USDT_SWAP").is_err());
+
+        let (rest, depth, level) = set_addr_for_kucoin("BTC-USDT", Some(50));
+        assert_eq!(
+            rest.unwrap(),
+            "https://api.kucoin.com/api/v1/market/orderbook/level2_100?symbol=BTC-USDT"
+        );
+        assert_eq!(depth.unwrap(), "https://api.kucoin.com/api/v1/bullet-public");
+        assert!(level.is_none());
+    }
+
+    #[test]
+    fn gateio_symbols() {
+        assert_eq!(
+            SymbolType::Spot(String::from("BTC_USDT")),
+            validate_symbol_gateio("BTC_USDT").unwrap()
+        );
+        assert!(validate_symbol_gateio("BTC").is_err());
+
+        let (rest, _, _) = set_addr_for_gateio("BTC_USDT", Some(1000));
+        assert_eq!(
+            rest.unwrap(),
+            "https://api.gateio.ws/api/v4/spot/order_book?currency_pair=BTC_USDT&limit=100&with_id=true"
+        );
     }
 
     #[test]
@@ -0,0 +1,97 @@
+use crate::config::instrument::valid_expiry;
+use crate::config::{Instrument, InstrumentType, SymbolType};
+use anyhow::{anyhow, Result};
+
+const PUBLIC_ADDRESS: &str = "wss://ws.okx.com:8443/ws/v5/public";
+
+/// Level Mode (limit is none): `books5` snapshots on the public stream,
+/// only need `level_depth_address`
+///
+/// Depth Mode (limit is some): `books` snapshot followed by deltas,
+/// need `rest_address` and `depth_address`
+pub fn set_addr_for_okx(
+    inst_id: &str,
+    limit: Option<i32>,
+) -> (Option<String>, Option<String>, Option<String>) {
+    match limit {
+        Some(limit) => {
+            let rest_address = format!(
+                "https://www.okx.com/api/v5/market/books?instId={}&sz={}",
+                inst_id, limit
+            );
+
+            (Some(rest_address), Some(PUBLIC_ADDRESS.to_string()), None)
+        }
+        None => (None, None, Some(PUBLIC_ADDRESS.to_string())),
+    }
+}
+
+/// `trades` share the public stream with the books
+pub fn set_ticker_addr_for_okx() -> String {
+    PUBLIC_ADDRESS.to_string()
+}
+
+/// Inputs: BTC_USDT / BTC_USDT_SWAP / BTC_USD_SWAP / BTC_USD_221230_SWAP
+/// Okx output: BTC-USDT / BTC-USDT-SWAP / BTC-USD-SWAP / BTC-USD-221230
+pub fn validate_symbol_okx(symbol: &str) -> Result<SymbolType> {
+    let instrument = symbol
+        .parse::<Instrument>()
+        .map_err(|_| anyhow!("Unsupported Symbol {} for okx", symbol))?;
+
+    symbol_type_okx(&instrument)
+}
+
+/// Contracts settled in the quote are `ContractUSDT`, in the base coin `ContractCoin`
+pub fn symbol_type_okx(instrument: &Instrument) -> Result<SymbolType> {
+    let pair = format!("{}-{}", instrument.base, instrument.quote);
+
+    let name = match &instrument.kind {
+        // e.g. "BTC-USDT"
+        InstrumentType::Spot => return Ok(SymbolType::Spot(pair)),
+        // e.g. "BTC-USDT-SWAP"
+        InstrumentType::Perpetual { .. } => format!("{}-SWAP", pair),
+        // e.g. "BTC-USD-221230"
+        InstrumentType::Future { expiry } => format!("{}-{}", pair, expiry),
+        _ => return Err(anyhow!("Unsupported Symbol {} for okx", instrument)),
+    };
+
+    if instrument.settle == instrument.quote {
+        Ok(SymbolType::ContractUSDT(name))
+    } else if instrument.settle == instrument.base {
+        Ok(SymbolType::ContractCoin(name))
+    } else {
+        Err(anyhow!("Unsupported Symbol {} for okx", instrument))
+    }
+}
+
+/// Inverse of `symbol_type_okx`
+pub fn instrument_okx(symbol_type: &SymbolType) -> Result<Instrument> {
+    let unsupported = || anyhow!("Unsupported symbol {:?} for okx", symbol_type);
+
+    let (name, linear) = match symbol_type {
+        SymbolType::Spot(name) => (name, None),
+        SymbolType::ContractUSDT(name) => (name, Some(true)),
+        SymbolType::ContractCoin(name) => (name, Some(false)),
+        SymbolType::Option(_) => return Err(unsupported()),
+    };
+
+    let splits = name.split('-').collect::<Vec<_>>();
+    if splits.iter().any(|s| s.is_empty()) {
+        return Err(unsupported());
+    }
+
+    let instrument = match (&splits[..], linear) {
+        ([base, quote], None) => return Ok(Instrument::spot(base, quote)),
+        ([base, quote, "SWAP"], Some(_)) => Instrument::perpetual(base, quote),
+        ([base, quote, expiry], Some(_)) if valid_expiry(expiry) => {
+            Instrument::future(base, quote, expiry)
+        }
+        _ => return Err(unsupported()),
+    };
+
+    let settle = match linear {
+        Some(true) => instrument.quote.clone(),
+        _ => instrument.base.clone(),
+    };
+    Ok(instrument.settled_in(&settle))
+}
@@ -0,0 +1,78 @@
+use crate::config::{instrument_crypto, symbol_type_crypto, Instrument};
+use crate::config::{
+    instruments_address_crypto, set_addr_for_crypto, validate_symbol_crypto, DepthType, SymbolType,
+};
+use crate::crypto::connection::instrument::fetch_instruments;
+use crate::crypto::{CryptoDepth, CryptoTicker};
+use crate::{
+    DepthConfig, DepthT, ExchangeAdapter, ExchangeType, InstrumentInfo, TickerConfig, TickerT,
+};
+use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
+use std::sync::Arc;
+
+/// Spot and USD perpetuals of crypto.com
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CryptoAdapter;
+
+impl CryptoAdapter {
+    /// Crypto has no coin margined contracts
+    fn instrument(symbol_type: &SymbolType) -> Result<&str> {
+        match symbol_type {
+            SymbolType::Spot(s) | SymbolType::ContractUSDT(s) => Ok(s),
+            _ => Err(anyhow!("Crypto is unsupported for {:?}", symbol_type)),
+        }
+    }
+}
+
+impl ExchangeAdapter for CryptoAdapter {
+    fn name(&self) -> &'static str {
+        "crypto"
+    }
+
+    fn exchange_type(&self) -> ExchangeType {
+        ExchangeType::Crypto
+    }
+
+    fn validate_symbol(&self, symbol: &str, _limit: Option<i32>) -> Result<SymbolType> {
+        validate_symbol_crypto(symbol)
+    }
+
+    fn depth_url(&self, symbol_type: &SymbolType, limit: Option<i32>) -> Result<DepthType> {
+        let (rest_address, depth_address, level_depth_address) =
+            set_addr_for_crypto(Self::instrument(symbol_type)?, limit);
+
+        DepthType::new(rest_address, depth_address, level_depth_address)
+            .ok_or_else(|| anyhow!("depth url is empty"))
+    }
+
+    fn ticker_url(&self, symbol_type: &SymbolType) -> Result<String> {
+        let (_, _, ticker_url) = set_addr_for_crypto(Self::instrument(symbol_type)?, None);
+
+        ticker_url.ok_or_else(|| anyhow!("ticker url is empty"))
+    }
+
+    fn depth_connection(&self, _config: &DepthConfig) -> Result<Arc<dyn DepthT + Send + Sync>> {
+        Ok(Arc::new(CryptoDepth::new()))
+    }
+
+    fn ticker_connection(&self, _config: &TickerConfig) -> Result<Arc<dyn TickerT + Send + Sync>> {
+        Ok(Arc::new(CryptoTicker::new()))
+    }
+
+    fn native_symbol(&self, instrument: &Instrument) -> Result<SymbolType> {
+        symbol_type_crypto(instrument)
+    }
+
+    fn instrument_of(&self, symbol_type: &SymbolType) -> Result<Instrument> {
+        instrument_crypto(symbol_type)
+    }
+
+    fn instrument_name(&self, symbol_type: &SymbolType) -> Option<String> {
+        Self::instrument(symbol_type).ok().map(String::from)
+    }
+
+    fn instruments(&self) -> BoxFuture<'_, Result<Vec<InstrumentInfo>>> {
+        Box::pin(fetch_instruments(instruments_address_crypto()))
+    }
+}
@@ -2,8 +2,10 @@ pub use super::depth::CryptoDepth;
 pub use super::ticker::CryptoTicker;
 use crate::crypto::connection::CryptoWebSocket;
 use crate::crypto::format::{
-    heartbeat_respond, subscribe_message, GeneralRespond, HeartbeatRequest,
+    heartbeat_respond, subscribe_book_update_message, subscribe_message, unsubscribe_message,
+    GeneralRespond, HeartbeatRequest,
 };
+use crate::RawFrames;
 use anyhow::{anyhow, Result};
 use futures_util::SinkExt;
 use serde::de::DeserializeOwned;
@@ -16,13 +18,48 @@ use tracing::debug;
 use url::Url;
 
 pub async fn crypto_initialize(address: &str, channel: String) -> Result<CryptoWebSocket> {
-    let mut stream = socket_stream(&address).await?;
+    let message = subscribe_message(channel.clone());
+    initialize_with(address, message, channel).await
+}
+
+/// Subscribe to one snapshot followed by `book.update` deltas
+pub async fn crypto_initialize_book_update(
+    address: &str,
+    channel: String,
+) -> Result<CryptoWebSocket> {
+    let message = subscribe_book_update_message(channel.clone());
+    initialize_with(address, message, channel).await
+}
+
+/// Subscribe again on the same connection to get a fresh snapshot
+pub async fn crypto_resubscribe_book_update(
+    stream: &mut CryptoWebSocket,
+    channel: String,
+) -> Result<()> {
+    stream
+        .send(Message::from(unsubscribe_message(channel.clone())))
+        .await?;
+    stream
+        .send(Message::from(subscribe_book_update_message(channel.clone())))
+        .await?;
+
+    debug!("Resubscribe to channel {} success", channel);
+
+    Ok(())
+}
+
+async fn initialize_with(
+    address: &str,
+    subscribe: String,
+    channel: String,
+) -> Result<CryptoWebSocket> {
+    let mut stream = socket_stream(address).await?;
     debug!("Connect to level_address success");
 
     // Official suggestion
     sleep(Duration::from_millis(1000)).await;
 
-    let message = Message::from(subscribe_message(channel.clone()));
+    let message = Message::from(subscribe);
 
     stream.send(message).await?;
 
@@ -57,6 +94,7 @@ pub async fn is_live_and_keep_alive<Respond: DeserializeOwned + Debug>(
     }
 
     let text = message.clone().into_text()?;
+    RawFrames::global().publish("crypto", &text);
 
     let response: GeneralRespond = serde_json::from_str(&text)?;
 
@@ -77,6 +115,7 @@ pub async fn is_live_and_keep_alive<Respond: DeserializeOwned + Debug>(
             debug!("Receive {:?}, initialize success", order_response);
         }
         ("subscribe", -1) => return Ok(true), // snapshot
+        ("unsubscribe", _) => debug!("Receive unsubscribe respond"),
         _ => return Err(anyhow!("Unknown respond {:?}", response)),
     }
 
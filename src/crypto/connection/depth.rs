@@ -1,21 +1,27 @@
-use crate::{Depth, DepthT};
+use crate::metrics;
+use crate::{Backoff, ChecksumVerifier, ConnectionState, Depth, DepthT, StateSender};
 use anyhow::Result;
 use futures_util::StreamExt;
 use std::sync::{Arc, Mutex, RwLock};
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::UnboundedReceiver;
-use tokio::time::{sleep, Duration};
 use tracing::{error, info, warn};
 
-use crate::config::DepthConfig;
-use crate::crypto::format::{DepthEventStream, DepthShared, OrderRespond};
+use crate::config::{depth_channel_crypto, DepthConfig};
+use crate::crypto::format::{
+    BookUpdateEventStream, DepthEventStream, DepthShared, OrderRespond, StreamChannel,
+};
 
-use super::abstraction::{crypto_initialize, is_live_and_keep_alive};
+use super::abstraction::{
+    crypto_initialize, crypto_initialize_book_update, crypto_resubscribe_book_update,
+    is_live_and_keep_alive,
+};
 
 #[derive(Clone)]
 pub struct CryptoDepth {
     /// Currently not using
     status: Arc<Mutex<bool>>,
+    state: StateSender,
     shared: Arc<RwLock<DepthShared>>,
 }
 
@@ -23,41 +29,189 @@ impl DepthT for CryptoDepth {
     fn new() -> Self {
         CryptoDepth {
             status: Arc::new(Mutex::new(false)),
+            state: StateSender::new("crypto"),
             shared: Arc::new(RwLock::new(DepthShared::new())),
         }
     }
 
-    fn depth_snapshot(&self, _config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
-        unreachable!()
+    /// acquire a order book with `book.update` deltas,
+    /// resubscribe for a fresh snapshot once a sequence gap
+    /// or a checksum mismatch is found
+    fn depth_snapshot(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
+        let (_, depth_address) = config.get_depth_snapshot_addresses();
+        let channel = depth_channel_crypto(&config.get_symbol(), config.limit);
+        let checksum = config.checksum.clone();
+
+        let shared = self.shared.clone();
+        let status = self.status.clone();
+        let state = self.state.clone();
+
+        let symbol = config.get_symbol();
+        let reconnect = config.reconnect.clone();
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            info!("Start OrderBook thread");
+            let mut backoff = Backoff::new(reconnect);
+            loop {
+                let mut stream =
+                    match crypto_initialize_book_update(&depth_address, channel.clone()).await {
+                        Ok(connection) => connection,
+                        Err(e) => {
+                            warn!("connection error {:?}", e);
+                            if !backoff.wait("crypto").await {
+                                break;
+                            }
+                            continue;
+                        }
+                    };
+                backoff.connected();
+
+                while let Some(Ok(message)) = stream.next().await {
+                    match is_live_and_keep_alive::<OrderRespond>(&mut stream, message.clone())
+                        .await
+                    {
+                        Ok(true) => (),
+                        Ok(false) => continue,
+                        Err(e) => {
+                            warn!("Decoding received message error {:?}", e);
+                            continue;
+                        }
+                    }
+
+                    let text = message.into_text().unwrap();
+
+                    let stream_channel: StreamChannel = match serde_json::from_str(&text) {
+                        Ok(stream_channel) => stream_channel,
+                        Err(e) => {
+                            warn!("Error {}, {:?}", e, text);
+                            metrics::decode_error("crypto");
+                            continue;
+                        }
+                    };
+
+                    let is_ready = *status.lock().unwrap();
+
+                    let result = match stream_channel.result.channel.as_str() {
+                        "book" => {
+                            let level_event: DepthEventStream = match serde_json::from_str(&text)
+                            {
+                                Ok(event) => event,
+                                Err(e) => {
+                                    warn!("Error {}, {:?}", e, text);
+                                    metrics::decode_error("crypto");
+                                    continue;
+                                }
+                            };
+
+                            let mut guard = shared.write().unwrap();
+                            (*guard).set_level_event(level_event);
+                            verified(&guard, &checksum)
+                        }
+                        "book.update" if is_ready => {
+                            let update_event: BookUpdateEventStream =
+                                match serde_json::from_str(&text) {
+                                    Ok(event) => event,
+                                    Err(e) => {
+                                        warn!("Error {}, {:?}", e, text);
+                                        metrics::decode_error("crypto");
+                                        continue;
+                                    }
+                                };
+
+                            let mut guard = shared.write().unwrap();
+                            (*guard)
+                                .add_update(update_event)
+                                .and_then(|_| verified(&guard, &checksum))
+                        }
+                        // Deltas before the fresh snapshot
+                        "book.update" => continue,
+                        other => {
+                            warn!("Unknown channel {}", other);
+                            continue;
+                        }
+                    };
+
+                    let snapshot = match result {
+                        Ok(snapshot) => snapshot,
+                        Err(e) => {
+                            warn!("{:?}, need a new snapshot", e);
+                            if let Ok(mut guard) = status.lock() {
+                                (*guard) = false;
+                            }
+                            state.send(ConnectionState::Resyncing(e.to_string()));
+
+                            if let Err(e) =
+                                crypto_resubscribe_book_update(&mut stream, channel.clone()).await
+                            {
+                                error!("Resubscribe error {:?}", e);
+                                break;
+                            }
+                            continue;
+                        }
+                    };
+
+                    if !is_ready {
+                        if let Ok(mut guard) = status.lock() {
+                            (*guard) = true;
+                        }
+                        info!("Overbook initialize success, now keep listening");
+                        state.send(ConnectionState::Connected);
+                    }
+
+                    metrics::book("crypto", &symbol, &snapshot);
+                    if sender.send(snapshot).is_err() {
+                        error!("depth send Snapshot error");
+                    }
+                }
+                metrics::reconnect("crypto");
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = false;
+                }
+                if !backoff.wait("crypto").await {
+                    break;
+                }
+            }
+        });
+
+        Ok(receiver)
     }
 
     fn depth(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
         let level_address = config.get_depth_addresses();
-        let symbol = config.get_symbol();
+        let channel = depth_channel_crypto(&config.get_symbol(), config.limit);
+        let checksum = config.checksum.clone();
 
         let shared = self.shared.clone();
         let status = self.status.clone();
+        let state = self.state.clone();
 
+        let symbol = config.get_symbol();
+        let reconnect = config.reconnect.clone();
         let (sender, receiver) = mpsc::unbounded_channel();
 
         let _ = tokio::spawn(async move {
             info!("Start Level Buffer maintain thread");
+            let mut backoff = Backoff::new(reconnect);
             loop {
                 let result: Result<()> = {
-                    let channel = format!("book.{}", &symbol);
-
-                    let mut stream = match crypto_initialize(&level_address, channel).await {
+                    let mut stream = match crypto_initialize(&level_address, channel.clone()).await {
                         Ok(connection) => connection,
                         Err(e) => {
                             warn!("connection error {:?}", e);
-                            sleep(Duration::from_millis(25)).await;
+                            if !backoff.wait("crypto").await {
+                                break;
+                            }
                             continue;
                         }
                     };
+                    backoff.connected();
 
                     if let Ok(mut guard) = status.lock() {
                         (*guard) = true;
                     }
+                    state.send(ConnectionState::Connected);
 
                     while let Ok(message) = stream.next().await.unwrap() {
                         match is_live_and_keep_alive::<OrderRespond>(&mut stream, message.clone())
@@ -80,21 +234,37 @@ impl DepthT for CryptoDepth {
                             Ok(event) => event,
                             Err(e) => {
                                 println!("Error {}, {:?}", e, text);
+                                metrics::decode_error("crypto");
                                 continue;
                             }
                         };
 
-                        if let Ok(mut guard) = shared.write() {
+                        let result = if let Ok(mut guard) = shared.write() {
                             (*guard).set_level_event(level_event);
-
-                            let snapshot = (*guard).get_snapshot();
-                            if let Err(_) = sender.send(snapshot) {
-                                error!("level_depth send Snapshot error");
-                            };
+                            verified(&guard, &checksum)
                         } else {
                             error!("SharedSpot is busy");
+                            continue;
+                        };
+
+                        match result {
+                            Ok(snapshot) => {
+                                metrics::book("crypto", &symbol, &snapshot);
+                                if sender.send(snapshot).is_err() {
+                                    error!("level_depth send Snapshot error");
+                                };
+                            }
+                            Err(e) => {
+                                warn!("{:?}, reconnecting", e);
+                                state.send(ConnectionState::Resyncing(e.to_string()));
+                                break;
+                            }
                         }
                     }
+
+                    if let Ok(mut guard) = status.lock() {
+                        (*guard) = false;
+                    }
                     Ok(())
                 };
 
@@ -102,6 +272,11 @@ impl DepthT for CryptoDepth {
                     Ok(_) => (),
                     Err(e) => error!("Error happen when running level_depth: {:?}", e),
                 }
+                metrics::reconnect("crypto");
+
+                if !backoff.wait("crypto").await {
+                    break;
+                }
             }
         });
 
@@ -123,32 +298,47 @@ impl DepthT for CryptoDepth {
             None
         }
     }
+
+    fn subscribe_state(&self) -> UnboundedReceiver<ConnectionState> {
+        self.state.subscribe()
+    }
+}
+
+/// Snapshot of `shared`, checked by the verifier configured with
+/// `DepthManager::with_checksum` if there is one
+fn verified(shared: &DepthShared, checksum: &Option<Arc<dyn ChecksumVerifier>>) -> Result<Depth> {
+    if let Some(verifier) = checksum {
+        shared.verify(verifier.as_ref())?;
+    }
+
+    Ok(shared.get_snapshot())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::config::Method;
-    use crate::config::{DepthConfig, SymbolType};
-    use crate::crypto::{CryptoDepth, DepthShared};
-    use crate::ExchangeType;
-    use std::sync::{Arc, Mutex, RwLock};
+    use crate::config::{DepthConfig, DepthType, SymbolType};
+    use crate::crypto::CryptoDepth;
+    use crate::{DepthT, ExchangeType, ReconnectPolicy};
     use tokio::runtime::Runtime;
 
     const LEVEL_DEPTH_URL: &str = "wss://stream.crypto.com/v2/market";
 
     #[test]
+    #[ignore = "requires live exchange connection"]
     fn crypto_order_book_function() {
         let config = DepthConfig {
-            rest_url: None,
-            depth_url: None,
-            level_depth_url: Some(LEVEL_DEPTH_URL.to_string()),
-            symbol_type: SymbolType::Spot(String::new()),
+            depth_url: DepthType::Depth(LEVEL_DEPTH_URL.to_string()),
+            symbol_type: SymbolType::Spot(String::from("BTC_USDT")),
             exchange_type: ExchangeType::Crypto,
+            limit: None,
+            checksum: None,
+            audit_interval: None,
+            reconnect: ReconnectPolicy::default(),
         };
 
         Runtime::new().unwrap().block_on(async {
             let book = CryptoDepth::new();
-            let mut recv = book.level_depth(config).unwrap();
+            let mut recv = book.depth(config).unwrap();
 
             let depth = recv.recv().await;
             assert!(depth.is_some());
@@ -0,0 +1,10 @@
+use crate::crypto::format::InstrumentsRespond;
+use crate::InstrumentInfo;
+use anyhow::Result;
+
+/// Instruments of `public/get-instruments`
+pub async fn fetch_instruments(address: String) -> Result<Vec<InstrumentInfo>> {
+    let respond: InstrumentsRespond = reqwest::get(&address).await?.json().await?;
+
+    respond.instruments()
+}
@@ -0,0 +1,121 @@
+use crate::config::KlineConfig;
+use crate::crypto::format::{CandlestickEventStream, OrderRespond};
+use crate::metrics;
+use crate::{Backoff, Bar};
+use anyhow::Result;
+use futures_util::StreamExt;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use tracing::{error, info, warn};
+
+use super::abstraction::{crypto_initialize, is_live_and_keep_alive};
+
+#[derive(Clone)]
+pub struct CryptoKline {
+    status: Arc<Mutex<bool>>,
+}
+
+impl CryptoKline {
+    pub fn new() -> Self {
+        CryptoKline {
+            status: Arc::new(Mutex::new(false)),
+        }
+    }
+
+    /// Crypto keeps pushing the current candlestick,
+    /// a bar is sent again with `closed: true` once a newer one shows up
+    pub fn connect(&self, config: KlineConfig) -> Result<UnboundedReceiver<Bar>> {
+        let kline_address = config.kline_url.clone();
+        let interval_ms = config.interval_ms;
+        let channel = config.get_channel()?;
+        let reconnect = config.reconnect.clone();
+
+        let status = self.status.clone();
+
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            info!("Start Kline maintain thread");
+            let mut backoff = Backoff::new(reconnect);
+            loop {
+                let mut stream = match crypto_initialize(&kline_address, channel.clone()).await {
+                    Ok(connection) => connection,
+                    Err(e) => {
+                        warn!("connection error {:?}", e);
+                        if !backoff.wait("crypto").await {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+                backoff.connected();
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = true;
+                }
+
+                let mut last: Option<Bar> = None;
+
+                while let Some(Ok(message)) = stream.next().await {
+                    match is_live_and_keep_alive::<OrderRespond>(&mut stream, message.clone())
+                        .await
+                    {
+                        Ok(true) => (),
+                        Ok(false) => continue,
+                        Err(e) => {
+                            warn!("Decoding received message error {:?}", e);
+                            continue;
+                        }
+                    }
+
+                    let text = message.into_text().unwrap();
+
+                    let event: CandlestickEventStream = match serde_json::from_str(&text) {
+                        Ok(event) => event,
+                        Err(e) => {
+                            warn!("Error {}, {:?}", e, text);
+                            metrics::decode_error("crypto");
+                            continue;
+                        }
+                    };
+
+                    for bar in event.result.add_timestamp_transform_to_bars(interval_ms) {
+                        if let Some(previous) = last {
+                            if bar.open_ts < previous.open_ts {
+                                continue;
+                            }
+
+                            if bar.open_ts > previous.open_ts {
+                                let closed = Bar {
+                                    closed: true,
+                                    ..previous
+                                };
+                                if sender.send(closed).is_err() {
+                                    error!("Crypto Kline send Bar error");
+                                }
+                            }
+                        }
+
+                        if sender.send(bar).is_err() {
+                            error!("Crypto Kline send Bar error");
+                        }
+                        last = Some(bar);
+                    }
+                }
+
+                metrics::reconnect("crypto");
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = false;
+                }
+                if !backoff.wait("crypto").await {
+                    break;
+                }
+            }
+        });
+
+        Ok(receiver)
+    }
+}
@@ -1,5 +1,7 @@
 mod abstraction;
 pub mod depth;
+pub(crate) mod instrument;
+pub mod kline;
 pub mod ticker;
 
 use tokio::net::TcpStream;
@@ -7,4 +9,5 @@ use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
 
 pub type CryptoWebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
 pub use depth::CryptoDepth;
+pub use kline::CryptoKline;
 pub use ticker::CryptoTicker;
@@ -1,12 +1,12 @@
 use crate::config::TickerConfig;
 use crate::crypto::format::TickerEventStream;
-use crate::Ticker;
+use crate::metrics;
+use crate::{Backoff, Ticker, TickerT};
 use anyhow::Result;
 use futures_util::StreamExt;
 use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::UnboundedReceiver;
-use tokio::time::{sleep, Duration};
 
 use tracing::{error, info, warn};
 
@@ -22,17 +22,21 @@ impl CryptoTicker {
             status: Arc::new(Mutex::new(false)),
         }
     }
+}
 
-    pub fn connect(&self, config: TickerConfig) -> Result<UnboundedReceiver<Vec<Ticker>>> {
+impl TickerT for CryptoTicker {
+    fn connect(&self, config: TickerConfig) -> Result<UnboundedReceiver<Vec<Ticker>>> {
         let level_address = config.ticker_url.clone();
         let symbol = config.get_symbol();
 
         let status = self.status.clone();
 
+        let reconnect = config.reconnect.clone();
         let (sender, receiver) = mpsc::unbounded_channel();
 
         let _ = tokio::spawn(async move {
             info!("Start Level Buffer maintain thread");
+            let mut backoff = Backoff::new(reconnect);
             loop {
                 let result: Result<()> = {
                     let channel = format!("trade.{}", &symbol);
@@ -40,10 +44,13 @@ impl CryptoTicker {
                         Ok(connection) => connection,
                         Err(e) => {
                             warn!("connection error {:?}", e);
-                            sleep(Duration::from_millis(25)).await;
+                            if !backoff.wait("crypto").await {
+                                break;
+                            }
                             continue;
                         }
                     };
+                    backoff.connected();
 
                     if let Ok(mut guard) = status.lock() {
                         (*guard) = true;
@@ -73,11 +80,13 @@ impl CryptoTicker {
                             Ok(event) => event,
                             Err(e) => {
                                 println!("Error {}, {:?}", e, text);
+                                metrics::decode_error("crypto");
                                 continue;
                             }
                         };
 
                         if let Some(ticks) = level_event.result.add_timestamp_transform_to_ticks() {
+                            metrics::trades("crypto", &symbol, &ticks);
                             if let Err(_) = sender.send(ticks) {
                                 error!("Crypto Ticker send Snapshot error");
                             };
@@ -85,6 +94,10 @@ impl CryptoTicker {
                             warn!("Crypto Received empty ticks")
                         }
                     }
+
+                    if let Ok(mut guard) = status.lock() {
+                        (*guard) = false;
+                    }
                     Ok(())
                 };
 
@@ -92,6 +105,11 @@ impl CryptoTicker {
                     Ok(_) => (),
                     Err(e) => error!("Error happen when running level_depth: {:?}", e),
                 }
+                metrics::reconnect("crypto");
+
+                if !backoff.wait("crypto").await {
+                    break;
+                }
             }
         });
 
@@ -103,17 +121,19 @@ impl CryptoTicker {
 mod tests {
     use crate::config::{Method, SymbolType, TickerConfig};
     use crate::crypto::connection::CryptoTicker;
-    use crate::ExchangeType;
+    use crate::{ExchangeType, ReconnectPolicy, TickerT};
     use std::sync::{Arc, Mutex, RwLock};
     use tokio::runtime::Runtime;
     const LEVEL_DEPTH_URL: &str = "wss://stream.crypto.com/v2/market";
 
     #[test]
+    #[ignore = "requires live exchange connection"]
     fn crypto_ticker_function() {
         let config = TickerConfig {
             ticker_url: LEVEL_DEPTH_URL.to_string(),
             symbol_type: SymbolType::Spot(String::from("BTCUSD-PERP")),
             exchange_type: ExchangeType::Crypto,
+            reconnect: ReconnectPolicy::default(),
         };
 
         tracing_subscriber::fmt::init();
@@ -1,20 +1,68 @@
-use crate::crypto::format::DepthEventStream;
-use crate::{Depth, Quote};
-use ordered_float::OrderedFloat;
-use serde::de::{SeqAccess, Visitor};
-use serde::{Deserialize, Deserializer};
-use std::collections::BTreeMap;
+use crate::crypto::format::{BookUpdateEventStream, DepthEventStream};
+use crate::{BookLevel, BookSide, ChecksumVerifier, Depth, OrderBook, Quote};
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
 use std::fmt;
 use std::fmt::Debug;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// `["price", "amount", "orders"]`,
+/// strings are kept as sent because the checksum is built from them
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "(String, String, String)")]
+pub struct Level(pub String, pub String, pub String);
+
+impl TryFrom<(String, String, String)> for Level {
+    type Error = String;
+
+    fn try_from((price, amount, orders): (String, String, String)) -> Result<Self, Self::Error> {
+        price
+            .parse::<f64>()
+            .map_err(|_| "Fail to convert price str to f64")?;
+        amount
+            .parse::<f64>()
+            .map_err(|_| "Fail to convert amount str to f64")?;
+        orders
+            .parse::<u64>()
+            .map_err(|_| "Fail to convert orders str to u64")?;
+
+        Ok(Level(price, amount, orders))
+    }
+}
+
+impl BookLevel for Level {
+    fn price(&self) -> f64 {
+        self.0.parse().unwrap_or_default()
+    }
+
+    fn amount(&self) -> f64 {
+        self.1.parse().unwrap_or_default()
+    }
+
+    fn quote(&self) -> Quote {
+        Quote {
+            price: self.price(),
+            amount: self.amount(),
+            orders: self.2.parse().ok(),
+        }
+    }
+}
+
+/// `(price, amount)` of `levels` as sent
+fn texts<'a>(levels: impl Iterator<Item = &'a Level>) -> Vec<(&'a str, &'a str)> {
+    levels
+        .map(|level| (level.0.as_str(), level.1.as_str()))
+        .collect()
+}
+
 pub struct DepthShared {
     instrument: String,
     last_update_id: i64,
+    /// `cs` of the last applied update
+    checksum: i64,
     send_time: i64,
     receive_time: i64,
-    asks: BTreeMap<OrderedFloat<f64>, f64>,
-    bids: BTreeMap<OrderedFloat<f64>, f64>,
+    book: OrderBook<Level>,
 }
 
 impl DepthShared {
@@ -22,78 +70,106 @@ impl DepthShared {
         DepthShared {
             instrument: String::new(),
             last_update_id: 0,
+            checksum: 0,
             send_time: 0,
             receive_time: 0,
-            asks: BTreeMap::new(),
-            bids: BTreeMap::new(),
+            book: OrderBook::new(),
         }
     }
 
-    /// Only used for "LevelEvent"
+    /// Used for "book" snapshots, each `data` entry is a whole book,
+    /// so only the latest one is kept.
+    ///
+    /// `last_update_id` becomes the update sequence `u`,
+    /// which `book.update` deltas are checked against
     pub fn set_level_event(&mut self, level_event: DepthEventStream) {
-        self.asks.clear();
-        self.bids.clear();
-
         let instrument = level_event.result.instrument_name;
-        let data = level_event.result.data;
-        let id = level_event.id;
-        let mut send_time = 0;
-        let data_len = data.len();
+        let latest = level_event
+            .result
+            .data
+            .into_iter()
+            .max_by_key(|data| (data.publish_time, data.update_sequence));
 
-        for DepthData {
+        let DepthData {
             asks,
             bids,
             publish_time,
+            update_sequence,
+            checksum,
+            ..
+        } = match latest {
+            Some(data) => data,
+            None => return,
+        };
+
+        self.book.apply_snapshot(bids, asks);
+
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        self.instrument = instrument;
+        self.last_update_id = update_sequence;
+        self.checksum = checksum;
+        self.send_time = publish_time;
+        self.receive_time = time.as_millis() as i64;
+    }
+
+    /// Err if the levels as crypto sent them do not pass `verifier`
+    /// against the last `cs`
+    pub fn verify(&self, verifier: &dyn ChecksumVerifier) -> Result<()> {
+        let bids = texts(self.book.bids());
+        let asks = texts(self.book.asks());
+
+        if !verifier.verify_text(&self.get_snapshot(), &bids, &asks, self.checksum) {
+            return Err(anyhow!(
+                "Checksum mismatch, order book {}, checksum {}",
+                self.last_update_id,
+                self.checksum
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Only used for "book.update",
+    /// Err if `pu` does not match the last applied `u`
+    pub fn add_update(&mut self, update_event: BookUpdateEventStream) -> Result<()> {
+        for BookUpdateData {
+            update,
+            publish_time,
+            update_sequence,
+            previous_update_sequence,
+            checksum,
             ..
-        } in data
+        } in update_event.result.data
         {
-            for ask in asks {
-                let ask_count = ask.order_numbers as usize;
-                for _ in 0..ask_count {
-                    self.asks.insert(OrderedFloat(ask.price), ask.amount);
-                }
+            if previous_update_sequence != self.last_update_id {
+                return Err(anyhow!(
+                    "Sequence gap, order book {}, update {}({})",
+                    self.last_update_id,
+                    update_sequence,
+                    previous_update_sequence
+                ));
             }
 
-            for bid in bids {
-                let bid_count = bid.order_numbers as usize;
-                for _ in 0..bid_count {
-                    self.bids.insert(OrderedFloat(bid.price), bid.amount);
-                }
-            }
-            send_time += publish_time;
+            self.book.apply_levels(BookSide::Ask, update.asks);
+            self.book.apply_levels(BookSide::Bid, update.bids);
+
+            self.last_update_id = update_sequence;
+            self.checksum = checksum;
+            self.send_time = self.send_time.max(publish_time);
         }
-        send_time /= data_len as i64;
 
         let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
-        self.instrument = instrument;
-        self.last_update_id = id;
-        self.send_time = send_time;
         self.receive_time = time.as_millis() as i64;
+
+        Ok(())
     }
 
     pub fn get_snapshot(&self) -> Depth {
         let id = self.last_update_id;
         let ts = self.send_time;
         let lts = self.receive_time;
-
-        let asks = self
-            .asks
-            .iter()
-            .map(|(price, amount)| Quote {
-                price: price.into_inner(),
-                amount: *amount,
-            })
-            .collect();
-
-        let bids = self
-            .bids
-            .iter()
-            .rev()
-            .map(|(price, amount)| Quote {
-                price: price.into_inner(),
-                amount: *amount,
-            })
-            .collect();
+        let asks = self.book.quotes(BookSide::Ask);
+        let bids = self.book.quotes(BookSide::Bid);
 
         Depth {
             id,
@@ -132,94 +208,62 @@ pub struct DepthData {
     #[serde(rename = "u")]
     pub update_sequence: i64,
 
+    /// CRC32 checksum of the order book after this update
     #[serde(rename = "cs")]
-    pub other: i64,
+    pub checksum: i64,
 
-    pub asks: Vec<Quotes>,
+    pub asks: Vec<Level>,
 
-    pub bids: Vec<Quotes>,
+    pub bids: Vec<Level>,
 }
 
-#[derive(Debug, Copy, Clone)]
-pub struct Quotes {
-    price: f64,
-    amount: f64,
-    order_numbers: i64,
-}
+#[allow(dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct BookUpdateEvent {
+    /// "book.update"
+    pub channel: String,
 
-impl<'de> Deserialize<'de> for Quotes {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        deserializer.deserialize_tuple(3, QuotesVisitor)
-    }
-}
+    pub subscription: String,
 
-struct QuotesVisitor;
+    /// Something like "BTC_USDT"
+    pub instrument_name: String,
 
-impl<'de> Visitor<'de> for QuotesVisitor {
-    type Value = Quotes;
+    pub data: Vec<BookUpdateData>,
 
-    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        write!(formatter, "a map with keys 'first' and 'second'")
-    }
+    /// Usually constant value `10` or `50`
+    pub depth: i64,
+}
 
-    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
-    where
-        A: SeqAccess<'de>,
-    {
-        let mut price = None;
-        let mut amount = None;
-        let mut order_numbers = None;
-
-        if let Some(val) = seq.next_element::<&str>()? {
-            match val.parse::<f64>() {
-                Ok(num) => price = Some(num),
-                Err(_) => return Err(serde::de::Error::custom("Fail to convert price str to f64")),
-            }
-        }
+#[allow(dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct BookUpdateData {
+    /// Some timestamp server tells us
+    #[serde(rename = "t")]
+    pub publish_time: i64,
 
-        if let Some(val) = seq.next_element::<&str>()? {
-            match val.parse::<f64>() {
-                Ok(num) => amount = Some(num),
-                Err(_) => {
-                    return Err(serde::de::Error::custom(
-                        "Fail to convert amount str to f64",
-                    ))
-                }
-            }
-        }
+    #[serde(rename = "tt")]
+    pub last_update_time: i64,
 
-        if let Some(val) = seq.next_element::<&str>()? {
-            match val.parse::<i64>() {
-                Ok(num) => order_numbers = Some(num),
-                Err(_) => {
-                    return Err(serde::de::Error::custom(
-                        "Fail to convert order_numbers str to u64",
-                    ))
-                }
-            }
-        }
+    #[serde(rename = "u")]
+    pub update_sequence: i64,
 
-        if price.is_none() {
-            return Err(serde::de::Error::custom("Missing price field"));
-        }
+    /// `u` of the previous update
+    #[serde(rename = "pu")]
+    pub previous_update_sequence: i64,
 
-        if amount.is_none() {
-            return Err(serde::de::Error::custom("Missing amount field"));
-        }
+    /// CRC32 checksum of the order book after this update
+    #[serde(rename = "cs")]
+    pub checksum: i64,
 
-        if order_numbers.is_none() {
-            return Err(serde::de::Error::custom("Missing order_numbers field"));
-        }
+    /// Changed levels, amount `0` means the level is removed
+    pub update: BookUpdate,
+}
 
-        Ok(Quotes {
-            price: price.unwrap(),
-            amount: amount.unwrap(),
-            order_numbers: order_numbers.unwrap(),
-        })
-    }
+#[derive(Deserialize, Debug, Clone)]
+pub struct BookUpdate {
+    pub asks: Vec<Level>,
+
+    pub bids: Vec<Level>,
 }
 
 impl Debug for DepthData {
@@ -230,7 +274,140 @@ impl Debug for DepthData {
             .field("update_sequence", &self.update_sequence)
             .field("asks", &self.asks.len())
             .field("bids", &self.bids.len())
-            .field("other", &self.other)
+            .field("checksum", &self.checksum)
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::format::depth::Level;
+    use crate::crypto::format::{BookUpdateEventStream, DepthEventStream, DepthShared};
+    use crate::{BookLevel, Crc32Checksum, Quote};
+
+    /// Shaped like a `book.BTC_USDT.10` frame with 3 levels,
+    /// `cs` computed by `Crc32Checksum` rather than by crypto.com
+    const LEVEL_BOOK: &str = r#"{"id":-1,"method":"subscribe","code":0,"result":{"instrument_name":"BTC_USDT","subscription":"book.BTC_USDT.10","channel":"book","depth":10,"data":[{"asks":[["16593.36","0.04950","1"],["16593.94","0.60000","2"],["16594.35","0.12054","1"]],"bids":[["16592.51","0.00602","1"],["16592.18","0.24000","3"],["16591.81","0.16000","1"]],"t":1672531205346,"tt":1672531205331,"u":167253120533100,"cs":-761941762}]}}"#;
+
+    /// Two snapshots in one frame, the second one is newer
+    const LEVEL_BOOK_BATCH: &str = r#"{"id":-1,"method":"subscribe","code":0,"result":{"instrument_name":"BTC_USDT","subscription":"book.BTC_USDT.10","channel":"book","depth":10,"data":[{"asks":[["16593.36","0.04950","1"]],"bids":[["16592.51","0.00602","1"]],"t":1672531205346,"tt":1672531205331,"u":167253120533100,"cs":1},{"asks":[["16593.40","0.50000","4"]],"bids":[["16592.00","1.00000","2"]],"t":1672531205446,"tt":1672531205431,"u":167253120543100,"cs":2}]}}"#;
+
+    /// `cs` computed by `Crc32Checksum`, as of `UPDATE`
+    const BOOK: &str = r#"{"id":-1,"method":"subscribe","code":0,"result":{"instrument_name":"BTC_USDT","subscription":"book.BTC_USDT.10","channel":"book","depth":10,"data":[{"asks":[["16630.00","0.50","2"],["16631.00","1.20","1"]],"bids":[["16629.00","0.80","3"],["16628.50","2.00","1"]],"t":1672531200100,"tt":1672531200090,"u":1000,"cs":-558264139}]}}"#;
+
+    const UPDATE: &str = r#"{"id":-1,"method":"subscribe","code":0,"result":{"instrument_name":"BTC_USDT","subscription":"book.BTC_USDT.10","channel":"book.update","depth":10,"data":[{"update":{"asks":[["16630.00","0","0"],["16632.00","0.30","1"]],"bids":[["16629.00","1.10","4"]]},"t":1672531200200,"tt":1672531200190,"u":1010,"pu":1000,"cs":1565140857}]}}"#;
+
+    const GAP: &str = r#"{"id":-1,"method":"subscribe","code":0,"result":{"instrument_name":"BTC_USDT","subscription":"book.BTC_USDT.10","channel":"book.update","depth":10,"data":[{"update":{"asks":[],"bids":[]},"t":1672531200300,"tt":1672531200290,"u":1030,"pu":1020,"cs":0}]}}"#;
+
+    #[test]
+    fn level_deserialize() {
+        let level: Level = serde_json::from_str(r#"["16593.36","0.04950","3"]"#).unwrap();
+        assert_eq!(level.0, "16593.36");
+        assert_eq!(level.1, "0.04950");
+        assert_eq!(
+            level.quote(),
+            Quote {
+                price: 16593.36,
+                amount: 0.0495,
+                orders: Some(3),
+            }
+        );
+
+        // Binance style [price, amount]
+        assert!(serde_json::from_str::<Level>(r#"["16593.36","0.04950"]"#).is_err());
+        assert!(serde_json::from_str::<Level>(r#"["16593.36","0.1","x"]"#).is_err());
+    }
+
+    #[test]
+    fn level_book_deserialize() {
+        let event: DepthEventStream = serde_json::from_str(LEVEL_BOOK).unwrap();
+        assert_eq!(event.id, -1);
+        assert_eq!(event.result.data.len(), 1);
+        assert_eq!(event.result.data[0].update_sequence, 167253120533100);
+
+        let mut shared = DepthShared::new();
+        shared.set_level_event(event);
+        shared.verify(&Crc32Checksum::new(10)).unwrap();
+
+        let depth = shared.get_snapshot();
+        assert_eq!(depth.id, 167253120533100);
+        assert_eq!(depth.ts, 1672531205346);
+        assert_eq!(depth.asks.len(), 3);
+        assert_eq!(depth.bids.len(), 3);
+        assert_eq!(
+            depth.asks[1],
+            Quote {
+                price: 16593.94,
+                amount: 0.6,
+                orders: Some(2),
+            }
+        );
+        assert_eq!(
+            depth.bids[1],
+            Quote {
+                price: 16592.18,
+                amount: 0.24,
+                orders: Some(3),
+            }
+        );
+        assert!(depth.bids[0].price > depth.bids[1].price);
+    }
+
+    #[test]
+    fn level_book_keeps_latest_data() {
+        let event: DepthEventStream = serde_json::from_str(LEVEL_BOOK_BATCH).unwrap();
+
+        let mut shared = DepthShared::new();
+        shared.set_level_event(event);
+
+        let depth = shared.get_snapshot();
+        assert_eq!(depth.id, 167253120543100);
+        assert_eq!(depth.ts, 1672531205446);
+        assert_eq!(depth.asks.len(), 1);
+        assert_eq!(depth.asks[0].orders, Some(4));
+        assert_eq!(depth.bids[0].price, 16592.0);
+    }
+
+    #[test]
+    fn book_update_sequence() {
+        let mut shared = DepthShared::new();
+
+        let snapshot: DepthEventStream = serde_json::from_str(BOOK).unwrap();
+        shared.set_level_event(snapshot);
+        assert_eq!(shared.get_snapshot().id, 1000);
+        shared.verify(&Crc32Checksum::new(10)).unwrap();
+
+        let update: BookUpdateEventStream = serde_json::from_str(UPDATE).unwrap();
+        assert_eq!(update.result.channel, "book.update");
+        shared.add_update(update).unwrap();
+        shared.verify(&Crc32Checksum::new(10)).unwrap();
+
+        let depth = shared.get_snapshot();
+        assert_eq!(depth.id, 1010);
+        assert_eq!(depth.ts, 1672531200200);
+        assert_eq!(depth.asks.len(), 2);
+        assert_eq!(depth.asks[0].price, 16631.0);
+        assert_eq!(depth.asks[1].price, 16632.0);
+        assert_eq!(depth.bids[0].price, 16629.0);
+        assert_eq!(depth.bids[0].amount, 1.1);
+        assert_eq!(depth.bids[0].orders, Some(4));
+
+        let gap: BookUpdateEventStream = serde_json::from_str(GAP).unwrap();
+        assert!(shared.add_update(gap).is_err());
+        assert_eq!(shared.get_snapshot().id, 1010);
+    }
+
+    #[test]
+    fn checksum_mismatch() {
+        let mut shared = DepthShared::new();
+
+        // Unsigned `cs` of the same book
+        let unsigned = BOOK.replace("-558264139", "3736703157");
+        shared.set_level_event(serde_json::from_str(&unsigned).unwrap());
+        shared.verify(&Crc32Checksum::new(10)).unwrap();
+
+        let wrong = BOOK.replace("-558264139", "-558264138");
+        shared.set_level_event(serde_json::from_str(&wrong).unwrap());
+        assert!(shared.verify(&Crc32Checksum::new(10)).is_err());
+    }
+}
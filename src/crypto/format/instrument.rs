@@ -0,0 +1,129 @@
+use crate::{InstrumentInfo, InstrumentKind, InstrumentStatus};
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use tracing::debug;
+
+/// Respond of REST `public/get-instruments`
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+pub struct InstrumentsRespond {
+    pub id: i64,
+
+    pub code: i64,
+
+    #[serde(default)]
+    pub result: Option<InstrumentsResult>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct InstrumentsResult {
+    pub data: Vec<InstrumentData>,
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+pub struct InstrumentData {
+    /// Something like "BTC_USDT" / "BTCUSD-PERP"
+    pub symbol: String,
+
+    /// "CCY_PAIR" / "PERPETUAL_SWAP" / "FUTURE"
+    pub inst_type: String,
+
+    pub price_tick_size: String,
+
+    pub qty_tick_size: String,
+
+    #[serde(default)]
+    pub contract_size: Option<String>,
+
+    pub tradable: bool,
+
+    /// Milliseconds, `0` if the instrument does not expire
+    #[serde(default)]
+    pub expiry_timestamp_ms: i64,
+}
+
+impl InstrumentData {
+    /// `None` for instrument types other than spot, perpetuals and futures
+    pub fn kind(&self) -> Option<InstrumentKind> {
+        match self.inst_type.as_str() {
+            "CCY_PAIR" => Some(InstrumentKind::Spot),
+            "PERPETUAL_SWAP" => Some(InstrumentKind::Perpetual),
+            "FUTURE" => Some(InstrumentKind::Future),
+            _ => None,
+        }
+    }
+
+    pub fn instrument(&self, kind: InstrumentKind) -> Result<InstrumentInfo> {
+        let contract_size = match &self.contract_size {
+            Some(size) => Some(size.parse()?),
+            None => None,
+        };
+
+        Ok(InstrumentInfo {
+            name: self.symbol.clone(),
+            kind,
+            tick_size: self.price_tick_size.parse()?,
+            lot_size: self.qty_tick_size.parse()?,
+            min_notional: None,
+            contract_size,
+            expiry: (self.expiry_timestamp_ms > 0).then_some(self.expiry_timestamp_ms),
+            status: if self.tradable {
+                InstrumentStatus::Trading
+            } else {
+                InstrumentStatus::Halted
+            },
+        })
+    }
+}
+
+impl InstrumentsRespond {
+    pub fn instruments(&self) -> Result<Vec<InstrumentInfo>> {
+        let result = match (self.code, &self.result) {
+            (0, Some(result)) => result,
+            _ => return Err(anyhow!("Crypto get-instruments error code {}", self.code)),
+        };
+
+        let mut instruments = Vec::new();
+        for data in &result.data {
+            match data.kind() {
+                Some(kind) => instruments.push(data.instrument(kind)?),
+                None => debug!("Skip {} of type {}", data.symbol, data.inst_type),
+            }
+        }
+
+        Ok(instruments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::format::InstrumentsRespond;
+    use crate::{InstrumentKind, InstrumentStatus};
+
+    /// Recorded from `public/get-instruments`, trimmed to 3 instruments
+    const INSTRUMENTS: &str = r#"{"id":1,"method":"public/get-instruments","code":0,"result":{"data":[{"symbol":"BTC_USDT","inst_type":"CCY_PAIR","display_name":"BTC/USDT","base_ccy":"BTC","quote_ccy":"USDT","quote_decimals":2,"quantity_decimals":5,"price_tick_size":"0.01","qty_tick_size":"0.00001","max_leverage":"50","tradable":true,"expiry_timestamp_ms":0},{"symbol":"BTCUSD-PERP","inst_type":"PERPETUAL_SWAP","display_name":"BTCUSD Perpetual","base_ccy":"BTC","quote_ccy":"USD","quote_decimals":1,"quantity_decimals":4,"price_tick_size":"0.1","qty_tick_size":"0.0001","max_leverage":"100","tradable":true,"expiry_timestamp_ms":0,"underlying_symbol":"BTCUSD-INDEX","contract_size":"1"},{"symbol":"BTCUSD-230127","inst_type":"FUTURE","display_name":"BTCUSD Futures 20230127","base_ccy":"BTC","quote_ccy":"USD","quote_decimals":1,"quantity_decimals":4,"price_tick_size":"0.5","qty_tick_size":"0.0001","max_leverage":"100","tradable":false,"expiry_timestamp_ms":1674806400000,"underlying_symbol":"BTCUSD-INDEX","contract_size":"1"}]}}"#;
+
+    #[test]
+    fn instruments_deserialize() {
+        let respond: InstrumentsRespond = serde_json::from_str(INSTRUMENTS).unwrap();
+        let instruments = respond.instruments().unwrap();
+
+        assert_eq!(instruments.len(), 3);
+        assert_eq!(instruments[0].name, "BTC_USDT");
+        assert_eq!(instruments[0].lot_size, 0.00001);
+        assert_eq!(instruments[0].contract_size, None);
+        assert_eq!(instruments[0].kind, InstrumentKind::Spot);
+        assert_eq!(instruments[1].kind, InstrumentKind::Perpetual);
+        assert_eq!(instruments[1].tick_size, 0.1);
+        assert_eq!(instruments[1].contract_size, Some(1.0));
+        assert_eq!(instruments[1].expiry, None);
+        assert_eq!(instruments[2].expiry, Some(1674806400000));
+        assert_eq!(instruments[2].status, InstrumentStatus::Halted);
+
+        let error: InstrumentsRespond =
+            serde_json::from_str(r#"{"id":1,"method":"public/get-instruments","code":10004}"#)
+                .unwrap();
+        assert!(error.instruments().is_err());
+    }
+}
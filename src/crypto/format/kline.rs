@@ -0,0 +1,117 @@
+use crate::Bar;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[allow(dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct CandlestickEvent {
+    pub channel: String,
+
+    pub subscription: String,
+
+    /// Something like "BTC_USDT"
+    pub instrument_name: String,
+
+    /// Something like "1m" or "1D"
+    pub interval: String,
+
+    pub data: Vec<CandlestickData>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CandlestickData {
+    /// Start time of the candlestick
+    #[serde(rename = "t")]
+    pub start_time: i64,
+
+    #[serde(rename = "o", deserialize_with = "number_or_str")]
+    pub open: f64,
+
+    #[serde(rename = "h", deserialize_with = "number_or_str")]
+    pub high: f64,
+
+    #[serde(rename = "l", deserialize_with = "number_or_str")]
+    pub low: f64,
+
+    #[serde(rename = "c", deserialize_with = "number_or_str")]
+    pub close: f64,
+
+    #[serde(rename = "v", deserialize_with = "number_or_str")]
+    pub volume: f64,
+}
+
+/// Crypto sends prices as numbers in some versions and as strings in others
+fn number_or_str<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrStr<'a> {
+        Number(f64),
+        Str(&'a str),
+    }
+
+    match NumberOrStr::deserialize(deserializer)? {
+        NumberOrStr::Number(num) => Ok(num),
+        NumberOrStr::Str(val) => val
+            .parse::<f64>()
+            .map_err(|_| D::Error::custom("Fail to convert str to f64")),
+    }
+}
+
+impl CandlestickEvent {
+    /// Crypto does not tell whether a candlestick is final,
+    /// so all bars are marked as not closed here
+    pub fn add_timestamp_transform_to_bars(&self, interval_ms: i64) -> Vec<Bar> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let lts = now.as_millis() as i64;
+
+        let mut bars: Vec<Bar> = self
+            .data
+            .iter()
+            .map(|data| Bar {
+                open_ts: data.start_time,
+                close_ts: data.start_time + interval_ms - 1,
+                lts,
+                open: data.open,
+                high: data.high,
+                low: data.low,
+                close: data.close,
+                volume: data.volume,
+                trades: None,
+                closed: false,
+            })
+            .collect();
+
+        bars.sort_by_key(|bar| bar.open_ts);
+        bars
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::format::CandlestickEventStream;
+
+    const CANDLESTICK: &str = r#"{"id":-1,"method":"subscribe","code":0,"result":{"instrument_name":"BTC_USDT","subscription":"candlestick.1m.BTC_USDT","channel":"candlestick","interval":"1m","data":[{"o":"16590.01","h":"16592.10","l":"16588.00","c":"16591.50","v":"1.2345","t":1672531260000,"ut":1672531275123},{"o":16580.0,"h":16590.5,"l":16579.0,"c":16590.01,"v":3.5,"t":1672531200000}]}}"#;
+
+    #[test]
+    fn candlestick_deserialize() {
+        let event: CandlestickEventStream = serde_json::from_str(CANDLESTICK).unwrap();
+        assert_eq!(event.result.interval, "1m");
+
+        let bars = event.result.add_timestamp_transform_to_bars(60_000);
+        assert_eq!(bars.len(), 2);
+
+        assert_eq!(bars[0].open_ts, 1672531200000);
+        assert_eq!(bars[0].close_ts, 1672531259999);
+        assert_eq!(bars[0].volume, 3.5);
+
+        assert_eq!(bars[1].open, 16590.01);
+        assert_eq!(bars[1].high, 16592.10);
+        assert_eq!(bars[1].low, 16588.0);
+        assert_eq!(bars[1].close, 16591.5);
+        assert_eq!(bars[1].trades, None);
+    }
+}
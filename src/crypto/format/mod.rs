@@ -1,4 +1,6 @@
 mod depth;
+mod instrument;
+mod kline;
 mod request;
 mod respond;
 mod stream;
@@ -7,10 +9,14 @@ mod ticker;
 pub use depth::DepthData;
 pub use depth::DepthEvent;
 pub use depth::DepthShared;
-pub use depth::Quotes;
+pub use instrument::InstrumentsRespond;
 pub use request::subscribe_message;
+pub use request::{subscribe_book_update_message, unsubscribe_message};
 pub use request::HeartbeatRequest;
 pub use respond::heartbeat_respond;
 pub use respond::GeneralRespond;
 pub use respond::OrderRespond;
-pub use stream::{DepthEventStream, TickerEventStream};
+pub use stream::{
+    BookUpdateEventStream, CandlestickEventStream, DepthEventStream, StreamChannel,
+    TickerEventStream,
+};
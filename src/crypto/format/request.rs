@@ -19,6 +19,14 @@ pub struct OrderRequest {
 #[derive(Deserialize, Serialize)]
 pub struct Params {
     pub channels: Vec<String>,
+
+    /// "SNAPSHOT_AND_UPDATE" for `book.update` deltas
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub book_subscription_type: Option<String>,
+
+    /// Delta interval in milliseconds, `10` or `100`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub book_update_frequency: Option<i64>,
 }
 
 pub fn subscribe_message(channel: String) -> String {
@@ -28,6 +36,36 @@ pub fn subscribe_message(channel: String) -> String {
         method: String::from("subscribe"),
         params: Params {
             channels: vec![channel],
+            book_subscription_type: None,
+            book_update_frequency: None,
+        },
+    };
+    serde_json::to_string(&inner).unwrap()
+}
+
+/// Subscribe to `book.{instrument}.{depth}` as one snapshot
+/// followed by `book.update` deltas
+pub fn subscribe_book_update_message(channel: String) -> String {
+    let inner = OrderRequest {
+        id: 1,
+        method: String::from("subscribe"),
+        params: Params {
+            channels: vec![channel],
+            book_subscription_type: Some(String::from("SNAPSHOT_AND_UPDATE")),
+            book_update_frequency: Some(10),
+        },
+    };
+    serde_json::to_string(&inner).unwrap()
+}
+
+pub fn unsubscribe_message(channel: String) -> String {
+    let inner = OrderRequest {
+        id: 2,
+        method: String::from("unsubscribe"),
+        params: Params {
+            channels: vec![channel],
+            book_subscription_type: None,
+            book_update_frequency: None,
         },
     };
     serde_json::to_string(&inner).unwrap()
@@ -1,4 +1,5 @@
-use crate::crypto::format::depth::DepthEvent;
+use crate::crypto::format::depth::{BookUpdateEvent, DepthEvent};
+use crate::crypto::format::kline::CandlestickEvent;
 use crate::crypto::format::ticker::TickerEvent;
 use serde::Deserialize;
 
@@ -16,6 +17,32 @@ pub struct DepthEventStream {
     pub result: DepthEvent,
 }
 
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+pub struct BookUpdateEventStream {
+    /// Usually constant value `-1`
+    pub id: i64,
+
+    /// Something like: "subscribe"
+    pub method: String,
+
+    /// Usually constant value `0`
+    pub code: i64,
+
+    pub result: BookUpdateEvent,
+}
+
+/// Only used to tell "book" snapshots from "book.update" deltas
+#[derive(Deserialize, Debug)]
+pub struct StreamChannel {
+    pub result: ChannelResult,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ChannelResult {
+    pub channel: String,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct TickerEventStream {
     /// Usually constant value `-1`
@@ -29,3 +56,17 @@ pub struct TickerEventStream {
 
     pub result: TickerEvent,
 }
+
+#[derive(Deserialize, Debug)]
+pub struct CandlestickEventStream {
+    /// Usually constant value `-1`
+    pub id: i64,
+
+    /// Something like: "subscribe"
+    pub method: String,
+
+    /// Usually constant value `0`
+    pub code: i64,
+
+    pub result: CandlestickEvent,
+}
@@ -1,6 +1,10 @@
+mod adapter;
 pub mod connection;
 pub mod format;
 
+pub use adapter::CryptoAdapter;
+
 pub use connection::CryptoDepth;
+pub use connection::CryptoKline;
 pub use connection::CryptoTicker;
 pub use format::DepthShared;
@@ -0,0 +1,58 @@
+use crate::config::{instrument_deribit, symbol_type_deribit, Instrument};
+use crate::config::{set_addr_for_deribit, set_ticker_addr_for_deribit, validate_symbol_deribit};
+use crate::config::{DepthType, SymbolType};
+use crate::deribit::{DeribitDepth, DeribitTicker};
+use crate::{DepthConfig, DepthT, ExchangeAdapter, ExchangeType, TickerConfig, TickerT};
+use anyhow::{anyhow, Result};
+use std::sync::Arc;
+
+/// Perpetuals, dated futures and options of Deribit, all coin-margined
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DeribitAdapter;
+
+impl ExchangeAdapter for DeribitAdapter {
+    fn name(&self) -> &'static str {
+        "deribit"
+    }
+
+    fn exchange_type(&self) -> ExchangeType {
+        ExchangeType::Deribit
+    }
+
+    fn validate_symbol(&self, symbol: &str, _limit: Option<i32>) -> Result<SymbolType> {
+        validate_symbol_deribit(symbol)
+    }
+
+    fn depth_url(&self, symbol_type: &SymbolType, limit: Option<i32>) -> Result<DepthType> {
+        let instrument = match symbol_type {
+            SymbolType::ContractCoin(s) => s,
+            SymbolType::Option(option) => &option.name,
+            other => return Err(anyhow!("Unsupported symbol {:?} for deribit", other)),
+        };
+        let (rest_address, depth_address, level_depth_address) =
+            set_addr_for_deribit(instrument, limit);
+
+        DepthType::new(rest_address, depth_address, level_depth_address)
+            .ok_or_else(|| anyhow!("depth url is empty"))
+    }
+
+    fn ticker_url(&self, _symbol_type: &SymbolType) -> Result<String> {
+        Ok(set_ticker_addr_for_deribit())
+    }
+
+    fn depth_connection(&self, _config: &DepthConfig) -> Result<Arc<dyn DepthT + Send + Sync>> {
+        Ok(Arc::new(DeribitDepth::new()))
+    }
+
+    fn ticker_connection(&self, _config: &TickerConfig) -> Result<Arc<dyn TickerT + Send + Sync>> {
+        Ok(Arc::new(DeribitTicker::new()))
+    }
+
+    fn native_symbol(&self, instrument: &Instrument) -> Result<SymbolType> {
+        symbol_type_deribit(instrument)
+    }
+
+    fn instrument_of(&self, symbol_type: &SymbolType) -> Result<Instrument> {
+        instrument_deribit(symbol_type)
+    }
+}
@@ -0,0 +1,145 @@
+use crate::api::driver::BookFeed;
+use crate::deribit::connection::DeribitWebSocket;
+use crate::deribit::format::{
+    set_heartbeat_message, subscribe_message, test_message, unsubscribe_message, Notification,
+    RpcMessage,
+};
+use crate::metrics;
+use crate::RawFrames;
+use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
+use futures_util::{SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+use tokio::time::timeout;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, warn};
+use url::Url;
+
+/// Seconds between the `test_request` heartbeats of Deribit
+const HEARTBEAT_INTERVAL: u64 = 30;
+
+/// Deribit sends something at least every `HEARTBEAT_INTERVAL`
+const QUIET_TIMEOUT: Duration = Duration::from_secs(2 * HEARTBEAT_INTERVAL);
+
+pub async fn deribit_initialize(address: &str, channel: &str) -> Result<DeribitWebSocket> {
+    let url = Url::parse(address)?;
+
+    let mut stream = match connect_async(url).await {
+        Ok((connection, _)) => connection,
+        Err(e) => return Err(anyhow!("{:?}", e)),
+    };
+    debug!("Connect to {} success", address);
+
+    stream
+        .send(Message::from(set_heartbeat_message(HEARTBEAT_INTERVAL)))
+        .await?;
+    stream
+        .send(Message::from(subscribe_message(channel)))
+        .await?;
+
+    debug!("Subscribe to {} success", channel);
+
+    Ok(stream)
+}
+
+/// Subscribe again on the same connection to get a fresh snapshot
+pub async fn deribit_resubscribe(stream: &mut DeribitWebSocket, channel: &str) -> Result<()> {
+    stream
+        .send(Message::from(unsubscribe_message(channel)))
+        .await?;
+    stream
+        .send(Message::from(subscribe_message(channel)))
+        .await?;
+
+    debug!("Resubscribe to {} success", channel);
+
+    Ok(())
+}
+
+/// Next `subscription` notification, keeping the connection alive on the way:
+/// `test_request` heartbeats are answered with `public/test`,
+/// Err if the stream is quiet for `QUIET_TIMEOUT`.
+///
+/// Responds are skipped, Err for failed ones
+pub async fn next_push(stream: &mut DeribitWebSocket) -> Result<Notification> {
+    loop {
+        let message = match timeout(QUIET_TIMEOUT, stream.next()).await {
+            Ok(Some(message)) => message?,
+            Ok(None) => return Err(anyhow!("Connection closed")),
+            Err(_) => return Err(anyhow!("Nothing received in {:?}", QUIET_TIMEOUT)),
+        };
+
+        let text = match message {
+            Message::Text(text) => {
+                RawFrames::global().publish("deribit", &text);
+                text
+            }
+            Message::Ping(payload) => {
+                stream.send(Message::Pong(payload)).await?;
+                continue;
+            }
+            Message::Close(frame) => return Err(anyhow!("Connection closed {:?}", frame)),
+            _ => continue,
+        };
+
+        let message: RpcMessage = serde_json::from_str(&text)?;
+
+        if let Some(error) = message.error {
+            return Err(anyhow!(
+                "Deribit error of request {:?}, {}: {}",
+                message.id,
+                error.code,
+                error.message
+            ));
+        }
+
+        if message.is_test_request() {
+            stream.send(Message::from(test_message())).await?;
+            debug!("Answer test_request");
+            continue;
+        }
+
+        match (message.method.as_deref(), message.params) {
+            (Some("subscription"), Some(params)) => return Ok(serde_json::from_value(params)?),
+            _ => debug!("Skip {}", text),
+        }
+    }
+}
+
+/// Connection subscribed to the book `channel`
+pub struct DeribitStream {
+    stream: DeribitWebSocket,
+    channel: String,
+}
+
+impl DeribitStream {
+    pub async fn connect(address: &str, channel: String) -> Result<Self> {
+        let stream = deribit_initialize(address, &channel).await?;
+
+        Ok(DeribitStream { stream, channel })
+    }
+}
+
+impl BookFeed for DeribitStream {
+    /// `data` of the next notification
+    fn next<T: DeserializeOwned + Send + 'static>(&mut self) -> BoxFuture<'_, Result<T>> {
+        Box::pin(async move {
+            loop {
+                let notification = next_push(&mut self.stream).await?;
+                match serde_json::from_value(notification.data) {
+                    Ok(data) => return Ok(data),
+                    Err(e) => {
+                        warn!("Error {} of {}", e, notification.channel);
+                        metrics::decode_error("deribit");
+                    }
+                }
+            }
+        })
+    }
+
+    fn resubscribe(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(deribit_resubscribe(&mut self.stream, &self.channel))
+    }
+}
@@ -0,0 +1,224 @@
+use crate::api::driver::{BookDriver, Connect};
+use crate::config::depth_channel_deribit;
+use crate::deribit::connection::abstraction::DeribitStream;
+use crate::deribit::format::{BookData, BookShared};
+use crate::{ConnectionState, Depth, DepthConfig, DepthT};
+use anyhow::Result;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tracing::{debug, warn};
+
+/// Levels sent in Level Mode
+const LEVEL_DEPTH: usize = 20;
+
+#[derive(Clone)]
+pub struct DeribitDepth {
+    driver: BookDriver<BookShared>,
+    /// Levels of each side in `snapshot()`
+    levels: Arc<Mutex<usize>>,
+}
+
+/// Connect to `address` and subscribe `channel`
+fn connect(address: String, channel: String) -> Connect<DeribitStream> {
+    Box::new(move || {
+        let address = address.clone();
+        let channel = channel.clone();
+        Box::pin(async move { DeribitStream::connect(&address, channel).await })
+    })
+}
+
+/// Put a snapshot or change of `book.{instrument}.100ms` onto `shared`,
+/// giving the best `levels` of each side.
+///
+/// Err once `prev_change_id` breaks the chain
+fn apply(
+    shared: &mut BookShared,
+    data: BookData,
+    is_ready: bool,
+    levels: usize,
+) -> Option<Result<Depth>> {
+    match data.ttype.as_str() {
+        "snapshot" => Some(
+            shared
+                .set_snapshot(data)
+                .map(|_| shared.get_snapshot(levels)),
+        ),
+        "change" if is_ready => Some(shared.add_change(data).map(|_| shared.get_snapshot(levels))),
+        // Changes before the fresh snapshot
+        "change" => {
+            debug!("Skip change before snapshot");
+            None
+        }
+        other => {
+            warn!("Unknown type {}", other);
+            None
+        }
+    }
+}
+
+impl DeribitDepth {
+    /// Keep the book in line with `channel`,
+    /// sending the best `levels` of each side after every notification
+    fn maintain(
+        &self,
+        address: String,
+        levels: usize,
+        config: DepthConfig,
+    ) -> UnboundedReceiver<Depth> {
+        *self.levels.lock().unwrap() = levels;
+        let channel = depth_channel_deribit(&config.get_symbol());
+
+        self.driver.clone().pushed_book(
+            connect(address, channel),
+            config.get_symbol(),
+            config.reconnect,
+            move |shared, data, is_ready| apply(shared, data, is_ready, levels),
+        )
+    }
+}
+
+impl DepthT for DeribitDepth {
+    fn new() -> Self {
+        DeribitDepth {
+            driver: BookDriver::new("deribit", BookShared::new()),
+            levels: Arc::new(Mutex::new(LEVEL_DEPTH)),
+        }
+    }
+
+    /// acquire the best `limit` levels of the whole book
+    fn depth_snapshot(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
+        let (_, depth_address) = config.get_depth_snapshot_addresses();
+        let levels = config.limit.map_or(LEVEL_DEPTH, |limit| limit as usize);
+
+        Ok(self.maintain(depth_address, levels, config))
+    }
+
+    /// acquire the best 20 levels of the whole book
+    fn depth(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
+        let level_address = config.get_depth_addresses();
+
+        Ok(self.maintain(level_address, LEVEL_DEPTH, config))
+    }
+
+    /// Get the snapshot of the current Order Book
+    fn snapshot(&self) -> Option<Depth> {
+        let levels = *self.levels.lock().unwrap();
+        self.driver.snapshot(|shared| shared.get_snapshot(levels))
+    }
+
+    fn subscribe_state(&self) -> UnboundedReceiver<ConnectionState> {
+        self.driver.subscribe_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{DepthConfig, DepthType, SymbolType};
+    use crate::deribit::connection::DeribitDepth;
+    use crate::{ConnectionState, DepthT, ExchangeType, ReconnectPolicy};
+    use futures_util::{SinkExt, StreamExt};
+    use std::time::Duration;
+    use tokio::net::TcpListener;
+    use tokio::runtime::Runtime;
+    use tokio::time::timeout;
+    use tokio_tungstenite::accept_async;
+    use tokio_tungstenite::tungstenite::Message;
+
+    const REST: &str = "https://www.deribit.com/api/v2/public/get_order_book?instrument_name=BTC-PERPETUAL&depth=10";
+
+    const SUBSCRIBED: &str = r#"{"jsonrpc":"2.0","id":2,"result":["book.BTC-PERPETUAL.100ms"],"usIn":1554373962450,"usOut":1554373962452,"usDiff":2,"testnet":false}"#;
+
+    const TEST_REQUEST: &str =
+        r#"{"jsonrpc":"2.0","method":"heartbeat","params":{"type":"test_request"}}"#;
+
+    const SNAPSHOT: &str = r#"{"jsonrpc":"2.0","method":"subscription","params":{"channel":"book.BTC-PERPETUAL.100ms","data":{"type":"snapshot","timestamp":1554373962454,"instrument_name":"BTC-PERPETUAL","change_id":297217,"bids":[["new",5042.34,30.0],["new",5041.94,20.0]],"asks":[["new",5042.64,40.0],["new",5043.3,40.0]]}}}"#;
+
+    const CHANGE: &str = r#"{"jsonrpc":"2.0","method":"subscription","params":{"channel":"book.BTC-PERPETUAL.100ms","data":{"type":"change","timestamp":1554373962554,"prev_change_id":297217,"instrument_name":"BTC-PERPETUAL","change_id":297218,"bids":[["delete",5041.94,0.0]],"asks":[["change",5042.64,35.0]]}}}"#;
+
+    const GAP: &str = r#"{"jsonrpc":"2.0","method":"subscription","params":{"channel":"book.BTC-PERPETUAL.100ms","data":{"type":"change","timestamp":1554373962754,"prev_change_id":297220,"instrument_name":"BTC-PERPETUAL","change_id":297221,"bids":[],"asks":[]}}}"#;
+
+    async fn next_text<S>(ws: &mut S) -> String
+    where
+        S: StreamExt<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+    {
+        ws.next().await.unwrap().unwrap().into_text().unwrap()
+    }
+
+    #[test]
+    fn book_from_mock_server() {
+        Runtime::new().unwrap().block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let address = format!("ws://{}", listener.local_addr().unwrap());
+
+            let server = tokio::spawn(async move {
+                let (socket, _) = listener.accept().await.unwrap();
+                let mut ws = accept_async(socket).await.unwrap();
+
+                assert!(next_text(&mut ws).await.contains("public/set_heartbeat"));
+                let request = next_text(&mut ws).await;
+                assert!(request.contains("public/subscribe"));
+                assert!(request.contains("book.BTC-PERPETUAL.100ms"));
+
+                ws.send(Message::from(SUBSCRIBED)).await.unwrap();
+                ws.send(Message::from(TEST_REQUEST)).await.unwrap();
+                assert!(next_text(&mut ws).await.contains("public/test"));
+
+                for frame in [SNAPSHOT, CHANGE, GAP] {
+                    ws.send(Message::from(frame)).await.unwrap();
+                }
+
+                assert!(next_text(&mut ws).await.contains("public/unsubscribe"));
+                assert!(next_text(&mut ws).await.contains("public/subscribe"));
+
+                ws.send(Message::from(SNAPSHOT)).await.unwrap();
+                ws
+            });
+
+            let config = DepthConfig {
+                depth_url: DepthType::DepthSnapshot(REST.to_string(), address),
+                symbol_type: SymbolType::ContractCoin(String::from("BTC-PERPETUAL")),
+                exchange_type: ExchangeType::Deribit,
+                limit: Some(10),
+                checksum: None,
+                audit_interval: None,
+                reconnect: ReconnectPolicy::default(),
+            };
+
+            let book = DeribitDepth::new();
+            let mut state = book.subscribe_state();
+            let mut recv = book.depth_snapshot(config).unwrap();
+
+            timeout(Duration::from_secs(10), async {
+                let depth = recv.recv().await.unwrap();
+                assert_eq!(depth.id, 297217);
+                assert_eq!(depth.bids.len(), 2);
+                assert!(matches!(
+                    state.recv().await,
+                    Some(ConnectionState::Connected)
+                ));
+
+                let depth = recv.recv().await.unwrap();
+                assert_eq!(depth.id, 297218);
+                assert_eq!(depth.ts, 1554373962554);
+                assert_eq!(depth.bids.len(), 1);
+                assert_eq!(depth.asks[0].amount, 35.0);
+
+                assert!(matches!(
+                    state.recv().await,
+                    Some(ConnectionState::Resyncing(_))
+                ));
+
+                let depth = recv.recv().await.unwrap();
+                assert_eq!(depth.id, 297217);
+                assert!(matches!(
+                    state.recv().await,
+                    Some(ConnectionState::Connected)
+                ));
+            })
+            .await
+            .unwrap();
+
+            server.await.unwrap();
+        })
+    }
+}
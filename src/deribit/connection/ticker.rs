@@ -0,0 +1,104 @@
+use crate::config::trade_channel_deribit;
+use crate::deribit::connection::abstraction::{deribit_initialize, next_push};
+use crate::deribit::format::TradeData;
+use crate::metrics;
+use crate::{Backoff, Ticker, TickerConfig, TickerT};
+use anyhow::Result;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tracing::{error, info, warn};
+
+#[derive(Clone)]
+pub struct DeribitTicker {
+    status: Arc<Mutex<bool>>,
+}
+
+impl DeribitTicker {
+    pub fn new() -> Self {
+        DeribitTicker {
+            status: Arc::new(Mutex::new(false)),
+        }
+    }
+}
+
+impl TickerT for DeribitTicker {
+    fn connect(&self, config: TickerConfig) -> Result<UnboundedReceiver<Vec<Ticker>>> {
+        let address = config.ticker_url.clone();
+        let channel = trade_channel_deribit(&config.get_symbol());
+
+        let status = self.status.clone();
+
+        let symbol = config.get_symbol();
+        let reconnect = config.reconnect.clone();
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            info!("Start Deribit trades thread");
+            let mut backoff = Backoff::new(reconnect);
+            loop {
+                let mut stream = match deribit_initialize(&address, &channel).await {
+                    Ok(connection) => connection,
+                    Err(e) => {
+                        warn!("connection error {:?}", e);
+                        if !backoff.wait("deribit").await {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+                backoff.connected();
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = true;
+                }
+
+                loop {
+                    let notification = match next_push(&mut stream).await {
+                        Ok(notification) => notification,
+                        Err(e) => {
+                            warn!("{:?}, reconnecting", e);
+                            metrics::reconnect("deribit");
+                            break;
+                        }
+                    };
+
+                    let trades: Vec<TradeData> = match serde_json::from_value(notification.data) {
+                        Ok(trades) => trades,
+                        Err(e) => {
+                            warn!("Error {} of {}", e, notification.channel);
+                            metrics::decode_error("deribit");
+                            continue;
+                        }
+                    };
+
+                    let ticks = trades
+                        .iter()
+                        .filter_map(|trade| match trade.tick() {
+                            Ok(tick) => Some(tick),
+                            Err(e) => {
+                                warn!("Bad trade {:?}, {:?}", trade, e);
+                                None
+                            }
+                        })
+                        .collect::<Vec<_>>();
+
+                    metrics::trades("deribit", &symbol, &ticks);
+                    if ticks.is_empty() {
+                        warn!("Deribit Received empty ticks");
+                    } else if sender.send(ticks).is_err() {
+                        error!("Deribit Ticker send error");
+                    }
+                }
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = false;
+                }
+                if !backoff.wait("deribit").await {
+                    break;
+                }
+            }
+        });
+
+        Ok(receiver)
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{BookSide, Depth, OrderBook, Quote};
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// `["new" / "change" / "delete", price, amount]`
+#[derive(Deserialize, Debug, Clone)]
+pub struct Level(pub String, pub f64, pub f64);
+
+#[allow(dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct BookData {
+    /// "snapshot" / "change"
+    #[serde(rename = "type")]
+    pub ttype: String,
+
+    pub timestamp: i64,
+
+    /// Something like "BTC-PERPETUAL"
+    pub instrument_name: String,
+
+    pub change_id: i64,
+
+    /// Missing in snapshots
+    #[serde(default)]
+    pub prev_change_id: Option<i64>,
+
+    pub bids: Vec<Level>,
+
+    pub asks: Vec<Level>,
+}
+
+/// `Depth.id` is the `change_id`
+pub struct BookShared {
+    change_id: i64,
+    send_time: i64,
+    receive_time: i64,
+    book: OrderBook,
+}
+
+impl BookShared {
+    pub fn new() -> Self {
+        BookShared {
+            change_id: 0,
+            send_time: 0,
+            receive_time: 0,
+            book: OrderBook::new(),
+        }
+    }
+
+    /// "snapshot", replaces the whole book
+    pub fn set_snapshot(&mut self, data: BookData) -> Result<()> {
+        self.book.clear();
+
+        self.apply(data)
+    }
+
+    /// "change", Err if `prev_change_id` is not the last applied `change_id`
+    pub fn add_change(&mut self, data: BookData) -> Result<()> {
+        let prev_change_id = data.prev_change_id.unwrap_or_default();
+        if prev_change_id != self.change_id {
+            return Err(anyhow!(
+                "Sequence gap, order book {}, change {}({})",
+                self.change_id,
+                data.change_id,
+                prev_change_id
+            ));
+        }
+
+        self.apply(data)
+    }
+
+    fn apply(&mut self, data: BookData) -> Result<()> {
+        for (side, levels) in [(BookSide::Ask, data.asks), (BookSide::Bid, data.bids)] {
+            for Level(action, price, amount) in levels {
+                let amount = match action.as_str() {
+                    "new" | "change" => amount,
+                    "delete" => 0.0,
+                    other => return Err(anyhow!("Unknown action {}", other)),
+                };
+
+                let quote = Quote {
+                    price,
+                    amount,
+                    orders: None,
+                };
+                self.book.apply_level(side, quote);
+            }
+        }
+
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        self.change_id = data.change_id;
+        self.send_time = data.timestamp;
+        self.receive_time = time.as_millis() as i64;
+
+        Ok(())
+    }
+
+    /// The best `levels` of each side
+    pub fn get_snapshot(&self, levels: usize) -> Depth {
+        Depth {
+            id: self.change_id,
+            ts: self.send_time,
+            lts: self.receive_time,
+            asks: self.book.top(BookSide::Ask, levels),
+            bids: self.book.top(BookSide::Bid, levels),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::deribit::format::{BookData, BookShared};
+
+    const SNAPSHOT: &str = r#"{"type":"snapshot","timestamp":1554373962454,"instrument_name":"BTC-PERPETUAL","change_id":297217,"bids":[["new",5042.34,30.0],["new",5041.94,20.0]],"asks":[["new",5042.64,40.0],["new",5043.3,40.0]]}"#;
+
+    const CHANGE: &str = r#"{"type":"change","timestamp":1554373911330,"prev_change_id":297217,"instrument_name":"BTC-PERPETUAL","change_id":297218,"bids":[["delete",5041.94,0.0],["change",5042.34,10.0]],"asks":[["new",5042.5,5.0]]}"#;
+
+    const GAP: &str = r#"{"type":"change","timestamp":1554373912330,"prev_change_id":297220,"instrument_name":"BTC-PERPETUAL","change_id":297221,"bids":[],"asks":[]}"#;
+
+    #[test]
+    fn snapshot_and_change() {
+        let mut shared = BookShared::new();
+
+        let snapshot: BookData = serde_json::from_str(SNAPSHOT).unwrap();
+        shared.set_snapshot(snapshot).unwrap();
+
+        let depth = shared.get_snapshot(10);
+        assert_eq!(depth.id, 297217);
+        assert_eq!(depth.ts, 1554373962454);
+        assert_eq!(depth.bids[0].price, 5042.34);
+        assert_eq!(depth.asks.len(), 2);
+
+        let change: BookData = serde_json::from_str(CHANGE).unwrap();
+        shared.add_change(change).unwrap();
+
+        let depth = shared.get_snapshot(1);
+        assert_eq!(depth.id, 297218);
+        assert_eq!(depth.bids.len(), 1);
+        assert_eq!(depth.bids[0].amount, 10.0);
+        assert_eq!(depth.asks[0].price, 5042.5);
+        assert_eq!(shared.get_snapshot(10).bids.len(), 1);
+
+        let gap: BookData = serde_json::from_str(GAP).unwrap();
+        assert!(shared.add_change(gap).is_err());
+        assert_eq!(shared.get_snapshot(10).id, 297218);
+    }
+}
@@ -0,0 +1,9 @@
+mod depth;
+mod request;
+mod stream;
+mod ticker;
+
+pub use depth::{BookData, BookShared};
+pub use request::{set_heartbeat_message, subscribe_message, test_message, unsubscribe_message};
+pub use stream::{Notification, RpcMessage};
+pub use ticker::TradeData;
@@ -0,0 +1,79 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// Ids of the requests, one per method,
+/// responds only tell which request failed
+const SET_HEARTBEAT_ID: u64 = 1;
+const SUBSCRIBE_ID: u64 = 2;
+const UNSUBSCRIBE_ID: u64 = 3;
+const TEST_ID: u64 = 4;
+
+#[derive(Serialize)]
+struct RpcRequest<'a> {
+    jsonrpc: &'a str,
+    id: u64,
+    method: &'a str,
+    params: Value,
+}
+
+fn request_message(id: u64, method: &str, params: Value) -> String {
+    let inner = RpcRequest {
+        jsonrpc: "2.0",
+        id,
+        method,
+        params,
+    };
+    serde_json::to_string(&inner).unwrap()
+}
+
+/// Deribit sends `test_request` heartbeats every `interval` seconds,
+/// at least 10
+pub fn set_heartbeat_message(interval: u64) -> String {
+    request_message(
+        SET_HEARTBEAT_ID,
+        "public/set_heartbeat",
+        json!({ "interval": interval }),
+    )
+}
+
+/// Answer of a `test_request` heartbeat
+pub fn test_message() -> String {
+    request_message(TEST_ID, "public/test", json!({}))
+}
+
+pub fn subscribe_message(channel: &str) -> String {
+    request_message(
+        SUBSCRIBE_ID,
+        "public/subscribe",
+        json!({ "channels": [channel] }),
+    )
+}
+
+pub fn unsubscribe_message(channel: &str) -> String {
+    request_message(
+        UNSUBSCRIBE_ID,
+        "public/unsubscribe",
+        json!({ "channels": [channel] }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::deribit::format::{set_heartbeat_message, subscribe_message, test_message};
+
+    #[test]
+    fn rpc_messages() {
+        assert_eq!(
+            subscribe_message("book.BTC-PERPETUAL.100ms"),
+            r#"{"jsonrpc":"2.0","id":2,"method":"public/subscribe","params":{"channels":["book.BTC-PERPETUAL.100ms"]}}"#
+        );
+        assert_eq!(
+            set_heartbeat_message(30),
+            r#"{"jsonrpc":"2.0","id":1,"method":"public/set_heartbeat","params":{"interval":30}}"#
+        );
+        assert_eq!(
+            test_message(),
+            r#"{"jsonrpc":"2.0","id":4,"method":"public/test","params":{}}"#
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Any JSON-RPC message: respond of a request,
+/// `subscription` notification or `heartbeat`
+#[derive(Deserialize, Debug)]
+pub struct RpcMessage {
+    /// Only in responds
+    #[serde(default)]
+    pub id: Option<u64>,
+
+    /// "subscription" / "heartbeat" for pushes
+    #[serde(default)]
+    pub method: Option<String>,
+
+    #[serde(default)]
+    pub params: Option<Value>,
+
+    #[serde(default)]
+    pub error: Option<RpcError>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RpcError {
+    pub code: i64,
+
+    pub message: String,
+}
+
+/// `params` of a `subscription`, `data` depends on the channel
+#[derive(Deserialize, Debug)]
+pub struct Notification {
+    pub channel: String,
+
+    pub data: Value,
+}
+
+/// `params` of a `heartbeat`
+#[derive(Deserialize, Debug)]
+pub struct Heartbeat {
+    /// "heartbeat" / "test_request", the latter must be answered by `public/test`
+    #[serde(rename = "type")]
+    pub ttype: String,
+}
+
+impl RpcMessage {
+    /// Whether Deribit asks for a `public/test`
+    pub fn is_test_request(&self) -> bool {
+        self.method.as_deref() == Some("heartbeat")
+            && self
+                .params
+                .clone()
+                .and_then(|params| serde_json::from_value::<Heartbeat>(params).ok())
+                .is_some_and(|heartbeat| heartbeat.ttype == "test_request")
+    }
+}
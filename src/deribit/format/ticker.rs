@@ -0,0 +1,73 @@
+use crate::{OrderDirection, Ticker};
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[allow(dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct TradeData {
+    /// Something like "48079269", or "ETH-62331738" for some instruments
+    pub trade_id: String,
+
+    pub timestamp: i64,
+
+    pub price: f64,
+
+    /// USD for perpetuals and futures, the underlying for options
+    pub amount: f64,
+
+    /// "buy" / "sell", direction of the taker
+    pub direction: String,
+
+    pub instrument_name: String,
+}
+
+impl TradeData {
+    pub fn tick(&self) -> Result<Ticker> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let direction = match self.direction.as_str() {
+            "buy" => OrderDirection::Buy,
+            "sell" => OrderDirection::Sell,
+            other => return Err(anyhow!("Unknown direction {}", other)),
+        };
+
+        let id = self
+            .trade_id
+            .rsplit('-')
+            .next()
+            .unwrap_or_default()
+            .parse()?;
+
+        Ok(Ticker {
+            lts: now.as_millis() as i64,
+            ts: self.timestamp,
+            price: self.price,
+            amount: self.amount,
+            direction,
+            id,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::deribit::format::TradeData;
+    use crate::OrderDirection;
+
+    const TRADES: &str = r#"[{"trade_seq":30289432,"trade_id":"48079254","timestamp":1590484156350,"tick_direction":0,"price":8950.0,"mark_price":8948.9,"instrument_name":"BTC-PERPETUAL","index_price":8955.88,"direction":"sell","amount":10.0},{"trade_seq":1966031,"trade_id":"ETH-62331738","timestamp":1590484156355,"tick_direction":1,"price":0.034,"mark_price":0.0339,"instrument_name":"ETH-29MAY20-200-C","index_price":205.1,"direction":"buy","amount":2.0}]"#;
+
+    #[test]
+    fn trade_to_ticker() {
+        let trades: Vec<TradeData> = serde_json::from_str(TRADES).unwrap();
+
+        let tick = trades[0].tick().unwrap();
+        assert_eq!(tick.id, 48079254);
+        assert_eq!(tick.ts, 1590484156350);
+        assert_eq!(tick.price, 8950.0);
+        assert!(matches!(tick.direction, OrderDirection::Sell));
+
+        let tick = trades[1].tick().unwrap();
+        assert_eq!(tick.id, 62331738);
+        assert!(matches!(tick.direction, OrderDirection::Buy));
+    }
+}
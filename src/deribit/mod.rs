@@ -0,0 +1,7 @@
+mod adapter;
+pub mod connection;
+pub mod format;
+
+pub use adapter::DeribitAdapter;
+pub use connection::DeribitDepth;
+pub use connection::DeribitTicker;
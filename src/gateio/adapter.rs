@@ -0,0 +1,57 @@
+use crate::config::{instrument_gateio, symbol_type_gateio, Instrument};
+use crate::config::{set_addr_for_gateio, set_ticker_addr_for_gateio, validate_symbol_gateio};
+use crate::config::{DepthType, SymbolType};
+use crate::gateio::{GateioDepth, GateioTicker};
+use crate::{DepthConfig, DepthT, ExchangeAdapter, ExchangeType, TickerConfig, TickerT};
+use anyhow::{anyhow, Result};
+use std::sync::Arc;
+
+/// Spot pairs of the Gate.io v4 feed
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GateioAdapter;
+
+impl ExchangeAdapter for GateioAdapter {
+    fn name(&self) -> &'static str {
+        "gateio"
+    }
+
+    fn exchange_type(&self) -> ExchangeType {
+        ExchangeType::Gateio
+    }
+
+    fn validate_symbol(&self, symbol: &str, _limit: Option<i32>) -> Result<SymbolType> {
+        validate_symbol_gateio(symbol)
+    }
+
+    fn depth_url(&self, symbol_type: &SymbolType, limit: Option<i32>) -> Result<DepthType> {
+        let currency_pair = match symbol_type {
+            SymbolType::Spot(s) => s,
+            other => return Err(anyhow!("Unsupported symbol {:?} for gateio", other)),
+        };
+        let (rest_address, depth_address, level_depth_address) =
+            set_addr_for_gateio(currency_pair, limit);
+
+        DepthType::new(rest_address, depth_address, level_depth_address)
+            .ok_or_else(|| anyhow!("depth url is empty"))
+    }
+
+    fn ticker_url(&self, _symbol_type: &SymbolType) -> Result<String> {
+        Ok(set_ticker_addr_for_gateio())
+    }
+
+    fn depth_connection(&self, _config: &DepthConfig) -> Result<Arc<dyn DepthT + Send + Sync>> {
+        Ok(Arc::new(GateioDepth::new()))
+    }
+
+    fn ticker_connection(&self, _config: &TickerConfig) -> Result<Arc<dyn TickerT + Send + Sync>> {
+        Ok(Arc::new(GateioTicker::new()))
+    }
+
+    fn native_symbol(&self, instrument: &Instrument) -> Result<SymbolType> {
+        symbol_type_gateio(instrument)
+    }
+
+    fn instrument_of(&self, symbol_type: &SymbolType) -> Result<Instrument> {
+        instrument_gateio(symbol_type)
+    }
+}
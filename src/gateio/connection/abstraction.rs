@@ -0,0 +1,108 @@
+use crate::api::driver::BookFeed;
+use crate::gateio::connection::GateioWebSocket;
+use crate::gateio::format::{ping_message, subscribe_message, ChannelPush};
+use crate::metrics;
+use crate::RawFrames;
+use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
+use futures_util::{SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
+use tokio::time::{interval_at, Duration, Instant, Interval};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, warn};
+use url::Url;
+
+/// Application pings keep an idle connection open
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Connection subscribed to one channel
+pub struct GateioStream {
+    stream: GateioWebSocket,
+    ping: Interval,
+}
+
+impl GateioStream {
+    pub async fn connect(address: &str, channel: &str, payload: Vec<String>) -> Result<Self> {
+        let url = Url::parse(address)?;
+
+        let mut stream = match connect_async(url).await {
+            Ok((connection, _)) => connection,
+            Err(e) => return Err(anyhow!("{:?}", e)),
+        };
+        debug!("Connect to {} success", address);
+
+        stream
+            .send(Message::from(subscribe_message(channel, payload.clone())))
+            .await?;
+        debug!("Subscribe to {} of {:?} success", channel, payload);
+
+        Ok(GateioStream {
+            stream,
+            ping: interval_at(Instant::now() + PING_INTERVAL, PING_INTERVAL),
+        })
+    }
+
+    /// Result of the next `update` push, pinging the server when it is due,
+    /// answers and pongs are skipped, Err for `error`
+    pub async fn next_update<T: DeserializeOwned>(&mut self) -> Result<T> {
+        loop {
+            let message = tokio::select! {
+                message = self.stream.next() => match message {
+                    Some(message) => message?,
+                    None => return Err(anyhow!("Connection closed")),
+                },
+                _ = self.ping.tick() => {
+                    self.stream.send(Message::from(ping_message())).await?;
+                    continue;
+                }
+            };
+
+            let text = match message {
+                Message::Text(text) => {
+                    RawFrames::global().publish("gateio", &text);
+                    text
+                }
+                Message::Ping(payload) => {
+                    self.stream.send(Message::Pong(payload)).await?;
+                    continue;
+                }
+                Message::Close(frame) => return Err(anyhow!("Connection closed {:?}", frame)),
+                _ => continue,
+            };
+
+            let push: ChannelPush = match serde_json::from_str(&text) {
+                Ok(push) => push,
+                Err(e) => {
+                    warn!("Error {}, {:?}", e, text);
+                    metrics::decode_error("gateio");
+                    continue;
+                }
+            };
+
+            if let Some(error) = push.error {
+                return Err(anyhow!("Gate.io error {}: {}", error.code, error.message));
+            }
+
+            if !push.is_update() {
+                debug!("Skip {}", text);
+                continue;
+            }
+
+            // `Quote` borrows its strings, so decode from a reference
+            match T::deserialize(&push.result) {
+                Ok(update) => return Ok(update),
+                Err(e) => {
+                    warn!("Error {}, {:?}", e, text);
+                    metrics::decode_error("gateio");
+                }
+            }
+        }
+    }
+}
+
+impl BookFeed for GateioStream {
+    fn next<T: DeserializeOwned + Send + 'static>(&mut self) -> BoxFuture<'_, Result<T>> {
+        Box::pin(self.next_update())
+    }
+}
@@ -0,0 +1,205 @@
+use crate::api::driver::{BookDriver, Connect};
+use crate::api::sync::RestSnapshot;
+use crate::gateio::connection::abstraction::GateioStream;
+use crate::gateio::format::{BookLevels, BookShared, BookUpdate};
+use crate::{ConnectionState, Depth, DepthConfig, DepthT};
+use anyhow::Result;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+const UPDATE_CHANNEL: &str = "spot.order_book_update";
+
+const LEVEL_CHANNEL: &str = "spot.order_book";
+
+/// Levels sent in Level Mode
+const LEVEL_DEPTH: usize = 20;
+
+/// Push interval of both channels
+const INTERVAL: &str = "100ms";
+
+#[derive(Clone)]
+pub struct GateioDepth {
+    driver: BookDriver<BookShared>,
+    /// Levels of each side in `snapshot()`
+    levels: Arc<Mutex<usize>>,
+}
+
+/// Connect to `address` and subscribe `channel` with `payload`
+fn connect(address: String, channel: &'static str, payload: Vec<String>) -> Connect<GateioStream> {
+    Box::new(move || {
+        let address = address.clone();
+        let payload = payload.clone();
+        Box::pin(async move { GateioStream::connect(&address, channel, payload).await })
+    })
+}
+
+impl DepthT for GateioDepth {
+    fn new() -> Self {
+        GateioDepth {
+            driver: BookDriver::new("gateio", BookShared::new()),
+            levels: Arc::new(Mutex::new(LEVEL_DEPTH)),
+        }
+    }
+
+    /// acquire a order book with "depth method",
+    /// `spot.order_book_update` synced to a REST snapshot
+    fn depth_snapshot(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
+        let (rest_address, depth_address) = config.get_depth_snapshot_addresses();
+        let payload = vec![config.get_symbol(), INTERVAL.to_string()];
+        let levels = config.limit.map_or(LEVEL_DEPTH, |limit| limit as usize);
+        *self.levels.lock().unwrap() = levels;
+
+        Ok(self.driver.clone().diff_book::<_, BookUpdate, _, _>(
+            connect(depth_address, UPDATE_CHANNEL, payload),
+            config.get_symbol(),
+            config.reconnect,
+            RestSnapshot::new("gateio", rest_address),
+            move |shared| shared.get_depth(levels),
+        ))
+    }
+
+    /// acquire the best 20 levels pushed by `spot.order_book`
+    fn depth(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
+        let level_address = config.get_depth_addresses();
+        let payload = vec![
+            config.get_symbol(),
+            LEVEL_DEPTH.to_string(),
+            INTERVAL.to_string(),
+        ];
+        *self.levels.lock().unwrap() = LEVEL_DEPTH;
+
+        Ok(self.driver.clone().level_book(
+            connect(level_address, LEVEL_CHANNEL, payload),
+            config.get_symbol(),
+            config.reconnect,
+            |shared, levels: BookLevels| {
+                shared.set_levels(levels);
+                shared.get_depth(LEVEL_DEPTH)
+            },
+        ))
+    }
+
+    /// Get the snapshot of the current Order Book
+    fn snapshot(&self) -> Option<Depth> {
+        let levels = *self.levels.lock().unwrap();
+        self.driver.snapshot(|shared| shared.get_depth(levels))
+    }
+
+    fn subscribe_state(&self) -> UnboundedReceiver<ConnectionState> {
+        self.driver.subscribe_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::mock::serve_json;
+    use crate::config::{DepthConfig, DepthType, SymbolType};
+    use crate::gateio::connection::GateioDepth;
+    use crate::{ConnectionState, DepthT, ExchangeType, ReconnectPolicy};
+    use futures_util::{SinkExt, StreamExt};
+    use std::time::Duration;
+    use tokio::net::TcpListener;
+    use tokio::runtime::Runtime;
+    use tokio::time::timeout;
+    use tokio_tungstenite::accept_async;
+    use tokio_tungstenite::tungstenite::Message;
+
+    const ANSWER: &str = r#"{"time":1662540621,"channel":"spot.order_book_update","event":"subscribe","error":null,"result":{"status":"success"}}"#;
+
+    const SNAPSHOT: &str = r#"{"id":100,"current":1662540621100,"update":1662540621100,"asks":[["101.0","1"],["102.0","2"]],"bids":[["99.0","1"],["98.0","2"]]}"#;
+
+    fn update(first: i64, last: i64, bids: &str, asks: &str) -> String {
+        format!(
+            r#"{{"time":1662540621,"channel":"spot.order_book_update","event":"update","result":{{"t":{},"e":"depthUpdate","E":1662540621,"s":"BTC_USDT","U":{},"u":{},"b":{},"a":{}}}}}"#,
+            1662540621000 + last,
+            first,
+            last,
+            bids,
+            asks
+        )
+    }
+
+    #[test]
+    fn book_from_mock_server() {
+        Runtime::new().unwrap().block_on(async {
+            let rest = serve_json(vec![("/api/v4/spot/order_book", SNAPSHOT.to_string())]).await;
+
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let address = format!("ws://{}", listener.local_addr().unwrap());
+
+            let server = tokio::spawn(async move {
+                let (socket, _) = listener.accept().await.unwrap();
+                let mut ws = accept_async(socket).await.unwrap();
+
+                let request = ws.next().await.unwrap().unwrap().into_text().unwrap();
+                assert!(request.contains(r#""channel":"spot.order_book_update""#));
+                assert!(request.contains(r#""payload":["BTC_USDT","100ms"]"#));
+
+                let frames = [
+                    ANSWER.to_string(),
+                    update(95, 99, r#"[["99.0","5"]]"#, "[]"),
+                    update(100, 102, "[]", r#"[["101.0","0"]]"#),
+                    update(103, 104, r#"[["99.5","1"]]"#, "[]"),
+                    update(105, 106, "[]", r#"[["101.5","3"]]"#),
+                    update(107, 108, r#"[["98.0","0"]]"#, "[]"),
+                    update(109, 110, "[]", r#"[["100.5","1"]]"#),
+                    update(200, 201, "[]", "[]"),
+                ];
+                for frame in frames {
+                    ws.send(Message::from(frame)).await.unwrap();
+                }
+                ws
+            });
+
+            let config = DepthConfig {
+                depth_url: DepthType::DepthSnapshot(
+                    format!(
+                        "{}/api/v4/spot/order_book?currency_pair=BTC_USDT&limit=2&with_id=true",
+                        rest
+                    ),
+                    address,
+                ),
+                symbol_type: SymbolType::Spot(String::from("BTC_USDT")),
+                exchange_type: ExchangeType::Gateio,
+                limit: Some(2),
+                checksum: None,
+                audit_interval: None,
+                reconnect: ReconnectPolicy::default(),
+            };
+
+            let book = GateioDepth::new();
+            let mut state = book.subscribe_state();
+            let mut recv = book.depth_snapshot(config).unwrap();
+
+            timeout(Duration::from_secs(10), async {
+                let depth = recv.recv().await.unwrap();
+                assert_eq!(depth.id, 108);
+                assert_eq!(depth.ts, 1662540621108);
+                assert_eq!(depth.bids.len(), 2);
+                assert_eq!(depth.bids[0].price, 99.5);
+                assert_eq!(depth.bids[1].price, 99.0);
+                assert_eq!(depth.asks[0].price, 101.5);
+                assert_eq!(depth.asks[1].price, 102.0);
+                assert!(matches!(
+                    state.recv().await,
+                    Some(ConnectionState::Connected)
+                ));
+
+                let depth = recv.recv().await.unwrap();
+                assert_eq!(depth.id, 110);
+                assert_eq!(depth.asks[0].price, 100.5);
+                assert_eq!(depth.asks.len(), 2);
+
+                assert!(matches!(
+                    state.recv().await,
+                    Some(ConnectionState::Resyncing(_))
+                ));
+                assert!(book.snapshot().is_none());
+            })
+            .await
+            .unwrap();
+
+            server.await.unwrap();
+        })
+    }
+}
@@ -0,0 +1,92 @@
+use crate::gateio::connection::abstraction::GateioStream;
+use crate::gateio::format::TradeData;
+use crate::metrics;
+use crate::{Backoff, Ticker, TickerConfig, TickerT};
+use anyhow::Result;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tracing::{error, info, warn};
+
+const CHANNEL: &str = "spot.trades";
+
+#[derive(Clone)]
+pub struct GateioTicker {
+    status: Arc<Mutex<bool>>,
+}
+
+impl GateioTicker {
+    pub fn new() -> Self {
+        GateioTicker {
+            status: Arc::new(Mutex::new(false)),
+        }
+    }
+}
+
+impl TickerT for GateioTicker {
+    fn connect(&self, config: TickerConfig) -> Result<UnboundedReceiver<Vec<Ticker>>> {
+        let address = config.ticker_url.clone();
+        let currency_pair = config.get_symbol();
+
+        let status = self.status.clone();
+
+        let reconnect = config.reconnect.clone();
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            info!("Start Gate.io trades thread");
+            let mut backoff = Backoff::new(reconnect);
+            loop {
+                let payload = vec![currency_pair.clone()];
+                let mut stream = match GateioStream::connect(&address, CHANNEL, payload).await {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        warn!("connection error {:?}", e);
+                        if !backoff.wait("gateio").await {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+                backoff.connected();
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = true;
+                }
+
+                loop {
+                    let trade = match stream.next_update::<TradeData>().await {
+                        Ok(trade) => trade,
+                        Err(e) => {
+                            warn!("{:?}, reconnecting", e);
+                            metrics::reconnect("gateio");
+                            break;
+                        }
+                    };
+
+                    let tick = match trade.tick() {
+                        Ok(tick) => tick,
+                        Err(e) => {
+                            warn!("Bad trade {:?}, {:?}", trade, e);
+                            continue;
+                        }
+                    };
+
+                    let ticks = vec![tick];
+                    metrics::trades("gateio", &currency_pair, &ticks);
+                    if sender.send(ticks).is_err() {
+                        error!("Gate.io Ticker send error");
+                    }
+                }
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = false;
+                }
+                if !backoff.wait("gateio").await {
+                    break;
+                }
+            }
+        });
+
+        Ok(receiver)
+    }
+}
@@ -0,0 +1,173 @@
+use crate::api::format::quote_tuples;
+use crate::api::sync::{DiffBook, DiffEvent, DiffSnapshot};
+use crate::{BookSide, Depth, OrderBook, Quote};
+
+use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::debug;
+
+/// Result of `spot.order_book_update`
+#[allow(dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct BookUpdate {
+    /// Update time in milliseconds
+    #[serde(rename = "t")]
+    pub ts: i64,
+    #[serde(rename = "s")]
+    pub currency_pair: String,
+    #[serde(rename = "U")]
+    pub first_update_id: i64,
+    #[serde(rename = "u")]
+    pub last_update_id: i64,
+    #[serde(rename = "b", default, deserialize_with = "quote_tuples")]
+    pub bids: Vec<Quote>,
+    #[serde(rename = "a", default, deserialize_with = "quote_tuples")]
+    pub asks: Vec<Quote>,
+}
+
+impl DiffEvent for BookUpdate {
+    /// [E.U,..,S.u,..,E.u]
+    fn matches(&self, snap_shot_id: i64) -> bool {
+        debug!(
+            "order book {}, Event {}-{}",
+            snap_shot_id, self.first_update_id, self.last_update_id
+        );
+
+        self.first_update_id <= snap_shot_id + 1 && snap_shot_id < self.last_update_id
+    }
+
+    /// [E.U,..,E.u] S.u
+    fn behind(&self, snap_shot_id: i64) -> bool {
+        self.last_update_id <= snap_shot_id
+    }
+
+    fn equals(&self, snap_shot_id: i64) -> bool {
+        self.first_update_id == snap_shot_id + 1
+    }
+}
+
+/// REST `order_book` with `with_id=true`
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+pub struct BookSnapshot {
+    pub id: i64,
+    /// Response time in milliseconds
+    pub current: i64,
+    /// Last change of the book in milliseconds
+    pub update: i64,
+    #[serde(deserialize_with = "quote_tuples")]
+    pub bids: Vec<Quote>,
+    #[serde(deserialize_with = "quote_tuples")]
+    pub asks: Vec<Quote>,
+}
+
+impl DiffSnapshot for BookSnapshot {
+    fn id(&self) -> i64 {
+        self.id
+    }
+}
+
+/// Result of `spot.order_book`, the best levels of the book
+#[derive(Deserialize, Debug)]
+pub struct BookLevels {
+    #[serde(rename = "t")]
+    pub ts: i64,
+    #[serde(rename = "lastUpdateId")]
+    pub last_update_id: i64,
+    #[serde(deserialize_with = "quote_tuples")]
+    pub bids: Vec<Quote>,
+    #[serde(deserialize_with = "quote_tuples")]
+    pub asks: Vec<Quote>,
+}
+
+#[derive(Default)]
+pub struct BookShared {
+    last_update_id: i64,
+    send_time: i64,
+    receive_time: i64,
+    book: OrderBook,
+}
+
+impl BookShared {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only used for `spot.order_book`
+    pub fn set_levels(&mut self, levels: BookLevels) {
+        self.book.apply_snapshot(levels.bids, levels.asks);
+
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        self.last_update_id = levels.last_update_id;
+        self.send_time = levels.ts;
+        self.receive_time = time.as_millis() as i64;
+    }
+
+    /// The best `levels` of each side
+    pub fn get_depth(&self, levels: usize) -> Depth {
+        Depth {
+            lts: self.receive_time,
+            ts: self.send_time,
+            id: self.last_update_id,
+            asks: self.book.top(BookSide::Ask, levels),
+            bids: self.book.top(BookSide::Bid, levels),
+        }
+    }
+}
+
+impl DiffBook<BookUpdate> for BookShared {
+    type Snapshot = BookSnapshot;
+
+    fn id(&self) -> i64 {
+        self.last_update_id
+    }
+
+    fn load_snapshot(&mut self, snapshot: &BookSnapshot) {
+        self.book
+            .apply_snapshot(snapshot.bids.iter().copied(), snapshot.asks.iter().copied());
+
+        self.last_update_id = snapshot.id;
+        self.send_time = snapshot.update;
+    }
+
+    fn add_event(&mut self, event: BookUpdate) {
+        self.book.apply_levels(BookSide::Ask, event.asks);
+        self.book.apply_levels(BookSide::Bid, event.bids);
+
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        self.last_update_id = event.last_update_id;
+        self.send_time = event.ts;
+        self.receive_time = time.as_millis() as i64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::sync::{DiffBook, DiffEvent};
+    use crate::gateio::format::depth::{BookShared, BookSnapshot, BookUpdate};
+
+    const SNAPSHOT: &str = r#"{"id":1027024,"current":1662540621410,"update":1662540621407,"asks":[["19079.55","0.01"],["19080.00","1.2"]],"bids":[["19079.50","0.5"],["19079.00","2.0"]]}"#;
+
+    const UPDATE: &str = r#"{"t":1662540621500,"e":"depthUpdate","E":1662540621,"s":"BTC_USDT","U":1027025,"u":1027027,"b":[["19079.50","0"]],"a":[["19079.60","0.3"]]}"#;
+
+    #[test]
+    fn update_follows_snapshot() {
+        let snapshot: BookSnapshot = serde_json::from_str(SNAPSHOT).unwrap();
+        let update: BookUpdate = serde_json::from_str(UPDATE).unwrap();
+        assert!(update.matches(snapshot.id));
+        assert!(update.equals(snapshot.id));
+        assert!(!update.behind(snapshot.id));
+
+        let mut book = BookShared::new();
+        book.load_snapshot(&snapshot);
+        book.add_event(update);
+
+        let snapshot = book.get_depth(usize::MAX);
+        assert_eq!(snapshot.id, 1027027);
+        assert_eq!(snapshot.ts, 1662540621500);
+        assert_eq!(snapshot.bids[0].price, 19079.0);
+        assert_eq!(snapshot.asks[0].price, 19079.55);
+        assert_eq!(snapshot.asks[1].price, 19079.6);
+        assert_eq!(snapshot.asks.len(), 3);
+    }
+}
@@ -0,0 +1,9 @@
+mod depth;
+mod request;
+mod stream;
+mod ticker;
+
+pub use depth::{BookLevels, BookShared, BookUpdate};
+pub use request::{ping_message, subscribe_message};
+pub use stream::ChannelPush;
+pub use ticker::TradeData;
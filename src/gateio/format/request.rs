@@ -0,0 +1,41 @@
+use serde::Serialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Serialize)]
+struct ChannelRequest<'a> {
+    time: i64,
+    channel: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    event: Option<&'a str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    payload: Vec<String>,
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+/// e.g. `{"time":1606292218,"channel":"spot.order_book_update","event":"subscribe","payload":["BTC_USDT","100ms"]}`
+pub fn subscribe_message(channel: &str, payload: Vec<String>) -> String {
+    let inner = ChannelRequest {
+        time: now(),
+        channel,
+        event: Some("subscribe"),
+        payload,
+    };
+    serde_json::to_string(&inner).unwrap()
+}
+
+/// Application ping, answered with `spot.pong`
+pub fn ping_message() -> String {
+    let inner = ChannelRequest {
+        time: now(),
+        channel: "spot.ping",
+        event: None,
+        payload: Vec::new(),
+    };
+    serde_json::to_string(&inner).unwrap()
+}
@@ -0,0 +1,54 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Deserialize, Debug)]
+pub struct ErrorData {
+    pub code: i64,
+    pub message: String,
+}
+
+/// Everything the server sends,
+/// `event` is "subscribe" for the answer of a request and "update" for data
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+pub struct ChannelPush {
+    pub time: i64,
+
+    pub channel: String,
+
+    #[serde(default)]
+    pub event: String,
+
+    #[serde(default)]
+    pub error: Option<ErrorData>,
+
+    #[serde(default)]
+    pub result: Value,
+}
+
+impl ChannelPush {
+    pub fn is_update(&self) -> bool {
+        self.event == "update"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gateio::format::ChannelPush;
+
+    #[test]
+    fn push_deserialize() {
+        let answer = r#"{"time":1606292218,"time_ms":1606292218231,"channel":"spot.order_book_update","event":"subscribe","error":null,"result":{"status":"success"}}"#;
+        let push: ChannelPush = serde_json::from_str(answer).unwrap();
+        assert!(!push.is_update());
+        assert!(push.error.is_none());
+
+        let error = r#"{"time":1606292218,"channel":"spot.order_book_update","event":"subscribe","error":{"code":2,"message":"unknown currency pair BTC_USTD"},"result":null}"#;
+        let push: ChannelPush = serde_json::from_str(error).unwrap();
+        assert_eq!(push.error.unwrap().code, 2);
+
+        let pong = r#"{"time":1606292218,"time_ms":1606292218231,"channel":"spot.pong","event":"","error":null,"result":null}"#;
+        let push: ChannelPush = serde_json::from_str(pong).unwrap();
+        assert_eq!(push.channel, "spot.pong");
+    }
+}
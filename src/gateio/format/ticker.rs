@@ -0,0 +1,67 @@
+use crate::{OrderDirection, Ticker};
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Result of `spot.trades`
+#[allow(dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct TradeData {
+    pub id: u64,
+
+    /// Seconds
+    pub create_time: i64,
+
+    /// Milliseconds with fraction, e.g. "1606292218213.4578"
+    pub create_time_ms: String,
+
+    /// "buy" / "sell", direction of the taker
+    pub side: String,
+
+    /// Something like "BTC_USDT"
+    pub currency_pair: String,
+
+    pub amount: String,
+
+    pub price: String,
+}
+
+impl TradeData {
+    pub fn tick(&self) -> Result<Ticker> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let direction = match self.side.as_str() {
+            "buy" => OrderDirection::Buy,
+            "sell" => OrderDirection::Sell,
+            other => return Err(anyhow!("Unknown side {}", other)),
+        };
+
+        Ok(Ticker {
+            lts: now.as_millis() as i64,
+            ts: self.create_time_ms.parse::<f64>()? as i64,
+            price: self.price.parse()?,
+            amount: self.amount.parse()?,
+            direction,
+            id: self.id,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gateio::format::TradeData;
+    use crate::OrderDirection;
+
+    const TRADE: &str = r#"{"id":309143071,"create_time":1606292218,"create_time_ms":"1606292218213.4578","side":"sell","currency_pair":"GT_USDT","amount":"16.47","price":"0.4705","range":"2390902-2390902"}"#;
+
+    #[test]
+    fn trade_to_ticker() {
+        let trade: TradeData = serde_json::from_str(TRADE).unwrap();
+
+        let tick = trade.tick().unwrap();
+        assert_eq!(tick.id, 309143071);
+        assert_eq!(tick.ts, 1606292218213);
+        assert_eq!(tick.price, 0.4705);
+        assert_eq!(tick.amount, 16.47);
+        assert!(matches!(tick.direction, OrderDirection::Sell));
+    }
+}
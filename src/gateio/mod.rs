@@ -0,0 +1,7 @@
+mod adapter;
+pub mod connection;
+pub mod format;
+
+pub use adapter::GateioAdapter;
+pub use connection::GateioDepth;
+pub use connection::GateioTicker;
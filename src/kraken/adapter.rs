@@ -0,0 +1,63 @@
+use crate::config::{instrument_kraken, symbol_type_kraken, Instrument};
+use crate::config::{normalize_symbol_kraken, set_addr_for_kraken, set_ticker_addr_for_kraken};
+use crate::config::{validate_symbol_kraken, DepthType, SymbolType};
+use crate::kraken::{KrakenDepth, KrakenTicker};
+use crate::{DepthConfig, DepthT, ExchangeAdapter, ExchangeType, TickerConfig, TickerT};
+use anyhow::{anyhow, Result};
+use std::sync::Arc;
+
+/// Spot pairs of the Kraken v2 feed
+#[derive(Clone, Copy, Debug, Default)]
+pub struct KrakenAdapter;
+
+impl KrakenAdapter {
+    /// Kraken pair back to our notation, e.g. "XBT/USD" -> "BTC_USD"
+    pub fn normalize_symbol(pair: &str) -> Result<String> {
+        normalize_symbol_kraken(pair)
+    }
+}
+
+impl ExchangeAdapter for KrakenAdapter {
+    fn name(&self) -> &'static str {
+        "kraken"
+    }
+
+    fn exchange_type(&self) -> ExchangeType {
+        ExchangeType::Kraken
+    }
+
+    fn validate_symbol(&self, symbol: &str, _limit: Option<i32>) -> Result<SymbolType> {
+        validate_symbol_kraken(symbol)
+    }
+
+    fn depth_url(&self, symbol_type: &SymbolType, limit: Option<i32>) -> Result<DepthType> {
+        let pair = match symbol_type {
+            SymbolType::Spot(s) => s,
+            other => return Err(anyhow!("Unsupported symbol {:?} for kraken", other)),
+        };
+        let (rest_address, depth_address, level_depth_address) = set_addr_for_kraken(pair, limit);
+
+        DepthType::new(rest_address, depth_address, level_depth_address)
+            .ok_or_else(|| anyhow!("depth url is empty"))
+    }
+
+    fn ticker_url(&self, _symbol_type: &SymbolType) -> Result<String> {
+        Ok(set_ticker_addr_for_kraken())
+    }
+
+    fn depth_connection(&self, _config: &DepthConfig) -> Result<Arc<dyn DepthT + Send + Sync>> {
+        Ok(Arc::new(KrakenDepth::new()))
+    }
+
+    fn ticker_connection(&self, _config: &TickerConfig) -> Result<Arc<dyn TickerT + Send + Sync>> {
+        Ok(Arc::new(KrakenTicker::new()))
+    }
+
+    fn native_symbol(&self, instrument: &Instrument) -> Result<SymbolType> {
+        symbol_type_kraken(instrument)
+    }
+
+    fn instrument_of(&self, symbol_type: &SymbolType) -> Result<Instrument> {
+        instrument_kraken(symbol_type)
+    }
+}
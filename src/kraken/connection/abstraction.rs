@@ -0,0 +1,151 @@
+use crate::api::driver::{decode, BookFeed};
+use crate::kraken::connection::KrakenWebSocket;
+use crate::kraken::format::{
+    subscribe_message, unsubscribe_message, AssetPairsRespond, ChannelPush, MethodRespond,
+    Precision, Subscription,
+};
+use crate::RawFrames;
+use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
+use futures_util::{SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+use tokio::time::timeout;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::debug;
+use url::Url;
+
+/// Kraken sends `heartbeat` every second once subscribed
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub async fn kraken_initialize(
+    address: &str,
+    subscription: Subscription,
+) -> Result<KrakenWebSocket> {
+    let url = Url::parse(address)?;
+
+    let mut stream = match connect_async(url).await {
+        Ok((connection, _)) => connection,
+        Err(e) => return Err(anyhow!("{:?}", e)),
+    };
+    debug!("Connect to {} success", address);
+
+    stream
+        .send(Message::from(subscribe_message(subscription.clone())))
+        .await?;
+
+    debug!("Subscribe to {:?} success", subscription);
+
+    Ok(stream)
+}
+
+/// Subscribe again on the same connection to get a fresh snapshot
+pub async fn kraken_resubscribe(
+    stream: &mut KrakenWebSocket,
+    subscription: Subscription,
+) -> Result<()> {
+    stream
+        .send(Message::from(unsubscribe_message(subscription.clone())))
+        .await?;
+    stream
+        .send(Message::from(subscribe_message(subscription.clone())))
+        .await?;
+
+    debug!("Resubscribe to {:?} success", subscription);
+
+    Ok(())
+}
+
+/// Precision of the pair from REST `AssetPairs`
+pub async fn kraken_precision(rest_address: &str) -> Result<Precision> {
+    let respond: AssetPairsRespond = reqwest::get(rest_address).await?.json().await?;
+
+    respond.precision()
+}
+
+/// Next push data of the stream,
+/// Err if nothing, not even a `heartbeat`, comes in `HEARTBEAT_TIMEOUT`.
+///
+/// `heartbeat` / `status` pushes and method responds are skipped,
+/// Err for unsuccessful ones
+pub async fn next_push(stream: &mut KrakenWebSocket) -> Result<String> {
+    loop {
+        let message = match timeout(HEARTBEAT_TIMEOUT, stream.next()).await {
+            Ok(Some(message)) => message?,
+            Ok(None) => return Err(anyhow!("Connection closed")),
+            Err(_) => return Err(anyhow!("No heartbeat in {:?}", HEARTBEAT_TIMEOUT)),
+        };
+
+        let text = match message {
+            Message::Text(text) => {
+                RawFrames::global().publish("kraken", &text);
+                text
+            }
+            Message::Ping(payload) => {
+                stream.send(Message::Pong(payload)).await?;
+                continue;
+            }
+            Message::Close(frame) => return Err(anyhow!("Connection closed {:?}", frame)),
+            _ => continue,
+        };
+
+        if let Ok(respond) = serde_json::from_str::<MethodRespond>(&text) {
+            if respond.success == Some(false) {
+                return Err(anyhow!(
+                    "Kraken {} error {}",
+                    respond.method,
+                    respond.error.unwrap_or_default()
+                ));
+            }
+
+            debug!("Receive {:?}", respond);
+            continue;
+        }
+
+        if let Ok(push) = serde_json::from_str::<ChannelPush>(&text) {
+            if push.channel == "heartbeat" || push.channel == "status" {
+                continue;
+            }
+        }
+
+        return Ok(text);
+    }
+}
+
+/// Connection subscribed to the book of `subscription`
+pub struct KrakenStream {
+    stream: KrakenWebSocket,
+    subscription: Subscription,
+}
+
+impl KrakenStream {
+    pub async fn connect(address: &str, subscription: Subscription) -> Result<Self> {
+        let stream = kraken_initialize(address, subscription.clone()).await?;
+
+        Ok(KrakenStream {
+            stream,
+            subscription,
+        })
+    }
+}
+
+impl BookFeed for KrakenStream {
+    fn next<T: DeserializeOwned + Send + 'static>(&mut self) -> BoxFuture<'_, Result<T>> {
+        Box::pin(async move {
+            loop {
+                let text = next_push(&mut self.stream).await?;
+                if let Some(push) = decode("kraken", &text) {
+                    return Ok(push);
+                }
+            }
+        })
+    }
+
+    fn resubscribe(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(kraken_resubscribe(
+            &mut self.stream,
+            self.subscription.clone(),
+        ))
+    }
+}
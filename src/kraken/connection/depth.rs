@@ -0,0 +1,137 @@
+use crate::api::checksum::verify_checksum;
+use crate::api::driver::{BookDriver, Connect};
+use crate::config::{asset_pair_address_kraken, depth_kraken};
+use crate::kraken::connection::abstraction::{kraken_precision, KrakenStream};
+use crate::kraken::format::{BookEventStream, BookShared, Subscription};
+use crate::{ChecksumVerifier, ConnectionState, Depth, DepthConfig, DepthT, ReconnectPolicy};
+use anyhow::Result;
+use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tracing::debug;
+
+#[derive(Clone)]
+pub struct KrakenDepth {
+    driver: BookDriver<BookShared>,
+}
+
+/// Put a snapshot or update of `book` onto `shared`.
+///
+/// Every push is verified by the checksum of Kraken
+/// and by `checksum` if there is one
+fn apply(
+    shared: &mut BookShared,
+    push: BookEventStream,
+    is_ready: bool,
+    checksum: &Option<Arc<dyn ChecksumVerifier>>,
+) -> Option<Result<Depth>> {
+    let is_update = push.ttype == "update";
+
+    // Updates before the fresh snapshot
+    if is_update && !is_ready {
+        debug!("Skip update before snapshot");
+        return None;
+    }
+
+    let result = push
+        .data
+        .into_iter()
+        .try_for_each(|data| {
+            if is_update {
+                shared.add_update(data)
+            } else {
+                shared.set_snapshot(data)
+            }
+        })
+        .and_then(|_| shared.verify())
+        .and_then(|_| verify_checksum(shared.get_snapshot(), shared.checksum(), checksum));
+
+    Some(result)
+}
+
+impl KrakenDepth {
+    /// Keep the book in line with the `book` pushes of `subscription`,
+    /// the precision of the checksum comes from `rest_address`
+    /// before every connection
+    fn maintain(
+        &self,
+        address: String,
+        rest_address: String,
+        subscription: Subscription,
+        checksum: Option<Arc<dyn ChecksumVerifier>>,
+        symbol: String,
+        reconnect: ReconnectPolicy,
+    ) -> UnboundedReceiver<Depth> {
+        let shared = self.driver.shared();
+        let depth = subscription.depth.unwrap_or_default() as usize;
+
+        let connect: Connect<KrakenStream> = Box::new(move || {
+            let address = address.clone();
+            let rest_address = rest_address.clone();
+            let subscription = subscription.clone();
+            let shared = shared.clone();
+            Box::pin(async move {
+                let precision = kraken_precision(&rest_address).await?;
+                shared.write().unwrap().reset(depth, precision);
+
+                KrakenStream::connect(&address, subscription).await
+            })
+        });
+
+        self.driver.clone().pushed_book(
+            connect,
+            symbol,
+            reconnect,
+            move |shared, push, is_ready| apply(shared, push, is_ready, &checksum),
+        )
+    }
+}
+
+impl DepthT for KrakenDepth {
+    fn new() -> Self {
+        KrakenDepth {
+            driver: BookDriver::new("kraken", BookShared::new()),
+        }
+    }
+
+    /// acquire a order book of `limit` levels
+    fn depth_snapshot(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
+        let (rest_address, depth_address) = config.get_depth_snapshot_addresses();
+        let depth = depth_kraken(config.limit);
+        let subscription = Subscription::new("book", &config.get_symbol(), Some(depth));
+
+        Ok(self.maintain(
+            depth_address,
+            rest_address,
+            subscription,
+            config.checksum.clone(),
+            config.get_symbol(),
+            config.reconnect,
+        ))
+    }
+
+    /// acquire a order book of 10 levels
+    fn depth(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
+        let level_address = config.get_depth_addresses();
+        let rest_address = asset_pair_address_kraken(&config.get_symbol());
+        let depth = depth_kraken(None);
+        let subscription = Subscription::new("book", &config.get_symbol(), Some(depth));
+
+        Ok(self.maintain(
+            level_address,
+            rest_address,
+            subscription,
+            config.checksum.clone(),
+            config.get_symbol(),
+            config.reconnect,
+        ))
+    }
+
+    /// Get the snapshot of the current Order Book
+    fn snapshot(&self) -> Option<Depth> {
+        self.driver.snapshot(BookShared::get_snapshot)
+    }
+
+    fn subscribe_state(&self) -> UnboundedReceiver<ConnectionState> {
+        self.driver.subscribe_state()
+    }
+}
@@ -0,0 +1,10 @@
+mod abstraction;
+pub mod depth;
+pub mod ticker;
+
+use tokio::net::TcpStream;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+pub type KrakenWebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+pub use depth::KrakenDepth;
+pub use ticker::KrakenTicker;
@@ -0,0 +1,104 @@
+use crate::kraken::connection::abstraction::{kraken_initialize, next_push};
+use crate::kraken::format::{Subscription, TradeEventStream};
+use crate::metrics;
+use crate::{Backoff, Ticker, TickerConfig, TickerT};
+use anyhow::Result;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tracing::{error, info, warn};
+
+#[derive(Clone)]
+pub struct KrakenTicker {
+    status: Arc<Mutex<bool>>,
+}
+
+impl KrakenTicker {
+    pub fn new() -> Self {
+        KrakenTicker {
+            status: Arc::new(Mutex::new(false)),
+        }
+    }
+}
+
+impl TickerT for KrakenTicker {
+    fn connect(&self, config: TickerConfig) -> Result<UnboundedReceiver<Vec<Ticker>>> {
+        let address = config.ticker_url.clone();
+        let subscription = Subscription::new("trade", &config.get_symbol(), None);
+
+        let status = self.status.clone();
+
+        let symbol = config.get_symbol();
+        let reconnect = config.reconnect.clone();
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            info!("Start Kraken trades thread");
+            let mut backoff = Backoff::new(reconnect);
+            loop {
+                let mut stream = match kraken_initialize(&address, subscription.clone()).await {
+                    Ok(connection) => connection,
+                    Err(e) => {
+                        warn!("connection error {:?}", e);
+                        if !backoff.wait("kraken").await {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+                backoff.connected();
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = true;
+                }
+
+                loop {
+                    let text = match next_push(&mut stream).await {
+                        Ok(text) => text,
+                        Err(e) => {
+                            warn!("{:?}, reconnecting", e);
+                            metrics::reconnect("kraken");
+                            break;
+                        }
+                    };
+
+                    let event: TradeEventStream = match serde_json::from_str(&text) {
+                        Ok(event) => event,
+                        Err(e) => {
+                            warn!("Error {}, {:?}", e, text);
+                            metrics::decode_error("kraken");
+                            continue;
+                        }
+                    };
+
+                    let ticks = event
+                        .data
+                        .iter()
+                        .filter_map(|trade| match trade.tick() {
+                            Ok(tick) => Some(tick),
+                            Err(e) => {
+                                warn!("Bad trade {:?}, {:?}", trade, e);
+                                None
+                            }
+                        })
+                        .collect::<Vec<_>>();
+
+                    metrics::trades("kraken", &symbol, &ticks);
+                    if ticks.is_empty() {
+                        warn!("Kraken Received empty ticks");
+                    } else if sender.send(ticks).is_err() {
+                        error!("Kraken Ticker send error");
+                    }
+                }
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = false;
+                }
+                if !backoff.wait("kraken").await {
+                    break;
+                }
+            }
+        });
+
+        Ok(receiver)
+    }
+}
@@ -0,0 +1,234 @@
+use crate::api::parse_time;
+use crate::{BookLevel, BookSide, Depth, OrderBook, Quote};
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Levels of each side covered by the checksum
+const CHECKSUM_LEVELS: usize = 10;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Level {
+    pub price: f64,
+
+    pub qty: f64,
+}
+
+impl BookLevel for Level {
+    fn price(&self) -> f64 {
+        self.price
+    }
+
+    fn amount(&self) -> f64 {
+        self.qty
+    }
+
+    fn quote(&self) -> Quote {
+        Quote {
+            price: self.price,
+            amount: self.qty,
+            orders: None,
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct BookData {
+    /// Something like "BTC/USD"
+    pub symbol: String,
+
+    pub bids: Vec<Level>,
+
+    pub asks: Vec<Level>,
+
+    /// CRC32 of the top 10 levels after this data is applied
+    pub checksum: u32,
+
+    #[serde(default)]
+    pub timestamp: Option<String>,
+}
+
+/// Decimals Kraken formats a pair with,
+/// the checksum is built from prices and quantities printed this way
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Precision {
+    pub price: usize,
+
+    pub qty: usize,
+}
+
+/// Kraken sends no update id with `book`,
+/// so `Depth.id` is a local sequence instead:
+/// `1` for the snapshot, then increased by one for every applied update.
+///
+/// The book is kept at the subscribed depth,
+/// levels pushed out by an update are dropped as Kraken expects
+pub struct BookShared {
+    sequence: i64,
+    depth: usize,
+    precision: Precision,
+    /// `checksum` of the last applied data
+    checksum: Option<u32>,
+    send_time: i64,
+    receive_time: i64,
+    book: OrderBook<Level>,
+}
+
+impl BookShared {
+    pub fn new() -> Self {
+        BookShared {
+            sequence: 0,
+            depth: CHECKSUM_LEVELS,
+            precision: Precision::default(),
+            checksum: None,
+            send_time: 0,
+            receive_time: 0,
+            book: OrderBook::new(),
+        }
+    }
+
+    /// Subscribed depth and the precision of the pair,
+    /// set before every subscription
+    pub fn reset(&mut self, depth: usize, precision: Precision) {
+        self.depth = depth;
+        self.precision = precision;
+    }
+
+    /// "snapshot", replaces the whole book
+    pub fn set_snapshot(&mut self, data: BookData) -> Result<()> {
+        self.book.clear();
+        self.sequence = 0;
+
+        self.apply(data)
+    }
+
+    /// "update", only changed levels, `qty` 0 removes the level
+    pub fn add_update(&mut self, data: BookData) -> Result<()> {
+        self.apply(data)
+    }
+
+    fn apply(&mut self, data: BookData) -> Result<()> {
+        self.book.apply_levels(BookSide::Ask, data.asks);
+        self.book.apply_levels(BookSide::Bid, data.bids);
+
+        // Out of the subscribed depth
+        self.book.truncate(self.depth);
+
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let receive_time = time.as_millis() as i64;
+
+        self.sequence += 1;
+        self.checksum = Some(data.checksum);
+        self.send_time = match data.timestamp {
+            Some(timestamp) => parse_time(&timestamp)?,
+            None => receive_time,
+        };
+        self.receive_time = receive_time;
+
+        Ok(())
+    }
+
+    pub fn checksum(&self) -> Option<i64> {
+        self.checksum.map(i64::from)
+    }
+
+    /// Err if the book does not match the last `checksum` Kraken sent
+    pub fn verify(&self) -> Result<()> {
+        let expected = match self.checksum {
+            Some(checksum) => checksum,
+            None => return Ok(()),
+        };
+
+        let checksum = crc32fast::hash(self.checksum_string().as_bytes());
+        if checksum != expected {
+            return Err(anyhow!(
+                "Checksum mismatch, order book {}, checksum {}, expected {}",
+                self.sequence,
+                checksum,
+                expected
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Top 10 asks from the best, then top 10 bids from the best,
+    /// every level as price and quantity printed with the pair precision,
+    /// without the decimal point and leading zeros
+    fn checksum_string(&self) -> String {
+        let Precision { price, qty } = self.precision;
+        let format = |value: f64, decimals: usize| {
+            format!("{:.*}", decimals, value)
+                .replace('.', "")
+                .trim_start_matches('0')
+                .to_string()
+        };
+
+        let asks = self.book.asks().take(CHECKSUM_LEVELS);
+        let bids = self.book.bids().take(CHECKSUM_LEVELS);
+
+        asks.chain(bids)
+            .map(|level| format!("{}{}", format(level.price, price), format(level.qty, qty)))
+            .collect()
+    }
+
+    pub fn get_snapshot(&self) -> Depth {
+        Depth {
+            id: self.sequence,
+            ts: self.send_time,
+            lts: self.receive_time,
+            asks: self.book.quotes(BookSide::Ask),
+            bids: self.book.quotes(BookSide::Bid),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kraken::format::{BookEventStream, BookShared, Precision};
+
+    const SNAPSHOT: &str = r#"{"channel":"book","type":"snapshot","data":[{"symbol":"BTC/USD","bids":[{"price":45283.5,"qty":0.10000000},{"price":45283.4,"qty":1.54582015},{"price":45282.1,"qty":0.10000000}],"asks":[{"price":45285.2,"qty":0.00100000},{"price":45286.4,"qty":1.54582015},{"price":45286.6,"qty":1.50000000}],"checksum":417012195,"timestamp":"2023-10-06T17:35:55.440295Z"}]}"#;
+
+    /// Pushes the worst bid out of a book of depth 3
+    const UPDATE: &str = r#"{"channel":"book","type":"update","data":[{"symbol":"BTC/USD","bids":[{"price":45283.5,"qty":0.0},{"price":45283.6,"qty":0.25},{"price":45283.7,"qty":2.0}],"asks":[{"price":45285.2,"qty":0.005}],"checksum":2087377350,"timestamp":"2023-10-06T17:35:55.540295Z"}]}"#;
+
+    const BAD_CHECKSUM: &str = r#"{"channel":"book","type":"update","data":[{"symbol":"BTC/USD","bids":[],"asks":[{"price":45286.6,"qty":1.0}],"checksum":2087377350,"timestamp":"2023-10-06T17:35:55.640295Z"}]}"#;
+
+    #[test]
+    fn checksum_of_top_levels() {
+        let mut shared = BookShared::new();
+        shared.reset(3, Precision { price: 1, qty: 8 });
+
+        let mut snapshot: BookEventStream = serde_json::from_str(SNAPSHOT).unwrap();
+        assert_eq!(snapshot.ttype, "snapshot");
+        shared.set_snapshot(snapshot.data.remove(0)).unwrap();
+
+        assert_eq!(
+            shared.checksum_string(),
+            "452852100000452864154582015452866150000000\
+             4528351000000045283415458201545282110000000"
+        );
+        assert!(shared.verify().is_ok());
+
+        let depth = shared.get_snapshot();
+        assert_eq!(depth.id, 1);
+        assert_eq!(depth.ts, 1696613755440);
+        assert_eq!(depth.bids[0].price, 45283.5);
+
+        let mut update: BookEventStream = serde_json::from_str(UPDATE).unwrap();
+        shared.add_update(update.data.remove(0)).unwrap();
+        assert!(shared.verify().is_ok());
+
+        let depth = shared.get_snapshot();
+        assert_eq!(depth.id, 2);
+        assert_eq!(depth.bids.len(), 3);
+        assert_eq!(depth.bids[0].price, 45283.7);
+        assert_eq!(depth.bids[2].price, 45283.4);
+        assert_eq!(depth.asks[0].amount, 0.005);
+
+        let mut bad: BookEventStream = serde_json::from_str(BAD_CHECKSUM).unwrap();
+        shared.add_update(bad.data.remove(0)).unwrap();
+        assert!(shared.verify().is_err());
+    }
+}
@@ -0,0 +1,11 @@
+mod depth;
+mod request;
+mod respond;
+mod stream;
+mod ticker;
+
+pub use depth::{BookData, BookShared, Precision};
+pub use request::{subscribe_message, unsubscribe_message, Subscription};
+pub use respond::AssetPairsRespond;
+pub use stream::{BookEventStream, ChannelPush, MethodRespond, TradeEventStream};
+pub use ticker::TradeData;
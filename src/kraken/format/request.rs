@@ -0,0 +1,45 @@
+use serde::Serialize;
+
+/// One subscription of the v2 feed, e.g. `book` of "BTC/USD" with depth 10
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Subscription {
+    pub channel: String,
+
+    pub symbol: Vec<String>,
+
+    /// Only for `book`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth: Option<i32>,
+}
+
+impl Subscription {
+    pub fn new(channel: &str, symbol: &str, depth: Option<i32>) -> Self {
+        Subscription {
+            channel: channel.to_string(),
+            symbol: vec![symbol.to_string()],
+            depth,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct MethodRequest<'a> {
+    method: &'a str,
+    params: Subscription,
+}
+
+pub fn subscribe_message(subscription: Subscription) -> String {
+    let inner = MethodRequest {
+        method: "subscribe",
+        params: subscription,
+    };
+    serde_json::to_string(&inner).unwrap()
+}
+
+pub fn unsubscribe_message(subscription: Subscription) -> String {
+    let inner = MethodRequest {
+        method: "unsubscribe",
+        params: subscription,
+    };
+    serde_json::to_string(&inner).unwrap()
+}
@@ -0,0 +1,64 @@
+use crate::kraken::format::Precision;
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Respond of REST `AssetPairs`, keyed by the pair name of Kraken, e.g. "XXBTZUSD"
+#[derive(Deserialize, Debug)]
+pub struct AssetPairsRespond {
+    pub error: Vec<String>,
+
+    #[serde(default)]
+    pub result: HashMap<String, AssetPair>,
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+pub struct AssetPair {
+    /// Something like "XBT/USD"
+    #[serde(default)]
+    pub wsname: Option<String>,
+
+    /// Decimals of prices
+    pub pair_decimals: usize,
+
+    /// Decimals of quantities
+    pub lot_decimals: usize,
+}
+
+impl AssetPairsRespond {
+    /// Precision of the only pair asked for
+    pub fn precision(self) -> Result<Precision> {
+        if !self.error.is_empty() {
+            return Err(anyhow!("Kraken error {:?}", self.error));
+        }
+
+        let pair = self
+            .result
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow!("Kraken returned no pair"))?;
+
+        Ok(Precision {
+            price: pair.pair_decimals,
+            qty: pair.lot_decimals,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kraken::format::{AssetPairsRespond, Precision};
+
+    const XBTUSD: &str = r#"{"error":[],"result":{"XXBTZUSD":{"altname":"XBTUSD","wsname":"XBT/USD","aclass_base":"currency","base":"XXBT","aclass_quote":"currency","quote":"ZUSD","lot":"unit","cost_decimals":5,"pair_decimals":1,"lot_decimals":8,"lot_multiplier":1,"ordermin":"0.0001","status":"online"}}}"#;
+
+    #[test]
+    fn asset_pair_precision() {
+        let respond: AssetPairsRespond = serde_json::from_str(XBTUSD).unwrap();
+        assert_eq!(respond.precision().unwrap(), Precision { price: 1, qty: 8 });
+
+        let respond: AssetPairsRespond =
+            serde_json::from_str(r#"{"error":["EQuery:Unknown asset pair"]}"#).unwrap();
+        assert!(respond.precision().is_err());
+    }
+}
@@ -0,0 +1,47 @@
+use crate::kraken::format::{BookData, TradeData};
+use serde::Deserialize;
+
+/// Respond of `subscribe` / `unsubscribe` / `ping`
+#[derive(Deserialize, Debug)]
+pub struct MethodRespond {
+    pub method: String,
+
+    /// Missing for `pong`
+    #[serde(default)]
+    pub success: Option<bool>,
+
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Any push of a channel, to tell `heartbeat` and `status` apart
+#[derive(Deserialize, Debug)]
+pub struct ChannelPush {
+    pub channel: String,
+}
+
+/// Push data of `book`
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+pub struct BookEventStream {
+    pub channel: String,
+
+    /// "snapshot" / "update"
+    #[serde(rename = "type")]
+    pub ttype: String,
+
+    pub data: Vec<BookData>,
+}
+
+/// Push data of `trade`
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+pub struct TradeEventStream {
+    pub channel: String,
+
+    /// "snapshot" / "update"
+    #[serde(rename = "type")]
+    pub ttype: String,
+
+    pub data: Vec<TradeData>,
+}
@@ -0,0 +1,64 @@
+use crate::api::parse_time;
+use crate::{OrderDirection, Ticker};
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[allow(dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct TradeData {
+    /// Something like "BTC/USD"
+    pub symbol: String,
+
+    /// "buy" / "sell", direction of the taker
+    pub side: String,
+
+    pub price: f64,
+
+    pub qty: f64,
+
+    pub trade_id: u64,
+
+    pub timestamp: String,
+}
+
+impl TradeData {
+    pub fn tick(&self) -> Result<Ticker> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let direction = match self.side.as_str() {
+            "buy" => OrderDirection::Buy,
+            "sell" => OrderDirection::Sell,
+            other => return Err(anyhow!("Unknown side {}", other)),
+        };
+
+        Ok(Ticker {
+            lts: now.as_millis() as i64,
+            ts: parse_time(&self.timestamp)?,
+            price: self.price,
+            amount: self.qty,
+            direction,
+            id: self.trade_id,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kraken::format::TradeEventStream;
+    use crate::OrderDirection;
+
+    const TRADE: &str = r#"{"channel":"trade","type":"update","data":[{"symbol":"MATIC/USD","side":"sell","price":0.5117,"qty":40.0,"ord_type":"market","trade_id":4665906,"timestamp":"2023-09-25T07:49:37.708706Z"}]}"#;
+
+    #[test]
+    fn trade_to_ticker() {
+        let event: TradeEventStream = serde_json::from_str(TRADE).unwrap();
+        assert_eq!(event.ttype, "update");
+
+        let tick = event.data[0].tick().unwrap();
+        assert_eq!(tick.id, 4665906);
+        assert_eq!(tick.ts, 1695628177708);
+        assert_eq!(tick.price, 0.5117);
+        assert_eq!(tick.amount, 40.0);
+        assert!(matches!(tick.direction, OrderDirection::Sell));
+    }
+}
@@ -0,0 +1,7 @@
+mod adapter;
+pub mod connection;
+pub mod format;
+
+pub use adapter::KrakenAdapter;
+pub use connection::KrakenDepth;
+pub use connection::KrakenTicker;
@@ -0,0 +1,56 @@
+use crate::config::{instrument_kucoin, symbol_type_kucoin, Instrument};
+use crate::config::{set_addr_for_kucoin, set_ticker_addr_for_kucoin, validate_symbol_kucoin};
+use crate::config::{DepthType, SymbolType};
+use crate::kucoin::{KucoinDepth, KucoinTicker};
+use crate::{DepthConfig, DepthT, ExchangeAdapter, ExchangeType, TickerConfig, TickerT};
+use anyhow::{anyhow, Result};
+use std::sync::Arc;
+
+/// Spot pairs of the KuCoin feed
+#[derive(Clone, Copy, Debug, Default)]
+pub struct KucoinAdapter;
+
+impl ExchangeAdapter for KucoinAdapter {
+    fn name(&self) -> &'static str {
+        "kucoin"
+    }
+
+    fn exchange_type(&self) -> ExchangeType {
+        ExchangeType::Kucoin
+    }
+
+    fn validate_symbol(&self, symbol: &str, _limit: Option<i32>) -> Result<SymbolType> {
+        validate_symbol_kucoin(symbol)
+    }
+
+    fn depth_url(&self, symbol_type: &SymbolType, limit: Option<i32>) -> Result<DepthType> {
+        let symbol = match symbol_type {
+            SymbolType::Spot(s) => s,
+            other => return Err(anyhow!("Unsupported symbol {:?} for kucoin", other)),
+        };
+        let (rest_address, depth_address, level_depth_address) = set_addr_for_kucoin(symbol, limit);
+
+        DepthType::new(rest_address, depth_address, level_depth_address)
+            .ok_or_else(|| anyhow!("depth url is empty"))
+    }
+
+    fn ticker_url(&self, _symbol_type: &SymbolType) -> Result<String> {
+        Ok(set_ticker_addr_for_kucoin())
+    }
+
+    fn depth_connection(&self, _config: &DepthConfig) -> Result<Arc<dyn DepthT + Send + Sync>> {
+        Ok(Arc::new(KucoinDepth::new()))
+    }
+
+    fn ticker_connection(&self, _config: &TickerConfig) -> Result<Arc<dyn TickerT + Send + Sync>> {
+        Ok(Arc::new(KucoinTicker::new()))
+    }
+
+    fn native_symbol(&self, instrument: &Instrument) -> Result<SymbolType> {
+        symbol_type_kucoin(instrument)
+    }
+
+    fn instrument_of(&self, symbol_type: &SymbolType) -> Result<Instrument> {
+        instrument_kucoin(symbol_type)
+    }
+}
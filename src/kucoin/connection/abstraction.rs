@@ -0,0 +1,125 @@
+use crate::api::driver::BookFeed;
+use crate::kucoin::connection::KucoinWebSocket;
+use crate::kucoin::format::{ping_message, subscribe_message, BulletRespond, FeedMessage};
+use crate::metrics;
+use crate::RawFrames;
+use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
+use futures_util::{SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::time::{interval_at, Instant, Interval};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, warn};
+use url::Url;
+
+/// Connection subscribed to one topic
+pub struct KucoinStream {
+    stream: KucoinWebSocket,
+    /// Ticks at `pingInterval` of the server
+    ping: Interval,
+    /// Id of the next request
+    id: u64,
+}
+
+impl KucoinStream {
+    /// Ask `bullet_address` for a token and an endpoint,
+    /// then connect to it and subscribe to `topic`
+    pub async fn connect(bullet_address: &str, topic: &str) -> Result<Self> {
+        let bullet: BulletRespond = reqwest::Client::new()
+            .post(bullet_address)
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        let connect_id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+            .to_string();
+        let (address, ping_interval) = bullet.connect_address(&connect_id)?;
+        let url = Url::parse(&address)?;
+
+        let mut stream = match connect_async(url).await {
+            Ok((connection, _)) => connection,
+            Err(e) => return Err(anyhow!("{:?}", e)),
+        };
+        debug!("Connect to {} success", bullet_address);
+
+        stream
+            .send(Message::from(subscribe_message(1, topic)))
+            .await?;
+        debug!("Subscribe to {} success", topic);
+
+        Ok(KucoinStream {
+            stream,
+            ping: interval_at(Instant::now() + ping_interval, ping_interval),
+            id: 2,
+        })
+    }
+
+    /// Data of the next `message`, pinging the server when it is due,
+    /// `welcome`, `ack` and `pong` are skipped, Err for `error`
+    pub async fn next_data<T: DeserializeOwned>(&mut self) -> Result<T> {
+        loop {
+            let message = tokio::select! {
+                message = self.stream.next() => match message {
+                    Some(message) => message?,
+                    None => return Err(anyhow!("Connection closed")),
+                },
+                _ = self.ping.tick() => {
+                    let ping = ping_message(self.id);
+                    self.id += 1;
+                    self.stream.send(Message::from(ping)).await?;
+                    continue;
+                }
+            };
+
+            let text = match message {
+                Message::Text(text) => {
+                    RawFrames::global().publish("kucoin", &text);
+                    text
+                }
+                Message::Ping(payload) => {
+                    self.stream.send(Message::Pong(payload)).await?;
+                    continue;
+                }
+                Message::Close(frame) => return Err(anyhow!("Connection closed {:?}", frame)),
+                _ => continue,
+            };
+
+            let data = match serde_json::from_str(&text) {
+                Ok(FeedMessage::Message { data, .. }) => data,
+                Ok(FeedMessage::Error { code, data }) => {
+                    return Err(anyhow!("KuCoin error {}: {}", code, data))
+                }
+                Ok(_) => {
+                    debug!("Skip {}", text);
+                    continue;
+                }
+                Err(e) => {
+                    warn!("Error {}, {:?}", e, text);
+                    metrics::decode_error("kucoin");
+                    continue;
+                }
+            };
+
+            // `Quote` borrows its strings, so decode from a reference
+            match T::deserialize(&data) {
+                Ok(data) => return Ok(data),
+                Err(e) => {
+                    warn!("Error {}, {:?}", e, text);
+                    metrics::decode_error("kucoin");
+                }
+            }
+        }
+    }
+}
+
+impl BookFeed for KucoinStream {
+    fn next<T: DeserializeOwned + Send + 'static>(&mut self) -> BoxFuture<'_, Result<T>> {
+        Box::pin(self.next_data())
+    }
+}
@@ -0,0 +1,209 @@
+use crate::api::driver::{BookDriver, Connect};
+use crate::api::sync::RestSnapshot;
+use crate::kucoin::connection::abstraction::KucoinStream;
+use crate::kucoin::format::{BookLevels, BookShared, L2Update};
+use crate::{ConnectionState, Depth, DepthConfig, DepthT};
+use anyhow::Result;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+const UPDATE_TOPIC: &str = "/market/level2";
+
+const LEVEL_TOPIC: &str = "/spotMarket/level2Depth50";
+
+/// Levels sent in Level Mode
+const LEVEL_DEPTH: usize = 20;
+
+#[derive(Clone)]
+pub struct KucoinDepth {
+    driver: BookDriver<BookShared>,
+    /// Levels of each side in `snapshot()`
+    levels: Arc<Mutex<usize>>,
+}
+
+/// Connect through `bullet_address` and subscribe `topic`
+fn connect(bullet_address: String, topic: String) -> Connect<KucoinStream> {
+    Box::new(move || {
+        let bullet_address = bullet_address.clone();
+        let topic = topic.clone();
+        Box::pin(async move { KucoinStream::connect(&bullet_address, &topic).await })
+    })
+}
+
+impl DepthT for KucoinDepth {
+    fn new() -> Self {
+        KucoinDepth {
+            driver: BookDriver::new("kucoin", BookShared::new()),
+            levels: Arc::new(Mutex::new(LEVEL_DEPTH)),
+        }
+    }
+
+    /// acquire a order book with "depth method",
+    /// `/market/level2` synced to a REST snapshot
+    fn depth_snapshot(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
+        let (rest_address, depth_address) = config.get_depth_snapshot_addresses();
+        let topic = format!("{}:{}", UPDATE_TOPIC, config.get_symbol());
+        let levels = config.limit.map_or(LEVEL_DEPTH, |limit| limit as usize);
+        *self.levels.lock().unwrap() = levels;
+
+        Ok(self.driver.clone().diff_book::<_, L2Update, _, _>(
+            connect(depth_address, topic),
+            config.get_symbol(),
+            config.reconnect,
+            RestSnapshot::new("kucoin", rest_address),
+            move |shared| shared.get_depth(levels),
+        ))
+    }
+
+    /// acquire the best 20 of the levels pushed by `/spotMarket/level2Depth50`
+    fn depth(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
+        let level_address = config.get_depth_addresses();
+        let topic = format!("{}:{}", LEVEL_TOPIC, config.get_symbol());
+        *self.levels.lock().unwrap() = LEVEL_DEPTH;
+
+        Ok(self.driver.clone().level_book(
+            connect(level_address, topic),
+            config.get_symbol(),
+            config.reconnect,
+            |shared, levels: BookLevels| {
+                shared.set_levels(levels);
+                shared.get_depth(LEVEL_DEPTH)
+            },
+        ))
+    }
+
+    /// Get the snapshot of the current Order Book
+    fn snapshot(&self) -> Option<Depth> {
+        let levels = *self.levels.lock().unwrap();
+        self.driver.snapshot(|shared| shared.get_depth(levels))
+    }
+
+    fn subscribe_state(&self) -> UnboundedReceiver<ConnectionState> {
+        self.driver.subscribe_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::mock::serve_json;
+    use crate::config::{DepthConfig, DepthType, SymbolType};
+    use crate::kucoin::connection::KucoinDepth;
+    use crate::{ConnectionState, DepthT, ExchangeType, ReconnectPolicy};
+    use futures_util::{SinkExt, StreamExt};
+    use std::time::Duration;
+    use tokio::net::TcpListener;
+    use tokio::runtime::Runtime;
+    use tokio::time::timeout;
+    use tokio_tungstenite::accept_async;
+    use tokio_tungstenite::tungstenite::Message;
+
+    const WELCOME: &str = r#"{"id":"hQvf8jkno","type":"welcome"}"#;
+
+    const ACK: &str = r#"{"id":"1","type":"ack"}"#;
+
+    const SNAPSHOT: &str = r#"{"code":"200000","data":{"time":1550653727100,"sequence":"100","bids":[["99.0","1"],["98.0","2"]],"asks":[["101.0","1"],["102.0","2"]]}}"#;
+
+    fn bullet(endpoint: &str) -> String {
+        format!(
+            r#"{{"code":"200000","data":{{"token":"token","instanceServers":[{{"endpoint":"{}","encrypt":false,"protocol":"websocket","pingInterval":18000,"pingTimeout":10000}}]}}}}"#,
+            endpoint
+        )
+    }
+
+    fn update(start: i64, end: i64, bids: &str, asks: &str) -> String {
+        format!(
+            r#"{{"type":"message","topic":"/market/level2:BTC-USDT","subject":"trade.l2update","data":{{"changes":{{"asks":{},"bids":{}}},"sequenceEnd":{},"sequenceStart":{},"symbol":"BTC-USDT","time":{}}}}}"#,
+            asks,
+            bids,
+            end,
+            start,
+            1550653727000 + end
+        )
+    }
+
+    #[test]
+    fn book_from_mock_server() {
+        Runtime::new().unwrap().block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let endpoint = format!("ws://{}/", listener.local_addr().unwrap());
+
+            let rest = serve_json(vec![
+                ("/api/v1/bullet-public", bullet(&endpoint)),
+                ("/api/v1/market/orderbook/level2_20", SNAPSHOT.to_string()),
+            ])
+            .await;
+
+            let server = tokio::spawn(async move {
+                let (socket, _) = listener.accept().await.unwrap();
+                let mut ws = accept_async(socket).await.unwrap();
+                ws.send(Message::from(WELCOME)).await.unwrap();
+
+                let request = ws.next().await.unwrap().unwrap().into_text().unwrap();
+                assert!(request.contains(r#""type":"subscribe""#));
+                assert!(request.contains(r#""topic":"/market/level2:BTC-USDT""#));
+
+                let frames = [
+                    ACK.to_string(),
+                    update(95, 99, r#"[["99.0","5","99"]]"#, "[]"),
+                    update(100, 102, "[]", r#"[["101.0","0","102"]]"#),
+                    update(103, 104, r#"[["99.5","1","104"]]"#, "[]"),
+                    update(105, 106, "[]", r#"[["101.5","3","106"]]"#),
+                    update(107, 108, r#"[["98.0","0","108"]]"#, "[]"),
+                    update(109, 110, "[]", r#"[["100.5","1","110"]]"#),
+                    update(200, 201, "[]", "[]"),
+                ];
+                for frame in frames {
+                    ws.send(Message::from(frame)).await.unwrap();
+                }
+                ws
+            });
+
+            let config = DepthConfig {
+                depth_url: DepthType::DepthSnapshot(
+                    format!("{}/api/v1/market/orderbook/level2_20?symbol=BTC-USDT", rest),
+                    format!("{}/api/v1/bullet-public", rest),
+                ),
+                symbol_type: SymbolType::Spot(String::from("BTC-USDT")),
+                exchange_type: ExchangeType::Kucoin,
+                limit: Some(2),
+                checksum: None,
+                audit_interval: None,
+                reconnect: ReconnectPolicy::default(),
+            };
+
+            let book = KucoinDepth::new();
+            let mut state = book.subscribe_state();
+            let mut recv = book.depth_snapshot(config).unwrap();
+
+            timeout(Duration::from_secs(10), async {
+                let depth = recv.recv().await.unwrap();
+                assert_eq!(depth.id, 108);
+                assert_eq!(depth.ts, 1550653727108);
+                assert_eq!(depth.bids.len(), 2);
+                assert_eq!(depth.bids[0].price, 99.5);
+                assert_eq!(depth.bids[1].price, 99.0);
+                assert_eq!(depth.asks[0].price, 101.5);
+                assert_eq!(depth.asks[1].price, 102.0);
+                assert!(matches!(
+                    state.recv().await,
+                    Some(ConnectionState::Connected)
+                ));
+
+                let depth = recv.recv().await.unwrap();
+                assert_eq!(depth.id, 110);
+                assert_eq!(depth.asks[0].price, 100.5);
+                assert_eq!(depth.asks.len(), 2);
+
+                assert!(matches!(
+                    state.recv().await,
+                    Some(ConnectionState::Resyncing(_))
+                ));
+                assert!(book.snapshot().is_none());
+            })
+            .await
+            .unwrap();
+
+            server.await.unwrap();
+        })
+    }
+}
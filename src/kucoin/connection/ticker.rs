@@ -0,0 +1,92 @@
+use crate::kucoin::connection::abstraction::KucoinStream;
+use crate::kucoin::format::MatchData;
+use crate::metrics;
+use crate::{Backoff, Ticker, TickerConfig, TickerT};
+use anyhow::Result;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tracing::{error, info, warn};
+
+const TOPIC: &str = "/market/match";
+
+#[derive(Clone)]
+pub struct KucoinTicker {
+    status: Arc<Mutex<bool>>,
+}
+
+impl KucoinTicker {
+    pub fn new() -> Self {
+        KucoinTicker {
+            status: Arc::new(Mutex::new(false)),
+        }
+    }
+}
+
+impl TickerT for KucoinTicker {
+    fn connect(&self, config: TickerConfig) -> Result<UnboundedReceiver<Vec<Ticker>>> {
+        let address = config.ticker_url.clone();
+        let topic = format!("{}:{}", TOPIC, config.get_symbol());
+
+        let status = self.status.clone();
+
+        let symbol = config.get_symbol();
+        let reconnect = config.reconnect.clone();
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            info!("Start KuCoin match thread");
+            let mut backoff = Backoff::new(reconnect);
+            loop {
+                let mut stream = match KucoinStream::connect(&address, &topic).await {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        warn!("connection error {:?}", e);
+                        if !backoff.wait("kucoin").await {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+                backoff.connected();
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = true;
+                }
+
+                loop {
+                    let trade = match stream.next_data::<MatchData>().await {
+                        Ok(trade) => trade,
+                        Err(e) => {
+                            warn!("{:?}, reconnecting", e);
+                            metrics::reconnect("kucoin");
+                            break;
+                        }
+                    };
+
+                    let tick = match trade.tick() {
+                        Ok(tick) => tick,
+                        Err(e) => {
+                            warn!("Bad trade {:?}, {:?}", trade, e);
+                            continue;
+                        }
+                    };
+
+                    let ticks = vec![tick];
+                    metrics::trades("kucoin", &symbol, &ticks);
+                    if sender.send(ticks).is_err() {
+                        error!("KuCoin Ticker send error");
+                    }
+                }
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = false;
+                }
+                if !backoff.wait("kucoin").await {
+                    break;
+                }
+            }
+        });
+
+        Ok(receiver)
+    }
+}
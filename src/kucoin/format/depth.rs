@@ -0,0 +1,238 @@
+use crate::api::format::quote_tuples;
+use crate::api::sync::{DiffBook, DiffEvent, DiffSnapshot};
+use crate::{BookSide, Depth, OrderBook, Quote};
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::debug;
+
+/// `["price", "size", "sequence"]`
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(try_from = "(String, String, String)")]
+pub struct Change {
+    pub price: f64,
+    pub size: f64,
+    pub sequence: i64,
+}
+
+impl TryFrom<(String, String, String)> for Change {
+    type Error = String;
+
+    fn try_from((price, size, sequence): (String, String, String)) -> Result<Self, Self::Error> {
+        Ok(Change {
+            price: price
+                .parse()
+                .map_err(|_| "Fail to convert price str to f64")?,
+            size: size
+                .parse()
+                .map_err(|_| "Fail to convert size str to f64")?,
+            sequence: sequence
+                .parse()
+                .map_err(|_| "Fail to convert sequence str to i64")?,
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Changes {
+    pub asks: Vec<Change>,
+    pub bids: Vec<Change>,
+}
+
+/// Data of `trade.l2update`
+#[allow(dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct L2Update {
+    pub sequence_start: i64,
+    pub sequence_end: i64,
+    /// Something like "BTC-USDT"
+    pub symbol: String,
+    pub changes: Changes,
+    /// Milliseconds, missing from older feeds
+    #[serde(default)]
+    pub time: Option<i64>,
+}
+
+impl DiffEvent for L2Update {
+    /// [E.U,..,S.u,..,E.u]
+    fn matches(&self, snap_shot_id: i64) -> bool {
+        debug!(
+            "order book {}, Event {}-{}",
+            snap_shot_id, self.sequence_start, self.sequence_end
+        );
+
+        self.sequence_start <= snap_shot_id + 1 && snap_shot_id < self.sequence_end
+    }
+
+    /// [E.U,..,E.u] S.u
+    fn behind(&self, snap_shot_id: i64) -> bool {
+        self.sequence_end <= snap_shot_id
+    }
+
+    fn equals(&self, snap_shot_id: i64) -> bool {
+        self.sequence_start == snap_shot_id + 1
+    }
+}
+
+/// REST `level2_20` / `level2_100`
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+pub struct SnapshotRespond {
+    pub code: String,
+    pub data: BookSnapshot,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BookSnapshot {
+    /// Milliseconds
+    pub time: i64,
+    #[serde(deserialize_with = "from_str")]
+    pub sequence: i64,
+    #[serde(deserialize_with = "quote_tuples")]
+    pub bids: Vec<Quote>,
+    #[serde(deserialize_with = "quote_tuples")]
+    pub asks: Vec<Quote>,
+}
+
+/// KuCoin sends sequences as strings
+fn from_str<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let val = <&str>::deserialize(deserializer)?;
+    val.parse::<i64>()
+        .map_err(|_| D::Error::custom("Fail to convert sequence str to i64"))
+}
+
+impl DiffSnapshot for SnapshotRespond {
+    fn id(&self) -> i64 {
+        self.data.sequence
+    }
+}
+
+/// Data of `/spotMarket/level2Depth50`, the best levels of the book
+#[derive(Deserialize, Debug)]
+pub struct BookLevels {
+    pub timestamp: i64,
+    #[serde(deserialize_with = "quote_tuples")]
+    pub bids: Vec<Quote>,
+    #[serde(deserialize_with = "quote_tuples")]
+    pub asks: Vec<Quote>,
+}
+
+#[derive(Default)]
+pub struct BookShared {
+    /// Sequence of the book, local sequence of `BookLevels` in Level Mode
+    last_update_id: i64,
+    send_time: i64,
+    receive_time: i64,
+    book: OrderBook,
+}
+
+impl BookShared {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only used for `/spotMarket/level2Depth50`
+    pub fn set_levels(&mut self, levels: BookLevels) {
+        self.book.apply_snapshot(levels.bids, levels.asks);
+
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        self.last_update_id += 1;
+        self.send_time = levels.timestamp;
+        self.receive_time = time.as_millis() as i64;
+    }
+
+    /// The best `levels` of each side
+    pub fn get_depth(&self, levels: usize) -> Depth {
+        Depth {
+            lts: self.receive_time,
+            ts: self.send_time,
+            id: self.last_update_id,
+            asks: self.book.top(BookSide::Ask, levels),
+            bids: self.book.top(BookSide::Bid, levels),
+        }
+    }
+
+    /// Changes up to the sequence of the book are already in it,
+    /// price "0" only moves the sequence forward
+    fn apply(&mut self, side: BookSide, changes: &[Change]) {
+        for change in changes {
+            if change.sequence <= self.last_update_id || change.price == 0.0 {
+                continue;
+            }
+
+            let quote = Quote {
+                price: change.price,
+                amount: change.size,
+                orders: None,
+            };
+            self.book.apply_level(side, quote);
+        }
+    }
+}
+
+impl DiffBook<L2Update> for BookShared {
+    type Snapshot = SnapshotRespond;
+
+    fn id(&self) -> i64 {
+        self.last_update_id
+    }
+
+    fn load_snapshot(&mut self, snapshot: &SnapshotRespond) {
+        self.book.apply_snapshot(
+            snapshot.data.bids.iter().copied(),
+            snapshot.data.asks.iter().copied(),
+        );
+
+        self.last_update_id = snapshot.data.sequence;
+        self.send_time = snapshot.data.time;
+    }
+
+    fn add_event(&mut self, event: L2Update) {
+        self.apply(BookSide::Ask, &event.changes.asks);
+        self.apply(BookSide::Bid, &event.changes.bids);
+
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        self.last_update_id = event.sequence_end;
+        self.receive_time = time.as_millis() as i64;
+        self.send_time = event.time.unwrap_or(self.receive_time);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::sync::{DiffBook, DiffEvent, DiffSnapshot};
+    use crate::kucoin::format::depth::{BookShared, L2Update, SnapshotRespond};
+
+    const SNAPSHOT: &str = r#"{"code":"200000","data":{"time":1550653727731,"sequence":"16","bids":[["6500.12","0.45054140"],["6500.11","0.45054140"]],"asks":[["6500.16","0.57753524"],["6500.15","0.57753524"]]}}"#;
+
+    const UPDATE: &str = r#"{"changes":{"asks":[["6500.15","0","15"],["6500.17","1.2","17"]],"bids":[["0","0","18"],["6500.11","0","18"]]},"sequenceEnd":18,"sequenceStart":15,"symbol":"BTC-USDT","time":1550653727800}"#;
+
+    #[test]
+    fn changes_after_snapshot_sequence() {
+        let snapshot: SnapshotRespond = serde_json::from_str(SNAPSHOT).unwrap();
+        let update: L2Update = serde_json::from_str(UPDATE).unwrap();
+        assert_eq!(snapshot.id(), 16);
+        assert!(update.matches(snapshot.id()));
+        assert!(!update.equals(snapshot.id()));
+
+        let mut book = BookShared::new();
+        book.load_snapshot(&snapshot);
+        book.add_event(update);
+
+        let snapshot = book.get_depth(usize::MAX);
+        assert_eq!(snapshot.id, 18);
+        assert_eq!(snapshot.ts, 1550653727800);
+        // Sequence 15 is older than the snapshot
+        assert_eq!(snapshot.asks[0].price, 6500.15);
+        assert_eq!(snapshot.asks[2].price, 6500.17);
+        assert_eq!(snapshot.bids.len(), 1);
+        assert_eq!(snapshot.bids[0].price, 6500.12);
+
+        assert_eq!(book.get_depth(1).asks.len(), 1);
+    }
+}
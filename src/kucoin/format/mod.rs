@@ -0,0 +1,11 @@
+mod depth;
+mod request;
+mod respond;
+mod stream;
+mod ticker;
+
+pub use depth::{BookLevels, BookShared, L2Update};
+pub use request::{ping_message, subscribe_message};
+pub use respond::BulletRespond;
+pub use stream::FeedMessage;
+pub use ticker::MatchData;
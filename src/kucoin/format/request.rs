@@ -0,0 +1,40 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TopicRequest<'a> {
+    id: String,
+    #[serde(rename = "type")]
+    ttype: &'a str,
+    topic: &'a str,
+    private_channel: bool,
+    response: bool,
+}
+
+#[derive(Serialize)]
+struct PingRequest<'a> {
+    id: String,
+    #[serde(rename = "type")]
+    ttype: &'a str,
+}
+
+/// e.g. `{"id":"1","type":"subscribe","topic":"/market/level2:BTC-USDT","privateChannel":false,"response":true}`
+pub fn subscribe_message(id: u64, topic: &str) -> String {
+    let inner = TopicRequest {
+        id: id.to_string(),
+        ttype: "subscribe",
+        topic,
+        private_channel: false,
+        response: true,
+    };
+    serde_json::to_string(&inner).unwrap()
+}
+
+/// Client ping, answered with `pong` of the same id
+pub fn ping_message(id: u64) -> String {
+    let inner = PingRequest {
+        id: id.to_string(),
+        ttype: "ping",
+    };
+    serde_json::to_string(&inner).unwrap()
+}
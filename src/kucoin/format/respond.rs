@@ -0,0 +1,66 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::time::Duration;
+
+/// `bullet-public` grants a token for one of `instance_servers`
+#[derive(Deserialize, Debug)]
+pub struct BulletRespond {
+    pub code: String,
+    pub data: BulletData,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BulletData {
+    pub token: String,
+    pub instance_servers: Vec<InstanceServer>,
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceServer {
+    pub endpoint: String,
+    pub protocol: String,
+    pub encrypt: bool,
+    /// Milliseconds between client pings
+    pub ping_interval: u64,
+    /// Milliseconds to wait for a pong
+    pub ping_timeout: u64,
+}
+
+impl BulletRespond {
+    /// Websocket address of the first server with the token,
+    /// and the interval the server expects pings at
+    pub fn connect_address(&self, connect_id: &str) -> Result<(String, Duration)> {
+        let server = match self.data.instance_servers.first() {
+            Some(server) => server,
+            None => return Err(anyhow!("KuCoin bullet {} has no server", self.code)),
+        };
+
+        let address = format!(
+            "{}?token={}&connectId={}",
+            server.endpoint, self.data.token, connect_id
+        );
+
+        Ok((address, Duration::from_millis(server.ping_interval)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kucoin::format::BulletRespond;
+    use std::time::Duration;
+
+    const BULLET: &str = r#"{"code":"200000","data":{"token":"2neAiuYvAU61ZDXANAGAsiL4-iAExhsBXZxftpOeh_55i3Ysy2q2LEsEWU64mdzUOPusi34M_wGoSf7iNyEWJ4aBZXpWhrmY9jKtqkdWoFa75w3istPvPtiYB9J6i9GjsxUuhPw3BlrzazF6ghq4L_zJAbjvhm-P_XrmuRvv0Rg5.zBSbS_gkGKNEXcfC-kp8fw==","instanceServers":[{"endpoint":"wss://ws-api-spot.kucoin.com/","encrypt":true,"protocol":"websocket","pingInterval":18000,"pingTimeout":10000}]}}"#;
+
+    #[test]
+    fn bullet_address() {
+        let bullet: BulletRespond = serde_json::from_str(BULLET).unwrap();
+        let (address, ping_interval) = bullet.connect_address("1545910660739").unwrap();
+
+        assert!(address.starts_with("wss://ws-api-spot.kucoin.com/?token=2neAiuYvAU61ZDXANAGAsiL4"));
+        assert!(address.ends_with("&connectId=1545910660739"));
+        assert_eq!(ping_interval, Duration::from_millis(18000));
+    }
+}
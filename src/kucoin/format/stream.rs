@@ -0,0 +1,55 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Everything the server sends, data of the subscribed topic is `Message`
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum FeedMessage {
+    Welcome {
+        id: String,
+    },
+    Ack {
+        id: String,
+    },
+    Pong {
+        id: String,
+    },
+    Message {
+        topic: String,
+        subject: String,
+        data: Value,
+    },
+    Error {
+        code: i64,
+        data: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kucoin::format::FeedMessage;
+
+    #[test]
+    fn feed_deserialize() {
+        let welcome = r#"{"id":"hQvf8jkno","type":"welcome"}"#;
+        assert!(matches!(
+            serde_json::from_str(welcome).unwrap(),
+            FeedMessage::Welcome { .. }
+        ));
+
+        let error = r#"{"id":"1","type":"error","code":404,"data":"topic /market/level2:BTC-USTD is not found"}"#;
+        match serde_json::from_str(error).unwrap() {
+            FeedMessage::Error { code, .. } => assert_eq!(code, 404),
+            other => panic!("Unexpected {:?}", other),
+        }
+
+        let message = r#"{"type":"message","topic":"/market/level2:BTC-USDT","subject":"trade.l2update","data":{}}"#;
+        match serde_json::from_str(message).unwrap() {
+            FeedMessage::Message { subject, .. } => assert_eq!(subject, "trade.l2update"),
+            other => panic!("Unexpected {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{OrderDirection, Ticker};
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Data of `trade.l3match`
+#[allow(dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchData {
+    pub sequence: String,
+
+    /// Something like "BTC-USDT"
+    pub symbol: String,
+
+    /// "buy" / "sell", direction of the taker
+    pub side: String,
+
+    pub price: String,
+
+    pub size: String,
+
+    pub trade_id: String,
+
+    /// Nanoseconds
+    pub time: String,
+}
+
+impl MatchData {
+    pub fn tick(&self) -> Result<Ticker> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let direction = match self.side.as_str() {
+            "buy" => OrderDirection::Buy,
+            "sell" => OrderDirection::Sell,
+            other => return Err(anyhow!("Unknown side {}", other)),
+        };
+
+        Ok(Ticker {
+            lts: now.as_millis() as i64,
+            ts: self.time.parse::<i64>()? / 1_000_000,
+            price: self.price.parse()?,
+            amount: self.size.parse()?,
+            direction,
+            id: self.trade_id.parse()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kucoin::format::MatchData;
+    use crate::OrderDirection;
+
+    const MATCH: &str = r#"{"makerOrderId":"6720da3fa30a360007f5f832","price":"67523","sequence":"11067996711960577","side":"buy","size":"0.003","symbol":"BTC-USDT","takerOrderId":"6720da3fa30a360007f5f833","time":"1730205247690000000","tradeId":"11067996711960577","type":"match"}"#;
+
+    #[test]
+    fn match_to_ticker() {
+        let data: MatchData = serde_json::from_str(MATCH).unwrap();
+
+        let tick = data.tick().unwrap();
+        assert_eq!(tick.id, 11067996711960577);
+        assert_eq!(tick.ts, 1730205247690);
+        assert_eq!(tick.price, 67523.0);
+        assert_eq!(tick.amount, 0.003);
+        assert!(matches!(tick.direction, OrderDirection::Buy));
+    }
+}
@@ -0,0 +1,7 @@
+mod adapter;
+pub mod connection;
+pub mod format;
+
+pub use adapter::KucoinAdapter;
+pub use connection::KucoinDepth;
+pub use connection::KucoinTicker;
@@ -1,17 +1,52 @@
 pub(crate) mod binance;
+pub(crate) mod bitfinex;
+pub(crate) mod bybit;
+pub(crate) mod coinbase;
 pub(crate) mod crypto;
+pub(crate) mod deribit;
+pub(crate) mod gateio;
+pub(crate) mod kraken;
+pub(crate) mod kucoin;
+pub(crate) mod okx;
 
 pub(crate) mod api;
 pub(crate) mod config;
 
-pub(crate) use config::{
-    get_depth_config_from, get_ticker_config_from, TickerConnection,
-};
-pub(crate) use api::depth::DepthT;
-
-pub use api::{Depth, DepthManager, ExchangeType, OrderDirection, Quote, Ticker, TickerManager};
+pub(crate) use api::{Backoff, StateSender};
+pub(crate) use config::{get_kline_config_from, KlineConnection};
 
-pub use config::{DepthConfig, TickerConfig};
+pub use api::{
+    AdapterRegistry, Bar, BarAggregator, BarKind, BookDiff, BookLevel, BookSide, ChecksumVerifier,
+    ConnectionState, Crc32Checksum, Depth, DepthManager, DepthT, EndpointOverrides,
+    ExchangeAdapter, ExchangeType, Fleet, FleetConfig, FleetData, FleetEvent, InstrumentInfo,
+    InstrumentKind, InstrumentRegistry, InstrumentStatus, KlineManager, OrderBook, OrderDirection,
+    Quote, RawFrame, RawFrames, ReconnectPolicy, SinkConfig, StreamKind, SubscriptionConfig,
+    Ticker, TickerManager, TickerT, SCHEMA_VERSION,
+};
+pub use api::{run_sink, CsvSink, FileSinkConfig, JsonlSink, Sink, TimeFormat};
+pub use api::metrics;
+#[cfg(feature = "parquet")]
+pub use api::{
+    convert_records, Conversion, DepthLayout, DepthParquetWriter, ParquetOptions,
+    TickerParquetWriter,
+};
+#[cfg(feature = "gateway")]
+pub use api::Gateway;
+pub use binance::BinanceAdapter;
+pub use bitfinex::{BitfinexAdapter, BitfinexPrecision};
+pub use bybit::BybitAdapter;
+pub use coinbase::CoinbaseAdapter;
+pub use crypto::CryptoAdapter;
+pub use deribit::DeribitAdapter;
+pub use gateio::GateioAdapter;
+pub use kraken::KrakenAdapter;
+pub use kucoin::KucoinAdapter;
+pub use okx::OkxAdapter;
+
+pub use config::{
+    DepthConfig, DepthType, Instrument, InstrumentType, KlineConfig, Margin, Method,
+    OptionContract, OptionKind, SymbolType, TickerConfig,
+};
 
 #[cfg(test)]
 mod tests {
@@ -19,28 +54,48 @@ mod tests {
     fn manager_builder_works() {
         use crate::DepthManager;
 
-        let exchange = "binance";
-        let exchange2 = "crypto";
-        let pc_symbol = "btcusd_221230_swap";
-        let pu_symbol = "btcusdt_swap";
-        let spot_symbol = "bnbbtc";
         let limit = 1000;
+        let symbols = [
+            ("binance", "BTC_USD_SWAP"),
+            ("binance", "BTC_USDT_SWAP"),
+            ("binance", "BNB_BTC"),
+            ("crypto", "BTC_USDT_SWAP"),
+            ("crypto", "BTC_USDT"),
+        ];
+
+        for (exchange, symbol) in symbols {
+            let manager = DepthManager::with_snapshot(exchange, symbol, limit);
+            assert!(manager.config.is_depth_snapshot(), "{} of {}", symbol, exchange);
+
+            let manager = DepthManager::new(exchange, symbol);
+            assert!(manager.config.is_depth(), "{} of {}", symbol, exchange);
+        }
+    }
 
-        let _ = DepthManager::with_snapshot(exchange, pc_symbol, limit);
-        let _ = DepthManager::with_snapshot(exchange, pu_symbol, limit);
-        let _ = DepthManager::with_snapshot(exchange, spot_symbol, limit);
-
-        let _ = DepthManager::new(exchange, pc_symbol);
-        let _ = DepthManager::new(exchange, pu_symbol);
-        let _ = DepthManager::new(exchange, spot_symbol);
-
-        let _ = DepthManager::with_snapshot(exchange2, pc_symbol, limit);
-        let _ = DepthManager::with_snapshot(exchange2, pu_symbol, limit);
-        let _ = DepthManager::with_snapshot(exchange2, spot_symbol, limit);
-
-        let _ = DepthManager::new(exchange2, pc_symbol);
-        let _ = DepthManager::new(exchange2, pu_symbol);
-        let _ = DepthManager::new(exchange2, spot_symbol);
+    #[test]
+    fn manager_builder_rejects() {
+        use crate::api::adapter::get_adapter;
+
+        let symbols = [
+            // Not canonical
+            ("binance", "btcusd_221230_swap"),
+            ("binance", "BTC_USD_221230SWAP"),
+            // Delivered
+            ("binance", "BTC_USD_221230_SWAP"),
+            // No coin margined contracts
+            ("crypto", "BTC_USD_221230_SWAP"),
+        ];
+
+        for (exchange, symbol) in symbols {
+            let adapter = get_adapter(exchange);
+            assert!(
+                adapter.depth_config(symbol, Some(1000)).is_err(),
+                "{} of {}",
+                symbol,
+                exchange
+            );
+            assert!(adapter.depth_config(symbol, None).is_err());
+        }
     }
 
     #[test]
@@ -49,19 +104,19 @@ mod tests {
         use crate::DepthManager;
 
         let wrong_exchange = "binanc";
-        let pc_symbol = "btcusd_221230_swap";
+        let pc_symbol = "BTC_USD_SWAP";
         let limit = 1000;
 
         let _ = DepthManager::with_snapshot(wrong_exchange, pc_symbol, limit);
     }
 
     #[test]
-    #[should_panic]
+    #[should_panic(expected = "Unsupported Symbol")]
     fn manager_builder_wrong_symbol() {
         use crate::DepthManager;
 
         let wrong_exchange = "binance";
-        let pc_symbol = "btcusd_221230swap";
+        let pc_symbol = "BTC_USD_221230SWAP";
         let limit = 1000;
 
         let _ = DepthManager::with_snapshot(wrong_exchange, pc_symbol, limit);
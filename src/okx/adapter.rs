@@ -0,0 +1,62 @@
+use crate::config::{instrument_okx, symbol_type_okx, Instrument};
+use crate::config::{set_addr_for_okx, set_ticker_addr_for_okx, validate_symbol_okx};
+use crate::config::{DepthType, SymbolType};
+use crate::okx::{OkxDepth, OkxTicker};
+use crate::{DepthConfig, DepthT, ExchangeAdapter, ExchangeType, TickerConfig, TickerT};
+use anyhow::{anyhow, Result};
+use std::sync::Arc;
+
+/// Spot, perpetual swaps and dated futures of Okx
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OkxAdapter;
+
+impl OkxAdapter {
+    fn inst_id(symbol_type: &SymbolType) -> &str {
+        match symbol_type {
+            SymbolType::Spot(s) | SymbolType::ContractUSDT(s) | SymbolType::ContractCoin(s) => s,
+            SymbolType::Option(option) => &option.name,
+        }
+    }
+}
+
+impl ExchangeAdapter for OkxAdapter {
+    fn name(&self) -> &'static str {
+        "okx"
+    }
+
+    fn exchange_type(&self) -> ExchangeType {
+        ExchangeType::Okx
+    }
+
+    fn validate_symbol(&self, symbol: &str, _limit: Option<i32>) -> Result<SymbolType> {
+        validate_symbol_okx(symbol)
+    }
+
+    fn depth_url(&self, symbol_type: &SymbolType, limit: Option<i32>) -> Result<DepthType> {
+        let (rest_address, depth_address, level_depth_address) =
+            set_addr_for_okx(Self::inst_id(symbol_type), limit);
+
+        DepthType::new(rest_address, depth_address, level_depth_address)
+            .ok_or_else(|| anyhow!("depth url is empty"))
+    }
+
+    fn ticker_url(&self, _symbol_type: &SymbolType) -> Result<String> {
+        Ok(set_ticker_addr_for_okx())
+    }
+
+    fn depth_connection(&self, _config: &DepthConfig) -> Result<Arc<dyn DepthT + Send + Sync>> {
+        Ok(Arc::new(OkxDepth::new()))
+    }
+
+    fn ticker_connection(&self, _config: &TickerConfig) -> Result<Arc<dyn TickerT + Send + Sync>> {
+        Ok(Arc::new(OkxTicker::new()))
+    }
+
+    fn native_symbol(&self, instrument: &Instrument) -> Result<SymbolType> {
+        symbol_type_okx(instrument)
+    }
+
+    fn instrument_of(&self, symbol_type: &SymbolType) -> Result<Instrument> {
+        instrument_okx(symbol_type)
+    }
+}
@@ -0,0 +1,132 @@
+use crate::api::driver::{decode, BookFeed};
+use crate::okx::connection::OkxWebSocket;
+use crate::okx::format::{subscribe_message, unsubscribe_message, Arg, EventRespond};
+use crate::RawFrames;
+use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
+use futures_util::{SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+use tokio::time::timeout;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::debug;
+use url::Url;
+
+/// Okx drops connections quiet for 30 seconds
+const PING_INTERVAL: Duration = Duration::from_secs(25);
+
+pub async fn okx_initialize(address: &str, arg: Arg) -> Result<OkxWebSocket> {
+    let url = Url::parse(address)?;
+
+    let mut stream = match connect_async(url).await {
+        Ok((connection, _)) => connection,
+        Err(e) => return Err(anyhow!("{:?}", e)),
+    };
+    debug!("Connect to {} success", address);
+
+    stream
+        .send(Message::from(subscribe_message(arg.clone())))
+        .await?;
+
+    debug!("Subscribe to {:?} success", arg);
+
+    Ok(stream)
+}
+
+/// Subscribe again on the same connection to get a fresh snapshot
+pub async fn okx_resubscribe(stream: &mut OkxWebSocket, arg: Arg) -> Result<()> {
+    stream
+        .send(Message::from(unsubscribe_message(arg.clone())))
+        .await?;
+    stream
+        .send(Message::from(subscribe_message(arg.clone())))
+        .await?;
+
+    debug!("Resubscribe to {:?} success", arg);
+
+    Ok(())
+}
+
+/// Next push data of the stream, keeping it alive on the way:
+/// a text "ping" is sent once the stream is quiet for `PING_INTERVAL`,
+/// Err if no "pong" or anything else follows.
+///
+/// `event` responds are skipped, Err for `error` ones
+pub async fn next_push(stream: &mut OkxWebSocket) -> Result<String> {
+    let mut pinged = false;
+
+    loop {
+        let message = match timeout(PING_INTERVAL, stream.next()).await {
+            Ok(Some(message)) => message?,
+            Ok(None) => return Err(anyhow!("Connection closed")),
+            Err(_) if pinged => return Err(anyhow!("No pong in {:?}", PING_INTERVAL)),
+            Err(_) => {
+                stream.send(Message::from("ping")).await?;
+                pinged = true;
+                continue;
+            }
+        };
+        pinged = false;
+
+        let text = match message {
+            Message::Text(text) => {
+                RawFrames::global().publish("okx", &text);
+                text
+            }
+            Message::Ping(payload) => {
+                stream.send(Message::Pong(payload)).await?;
+                continue;
+            }
+            Message::Close(frame) => return Err(anyhow!("Connection closed {:?}", frame)),
+            _ => continue,
+        };
+
+        if text == "pong" {
+            debug!("Receive pong");
+            continue;
+        }
+
+        if let Ok(respond) = serde_json::from_str::<EventRespond>(&text) {
+            if respond.event == "error" {
+                return Err(anyhow!("Okx error {}: {}", respond.code, respond.msg));
+            }
+
+            debug!("Receive {:?}", respond);
+            continue;
+        }
+
+        return Ok(text);
+    }
+}
+
+/// Connection subscribed to the book channel of `arg`
+pub struct OkxStream {
+    stream: OkxWebSocket,
+    arg: Arg,
+}
+
+impl OkxStream {
+    pub async fn connect(address: &str, arg: Arg) -> Result<Self> {
+        let stream = okx_initialize(address, arg.clone()).await?;
+
+        Ok(OkxStream { stream, arg })
+    }
+}
+
+impl BookFeed for OkxStream {
+    fn next<T: DeserializeOwned + Send + 'static>(&mut self) -> BoxFuture<'_, Result<T>> {
+        Box::pin(async move {
+            loop {
+                let text = next_push(&mut self.stream).await?;
+                if let Some(push) = decode("okx", &text) {
+                    return Ok(push);
+                }
+            }
+        })
+    }
+
+    fn resubscribe(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(okx_resubscribe(&mut self.stream, self.arg.clone()))
+    }
+}
@@ -0,0 +1,204 @@
+use crate::api::checksum::verify_checksum;
+use crate::api::driver::{BookDriver, Connect};
+use crate::okx::connection::abstraction::OkxStream;
+use crate::okx::format::{Arg, BookEventStream, BookShared};
+use crate::{ChecksumVerifier, ConnectionState, Depth, DepthConfig, DepthT};
+use anyhow::Result;
+use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tracing::debug;
+
+#[derive(Clone)]
+pub struct OkxDepth {
+    driver: BookDriver<BookShared>,
+}
+
+/// Connect to `address` and subscribe `arg`
+fn connect(address: String, arg: Arg) -> Connect<OkxStream> {
+    Box::new(move || {
+        let address = address.clone();
+        let arg = arg.clone();
+        Box::pin(async move { OkxStream::connect(&address, arg).await })
+    })
+}
+
+/// Put a push of `books` / `books5` onto `shared`,
+/// `books5` pushes are handled as snapshots.
+///
+/// Every push is verified by the sequence and by `checksum` if there is one
+fn apply(
+    shared: &mut BookShared,
+    push: BookEventStream,
+    is_ready: bool,
+    checksum: &Option<Arc<dyn ChecksumVerifier>>,
+) -> Option<Result<Depth>> {
+    let is_update = push.action.as_deref() == Some("update");
+
+    // Deltas before the fresh snapshot
+    if is_update && !is_ready {
+        debug!("Skip update before snapshot");
+        return None;
+    }
+
+    let result = push
+        .data
+        .into_iter()
+        .try_for_each(|data| {
+            if is_update {
+                shared.add_update(data)
+            } else {
+                shared.set_snapshot(data)
+            }
+        })
+        .and_then(|_| shared.verify())
+        .and_then(|_| verify_checksum(shared.get_snapshot(), shared.checksum(), checksum));
+
+    Some(result)
+}
+
+impl OkxDepth {
+    fn maintain(&self, address: String, arg: Arg, config: DepthConfig) -> UnboundedReceiver<Depth> {
+        let checksum = config.checksum.clone();
+
+        self.driver.clone().pushed_book(
+            connect(address, arg),
+            config.get_symbol(),
+            config.reconnect,
+            move |shared, push, is_ready| apply(shared, push, is_ready, &checksum),
+        )
+    }
+}
+
+impl DepthT for OkxDepth {
+    fn new() -> Self {
+        OkxDepth {
+            driver: BookDriver::new("okx", BookShared::new()),
+        }
+    }
+
+    /// acquire a order book with `books` deltas
+    fn depth_snapshot(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
+        let (_, depth_address) = config.get_depth_snapshot_addresses();
+        let arg = Arg::new("books", &config.get_symbol());
+
+        Ok(self.maintain(depth_address, arg, config))
+    }
+
+    /// acquire the top 5 levels with `books5`
+    fn depth(&self, config: DepthConfig) -> Result<UnboundedReceiver<Depth>> {
+        let level_address = config.get_depth_addresses();
+        let arg = Arg::new("books5", &config.get_symbol());
+
+        Ok(self.maintain(level_address, arg, config))
+    }
+
+    /// Get the snapshot of the current Order Book
+    fn snapshot(&self) -> Option<Depth> {
+        self.driver.snapshot(BookShared::get_snapshot)
+    }
+
+    fn subscribe_state(&self) -> UnboundedReceiver<ConnectionState> {
+        self.driver.subscribe_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{DepthConfig, DepthType, SymbolType};
+    use crate::okx::connection::OkxDepth;
+    use crate::{ConnectionState, DepthT, ExchangeType, ReconnectPolicy};
+    use futures_util::{SinkExt, StreamExt};
+    use std::time::Duration;
+    use tokio::net::TcpListener;
+    use tokio::runtime::Runtime;
+    use tokio::time::timeout;
+    use tokio_tungstenite::accept_async;
+    use tokio_tungstenite::tungstenite::Message;
+
+    const REST: &str = "https://www.okx.com/api/v5/market/books?instId=BTC-USDT&sz=400";
+
+    const SUBSCRIBED: &str = r#"{"event":"subscribe","arg":{"channel":"books","instId":"BTC-USDT"},"connId":"a4d3ae55"}"#;
+
+    const SNAPSHOT: &str = r#"{"arg":{"channel":"books","instId":"BTC-USDT"},"action":"snapshot","data":[{"asks":[["8476.98","415","0","13"],["8477","7","0","2"]],"bids":[["8476.9","256","0","12"],["8475.55","101","0","1"]],"ts":"1597026383085","checksum":2063024792,"prevSeqId":-1,"seqId":100}]}"#;
+
+    const UPDATE: &str = r#"{"arg":{"channel":"books","instId":"BTC-USDT"},"action":"update","data":[{"asks":[["8476.98","0","0","0"],["8477.5","3","0","1"]],"bids":[["8476.9","300","0","14"]],"ts":"1597026383185","checksum":1766859550,"prevSeqId":100,"seqId":101}]}"#;
+
+    const BAD_CHECKSUM: &str = r#"{"arg":{"channel":"books","instId":"BTC-USDT"},"action":"update","data":[{"asks":[],"bids":[],"ts":"1597026383285","checksum":1,"prevSeqId":101,"seqId":102}]}"#;
+
+    #[test]
+    fn books_from_mock_server() {
+        Runtime::new().unwrap().block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let address = format!("ws://{}", listener.local_addr().unwrap());
+
+            let server = tokio::spawn(async move {
+                let (socket, _) = listener.accept().await.unwrap();
+                let mut ws = accept_async(socket).await.unwrap();
+
+                let request = ws.next().await.unwrap().unwrap().into_text().unwrap();
+                assert!(request.contains(r#""op":"subscribe""#));
+                assert!(request.contains(r#""channel":"books""#));
+
+                for frame in [SUBSCRIBED, SNAPSHOT, "pong", UPDATE, BAD_CHECKSUM] {
+                    ws.send(Message::from(frame)).await.unwrap();
+                }
+
+                let request = ws.next().await.unwrap().unwrap().into_text().unwrap();
+                assert!(request.contains(r#""op":"unsubscribe""#));
+                let request = ws.next().await.unwrap().unwrap().into_text().unwrap();
+                assert!(request.contains(r#""op":"subscribe""#));
+
+                ws.send(Message::from(SNAPSHOT)).await.unwrap();
+                ws
+            });
+
+            let config = DepthConfig {
+                depth_url: DepthType::DepthSnapshot(REST.to_string(), address),
+                symbol_type: SymbolType::Spot(String::from("BTC-USDT")),
+                exchange_type: ExchangeType::Okx,
+                limit: Some(400),
+                checksum: None,
+                audit_interval: None,
+                reconnect: ReconnectPolicy::default(),
+            };
+
+            let book = OkxDepth::new();
+            let mut state = book.subscribe_state();
+            let mut recv = book.depth_snapshot(config).unwrap();
+
+            timeout(Duration::from_secs(10), async {
+                let depth = recv.recv().await.unwrap();
+                assert_eq!(depth.id, 100);
+                assert_eq!(depth.asks[0].price, 8476.98);
+                assert_eq!(depth.bids[0].orders, Some(12));
+                assert!(matches!(
+                    state.recv().await,
+                    Some(ConnectionState::Connected)
+                ));
+
+                let depth = recv.recv().await.unwrap();
+                assert_eq!(depth.id, 101);
+                assert_eq!(depth.ts, 1597026383185);
+                assert_eq!(depth.asks[0].price, 8477.0);
+                assert_eq!(depth.asks[1].price, 8477.5);
+                assert_eq!(depth.bids[0].amount, 300.0);
+
+                assert!(matches!(
+                    state.recv().await,
+                    Some(ConnectionState::Resyncing(_))
+                ));
+
+                let depth = recv.recv().await.unwrap();
+                assert_eq!(depth.id, 100);
+                assert!(matches!(
+                    state.recv().await,
+                    Some(ConnectionState::Connected)
+                ));
+            })
+            .await
+            .unwrap();
+
+            server.await.unwrap();
+        })
+    }
+}
@@ -0,0 +1,103 @@
+use crate::metrics;
+use crate::okx::connection::abstraction::{next_push, okx_initialize};
+use crate::okx::format::{Arg, TradeEventStream};
+use crate::{Backoff, Ticker, TickerConfig, TickerT};
+use anyhow::Result;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tracing::{error, info, warn};
+
+#[derive(Clone)]
+pub struct OkxTicker {
+    status: Arc<Mutex<bool>>,
+}
+
+impl OkxTicker {
+    pub fn new() -> Self {
+        OkxTicker {
+            status: Arc::new(Mutex::new(false)),
+        }
+    }
+}
+
+impl TickerT for OkxTicker {
+    fn connect(&self, config: TickerConfig) -> Result<UnboundedReceiver<Vec<Ticker>>> {
+        let address = config.ticker_url.clone();
+        let arg = Arg::new("trades", &config.get_symbol());
+
+        let status = self.status.clone();
+
+        let reconnect = config.reconnect.clone();
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            info!("Start Okx trades thread");
+            let mut backoff = Backoff::new(reconnect);
+            loop {
+                let mut stream = match okx_initialize(&address, arg.clone()).await {
+                    Ok(connection) => connection,
+                    Err(e) => {
+                        warn!("connection error {:?}", e);
+                        if !backoff.wait("okx").await {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+                backoff.connected();
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = true;
+                }
+
+                loop {
+                    let text = match next_push(&mut stream).await {
+                        Ok(text) => text,
+                        Err(e) => {
+                            warn!("{:?}, reconnecting", e);
+                            metrics::reconnect("okx");
+                            break;
+                        }
+                    };
+
+                    let event: TradeEventStream = match serde_json::from_str(&text) {
+                        Ok(event) => event,
+                        Err(e) => {
+                            warn!("Error {}, {:?}", e, text);
+                            metrics::decode_error("okx");
+                            continue;
+                        }
+                    };
+
+                    let ticks = event
+                        .data
+                        .iter()
+                        .filter_map(|trade| match trade.tick() {
+                            Ok(tick) => Some(tick),
+                            Err(e) => {
+                                warn!("Bad trade {:?}, {:?}", trade, e);
+                                None
+                            }
+                        })
+                        .collect::<Vec<_>>();
+
+                    metrics::trades("okx", &arg.inst_id, &ticks);
+                    if ticks.is_empty() {
+                        warn!("Okx Received empty ticks");
+                    } else if sender.send(ticks).is_err() {
+                        error!("Okx Ticker send error");
+                    }
+                }
+
+                if let Ok(mut guard) = status.lock() {
+                    (*guard) = false;
+                }
+                if !backoff.wait("okx").await {
+                    break;
+                }
+            }
+        });
+
+        Ok(receiver)
+    }
+}
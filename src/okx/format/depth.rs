@@ -0,0 +1,213 @@
+use crate::{BookLevel, BookSide, Depth, OrderBook, Quote};
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Levels covered by the checksum of `books`
+const CHECKSUM_LEVELS: usize = 25;
+
+/// `["price", "size", "liquidated orders", "orders"]`,
+/// strings are kept as sent because the checksum is built from them
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Level(pub String, pub String, pub String, pub String);
+
+impl Level {
+    /// Err if price or size are not numbers
+    fn parse(self) -> Result<Self> {
+        self.0.parse::<f64>()?;
+        self.1.parse::<f64>()?;
+        Ok(self)
+    }
+}
+
+impl BookLevel for Level {
+    fn price(&self) -> f64 {
+        self.0.parse().unwrap_or_default()
+    }
+
+    fn amount(&self) -> f64 {
+        self.1.parse().unwrap_or_default()
+    }
+
+    fn quote(&self) -> Quote {
+        Quote {
+            price: self.price(),
+            amount: self.amount(),
+            orders: self.3.parse().ok(),
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct BookData {
+    pub asks: Vec<Level>,
+
+    pub bids: Vec<Level>,
+
+    /// Only in `books5`
+    #[serde(rename = "instId", default)]
+    pub inst_id: Option<String>,
+
+    pub ts: String,
+
+    /// CRC32 of the top 25 levels, only in `books`
+    #[serde(default)]
+    pub checksum: Option<i64>,
+
+    /// `-1` for snapshots
+    #[serde(rename = "prevSeqId", default)]
+    pub prev_seq_id: Option<i64>,
+
+    #[serde(rename = "seqId", default)]
+    pub seq_id: Option<i64>,
+}
+
+pub struct BookShared {
+    seq_id: i64,
+    /// `checksum` of the last applied data, if Okx sent one
+    checksum: Option<i64>,
+    send_time: i64,
+    receive_time: i64,
+    book: OrderBook<Level>,
+}
+
+impl BookShared {
+    pub fn new() -> Self {
+        BookShared {
+            seq_id: 0,
+            checksum: None,
+            send_time: 0,
+            receive_time: 0,
+            book: OrderBook::new(),
+        }
+    }
+
+    /// `books` snapshot or `books5` push, replaces the whole book
+    pub fn set_snapshot(&mut self, data: BookData) -> Result<()> {
+        self.book.clear();
+
+        self.apply(data)
+    }
+
+    /// Only used for `books` updates,
+    /// Err if `prevSeqId` does not match the last applied `seqId`
+    pub fn add_update(&mut self, data: BookData) -> Result<()> {
+        let prev_seq_id = data.prev_seq_id.unwrap_or_default();
+        if prev_seq_id != self.seq_id {
+            return Err(anyhow!(
+                "Sequence gap, order book {}, update {:?}({})",
+                self.seq_id,
+                data.seq_id,
+                prev_seq_id
+            ));
+        }
+
+        self.apply(data)
+    }
+
+    fn apply(&mut self, data: BookData) -> Result<()> {
+        for ask in data.asks {
+            self.book.apply_level(BookSide::Ask, ask.parse()?);
+        }
+
+        for bid in data.bids {
+            self.book.apply_level(BookSide::Bid, bid.parse()?);
+        }
+
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        self.seq_id = data.seq_id.unwrap_or(self.seq_id);
+        self.checksum = data.checksum;
+        self.send_time = data.ts.parse()?;
+        self.receive_time = time.as_millis() as i64;
+
+        Ok(())
+    }
+
+    pub fn checksum(&self) -> Option<i64> {
+        self.checksum
+    }
+
+    /// Err if the book does not match the last `checksum` Okx sent
+    pub fn verify(&self) -> Result<()> {
+        let expected = match self.checksum {
+            Some(checksum) => checksum,
+            None => return Ok(()),
+        };
+
+        let checksum = crc32fast::hash(self.checksum_string().as_bytes()) as i32;
+        if checksum as i64 != expected {
+            return Err(anyhow!(
+                "Checksum mismatch, order book {}, checksum {}, expected {}",
+                self.seq_id,
+                checksum,
+                expected
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// "bid1px:bid1sz:ask1px:ask1sz:bid2px:..." over the top 25 levels,
+    /// the longer side carries on alone
+    fn checksum_string(&self) -> String {
+        let mut bids = self.book.bids().take(CHECKSUM_LEVELS);
+        let mut asks = self.book.asks().take(CHECKSUM_LEVELS);
+
+        let mut fields = Vec::new();
+        loop {
+            let (bid, ask) = (bids.next(), asks.next());
+            if bid.is_none() && ask.is_none() {
+                break;
+            }
+
+            for level in [bid, ask].into_iter().flatten() {
+                fields.push(level.0.as_str());
+                fields.push(level.1.as_str());
+            }
+        }
+
+        fields.join(":")
+    }
+
+    pub fn get_snapshot(&self) -> Depth {
+        Depth {
+            id: self.seq_id,
+            ts: self.send_time,
+            lts: self.receive_time,
+            asks: self.book.quotes(BookSide::Ask),
+            bids: self.book.quotes(BookSide::Bid),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::okx::format::{BookEventStream, BookShared};
+
+    /// Example of the Okx checksum documentation
+    const DOC_BOOK: &str = r#"{"arg":{"channel":"books","instId":"BTC-USDT"},"action":"snapshot","data":[{"asks":[["3366.8","9","10","3"],["3368","8","3","4"]],"bids":[["3366.1","7","0","3"],["3366","6","3","4"]],"ts":"1597026383085","checksum":-1881014294,"prevSeqId":-1,"seqId":10}]}"#;
+
+    const GAP: &str = r#"{"arg":{"channel":"books","instId":"BTC-USDT"},"action":"update","data":[{"asks":[],"bids":[],"ts":"1597026383185","checksum":-1881014294,"prevSeqId":12,"seqId":13}]}"#;
+
+    #[test]
+    fn checksum_of_doc_example() {
+        let mut event: BookEventStream = serde_json::from_str(DOC_BOOK).unwrap();
+        assert_eq!(event.action.as_deref(), Some("snapshot"));
+
+        let mut shared = BookShared::new();
+        shared.set_snapshot(event.data.remove(0)).unwrap();
+
+        assert_eq!(shared.checksum_string(), "3366.1:7:3366.8:9:3366:6:3368:8");
+        assert!(shared.verify().is_ok());
+
+        let depth = shared.get_snapshot();
+        assert_eq!(depth.id, 10);
+        assert_eq!(depth.ts, 1597026383085);
+        assert_eq!(depth.bids[0].price, 3366.1);
+        assert_eq!(depth.asks[1].orders, Some(4));
+
+        let mut gap: BookEventStream = serde_json::from_str(GAP).unwrap();
+        assert!(shared.add_update(gap.data.remove(0)).is_err());
+    }
+}
@@ -0,0 +1,9 @@
+mod depth;
+mod request;
+mod stream;
+mod ticker;
+
+pub use depth::{BookData, BookShared};
+pub use request::{subscribe_message, unsubscribe_message, Arg};
+pub use stream::{BookEventStream, EventRespond, TradeEventStream};
+pub use ticker::TradeData;
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// One subscription, e.g. `{"channel":"books","instId":"BTC-USDT"}`
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Arg {
+    pub channel: String,
+
+    #[serde(rename = "instId")]
+    pub inst_id: String,
+}
+
+impl Arg {
+    pub fn new(channel: &str, inst_id: &str) -> Self {
+        Arg {
+            channel: channel.to_string(),
+            inst_id: inst_id.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct OpRequest<'a> {
+    op: &'a str,
+    args: Vec<Arg>,
+}
+
+pub fn subscribe_message(arg: Arg) -> String {
+    let inner = OpRequest {
+        op: "subscribe",
+        args: vec![arg],
+    };
+    serde_json::to_string(&inner).unwrap()
+}
+
+pub fn unsubscribe_message(arg: Arg) -> String {
+    let inner = OpRequest {
+        op: "unsubscribe",
+        args: vec![arg],
+    };
+    serde_json::to_string(&inner).unwrap()
+}
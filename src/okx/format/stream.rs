@@ -0,0 +1,37 @@
+use crate::okx::format::{Arg, BookData, TradeData};
+use serde::Deserialize;
+
+/// Respond of `subscribe` / `unsubscribe`, or `error`
+#[derive(Deserialize, Debug)]
+pub struct EventRespond {
+    /// "subscribe" / "unsubscribe" / "error"
+    pub event: String,
+
+    #[serde(default)]
+    pub code: String,
+
+    #[serde(default)]
+    pub msg: String,
+}
+
+/// Push data of `books` / `books5`
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+pub struct BookEventStream {
+    pub arg: Arg,
+
+    /// "snapshot" / "update" for `books`, missing for `books5`
+    #[serde(default)]
+    pub action: Option<String>,
+
+    pub data: Vec<BookData>,
+}
+
+/// Push data of `trades`
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+pub struct TradeEventStream {
+    pub arg: Arg,
+
+    pub data: Vec<TradeData>,
+}
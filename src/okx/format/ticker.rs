@@ -0,0 +1,67 @@
+use crate::{OrderDirection, Ticker};
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[allow(dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct TradeData {
+    /// Something like "BTC-USDT"
+    #[serde(rename = "instId")]
+    pub inst_id: String,
+
+    #[serde(rename = "tradeId")]
+    pub trade_id: String,
+
+    #[serde(rename = "px")]
+    pub price: String,
+
+    #[serde(rename = "sz")]
+    pub amount: String,
+
+    /// "buy" / "sell", direction of the taker
+    pub side: String,
+
+    pub ts: String,
+}
+
+impl TradeData {
+    pub fn tick(&self) -> Result<Ticker> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let direction = match self.side.as_str() {
+            "buy" => OrderDirection::Buy,
+            "sell" => OrderDirection::Sell,
+            other => return Err(anyhow!("Unknown side {}", other)),
+        };
+
+        Ok(Ticker {
+            lts: now.as_millis() as i64,
+            ts: self.ts.parse()?,
+            price: self.price.parse()?,
+            amount: self.amount.parse()?,
+            direction,
+            id: self.trade_id.parse()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::okx::format::TradeEventStream;
+    use crate::OrderDirection;
+
+    const TRADES: &str = r#"{"arg":{"channel":"trades","instId":"BTC-USDT"},"data":[{"instId":"BTC-USDT","tradeId":"130639474","px":"42219.9","sz":"0.12060306","side":"sell","ts":"1630048897897","count":"3"}]}"#;
+
+    #[test]
+    fn trade_to_ticker() {
+        let event: TradeEventStream = serde_json::from_str(TRADES).unwrap();
+        assert_eq!(event.arg.channel, "trades");
+
+        let tick = event.data[0].tick().unwrap();
+        assert_eq!(tick.id, 130639474);
+        assert_eq!(tick.ts, 1630048897897);
+        assert_eq!(tick.price, 42219.9);
+        assert_eq!(tick.amount, 0.12060306);
+        assert!(matches!(tick.direction, OrderDirection::Sell));
+    }
+}
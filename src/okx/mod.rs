@@ -0,0 +1,7 @@
+mod adapter;
+pub mod connection;
+pub mod format;
+
+pub use adapter::OkxAdapter;
+pub use connection::OkxDepth;
+pub use connection::OkxTicker;